@@ -0,0 +1,412 @@
+use actix_web::{http::StatusCode, web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::api::{encoding::ResponseFormat, runes::handlers::rune_key};
+use crate::services::{
+    cache::RunesCache,
+    catalog::RuneCatalog,
+    events::watcher::BlockWatcher,
+    node::sync::SyncService,
+    rate_limit::{RateLimitBucket, RateLimitStats, RateLimiter},
+    webhook::manager::WebhookManager,
+};
+use crate::types::error::{RuneError, RuneResult};
+
+/// Buckets listed when no `limit` is given
+const DEFAULT_BUCKET_LIMIT: usize = 100;
+const MAX_BUCKET_LIMIT: usize = 1000;
+
+pub struct AdminApiContext {
+    pub cache: Arc<RunesCache>,
+    pub rate_limiter: Arc<RateLimiter>,
+    pub webhook_manager: Arc<WebhookManager>,
+    /// Without a sync the `/sync` routes answer 501
+    pub sync: Option<Arc<SyncService>>,
+    /// Rewound along with the sync, when there is one
+    pub catalog: Option<Arc<RuneCatalog>>,
+    /// Rewound along with the sync, when there is one
+    pub watcher: Option<Arc<BlockWatcher>>,
+}
+
+impl AdminApiContext {
+    fn sync(&self) -> RuneResult<&SyncService> {
+        self.sync
+            .as_deref()
+            .ok_or_else(|| RuneError::UnsupportedOperation("No sync service is configured".to_string()))
+    }
+}
+
+/// Which `RunesCache` an entry is invalidated in
+#[derive(Debug, Clone, Copy, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheKind {
+    Transaction,
+    Address,
+    Rune,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RateLimitQuery {
+    /// Only buckets whose key starts with this, e.g. `key:` for API keys
+    pub prefix: Option<String>,
+    /// Most buckets to list, up to 1000
+    #[param(default = 100)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RateLimitOverview {
+    pub stats: RateLimitStats,
+    /// Buckets matching the prefix, before `limit` is applied
+    pub total: usize,
+    /// The most throttled clients first
+    pub buckets: Vec<RateLimitBucket>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ResetResponse {
+    /// Buckets dropped, their clients start over with full ones
+    pub removed: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RewindRequest {
+    /// First block the sync walks again
+    #[schema(example = 840000)]
+    pub from_height: u64,
+}
+
+/// Entry counts and hit rates of the runes cache
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/cache",
+    responses(
+        (status = 200, description = "Cache statistics", body = CacheStats),
+    ),
+    security(
+        ("api_key" = [], "request_signature" = [])
+    ),
+    tag = "admin"
+)]
+pub async fn cache_stats(context: web::Data<AdminApiContext>, format: ResponseFormat) -> impl Responder {
+    format.respond(StatusCode::OK, &context.cache.get_metrics().await)
+}
+
+/// Drop every cached transaction, address and rune
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/cache",
+    responses(
+        (status = 200, description = "Cache flushed, statistics after the flush", body = CacheStats),
+    ),
+    security(
+        ("api_key" = [], "request_signature" = [])
+    ),
+    tag = "admin"
+)]
+pub async fn flush_cache(context: web::Data<AdminApiContext>, format: ResponseFormat) -> impl Responder {
+    context.cache.clear_all().await;
+    tracing::info!("Runes cache flushed");
+    format.respond(StatusCode::OK, &context.cache.get_metrics().await)
+}
+
+/// Drop one cached entry, so the next lookup goes to the node
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/cache/{kind}/{key}",
+    responses(
+        (status = 204, description = "Entry invalidated, whether or not it was cached"),
+        (status = 400, description = "Unknown kind or malformed rune", body = ErrorResponse),
+    ),
+    params(
+        ("kind" = CacheKind, Path, description = "`transaction`, `address` or `rune`"),
+        ("key" = String, Path, description = "Transaction id, address, or rune id or name")
+    ),
+    security(
+        ("api_key" = [], "request_signature" = [])
+    ),
+    tag = "admin"
+)]
+pub async fn invalidate_cache_entry(
+    path: web::Path<(CacheKind, String)>,
+    context: web::Data<AdminApiContext>,
+    format: ResponseFormat,
+) -> impl Responder {
+    let (kind, key) = path.into_inner();
+    match kind {
+        CacheKind::Transaction => context.cache.invalidate_transaction(&key).await,
+        CacheKind::Address => context.cache.invalidate_address(&key).await,
+        CacheKind::Rune => match rune_key(&key) {
+            Ok(key) => context.cache.invalidate_rune(&key).await,
+            Err(e) => return format.error_response(&e),
+        },
+    }
+    tracing::info!("Invalidated cached {:?} {}", kind, key);
+    HttpResponse::NoContent().finish()
+}
+
+/// Rate limit counters and the buckets of current clients
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/rate-limits",
+    params(RateLimitQuery),
+    responses(
+        (status = 200, description = "Rate limit buckets", body = RateLimitOverview),
+        (status = 400, description = "Invalid limit", body = ErrorResponse),
+    ),
+    security(
+        ("api_key" = [], "request_signature" = [])
+    ),
+    tag = "admin"
+)]
+pub async fn list_rate_limits(
+    query: web::Query<RateLimitQuery>,
+    context: web::Data<AdminApiContext>,
+    format: ResponseFormat,
+) -> impl Responder {
+    let limit = query.limit.unwrap_or(DEFAULT_BUCKET_LIMIT);
+    if limit == 0 || limit > MAX_BUCKET_LIMIT {
+        return format.error_response(&RuneError::InvalidRequest(format!(
+            "limit must be between 1 and {}",
+            MAX_BUCKET_LIMIT
+        )));
+    }
+
+    let mut buckets = context.rate_limiter.buckets().await;
+    if let Some(prefix) = &query.prefix {
+        buckets.retain(|bucket| bucket.key.starts_with(prefix.as_str()));
+    }
+    let total = buckets.len();
+    buckets.truncate(limit);
+
+    format.respond(StatusCode::OK, &RateLimitOverview {
+        stats: context.rate_limiter.get_metrics().await,
+        total,
+        buckets,
+    })
+}
+
+/// The bucket of one client
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/rate-limits/{key}",
+    params(
+        ("key" = String, Path, description = "Bucket key, `key:<api key id>` or the client IP")
+    ),
+    responses(
+        (status = 200, description = "Rate limit bucket", body = RateLimitBucket),
+        (status = 404, description = "No bucket for this key", body = ErrorResponse),
+    ),
+    security(
+        ("api_key" = [], "request_signature" = [])
+    ),
+    tag = "admin"
+)]
+pub async fn get_rate_limit(
+    key: web::Path<String>,
+    context: web::Data<AdminApiContext>,
+    format: ResponseFormat,
+) -> impl Responder {
+    match context.rate_limiter.bucket(&key).await {
+        Some(bucket) => format.respond(StatusCode::OK, &bucket),
+        None => format.error_response(&RuneError::NotFound(format!("No rate limit bucket for {}", key))),
+    }
+}
+
+/// Reset one client's bucket
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/rate-limits/{key}",
+    params(
+        ("key" = String, Path, description = "Bucket key, `key:<api key id>` or the client IP")
+    ),
+    responses(
+        (status = 200, description = "Bucket reset", body = ResetResponse),
+        (status = 404, description = "No bucket for this key", body = ErrorResponse),
+    ),
+    security(
+        ("api_key" = [], "request_signature" = [])
+    ),
+    tag = "admin"
+)]
+pub async fn reset_rate_limit(
+    key: web::Path<String>,
+    context: web::Data<AdminApiContext>,
+    format: ResponseFormat,
+) -> impl Responder {
+    if !context.rate_limiter.reset_bucket(&key) {
+        return format.error_response(&RuneError::NotFound(format!("No rate limit bucket for {}", key)));
+    }
+    tracing::info!("Rate limit bucket {} reset", key);
+    format.respond(StatusCode::OK, &ResetResponse { removed: 1 })
+}
+
+/// Reset every client's bucket
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/rate-limits",
+    responses(
+        (status = 200, description = "Buckets reset", body = ResetResponse),
+    ),
+    security(
+        ("api_key" = [], "request_signature" = [])
+    ),
+    tag = "admin"
+)]
+pub async fn reset_rate_limits(context: web::Data<AdminApiContext>, format: ResponseFormat) -> impl Responder {
+    let removed = context.rate_limiter.reset_all();
+    tracing::info!("{} rate limit buckets reset", removed);
+    format.respond(StatusCode::OK, &ResetResponse { removed })
+}
+
+/// Registered webhooks and the health of their deliveries
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/webhooks",
+    responses(
+        (status = 200, description = "Registered webhooks", body = [WebhookStatus]),
+    ),
+    security(
+        ("api_key" = [], "request_signature" = [])
+    ),
+    tag = "admin"
+)]
+pub async fn list_webhooks(context: web::Data<AdminApiContext>, format: ResponseFormat) -> impl Responder {
+    format.respond(StatusCode::OK, &context.webhook_manager.list_webhooks().await)
+}
+
+/// Status of `sync` after an operator action
+async fn sync_response(sync: &SyncService, format: ResponseFormat, status: StatusCode) -> HttpResponse {
+    match sync.get_sync_status().await {
+        Ok(sync_status) => format.respond(status, &sync_status),
+        Err(e) => format.error_response(&e),
+    }
+}
+
+/// Height, target and state of the block sync
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/sync",
+    responses(
+        (status = 200, description = "Sync status", body = SyncStatus),
+        (status = 501, description = "No sync service is configured", body = ErrorResponse),
+    ),
+    security(
+        ("api_key" = [], "request_signature" = [])
+    ),
+    tag = "admin"
+)]
+pub async fn sync_status(context: web::Data<AdminApiContext>, format: ResponseFormat) -> impl Responder {
+    match context.sync() {
+        Ok(sync) => sync_response(sync, format, StatusCode::OK).await,
+        Err(e) => format.error_response(&e),
+    }
+}
+
+/// Stop syncing until resumed
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/sync/pause",
+    responses(
+        (status = 200, description = "Sync paused", body = SyncStatus),
+        (status = 501, description = "No sync service is configured", body = ErrorResponse),
+    ),
+    security(
+        ("api_key" = [], "request_signature" = [])
+    ),
+    tag = "admin"
+)]
+pub async fn pause_sync(context: web::Data<AdminApiContext>, format: ResponseFormat) -> impl Responder {
+    tracing::info!("Pausing block sync");
+    let sync = match context.sync() {
+        Ok(sync) => sync,
+        Err(e) => return format.error_response(&e),
+    };
+    match sync.pause().await {
+        Ok(()) => sync_response(sync, format, StatusCode::OK).await,
+        Err(e) => format.error_response(&e),
+    }
+}
+
+/// Sync on from the current height
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/sync/resume",
+    responses(
+        (status = 200, description = "Sync resumed", body = SyncStatus),
+        (status = 501, description = "No sync service is configured", body = ErrorResponse),
+        (status = 503, description = "The node tip could not be fetched", body = ErrorResponse),
+    ),
+    security(
+        ("api_key" = [], "request_signature" = [])
+    ),
+    tag = "admin"
+)]
+pub async fn resume_sync(context: web::Data<AdminApiContext>, format: ResponseFormat) -> impl Responder {
+    tracing::info!("Resuming block sync");
+    let sync = match context.sync() {
+        Ok(sync) => sync,
+        Err(e) => return format.error_response(&e),
+    };
+    match sync.resume().await {
+        Ok(()) => sync_response(sync, format, StatusCode::OK).await,
+        Err(e) => format.error_response(&e),
+    }
+}
+
+/// Reindex from a height
+///
+/// Moves the sync back to `from_height` and restarts it. A paused sync only
+/// moves back and picks up from there once resumed.
+///
+/// Cached transactions confirmed from `from_height` on are dropped, as are
+/// all cached address transfers and runes. The rune catalog forgets runes
+/// etched from there and is refreshed in the background. Stream, WebSocket
+/// and webhook subscribers get a `reorg` event, then the blocks again.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/sync/rewind",
+    request_body = RewindRequest,
+    responses(
+        (status = 202, description = "Sync restarted from the height", body = SyncStatus),
+        (status = 400, description = "Height above the node tip", body = ErrorResponse),
+        (status = 501, description = "No sync service is configured", body = ErrorResponse),
+        (status = 503, description = "The node tip could not be fetched", body = ErrorResponse),
+    ),
+    security(
+        ("api_key" = [], "request_signature" = [])
+    ),
+    tag = "admin"
+)]
+pub async fn rewind_sync(
+    request: web::Json<RewindRequest>,
+    context: web::Data<AdminApiContext>,
+    format: ResponseFormat,
+) -> impl Responder {
+    let sync = match context.sync() {
+        Ok(sync) => sync,
+        Err(e) => return format.error_response(&e),
+    };
+    let height = request.from_height;
+    if let Err(e) = sync.rewind_to(height).await {
+        return format.error_response(&e);
+    }
+
+    context.cache.invalidate_from_height(height).await;
+    if let Some(catalog) = &context.catalog {
+        catalog.rewind_to(height).await;
+        let catalog = catalog.clone();
+        tokio::spawn(async move {
+            if let Err(e) = catalog.refresh().await {
+                tracing::warn!("Failed to refresh rune catalog after rewind: {}", e);
+            }
+        });
+    }
+    if let Some(watcher) = &context.watcher {
+        watcher.rewind_to(height).await;
+    }
+    sync_response(sync, format, StatusCode::ACCEPTED).await
+}
@@ -0,0 +1,29 @@
+use actix_web::{middleware::Compress, web};
+use crate::api::encoding::{json_config, path_config, query_config};
+use super::handlers::{
+    cache_stats, flush_cache, invalidate_cache_entry, list_rate_limits, get_rate_limit,
+    reset_rate_limit, reset_rate_limits, list_webhooks, sync_status, pause_sync, resume_sync,
+    rewind_sync,
+};
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/v1/admin")
+            .wrap(Compress::default())
+            .app_data(json_config())
+            .app_data(query_config())
+            .app_data(path_config())
+            .route("/cache", web::get().to(cache_stats))
+            .route("/cache", web::delete().to(flush_cache))
+            .route("/cache/{kind}/{key}", web::delete().to(invalidate_cache_entry))
+            .route("/rate-limits", web::get().to(list_rate_limits))
+            .route("/rate-limits", web::delete().to(reset_rate_limits))
+            .route("/rate-limits/{key}", web::get().to(get_rate_limit))
+            .route("/rate-limits/{key}", web::delete().to(reset_rate_limit))
+            .route("/webhooks", web::get().to(list_webhooks))
+            .route("/sync", web::get().to(sync_status))
+            .route("/sync/pause", web::post().to(pause_sync))
+            .route("/sync/resume", web::post().to(resume_sync))
+            .route("/sync/rewind", web::post().to(rewind_sync))
+    );
+}
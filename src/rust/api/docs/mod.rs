@@ -1,7 +1,22 @@
+use utoipa::openapi::{
+    header::HeaderBuilder,
+    path::{ParameterBuilder, ParameterIn, PathItemType},
+    security::{ApiKey, ApiKeyValue, SecurityRequirement, SecurityScheme},
+    ContentBuilder, ObjectBuilder, Ref, RefOr, Required, ResponseBuilder, SchemaType,
+};
 use utoipa::OpenApi;
+use crate::api::encoding::{CBOR, JSON, MESSAGE_PACK};
+use crate::services::events::{
+    bus::{ChainEvent, ChainEventData, ChainEventType},
+    protocol::Channel,
+};
 use crate::types::{
     error::ErrorResponse,
-    rune::{RunesTransactionResponse, RuneTransfer},
+    rune::{
+        AddressBalances, AddressRuneBalance, NetworkType, OutputRunes, RuneAmount, RuneAttributes,
+        RuneEtching, RuneInfo, RuneMetadata, RuneStats, RuneSupply, RuneTermsInfo, RuneUtxo,
+        RunesTransactionResponse, RuneTransfer, TransactionStatus, TransferType,
+    },
 };
 
 #[derive(OpenApi)]
@@ -10,26 +25,93 @@ use crate::types::{
         crate::api::runes::handlers::get_transaction,
         crate::api::runes::handlers::get_batch_transactions,
         crate::api::runes::handlers::get_address_transfers,
+        crate::api::runes::handlers::get_rune,
+        crate::api::runes::handlers::list_runes,
+        crate::api::runes::handlers::get_address_balances,
+        crate::api::runes::handlers::get_output,
+        crate::api::stream::handlers::stream_events,
         crate::api::webhook::handlers::register_webhook,
         crate::api::webhook::handlers::unregister_webhook,
+        crate::api::websocket::handlers::connect,
+        crate::api::graphql::handlers::graphql,
+        crate::api::health::handlers::live,
+        crate::api::health::handlers::ready,
+        crate::api::admin::handlers::cache_stats,
+        crate::api::admin::handlers::flush_cache,
+        crate::api::admin::handlers::invalidate_cache_entry,
+        crate::api::admin::handlers::list_rate_limits,
+        crate::api::admin::handlers::get_rate_limit,
+        crate::api::admin::handlers::reset_rate_limit,
+        crate::api::admin::handlers::reset_rate_limits,
+        crate::api::admin::handlers::list_webhooks,
+        crate::api::admin::handlers::sync_status,
+        crate::api::admin::handlers::pause_sync,
+        crate::api::admin::handlers::resume_sync,
+        crate::api::admin::handlers::rewind_sync,
     ),
     components(
         schemas(
             RunesTransactionResponse,
             RuneTransfer,
+            TransferType,
+            TransactionStatus,
+            NetworkType,
+            RuneInfo,
+            RuneMetadata,
+            RuneAttributes,
+            RuneSupply,
+            RuneEtching,
+            RuneTermsInfo,
+            RuneStats,
+            RuneAmount,
+            OutputRunes,
+            RuneUtxo,
+            AddressRuneBalance,
+            AddressBalances,
+            crate::api::runes::handlers::RuneListResponse,
+            crate::api::runes::handlers::RuneSort,
+            crate::api::runes::handlers::SortOrder,
             ErrorResponse,
             crate::api::runes::handlers::BatchTransactionRequest,
+            crate::api::runes::handlers::BatchTransactionResponse,
+            crate::api::runes::handlers::BatchTransactionResult,
+            crate::api::runes::handlers::BatchItemError,
             crate::api::webhook::handlers::RegisterWebhookRequest,
             crate::api::webhook::handlers::WebhookResponse,
+            crate::services::webhook::manager::WebhookEventType,
+            ChainEvent,
+            ChainEventData,
+            ChainEventType,
+            Channel,
+            crate::api::health::handlers::LivenessResponse,
+            crate::services::health::ReadinessReport,
+            crate::services::health::CheckResult,
+            crate::services::health::SyncLag,
+            crate::api::admin::handlers::CacheKind,
+            crate::api::admin::handlers::RateLimitOverview,
+            crate::api::admin::handlers::ResetResponse,
+            crate::api::admin::handlers::RewindRequest,
+            crate::services::cache::CacheStats,
+            crate::services::rate_limit::RateLimitStats,
+            crate::services::rate_limit::RateLimitBucket,
+            crate::services::webhook::manager::WebhookStatus,
+            crate::services::webhook::manager::DeliveryHealth,
+            crate::services::node::sync::SyncStatus,
         )
     ),
+    modifiers(&SecurityAddon, &CacheHeaders, &ResponseEncodings, &RateLimitHeaders),
     tags(
+        (name = "runes", description = "Etched runes and their supply"),
         (name = "transactions", description = "Rune transaction operations"),
         (name = "webhooks", description = "Webhook management operations"),
+        (name = "stream", description = "Live chain and rune events"),
+        (name = "websocket", description = "Live events over WebSocket subscriptions"),
+        (name = "graphql", description = "GraphQL queries over runes, addresses and transactions"),
+        (name = "health", description = "Liveness and readiness probes"),
+        (name = "admin", description = "Operator endpoints for the cache, rate limits, webhooks and sync, with the `admin` scope"),
     ),
     info(
         title = "Runes SDK API",
-        version = env!("CARGO_PKG_VERSION"),
         description = "A secure and scalable SDK for Runes",
         license(
             name = "MIT",
@@ -63,52 +145,195 @@ impl utoipa::Modify for SecurityAddon {
         if let Some(components) = openapi.components.as_mut() {
             components.add_security_scheme(
                 "api_key",
-                utoipa::openapi::security::SecurityScheme::ApiKey(
-                    utoipa::openapi::security::ApiKey::Header(
-                        utoipa::openapi::security::ApiKeyValue::new("X-API-Key")
-                    )
-                )
+                SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::with_description(
+                    "X-API-Key",
+                    "Keys carry scopes: `runes:read` for runes and GraphQL, `events:read` \
+                     for the event stream and WebSocket, `webhooks:write` for webhooks and \
+                     `admin` for everything",
+                ))),
+            );
+            components.add_security_scheme(
+                "request_signature",
+                SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::with_description(
+                    "X-Signature",
+                    "Hex HMAC-SHA256, keyed with the API key's signing secret, of \
+                     `METHOD\\npath?query\\ntimestamp\\nnonce\\nhex(sha256(body))`. \
+                     The unix timestamp goes in `X-Signature-Timestamp` and has to be within \
+                     the server's clock skew window, the nonce goes in `X-Signature-Nonce` \
+                     and can be used once",
+                ))),
+            );
+        }
+
+        // Every secured operation can be rejected by ApiKeyAuth
+        for path in openapi.paths.paths.values_mut() {
+            for operation in path.operations.values_mut() {
+                let public = operation.security.as_ref().is_none_or(|security| {
+                    security.is_empty() || security.contains(&SecurityRequirement::default())
+                });
+                if public {
+                    continue;
+                }
+                for (status, description) in AUTH_RESPONSES {
+                    operation
+                        .responses
+                        .responses
+                        .entry(status.to_string())
+                        .or_insert_with(|| {
+                            ResponseBuilder::new()
+                                .description(description)
+                                .content(
+                                    "application/json",
+                                    ContentBuilder::new()
+                                        .schema(Ref::from_schema_name("ErrorResponse"))
+                                        .build(),
+                                )
+                                .build()
+                                .into()
+                        });
+                }
+            }
+        }
+    }
+}
+
+const AUTH_RESPONSES: [(&str, &str); 2] = [
+    ("401", "Missing, invalid, disabled or expired API key"),
+    ("403", "API key lacks the scope of this endpoint"),
+];
+
+// ETag and Cache-Control of the runes lookups
+pub struct CacheHeaders;
+
+const CACHE_HEADERS: [(&str, &str); 2] = [
+    ("ETag", "Hash of the body, send it back in `If-None-Match` to revalidate"),
+    (
+        "Cache-Control",
+        "`max-age` by confirmations for transactions, short while pending or shallow and \
+         `immutable` once past the finality depth. A fixed short `max-age` elsewhere",
+    ),
+];
+
+impl utoipa::Modify for CacheHeaders {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        for (path, item) in openapi.paths.paths.iter_mut() {
+            if !path.starts_with("/api/v1/runes") {
+                continue;
+            }
+            let Some(operation) = item.operations.get_mut(&PathItemType::Get) else {
+                continue;
+            };
+
+            operation.parameters.get_or_insert_with(Vec::new).push(
+                ParameterBuilder::new()
+                    .name("If-None-Match")
+                    .parameter_in(ParameterIn::Header)
+                    .required(Required::False)
+                    .description(Some("ETag of a cached copy, answered with 304 if unchanged"))
+                    .schema(Some(ObjectBuilder::new().schema_type(SchemaType::String)))
+                    .build(),
+            );
+
+            let responses = &mut operation.responses.responses;
+            if let Some(RefOr::T(ok)) = responses.get_mut("200") {
+                for (name, description) in CACHE_HEADERS {
+                    let header = HeaderBuilder::new()
+                        .schema(ObjectBuilder::new().schema_type(SchemaType::String))
+                        .description(Some(description))
+                        .build();
+                    ok.headers.insert(name.to_string(), header);
+                }
+            }
+            responses.insert(
+                "304".to_string(),
+                ResponseBuilder::new()
+                    .description("Unchanged since the `If-None-Match` ETag")
+                    .build()
+                    .into(),
             );
         }
     }
 }
 
+// CBOR and MessagePack bodies, compression and 406 answers of the runes, webhook and admin routes
+pub struct ResponseEncodings;
+
+const NOT_ACCEPTABLE_DESCRIPTION: &str =
+    "`Accept` names none of `application/json`, `application/cbor` and `application/msgpack`";
+
+const CONTENT_ENCODING_DESCRIPTION: &str =
+    "`br`, `gzip`, `deflate` or `zstd` as negotiated with `Accept-Encoding`, absent when uncompressed";
+
+impl utoipa::Modify for ResponseEncodings {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        for (path, item) in openapi.paths.paths.iter_mut() {
+            if !["/api/v1/runes", "/api/v1/webhooks", "/api/v1/admin"].iter().any(|prefix| path.starts_with(prefix)) {
+                continue;
+            }
+            for operation in item.operations.values_mut() {
+                for response in operation.responses.responses.values_mut() {
+                    let RefOr::T(response) = response else {
+                        continue;
+                    };
+                    let Some(json) = response.content.get(JSON).cloned() else {
+                        continue;
+                    };
+                    // Same schema, picked with `Accept`
+                    response.content.insert(CBOR.to_string(), json.clone());
+                    response.content.insert(MESSAGE_PACK.to_string(), json);
+                    response.headers.insert(
+                        "Content-Encoding".to_string(),
+                        HeaderBuilder::new()
+                            .schema(ObjectBuilder::new().schema_type(SchemaType::String))
+                            .description(Some(CONTENT_ENCODING_DESCRIPTION))
+                            .build(),
+                    );
+                }
+                // Always JSON, the client accepts nothing else we could send
+                operation.responses.responses.insert(
+                    "406".to_string(),
+                    ResponseBuilder::new()
+                        .description(NOT_ACCEPTABLE_DESCRIPTION)
+                        .content(
+                            JSON,
+                            ContentBuilder::new()
+                                .schema(Ref::from_schema_name("ErrorResponse"))
+                                .build(),
+                        )
+                        .build()
+                        .into(),
+                );
+            }
+        }
+    }
+}
+
 // Header definitions for rate limit information
 pub struct RateLimitHeaders;
 
+const RATE_LIMIT_HEADERS: [(&str, &str); 3] = [
+    ("X-RateLimit-Limit", "The number of allowed requests in the current period"),
+    ("X-RateLimit-Remaining", "The number of remaining requests in the current period"),
+    ("X-RateLimit-Reset", "The remaining window before the rate limit resets in UTC epoch seconds"),
+];
+
 impl utoipa::Modify for RateLimitHeaders {
     fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
-        // Tüm path'lere rate limit header'larını ekle
-        for path in openapi.paths.iter_mut() {
-            for operation in path.1.operations.iter_mut() {
-                operation.1.responses.default = Some(
-                    utoipa::openapi::Response::new("Rate limit information")
-                        .add_header(
-                            "X-RateLimit-Limit",
-                            utoipa::openapi::Header::new()
-                                .description("The number of allowed requests in the current period")
-                                .schema(Some(utoipa::openapi::Schema::Integer(
-                                    utoipa::openapi::IntegerType::new()
-                                )))
-                        )
-                        .add_header(
-                            "X-RateLimit-Remaining",
-                            utoipa::openapi::Header::new()
-                                .description("The number of remaining requests in the current period")
-                                .schema(Some(utoipa::openapi::Schema::Integer(
-                                    utoipa::openapi::IntegerType::new()
-                                )))
-                        )
-                        .add_header(
-                            "X-RateLimit-Reset",
-                            utoipa::openapi::Header::new()
-                                .description("The remaining window before the rate limit resets in UTC epoch seconds")
-                                .schema(Some(utoipa::openapi::Schema::Integer(
-                                    utoipa::openapi::IntegerType::new()
-                                )))
-                        )
-                );
+        // Tüm yanıtlara rate limit header'larını ekle
+        for path in openapi.paths.paths.values_mut() {
+            for operation in path.operations.values_mut() {
+                for response in operation.responses.responses.values_mut() {
+                    if let RefOr::T(response) = response {
+                        for (name, description) in RATE_LIMIT_HEADERS {
+                            let header = HeaderBuilder::new()
+                                .schema(ObjectBuilder::new().schema_type(SchemaType::Integer))
+                                .description(Some(description))
+                                .build();
+                            response.headers.insert(name.to_string(), header);
+                        }
+                    }
+                }
             }
         }
     }
-} 
\ No newline at end of file
+}
@@ -0,0 +1,138 @@
+use actix_web::{
+    dev::Payload,
+    http::{
+        header::{self, q, Accept, Header},
+        StatusCode,
+    },
+    error::InternalError,
+    mime::Mime,
+    web, Error, FromRequest, HttpRequest, HttpResponse, ResponseError,
+};
+use futures::future::{ready, Ready};
+use serde::Serialize;
+
+use crate::types::error::{ErrorResponse, RuneError, RuneResult};
+
+pub const JSON: &str = "application/json";
+pub const CBOR: &str = "application/cbor";
+pub const MESSAGE_PACK: &str = "application/msgpack";
+
+/// Body encoding picked from the request's `Accept` header.
+///
+/// JSON when the header is missing or cannot be parsed, so clients that never
+/// asked for a format keep working. Handlers taking it as an extractor answer
+/// `406 Not Acceptable` to a header that names none of the formats.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResponseFormat {
+    #[default]
+    Json,
+    Cbor,
+    MessagePack,
+}
+
+impl ResponseFormat {
+    /// Format for error bodies, JSON when nothing acceptable was named
+    pub fn negotiate(req: &HttpRequest) -> Self {
+        Self::acceptable(req).unwrap_or_default()
+    }
+
+    /// Format of a successful response, `NotAcceptable` when the client
+    /// accepts none of JSON, CBOR and MessagePack
+    pub fn acceptable(req: &HttpRequest) -> RuneResult<Self> {
+        if !req.headers().contains_key(header::ACCEPT) {
+            return Ok(ResponseFormat::Json);
+        }
+        let Ok(Accept(mut items)) = Accept::parse(req) else {
+            return Ok(ResponseFormat::Json);
+        };
+        // `q=0` means the client does not want that type at all
+        items.retain(|item| item.quality > q(0.0));
+
+        Accept(items)
+            .ranked()
+            .iter()
+            .find_map(Self::from_mime)
+            .ok_or_else(|| {
+                RuneError::NotAcceptable(format!(
+                    "Responses are available as {}, {} or {}",
+                    JSON, CBOR, MESSAGE_PACK
+                ))
+            })
+    }
+
+    fn from_mime(mime: &Mime) -> Option<Self> {
+        match (mime.type_().as_str(), mime.subtype().as_str()) {
+            ("application", "json") | ("application", "*") | ("*", "*") => Some(ResponseFormat::Json),
+            ("application", "cbor") => Some(ResponseFormat::Cbor),
+            ("application", "msgpack" | "x-msgpack" | "vnd.msgpack") => Some(ResponseFormat::MessagePack),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ResponseFormat::Json => JSON,
+            ResponseFormat::Cbor => CBOR,
+            ResponseFormat::MessagePack => MESSAGE_PACK,
+        }
+    }
+
+    /// MessagePack maps keep their field names, like the JSON objects
+    pub fn encode<T: Serialize>(&self, value: &T) -> RuneResult<Vec<u8>> {
+        let encoded = match self {
+            ResponseFormat::Json => serde_json::to_vec(value).map_err(|e| e.to_string()),
+            ResponseFormat::Cbor => {
+                let mut body = Vec::new();
+                ciborium::into_writer(value, &mut body)
+                    .map(|_| body)
+                    .map_err(|e| e.to_string())
+            }
+            ResponseFormat::MessagePack => rmp_serde::to_vec_named(value).map_err(|e| e.to_string()),
+        };
+        encoded.map_err(RuneError::SerializationError)
+    }
+
+    pub fn respond<T: Serialize>(&self, status: StatusCode, value: &T) -> HttpResponse {
+        match self.encode(value) {
+            Ok(body) => HttpResponse::build(status)
+                .content_type(self.content_type())
+                .insert_header((header::VARY, "Accept"))
+                .body(body),
+            Err(e) => e.error_response(),
+        }
+    }
+
+    /// `error` as an `ErrorResponse` in this format
+    pub fn error_response(&self, error: &RuneError) -> HttpResponse {
+        self.respond(error.status_code(), &ErrorResponse::from(error))
+    }
+}
+
+impl FromRequest for ResponseFormat {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(Self::acceptable(req).map_err(Error::from))
+    }
+}
+
+/// Answers a request whose path, query or JSON body could not be extracted
+/// with an `INVALID_REQUEST` error in the negotiated format
+fn invalid_input<E: ResponseError + 'static>(err: E, req: &HttpRequest) -> Error {
+    let error = ErrorResponse::from(&RuneError::InvalidRequest(err.to_string()));
+    let response = ResponseFormat::negotiate(req).respond(err.status_code(), &error);
+    InternalError::from_response(err, response).into()
+}
+
+pub fn json_config() -> web::JsonConfig {
+    web::JsonConfig::default().error_handler(invalid_input)
+}
+
+pub fn query_config() -> web::QueryConfig {
+    web::QueryConfig::default().error_handler(invalid_input)
+}
+
+pub fn path_config() -> web::PathConfig {
+    web::PathConfig::default().error_handler(invalid_input)
+}
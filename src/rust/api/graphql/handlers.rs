@@ -0,0 +1,54 @@
+use actix_web::{web, HttpResponse, Responder};
+use async_graphql::http::GraphiQLSource;
+use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse};
+use std::sync::Arc;
+
+use crate::api::http_cache::HttpCacheConfig;
+use crate::services::{cache::RunesCache, node::backend::NodeBackend};
+use super::{loaders::with_loaders, schema::RunesSchema};
+
+pub struct GraphQLApiContext {
+    pub schema: RunesSchema,
+    pub node: Arc<dyn NodeBackend>,
+    pub cache: Arc<RunesCache>,
+    /// Decides which cached transactions are final enough to serve
+    pub http_cache: HttpCacheConfig,
+}
+
+/// Run a GraphQL query over runes, addresses, outpoints, transactions and blocks
+///
+/// Query errors, including exceeded depth or complexity limits, are reported
+/// in the `errors` of a 200 response like any GraphQL server does.
+#[utoipa::path(
+    post,
+    path = "/api/v1/graphql",
+    request_body(content = Object, description = "GraphQL request with `query`, optional `variables` and `operationName`", content_type = "application/json"),
+    responses(
+        (status = 200, description = "GraphQL response with `data` and `errors`", content_type = "application/json"),
+        (status = 400, description = "Malformed GraphQL request"),
+        (status = 429, description = "Too many requests", body = ErrorResponse),
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "graphql"
+)]
+pub async fn graphql(
+    request: GraphQLRequest,
+    context: web::Data<GraphQLApiContext>,
+) -> GraphQLResponse {
+    let request = with_loaders(
+        request.into_inner(),
+        &context.node,
+        &context.cache,
+        &context.http_cache,
+    );
+    context.schema.execute(request).await.into()
+}
+
+/// GraphiQL explorer for the GraphQL endpoint
+pub async fn graphiql() -> impl Responder {
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(GraphiQLSource::build().endpoint("/api/v1/graphql").finish())
+}
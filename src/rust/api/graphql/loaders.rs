@@ -0,0 +1,282 @@
+use async_graphql::dataloader::{DataLoader, HashMapCache, Loader};
+use futures::{stream, StreamExt};
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::Arc;
+
+use crate::api::{
+    http_cache::{CachePolicy, HttpCacheConfig},
+    runes::handlers::{rune_key, unix_now},
+};
+use crate::services::{
+    cache::RunesCache,
+    node::backend::{BlockInfo, NodeBackend},
+};
+use crate::types::{
+    error::{RuneError, RuneResult},
+    rune::{RuneInfo, RuneOutput, RuneTransfer, RunesTransactionResponse},
+};
+
+/// Node lookups in flight while loading one batch
+const LOAD_CONCURRENCY: usize = 8;
+
+type LoadResult<K, V> = Result<HashMap<K, V>, Arc<RuneError>>;
+
+/// Fetches every key with bounded concurrency. Keys the node does not know or
+/// rejects as malformed are left out of the result, other errors fail the batch.
+///
+/// The node has no batch endpoints, so this is still one call per key. What
+/// the loaders save is asking twice for the same key within a query.
+async fn load_each<K, V, F, Fut>(keys: impl IntoIterator<Item = K>, fetch: F) -> LoadResult<K, V>
+where
+    K: Clone + Eq + Hash,
+    F: Fn(K) -> Fut,
+    Fut: Future<Output = RuneResult<V>>,
+{
+    let results: Vec<_> = stream::iter(keys)
+        .map(|key| {
+            let lookup = fetch(key.clone());
+            async move { (key, lookup.await) }
+        })
+        .buffer_unordered(LOAD_CONCURRENCY)
+        .collect()
+        .await;
+
+    let mut values = HashMap::with_capacity(results.len());
+    for (key, result) in results {
+        match result {
+            Ok(value) => {
+                values.insert(key, value);
+            }
+            Err(
+                RuneError::NotFound(_)
+                | RuneError::InvalidRequest(_)
+                | RuneError::InvalidTransaction(_),
+            ) => {}
+            Err(e) => return Err(Arc::new(e)),
+        }
+    }
+    Ok(values)
+}
+
+/// Runes by id or name, spaced or not
+pub struct RuneLoader {
+    pub node: Arc<dyn NodeBackend>,
+    pub cache: Arc<RunesCache>,
+}
+
+impl Loader<String> for RuneLoader {
+    type Value = RuneInfo;
+    type Error = Arc<RuneError>;
+
+    async fn load(&self, keys: &[String]) -> LoadResult<String, RuneInfo> {
+        let mut found = HashMap::new();
+        // Spellings of the same rune share one lookup
+        let mut missing: HashMap<String, Vec<String>> = HashMap::new();
+
+        for key in keys {
+            let Ok(normalized) = rune_key(key) else {
+                continue;
+            };
+            match self.cache.get_rune(&normalized).await {
+                Some(info) => {
+                    found.insert(key.clone(), info.as_ref().clone());
+                }
+                None => missing.entry(normalized).or_default().push(key.clone()),
+            }
+        }
+        if missing.is_empty() {
+            return Ok(found);
+        }
+
+        // One tip lookup for the whole batch
+        let height = self.node.get_block_height().await.map_err(Arc::new)?;
+        let updated_at = unix_now();
+
+        let entries = load_each(missing.keys().cloned(), |rune| {
+            let node = self.node.clone();
+            async move { node.get_rune(&rune).await }
+        })
+        .await?;
+
+        for (normalized, entry) in entries {
+            let info = RuneInfo::from_entry(&entry, height, updated_at);
+            if let Err(e) = self.cache.set_rune(info.clone()).await {
+                tracing::error!("Failed to cache rune {}: {}", normalized, e);
+            }
+            for key in missing.remove(&normalized).unwrap_or_default() {
+                found.insert(key, info.clone());
+            }
+        }
+        Ok(found)
+    }
+}
+
+/// Transactions by txid. Like the REST route, only final transactions are
+/// served from the cache, younger ones would carry stale confirmations.
+pub struct TransactionLoader {
+    pub node: Arc<dyn NodeBackend>,
+    pub cache: Arc<RunesCache>,
+    pub http_cache: HttpCacheConfig,
+}
+
+impl Loader<String> for TransactionLoader {
+    type Value = RunesTransactionResponse;
+    type Error = Arc<RuneError>;
+
+    async fn load(&self, keys: &[String]) -> LoadResult<String, RunesTransactionResponse> {
+        let mut found = HashMap::new();
+        let mut missing = Vec::new();
+
+        for tx_id in keys {
+            match self.cache.get_transaction(tx_id).await {
+                Some(tx) if self.http_cache.transaction_policy(&tx) == CachePolicy::Immutable => {
+                    found.insert(tx_id.clone(), tx.as_ref().clone());
+                }
+                _ => missing.push(tx_id.clone()),
+            }
+        }
+
+        let fetched = load_each(missing, |tx_id| {
+            let node = self.node.clone();
+            async move { node.get_transaction(&tx_id).await }
+        })
+        .await?;
+
+        for (tx_id, tx) in fetched {
+            if let Err(e) = self.cache.set_transaction(tx_id.clone(), tx.clone()).await {
+                tracing::error!("Failed to cache transaction {}: {}", tx_id, e);
+            }
+            found.insert(tx_id, tx);
+        }
+        Ok(found)
+    }
+}
+
+/// Recent rune transfers per address
+pub struct AddressTransfersLoader {
+    pub node: Arc<dyn NodeBackend>,
+    pub cache: Arc<RunesCache>,
+}
+
+impl Loader<String> for AddressTransfersLoader {
+    type Value = Vec<RuneTransfer>;
+    type Error = Arc<RuneError>;
+
+    async fn load(&self, keys: &[String]) -> LoadResult<String, Vec<RuneTransfer>> {
+        let mut found = HashMap::new();
+        let mut missing = Vec::new();
+
+        for address in keys {
+            match self.cache.get_address_transfers(address).await {
+                Some(transfers) => {
+                    found.insert(address.clone(), transfers.as_ref().clone());
+                }
+                None => missing.push(address.clone()),
+            }
+        }
+
+        let fetched = load_each(missing, |address| {
+            let node = self.node.clone();
+            async move { node.get_address_transfers(&address).await }
+        })
+        .await?;
+
+        for (address, transfers) in fetched {
+            if let Err(e) = self.cache.set_address_transfers(address.clone(), transfers.clone()).await {
+                tracing::error!("Failed to cache address transfers {}: {}", address, e);
+            }
+            found.insert(address, transfers);
+        }
+        Ok(found)
+    }
+}
+
+/// Unspent rune outputs per address
+pub struct AddressOutputsLoader {
+    pub node: Arc<dyn NodeBackend>,
+}
+
+impl Loader<String> for AddressOutputsLoader {
+    type Value = Vec<RuneOutput>;
+    type Error = Arc<RuneError>;
+
+    async fn load(&self, keys: &[String]) -> LoadResult<String, Vec<RuneOutput>> {
+        load_each(keys.iter().cloned(), |address| {
+            let node = self.node.clone();
+            async move { node.get_address_outputs(&address).await }
+        })
+        .await
+    }
+}
+
+/// Outputs by `txid:vout`
+pub struct OutputLoader {
+    pub node: Arc<dyn NodeBackend>,
+}
+
+impl Loader<String> for OutputLoader {
+    type Value = RuneOutput;
+    type Error = Arc<RuneError>;
+
+    async fn load(&self, keys: &[String]) -> LoadResult<String, RuneOutput> {
+        load_each(keys.iter().cloned(), |outpoint| {
+            let node = self.node.clone();
+            async move { node.get_output(&outpoint).await }
+        })
+        .await
+    }
+}
+
+/// Blocks by height
+pub struct BlockLoader {
+    pub node: Arc<dyn NodeBackend>,
+}
+
+impl Loader<u64> for BlockLoader {
+    type Value = BlockInfo;
+    type Error = Arc<RuneError>;
+
+    async fn load(&self, keys: &[u64]) -> LoadResult<u64, BlockInfo> {
+        load_each(keys.iter().copied(), |height| {
+            let node = self.node.clone();
+            async move {
+                let hash = node.get_block_hash(height).await?;
+                node.get_block(&hash).await
+            }
+        })
+        .await
+    }
+}
+
+fn loader<T>(loader: T) -> DataLoader<T, HashMapCache> {
+    DataLoader::with_cache(loader, tokio::spawn, HashMapCache::default())
+}
+
+/// Attaches a fresh set of loaders to `request`, so batches and what they
+/// cache never outlive the query
+pub fn with_loaders(
+    request: async_graphql::Request,
+    node: &Arc<dyn NodeBackend>,
+    cache: &Arc<RunesCache>,
+    http_cache: &HttpCacheConfig,
+) -> async_graphql::Request {
+    request
+        .data(loader(RuneLoader {
+            node: node.clone(),
+            cache: cache.clone(),
+        }))
+        .data(loader(TransactionLoader {
+            node: node.clone(),
+            cache: cache.clone(),
+            http_cache: http_cache.clone(),
+        }))
+        .data(loader(AddressTransfersLoader {
+            node: node.clone(),
+            cache: cache.clone(),
+        }))
+        .data(loader(AddressOutputsLoader { node: node.clone() }))
+        .data(loader(OutputLoader { node: node.clone() }))
+        .data(loader(BlockLoader { node: node.clone() }))
+}
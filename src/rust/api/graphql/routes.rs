@@ -0,0 +1,10 @@
+use actix_web::web;
+use super::handlers::{graphiql, graphql};
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/api/v1/graphql")
+            .route(web::post().to(graphql))
+            .route(web::get().to(graphiql)),
+    );
+}
@@ -0,0 +1,518 @@
+use async_graphql::dataloader::{DataLoader, HashMapCache, Loader};
+use async_graphql::{
+    Context, EmptyMutation, EmptySubscription, ErrorExtensions, Object, Result, Schema,
+};
+use std::hash::Hash;
+use std::sync::Arc;
+
+use crate::api::runes::handlers::{name_prefix, rune_key, unix_now, validate_address, validate_tx_id};
+use crate::services::{
+    catalog::RuneCatalog,
+    node::backend::{BlockInfo, NodeBackend},
+};
+use crate::types::{
+    error::RuneError,
+    rune::{
+        AddressBalances, AddressRuneBalance, NetworkType, RuneAmount, RuneAttributes, RuneEtching,
+        RuneInfo, RuneOutput, RuneStats, RuneSupply, RuneTransfer, RuneUtxo,
+        RunesTransactionResponse, TransactionStatus, TransferType,
+    },
+};
+use super::loaders::{
+    AddressOutputsLoader, AddressTransfersLoader, BlockLoader, OutputLoader, RuneLoader,
+    TransactionLoader,
+};
+
+pub type RunesSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Assumed length of lists without a size argument, for complexity scoring
+const LIST_COMPLEXITY: usize = 10;
+
+#[derive(Debug, Clone)]
+pub struct GraphQLConfig {
+    /// Deepest selection nesting accepted
+    pub max_depth: usize,
+    /// Highest query complexity accepted, every field counts 1 and list
+    /// fields multiply the complexity of their items
+    pub max_complexity: usize,
+}
+
+impl Default for GraphQLConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: 10,
+            max_complexity: 1_000,
+        }
+    }
+}
+
+/// Node and catalog back the root fields, the per request loaders from
+/// `loaders::with_loaders` resolve everything else
+pub fn build_schema(
+    node: Arc<dyn NodeBackend>,
+    catalog: Arc<RuneCatalog>,
+    config: &GraphQLConfig,
+) -> RunesSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(node)
+        .data(catalog)
+        .limit_depth(config.max_depth)
+        .limit_complexity(config.max_complexity)
+        .finish()
+}
+
+/// GraphQL error carrying the REST error code in its `code` extension
+fn graphql_error(error: &RuneError) -> async_graphql::Error {
+    async_graphql::Error::new(error.to_string())
+        .extend_with(|_, extensions| extensions.set("code", error.error_code()))
+}
+
+async fn load_one<T, K>(ctx: &Context<'_>, key: K) -> Result<Option<T::Value>>
+where
+    T: Loader<K, Error = Arc<RuneError>>,
+    K: Send + Sync + Hash + Eq + Clone + 'static,
+{
+    ctx.data_unchecked::<DataLoader<T, HashMapCache>>()
+        .load_one(key)
+        .await
+        .map_err(|e| graphql_error(&e))
+}
+
+/// Loads `keys` in one batch, keeping their order and dropping unknown ones
+async fn load_many<T, K>(ctx: &Context<'_>, keys: Vec<K>) -> Result<Vec<T::Value>>
+where
+    T: Loader<K, Error = Arc<RuneError>>,
+    K: Send + Sync + Hash + Eq + Clone + 'static,
+{
+    let mut values = ctx
+        .data_unchecked::<DataLoader<T, HashMapCache>>()
+        .load_many(keys.iter().cloned())
+        .await
+        .map_err(|e| graphql_error(&e))?;
+
+    Ok(keys.iter().filter_map(|key| values.remove(key)).collect())
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Rune by `block:tx` id or name, with or without spacers
+    async fn rune(&self, ctx: &Context<'_>, id: String) -> Result<Option<Rune>> {
+        rune_key(&id).map_err(|e| graphql_error(&e))?;
+        Ok(load_one::<RuneLoader, _>(ctx, id).await?.map(Rune))
+    }
+
+    /// Etched runes, newest first
+    #[graphql(complexity = "first as usize * child_complexity")]
+    async fn runes(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "Rune name or name prefix, spacers and case are ignored")]
+        search: Option<String>,
+        #[graphql(default = 20, validator(minimum = 1, maximum = 100))] first: u32,
+        #[graphql(default = 0)] offset: u32,
+    ) -> Result<Vec<Rune>> {
+        let prefix = name_prefix(search.as_deref()).map_err(|e| graphql_error(&e))?;
+        let snapshot = ctx
+            .data_unchecked::<Arc<RuneCatalog>>()
+            .snapshot()
+            .await
+            .map_err(|e| graphql_error(&e))?;
+
+        Ok(snapshot
+            .entries
+            .iter()
+            .filter(|entry| prefix.as_deref().is_none_or(|prefix| entry.name.starts_with(prefix)))
+            .skip(offset as usize)
+            .take(first as usize)
+            .map(|entry| Rune(RuneInfo::from_entry(entry, snapshot.height, snapshot.refreshed_at)))
+            .collect())
+    }
+
+    async fn address(&self, address: String) -> Result<Address> {
+        validate_address(&address).map_err(|e| graphql_error(&e))?;
+        Ok(Address(address))
+    }
+
+    /// Output by `txid:vout`
+    async fn outpoint(&self, ctx: &Context<'_>, outpoint: String) -> Result<Option<Outpoint>> {
+        let (txid, vout) = outpoint.split_once(':').unwrap_or((&outpoint, ""));
+        validate_tx_id(txid).map_err(|e| graphql_error(&e))?;
+        if vout.parse::<u32>().is_err() {
+            let error = RuneError::InvalidRequest(format!("Invalid outpoint: {}", outpoint));
+            return Err(graphql_error(&error));
+        }
+
+        Ok(load_one::<OutputLoader, _>(ctx, outpoint).await?.map(Outpoint))
+    }
+
+    async fn transaction(&self, ctx: &Context<'_>, txid: String) -> Result<Option<Transaction>> {
+        validate_tx_id(&txid).map_err(|e| graphql_error(&e))?;
+        Ok(load_one::<TransactionLoader, _>(ctx, txid).await?.map(Transaction))
+    }
+
+    async fn block(&self, ctx: &Context<'_>, height: u64) -> Result<Option<Block>> {
+        Ok(load_one::<BlockLoader, _>(ctx, height).await?.map(Block))
+    }
+}
+
+pub struct Rune(RuneInfo);
+
+#[Object]
+impl Rune {
+    /// `block:tx` of the etching transaction
+    async fn id(&self) -> &str {
+        &self.0.id
+    }
+
+    /// Name without spacers
+    async fn name(&self) -> &str {
+        &self.0.metadata.name
+    }
+
+    async fn spaced_name(&self) -> &str {
+        &self.0.metadata.spaced_name
+    }
+
+    async fn number(&self) -> u64 {
+        self.0.metadata.number
+    }
+
+    async fn symbol(&self) -> Option<String> {
+        self.0.metadata.symbol.map(String::from)
+    }
+
+    async fn divisibility(&self) -> u8 {
+        self.0.metadata.decimals
+    }
+
+    async fn spacers(&self) -> u32 {
+        self.0.metadata.spacers
+    }
+
+    async fn attributes(&self) -> &RuneAttributes {
+        &self.0.attributes
+    }
+
+    async fn supply(&self) -> &RuneSupply {
+        &self.0.supply
+    }
+
+    async fn stats(&self) -> &RuneStats {
+        &self.0.stats
+    }
+
+    async fn etching(&self) -> &RuneEtching {
+        &self.0.etching
+    }
+
+    /// Timestamp of the etching block
+    async fn created_at(&self) -> u64 {
+        self.0.created_at
+    }
+
+    async fn etching_transaction(&self, ctx: &Context<'_>) -> Result<Option<Transaction>> {
+        let txid = self.0.etching.txid.clone();
+        Ok(load_one::<TransactionLoader, _>(ctx, txid).await?.map(Transaction))
+    }
+
+    async fn etching_block(&self, ctx: &Context<'_>) -> Result<Option<Block>> {
+        let height = self.0.etching.block_height;
+        Ok(load_one::<BlockLoader, _>(ctx, height).await?.map(Block))
+    }
+}
+
+pub struct Address(String);
+
+#[Object]
+impl Address {
+    async fn address(&self) -> &str {
+        &self.0
+    }
+
+    /// Runes held on unspent outputs, one entry per rune
+    #[graphql(complexity = "LIST_COMPLEXITY * child_complexity")]
+    async fn balances(&self, ctx: &Context<'_>) -> Result<Vec<RuneBalance>> {
+        let outputs = load_one::<AddressOutputsLoader, _>(ctx, self.0.clone())
+            .await?
+            .unwrap_or_default();
+
+        Ok(AddressBalances::from_outputs(&self.0, &outputs)
+            .balances
+            .into_iter()
+            .map(RuneBalance)
+            .collect())
+    }
+
+    /// Unspent outputs holding runes
+    #[graphql(complexity = "LIST_COMPLEXITY * child_complexity")]
+    async fn outputs(&self, ctx: &Context<'_>) -> Result<Vec<Outpoint>> {
+        let outputs = load_one::<AddressOutputsLoader, _>(ctx, self.0.clone())
+            .await?
+            .unwrap_or_default();
+
+        Ok(outputs.into_iter().map(Outpoint).collect())
+    }
+
+    /// Most recent rune transfers first
+    #[graphql(complexity = "first as usize * child_complexity")]
+    async fn transfers(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 20, validator(minimum = 1, maximum = 100))] first: u32,
+    ) -> Result<Vec<Transfer>> {
+        let transfers = load_one::<AddressTransfersLoader, _>(ctx, self.0.clone())
+            .await?
+            .unwrap_or_default();
+
+        Ok(transfers
+            .into_iter()
+            .take(first as usize)
+            .map(Transfer)
+            .collect())
+    }
+}
+
+/// Amount of one rune, raw and formatted with its divisibility
+pub struct Amount(RuneAmount);
+
+#[Object(name = "RuneAmount")]
+impl Amount {
+    /// Spaced rune name
+    async fn rune_name(&self) -> &str {
+        &self.0.rune
+    }
+
+    async fn rune(&self, ctx: &Context<'_>) -> Result<Option<Rune>> {
+        Ok(load_one::<RuneLoader, _>(ctx, self.0.rune.clone()).await?.map(Rune))
+    }
+
+    /// Raw amount in the smallest unit
+    async fn amount(&self) -> &str {
+        &self.0.amount
+    }
+
+    async fn formatted(&self) -> &str {
+        &self.0.formatted
+    }
+
+    async fn divisibility(&self) -> u8 {
+        self.0.divisibility
+    }
+
+    async fn symbol(&self) -> Option<String> {
+        self.0.symbol.map(String::from)
+    }
+}
+
+/// Total of one rune held by an address and the outputs it sits on
+pub struct RuneBalance(AddressRuneBalance);
+
+#[Object]
+impl RuneBalance {
+    async fn balance(&self) -> Amount {
+        Amount(self.0.balance.clone())
+    }
+
+    #[graphql(complexity = "LIST_COMPLEXITY * child_complexity")]
+    async fn utxos(&self) -> &[RuneUtxo] {
+        &self.0.utxos
+    }
+}
+
+pub struct Outpoint(RuneOutput);
+
+#[Object]
+impl Outpoint {
+    /// `txid:vout`
+    async fn outpoint(&self) -> &str {
+        &self.0.outpoint
+    }
+
+    async fn address(&self) -> Option<&str> {
+        self.0.address.as_deref()
+    }
+
+    /// Value in satoshis
+    async fn value(&self) -> u64 {
+        self.0.value
+    }
+
+    async fn spent(&self) -> bool {
+        self.0.spent
+    }
+
+    async fn confirmations(&self) -> u32 {
+        self.0.confirmations
+    }
+
+    #[graphql(complexity = "LIST_COMPLEXITY * child_complexity")]
+    async fn runes(&self) -> Vec<Amount> {
+        self.0
+            .runes
+            .iter()
+            .map(|balance| Amount(RuneAmount::from(balance)))
+            .collect()
+    }
+
+    async fn transaction(&self, ctx: &Context<'_>) -> Result<Option<Transaction>> {
+        let txid = self.0.outpoint.split(':').next().unwrap_or_default().to_string();
+        Ok(load_one::<TransactionLoader, _>(ctx, txid).await?.map(Transaction))
+    }
+}
+
+pub struct Transaction(RunesTransactionResponse);
+
+#[Object]
+impl Transaction {
+    async fn txid(&self) -> &str {
+        &self.0.transaction_id
+    }
+
+    async fn block_height(&self) -> Option<u32> {
+        self.0.block_height
+    }
+
+    async fn confirmations(&self) -> u32 {
+        self.0.confirmation_count
+    }
+
+    async fn timestamp(&self) -> u64 {
+        self.0.timestamp
+    }
+
+    async fn status(&self) -> TransactionStatus {
+        self.0.status
+    }
+
+    async fn network(&self) -> NetworkType {
+        self.0.network_type
+    }
+
+    #[graphql(complexity = "LIST_COMPLEXITY * child_complexity")]
+    async fn transfers(&self) -> Vec<Transfer> {
+        self.0.runes.iter().cloned().map(Transfer).collect()
+    }
+
+    async fn block(&self, ctx: &Context<'_>) -> Result<Option<Block>> {
+        match self.0.block_height {
+            Some(height) => Ok(load_one::<BlockLoader, _>(ctx, u64::from(height)).await?.map(Block)),
+            None => Ok(None),
+        }
+    }
+}
+
+pub struct Transfer(RuneTransfer);
+
+#[Object]
+impl Transfer {
+    async fn rune_id(&self) -> &str {
+        &self.0.rune_id
+    }
+
+    async fn rune(&self, ctx: &Context<'_>) -> Result<Option<Rune>> {
+        Ok(load_one::<RuneLoader, _>(ctx, self.0.rune_id.clone()).await?.map(Rune))
+    }
+
+    async fn from_address(&self) -> &str {
+        &self.0.from_address
+    }
+
+    async fn to_address(&self) -> &str {
+        &self.0.to_address
+    }
+
+    /// Raw amount in the smallest unit
+    async fn amount(&self) -> String {
+        self.0.amount.to_string()
+    }
+
+    async fn transfer_type(&self) -> TransferType {
+        self.0.transfer_type
+    }
+
+    async fn fee(&self) -> Option<u64> {
+        self.0.fee
+    }
+}
+
+pub struct Block(BlockInfo);
+
+#[Object]
+impl Block {
+    async fn height(&self) -> u64 {
+        self.0.height
+    }
+
+    async fn hash(&self) -> &str {
+        &self.0.hash
+    }
+
+    async fn previous_hash(&self) -> Option<&str> {
+        self.0.previous_block_hash.as_deref()
+    }
+
+    async fn timestamp(&self) -> u64 {
+        self.0.timestamp
+    }
+
+    async fn transaction_count(&self) -> usize {
+        self.0.tx_ids.len()
+    }
+
+    #[graphql(complexity = "first as usize * child_complexity")]
+    async fn transaction_ids(
+        &self,
+        #[graphql(default = 20, validator(minimum = 1, maximum = 100))] first: u32,
+        #[graphql(default = 0)] offset: u32,
+    ) -> Vec<&str> {
+        self.0
+            .tx_ids
+            .iter()
+            .skip(offset as usize)
+            .take(first as usize)
+            .map(String::as_str)
+            .collect()
+    }
+
+    #[graphql(complexity = "first as usize * child_complexity")]
+    async fn transactions(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 20, validator(minimum = 1, maximum = 100))] first: u32,
+        #[graphql(default = 0)] offset: u32,
+    ) -> Result<Vec<Transaction>> {
+        let tx_ids = self
+            .0
+            .tx_ids
+            .iter()
+            .skip(offset as usize)
+            .take(first as usize)
+            .cloned()
+            .collect();
+
+        Ok(load_many::<TransactionLoader, _>(ctx, tx_ids)
+            .await?
+            .into_iter()
+            .map(Transaction)
+            .collect())
+    }
+
+    /// Runes etched in this block
+    #[graphql(complexity = "LIST_COMPLEXITY * child_complexity")]
+    async fn etchings(&self, ctx: &Context<'_>) -> Result<Vec<Rune>> {
+        let node = ctx.data_unchecked::<Arc<dyn NodeBackend>>();
+        let (entries, tip) = tokio::try_join!(
+            node.get_block_etchings(self.0.height),
+            node.get_block_height()
+        )
+        .map_err(|e| graphql_error(&e))?;
+
+        let updated_at = unix_now();
+        Ok(entries
+            .iter()
+            .map(|entry| Rune(RuneInfo::from_entry(entry, tip, updated_at)))
+            .collect())
+    }
+}
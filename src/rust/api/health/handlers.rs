@@ -0,0 +1,65 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde::Serialize;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+use crate::services::health::HealthChecker;
+
+pub struct HealthApiContext {
+    pub checker: Arc<HealthChecker>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LivenessResponse {
+    #[schema(example = "ok")]
+    pub status: String,
+}
+
+/// Whether the process is up, without touching any dependency
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    responses(
+        (status = 200, description = "The server is running", body = LivenessResponse),
+    ),
+    security(()),
+    tag = "health"
+)]
+pub async fn live() -> impl Responder {
+    HttpResponse::Ok().json(LivenessResponse {
+        status: "ok".to_string(),
+    })
+}
+
+/// Whether the instance should receive traffic
+///
+/// Checks that the node answers, that registered dependencies such as the
+/// database are reachable and that the sync is within the allowed lag of the
+/// node tip. Load balancers should route only to instances answering 200.
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    responses(
+        (status = 200, description = "Ready to serve requests", body = ReadinessReport),
+        (status = 503, description = "A dependency failed or the sync lags behind", body = ReadinessReport),
+    ),
+    security(()),
+    tag = "health"
+)]
+pub async fn ready(context: web::Data<HealthApiContext>) -> impl Responder {
+    let report = context.checker.readiness().await;
+    if report.ready {
+        HttpResponse::Ok().json(report)
+    } else {
+        tracing::warn!(
+            "Readiness check failed: {}",
+            report
+                .checks
+                .iter()
+                .filter_map(|check| check.error.as_ref().map(|e| format!("{}: {}", check.name, e)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        HttpResponse::ServiceUnavailable().json(report)
+    }
+}
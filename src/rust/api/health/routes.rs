@@ -0,0 +1,10 @@
+use actix_web::web;
+use super::handlers::{live, ready};
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/health")
+            .route("/live", web::get().to(live))
+            .route("/ready", web::get().to(ready))
+    );
+}
@@ -0,0 +1,126 @@
+use actix_web::{
+    http::header::{self, EntityTag, Header, IfNoneMatch},
+    HttpRequest, HttpResponse,
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+use crate::api::encoding::ResponseFormat;
+use crate::types::rune::{RunesTransactionResponse, TransactionStatus};
+
+/// A year, the longest max-age caches are expected to honour
+const IMMUTABLE_MAX_AGE: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+#[derive(Debug, Clone)]
+pub struct HttpCacheConfig {
+    /// Confirmations after which a transaction is not expected to change
+    pub finality_depth: u32,
+    /// max-age of pending, failed and unconfirmed transactions
+    pub pending_max_age: Duration,
+    /// max-age of transactions with fewer than `finality_depth` confirmations
+    pub shallow_max_age: Duration,
+    /// max-age of everything not tied to a transaction, e.g. runes and balances
+    pub default_max_age: Duration,
+    /// Marks responses `public` so CDNs and proxies may store them. Off by
+    /// default, a shared cache would hand responses to callers without a key.
+    pub shared: bool,
+}
+
+impl Default for HttpCacheConfig {
+    fn default() -> Self {
+        Self {
+            finality_depth: 6,
+            pending_max_age: Duration::from_secs(5),
+            shallow_max_age: Duration::from_secs(60),
+            default_max_age: Duration::from_secs(30),
+            shared: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CachePolicy {
+    MaxAge(Duration),
+    /// Never revalidated. The `confirmation_count` of such a response is a
+    /// lower bound, it is not refreshed once the transaction is final.
+    Immutable,
+}
+
+impl HttpCacheConfig {
+    pub fn transaction_policy(&self, tx: &RunesTransactionResponse) -> CachePolicy {
+        if tx.status != TransactionStatus::Confirmed || tx.confirmation_count == 0 {
+            CachePolicy::MaxAge(self.pending_max_age)
+        } else if tx.confirmation_count >= self.finality_depth {
+            CachePolicy::Immutable
+        } else {
+            CachePolicy::MaxAge(self.shallow_max_age)
+        }
+    }
+
+    pub fn default_policy(&self) -> CachePolicy {
+        CachePolicy::MaxAge(self.default_max_age)
+    }
+
+    pub fn cache_control(&self, policy: CachePolicy) -> String {
+        let scope = if self.shared { "public" } else { "private" };
+        match policy {
+            CachePolicy::MaxAge(max_age) => format!("{}, max-age={}", scope, max_age.as_secs()),
+            CachePolicy::Immutable => format!(
+                "{}, max-age={}, immutable",
+                scope,
+                IMMUTABLE_MAX_AGE.as_secs()
+            ),
+        }
+    }
+}
+
+/// ETag of a response body. Weak, since `Compress` serves the same body in
+/// several content encodings under it.
+pub fn etag(body: &[u8]) -> EntityTag {
+    EntityTag::new_weak(hex::encode(&Sha256::digest(body)[..16]))
+}
+
+/// Whether the request's `If-None-Match` already names `etag`
+fn not_modified(req: &HttpRequest, etag: &EntityTag) -> bool {
+    match IfNoneMatch::parse(req) {
+        Ok(IfNoneMatch::Any) => true,
+        Ok(IfNoneMatch::Items(tags)) => tags.iter().any(|tag| tag.weak_eq(etag)),
+        Err(_) => false,
+    }
+}
+
+/// Encodes `value` in `format` with an ETag and the `Cache-Control` of
+/// `policy`, answering 304 without a body when the client's copy matches.
+///
+/// The ETag only depends on the encoded body, so a response served from
+/// `RunesCache` and one fetched from the node validate each other.
+pub fn cached_response<T: Serialize>(
+    req: &HttpRequest,
+    format: ResponseFormat,
+    value: &T,
+    policy: CachePolicy,
+    config: &HttpCacheConfig,
+) -> HttpResponse {
+    let body = match format.encode(value) {
+        Ok(body) => body,
+        Err(e) => return format.error_response(&e),
+    };
+
+    let etag = etag(&body);
+    let cache_control = (header::CACHE_CONTROL, config.cache_control(policy));
+    if not_modified(req, &etag) {
+        return HttpResponse::NotModified()
+            .insert_header(header::ETag(etag))
+            .insert_header(cache_control)
+            .insert_header((header::VARY, "Accept"))
+            .finish();
+    }
+
+    HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header(header::ETag(etag))
+        .insert_header(cache_control)
+        .insert_header((header::VARY, "Accept"))
+        .body(body)
+}
@@ -0,0 +1,184 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::{task::JoinHandle, time::Instant};
+
+use crate::services::{
+    auth::ApiKeyStore,
+    node::sync::SyncService,
+    rate_limit::RateLimiter,
+    webhook::manager::WebhookManager,
+};
+
+#[derive(Debug, Clone)]
+pub struct LifecycleConfig {
+    /// How long in-flight HTTP requests and webhook deliveries get to finish
+    /// once shutdown starts
+    pub shutdown_timeout: Duration,
+    /// How often a finished or failed sync is started again towards the new tip
+    pub sync_check_interval: Duration,
+    /// How often idle rate limit buckets are dropped
+    pub rate_limit_cleanup_interval: Duration,
+    /// Buckets of clients idle for this long are dropped
+    pub rate_limit_max_idle: Duration,
+    /// How often expired API keys are removed from the key store
+    pub api_key_cleanup_interval: Duration,
+}
+
+impl Default for LifecycleConfig {
+    fn default() -> Self {
+        Self {
+            shutdown_timeout: Duration::from_secs(30),
+            sync_check_interval: Duration::from_secs(30),
+            rate_limit_cleanup_interval: Duration::from_secs(60),
+            rate_limit_max_idle: Duration::from_secs(600),
+            api_key_cleanup_interval: Duration::from_secs(300),
+        }
+    }
+}
+
+/// Resolves once the process receives SIGTERM or SIGINT, or Ctrl-C where
+/// there are no Unix signals
+pub async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match (signal(SignalKind::terminate()), signal(SignalKind::interrupt())) {
+            (Ok(mut terminate), Ok(mut interrupt)) => {
+                tokio::select! {
+                    _ = terminate.recv() => tracing::info!("Received SIGTERM"),
+                    _ = interrupt.recv() => tracing::info!("Received SIGINT"),
+                }
+                return;
+            }
+            (Err(e), _) | (_, Err(e)) => {
+                tracing::warn!("Could not listen for SIGTERM, falling back to Ctrl-C: {}", e);
+            }
+        }
+    }
+
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        tracing::error!("Could not listen for Ctrl-C: {}", e);
+        std::future::pending::<()>().await;
+    }
+    tracing::info!("Received Ctrl-C");
+}
+
+/// Starts and stops the work running next to the HTTP server: the block
+/// sync, rate limit and API key cleanup, webhook deliveries and any task handed to
+/// `manage`, such as the catalog refresh and block watcher.
+pub struct Supervisor {
+    config: LifecycleConfig,
+    rate_limiter: Arc<RateLimiter>,
+    webhook_manager: Arc<WebhookManager>,
+    sync: Option<Arc<SyncService>>,
+    api_keys: Option<Arc<ApiKeyStore>>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl Supervisor {
+    pub fn new(
+        config: LifecycleConfig,
+        rate_limiter: Arc<RateLimiter>,
+        webhook_manager: Arc<WebhookManager>,
+    ) -> Self {
+        Self {
+            config,
+            rate_limiter,
+            webhook_manager,
+            sync: None,
+            api_keys: None,
+            tasks: Vec::new(),
+        }
+    }
+
+    /// Sync started by `start` and persisted by `shutdown`
+    pub fn with_sync(mut self, sync: Arc<SyncService>) -> Self {
+        self.sync = Some(sync);
+        self
+    }
+
+    /// Key store whose expired keys `start` removes periodically
+    pub fn with_api_keys(mut self, store: Arc<ApiKeyStore>) -> Self {
+        self.api_keys = Some(store);
+        self
+    }
+
+    /// Aborts `task` on shutdown
+    pub fn manage(&mut self, task: JoinHandle<()>) {
+        self.tasks.push(task);
+    }
+
+    /// Starts the sync, rate limit cleanup and API key cleanup
+    pub fn start(&mut self) {
+        if let Some(sync) = self.sync.clone() {
+            let interval = self.config.sync_check_interval;
+            // `start_sync` does nothing while syncing or paused, so this only
+            // restarts a sync that reached its target or hit a node error
+            self.manage(tokio::spawn(async move {
+                let mut ticks = tokio::time::interval(interval);
+                loop {
+                    ticks.tick().await;
+                    if let Err(e) = sync.start_sync().await {
+                        tracing::warn!("Could not start block sync: {}", e);
+                    }
+                }
+            }));
+        }
+
+        let rate_limiter = self.rate_limiter.clone();
+        let interval = self.config.rate_limit_cleanup_interval;
+        let max_idle = self.config.rate_limit_max_idle;
+        self.manage(tokio::spawn(async move {
+            let mut ticks = tokio::time::interval(interval);
+            // The first tick is immediate and there is nothing to clean up yet
+            ticks.tick().await;
+            loop {
+                ticks.tick().await;
+                rate_limiter.cleanup_old_buckets(max_idle);
+            }
+        }));
+
+        if let Some(store) = self.api_keys.clone() {
+            let interval = self.config.api_key_cleanup_interval;
+            // Expired keys are already rejected, this keeps them from piling up
+            self.manage(tokio::spawn(async move {
+                let mut ticks = tokio::time::interval(interval);
+                loop {
+                    ticks.tick().await;
+                    let removed = store.cleanup_expired_keys();
+                    if removed > 0 {
+                        tracing::info!("Removed {} expired API keys", removed);
+                    }
+                }
+            }));
+        }
+    }
+
+    /// Stops the managed tasks and the sync, persists the sync progress and
+    /// waits until `deadline` for webhook deliveries in flight
+    pub async fn shutdown(self, deadline: Instant) {
+        for task in &self.tasks {
+            task.abort();
+        }
+
+        if let Some(sync) = &self.sync {
+            if let Err(e) = sync.stop_sync().await {
+                tracing::error!("Could not stop block sync: {}", e);
+            }
+            if let Err(e) = sync.persist_progress().await {
+                tracing::error!("Could not persist sync progress: {}", e);
+            }
+        }
+
+        let abandoned = self
+            .webhook_manager
+            .drain(deadline.saturating_duration_since(Instant::now()))
+            .await;
+        if abandoned > 0 {
+            tracing::warn!("Gave up on {} webhook events still being delivered", abandoned);
+        } else {
+            tracing::info!("Webhook deliveries drained");
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use std::rc::Rc;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use actix_web::{
+    body::EitherBody,
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    http::Method,
+    web, Error, HttpMessage, ResponseError,
+};
+use futures::future::{ok, LocalBoxFuture, Ready};
+use serde::Deserialize;
+
+use crate::services::auth::{ApiKeyId, ApiKeyScope, ApiKeyStore};
+use crate::types::error::RuneError;
+
+/// What a request has to present
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Public,
+    /// A valid key with the scope
+    Required(ApiKeyScope),
+    /// Checked when present. Without one the handler authenticates the client
+    /// itself, like the WebSocket endpoint does with its `auth` message.
+    Optional(ApiKeyScope),
+}
+
+/// Scope each route needs. Docs, metrics and the GraphiQL page stay public.
+///
+/// Takes the percent-decoded path the router matches on. On the raw one
+/// `/api/v1/%61dmin/cache` would be public and still reach the admin handler.
+pub fn route_access(method: &Method, path: &str) -> Access {
+    let under = |prefix: &str| {
+        path.strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    };
+
+    if under("/api/v1/runes") {
+        Access::Required(ApiKeyScope::RunesRead)
+    } else if under("/api/v1/graphql") {
+        if method == Method::GET {
+            Access::Public
+        } else {
+            Access::Required(ApiKeyScope::RunesRead)
+        }
+    } else if under("/api/v1/stream") {
+        Access::Required(ApiKeyScope::EventsRead)
+    } else if under("/api/v1/ws") {
+        Access::Optional(ApiKeyScope::EventsRead)
+    } else if under("/api/v1/webhooks") {
+        Access::Required(ApiKeyScope::WebhooksWrite)
+    } else if under("/api/v1/admin") {
+        Access::Required(ApiKeyScope::Admin)
+    } else {
+        Access::Public
+    }
+}
+
+#[derive(Deserialize)]
+struct KeyQuery {
+    api_key: Option<String>,
+}
+
+/// Key from the `X-API-Key` header. Browsers cannot set headers on
+/// `EventSource` and WebSocket requests, so those may use `?api_key=` instead.
+fn request_key(req: &ServiceRequest) -> Option<String> {
+    if let Some(key) = req.headers().get("X-API-Key").and_then(|v| v.to_str().ok()) {
+        return Some(key.to_string());
+    }
+
+    let path = req.match_info().as_str();
+    if req.method() != Method::GET || !(path == "/api/v1/stream" || path == "/api/v1/ws") {
+        return None;
+    }
+    web::Query::<KeyQuery>::from_query(req.query_string())
+        .ok()
+        .and_then(|query| query.into_inner().api_key)
+}
+
+/// Enforces API keys from an `ApiKeyStore`.
+///
+/// Missing and invalid keys get 401, keys without the route's scope 403.
+/// Authenticated requests carry an `ApiKeyId` in their extensions.
+pub struct ApiKeyAuth {
+    store: Arc<ApiKeyStore>,
+}
+
+impl ApiKeyAuth {
+    pub fn new(store: Arc<ApiKeyStore>) -> Self {
+        Self { store }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for ApiKeyAuth
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = ApiKeyAuthMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(ApiKeyAuthMiddleware {
+            service: Rc::new(service),
+            store: self.store.clone(),
+        })
+    }
+}
+
+pub struct ApiKeyAuthMiddleware<S> {
+    service: Rc<S>,
+    store: Arc<ApiKeyStore>,
+}
+
+impl<S, B> Service<ServiceRequest> for ApiKeyAuthMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let store = self.store.clone();
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            let (scope, required) = match route_access(req.method(), req.match_info().as_str()) {
+                Access::Public => {
+                    return service.call(req).await.map(ServiceResponse::map_into_left_body);
+                }
+                Access::Required(scope) => (scope, true),
+                Access::Optional(scope) => (scope, false),
+            };
+
+            let result = match request_key(&req) {
+                Some(key) => store.authenticate(&key, scope).map(Some),
+                None if required => Err(RuneError::Unauthorized("Missing API key".to_string())),
+                None => Ok(None),
+            };
+
+            match result {
+                Ok(key) => {
+                    if let Some(key) = key {
+                        req.extensions_mut().insert(ApiKeyId(key.id));
+                    }
+                    service.call(req).await.map(ServiceResponse::map_into_left_body)
+                }
+                Err(e) => {
+                    tracing::warn!("Rejected request to {}: {}", req.path(), e);
+                    Ok(req.into_response(e.error_response()).map_into_right_body())
+                }
+            }
+        })
+    }
+}
@@ -1,15 +1,16 @@
 use std::task::{Context, Poll};
 use actix_web::{
     dev::{Service, ServiceRequest, ServiceResponse, Transform},
-    Error, HttpMessage, HttpResponse,
+    error::InternalError,
+    Error,
 };
-use futures::future::{ok, Ready};
-use futures::Future;
-use std::pin::Pin;
+use futures::future::{ok, LocalBoxFuture, Ready};
 use tracing::{error, warn};
 
+use crate::api::{encoding::ResponseFormat, middleware::request_id::X_REQUEST_ID};
 use crate::types::error::{RuneError, ErrorResponse};
 
+#[derive(Default)]
 pub struct ErrorHandler;
 
 impl ErrorHandler {
@@ -47,71 +48,59 @@ where
 {
     type Response = ServiceResponse<B>;
     type Error = Error;
-    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
 
     fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
         self.service.poll_ready(cx)
     }
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
+        // A clone of the request would keep the router from resolving its
+        // path, so what the error response needs is read up front
+        let format = ResponseFormat::negotiate(req.request());
+        let request_id = req
+            .headers()
+            .get(X_REQUEST_ID)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
         let fut = self.service.call(req);
 
         Box::pin(async move {
-            match fut.await {
-                Ok(res) => Ok(res),
-                Err(err) => {
-                    // Request ID'yi al
-                    let request_id = if let Some(extensions) = fut.await.ok().map(|r| r.request().extensions()) {
-                        extensions.get::<String>().cloned()
-                    } else {
-                        None
-                    };
+            let err = match fut.await {
+                Ok(res) => return Ok(res),
+                Err(err) => err,
+            };
+            let status_code = err.as_response_error().status_code();
 
-                    // Hatayı logla
-                    match err.as_response_error() {
-                        e if e.status().is_server_error() => {
-                            error!(
-                                "Server error occurred: {} (status: {}, request_id: {:?})",
-                                e,
-                                e.status(),
-                                request_id
-                            );
-                        }
-                        e => {
-                            warn!(
-                                "Client error occurred: {} (status: {}, request_id: {:?})",
-                                e,
-                                e.status(),
-                                request_id
-                            );
-                        }
-                    }
+            // Hatayı logla
+            if status_code.is_server_error() {
+                error!(
+                    "Server error occurred: {} (status: {}, request_id: {:?})",
+                    err, status_code, request_id
+                );
+            } else {
+                warn!(
+                    "Client error occurred: {} (status: {}, request_id: {:?})",
+                    err, status_code, request_id
+                );
+            }
 
-                    // Hata yanıtını oluştur
-                    let error_response = match err.as_error::<RuneError>() {
-                        Some(rune_error) => ErrorResponse {
-                            code: rune_error.error_code(),
-                            message: rune_error.to_string(),
-                            details: rune_error.error_details(),
-                            request_id,
-                        },
-                        None => ErrorResponse {
-                            code: "INTERNAL_ERROR".to_string(),
-                            message: "An unexpected error occurred".to_string(),
-                            details: None,
-                            request_id,
-                        },
-                    };
+            // Hata yanıtını oluştur
+            let error_response = match err.as_error::<RuneError>() {
+                Some(rune_error) => ErrorResponse {
+                    request_id,
+                    ..ErrorResponse::from(rune_error)
+                },
+                None => ErrorResponse {
+                    code: "INTERNAL_ERROR".to_string(),
+                    message: "An unexpected error occurred".to_string(),
+                    details: None,
+                    request_id,
+                },
+            };
 
-                    // HTTP status code'unu belirle
-                    let status_code = err.as_response_error().status();
-                    
-                    Ok(ServiceResponse::new(
-                        fut.await.unwrap().request().clone(),
-                        HttpResponse::build(status_code).json(error_response),
-                    ))
-                }
-            }
+            let response = format.respond(status_code, &error_response);
+            Err(InternalError::from_response(err, response).into())
         })
     }
-} 
\ No newline at end of file
+}
@@ -1,18 +1,18 @@
-use std::future::Future;
-use std::pin::Pin;
 use std::task::{Context, Poll};
 use std::time::Instant;
 
 use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
-use actix_web::Error;
+use actix_web::{Error, HttpMessage};
 use futures::future::LocalBoxFuture;
-use tracing::{Span, Level};
+use tracing::{Instrument, Level};
+
+use crate::services::auth::ApiKeyId;
 
 pub struct LoggingMiddleware;
 
 impl<S, B> Transform<S, ServiceRequest> for LoggingMiddleware
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -52,8 +52,8 @@ where
         let method = req.method().as_str().to_string();
         let path = req.path().to_string();
         let version = req.version();
-        let connection_info = req.connection_info();
-        let remote_addr = connection_info.realip_remote_addr()
+        let remote_addr = req.connection_info()
+            .realip_remote_addr()
             .unwrap_or("unknown")
             .to_string();
         
@@ -69,20 +69,23 @@ where
             Level::INFO,
             "http_request",
             request_id = %request_id,
+            // Recorded once ApiKeyAuth, which runs further in, has seen the request
+            api_key_id = tracing::field::Empty,
             method = %method,
             path = %path,
             version = ?version,
             remote_addr = %remote_addr,
         );
-        let _enter = span.enter();
 
         // İstek başlangıcını logla
-        tracing::info!(
-            "Started {} {} from {}",
-            method,
-            path,
-            remote_addr,
-        );
+        span.in_scope(|| {
+            tracing::info!(
+                "Started {} {} from {}",
+                method,
+                path,
+                remote_addr,
+            );
+        });
 
         let fut = self.service.call(req);
         Box::pin(async move {
@@ -92,6 +95,10 @@ where
             
             match &result {
                 Ok(response) => {
+                    if let Some(id) = response.request().extensions().get::<ApiKeyId>() {
+                        tracing::Span::current().record("api_key_id", id.0.as_str());
+                    }
+
                     // Başarılı yanıtı logla
                     let status = response.status();
                     tracing::info!(
@@ -115,6 +122,6 @@ where
             }
             
             result
-        })
+        }.instrument(span))
     }
 } 
\ No newline at end of file
@@ -0,0 +1,7 @@
+pub mod auth;
+pub mod error_handler;
+pub mod logging;
+pub mod metrics;
+pub mod rate_limit;
+pub mod request_id;
+pub mod signature;
@@ -1,16 +1,26 @@
+use std::rc::Rc;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 use actix_web::{
+    body::EitherBody,
     dev::{Service, ServiceRequest, ServiceResponse, Transform},
-    Error, HttpResponse,
+    Error, HttpMessage, HttpResponse, ResponseError,
 };
-use futures::future::{ok, Ready};
-use futures::Future;
-use std::pin::Pin;
+use futures::future::{ok, LocalBoxFuture, Ready};
 
-use crate::services::rate_limit::RateLimiter;
+use crate::services::{auth::ApiKeyId, rate_limit::RateLimiter};
 use crate::types::error::RuneError;
 
+/// Whether a route counts against the rate limit. Probes and the metrics
+/// scrape are left out, an orchestrator must not be throttled into
+/// restarting a healthy server. Takes the decoded path like `route_access`.
+pub fn is_rate_limited(path: &str) -> bool {
+    !["/health", "/metrics"].iter().any(|prefix| {
+        path.strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    })
+}
+
 pub struct RateLimitMiddleware {
     limiter: Arc<RateLimiter>,
 }
@@ -23,11 +33,11 @@ impl RateLimitMiddleware {
 
 impl<S, B> Transform<S, ServiceRequest> for RateLimitMiddleware
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
-    type Response = ServiceResponse<B>;
+    type Response = ServiceResponse<EitherBody<B>>;
     type Error = Error;
     type InitError = ();
     type Transform = RateLimitMiddlewareService<S>;
@@ -35,26 +45,26 @@ where
 
     fn new_transform(&self, service: S) -> Self::Future {
         ok(RateLimitMiddlewareService {
-            service,
+            service: Rc::new(service),
             limiter: self.limiter.clone(),
         })
     }
 }
 
 pub struct RateLimitMiddlewareService<S> {
-    service: S,
+    service: Rc<S>,
     limiter: Arc<RateLimiter>,
 }
 
 impl<S, B> Service<ServiceRequest> for RateLimitMiddlewareService<S>
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
-    type Response = ServiceResponse<B>;
+    type Response = ServiceResponse<EitherBody<B>>;
     type Error = Error;
-    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
 
     fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
         self.service.poll_ready(cx)
@@ -62,31 +72,34 @@ where
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
         let limiter = self.limiter.clone();
-        let fut = self.service.call(req);
+        let service = self.service.clone();
 
         Box::pin(async move {
-            // Client IP'sini al
-            let ip = if let Some(ip) = get_client_ip(&fut.await?.request()) {
-                ip
-            } else {
-                return Ok(ServiceResponse::new(
-                    fut.await?.request().clone(),
-                    HttpResponse::BadRequest().json("Invalid IP address"),
-                ));
+            if !is_rate_limited(req.match_info().as_str()) {
+                return service.call(req).await.map(ServiceResponse::map_into_left_body);
+            }
+
+            // Authenticated clients get a bucket per key, others one per IP
+            let api_key_id = req.extensions().get::<ApiKeyId>().map(|id| id.0.clone());
+            let bucket = match api_key_id {
+                Some(id) => format!("key:{}", id),
+                None => match get_client_ip(req.request()) {
+                    Some(ip) => ip,
+                    None => {
+                        let resp = HttpResponse::BadRequest().json("Invalid IP address");
+                        return Ok(req.into_response(resp).map_into_right_body());
+                    }
+                },
             };
 
             // Rate limit kontrolü
-            match limiter.check_rate_limit(&ip, 1).await {
-                Ok(_) => fut.await,
-                Err(RuneError::RateLimitExceeded) => {
-                    let resp = HttpResponse::TooManyRequests()
-                        .json("Rate limit exceeded. Please try again later.");
-                    Ok(ServiceResponse::new(fut.await?.request().clone(), resp))
-                }
+            match limiter.check_rate_limit(&bucket, 1).await {
+                Ok(_) => service.call(req).await.map(ServiceResponse::map_into_left_body),
                 Err(e) => {
-                    let resp = HttpResponse::InternalServerError()
-                        .json(format!("Rate limit error: {}", e));
-                    Ok(ServiceResponse::new(fut.await?.request().clone(), resp))
+                    if !matches!(e, RuneError::RateLimitExceeded) {
+                        tracing::error!("Rate limit error: {}", e);
+                    }
+                    Ok(req.into_response(e.error_response()).map_into_right_body())
                 }
             }
         })
@@ -1,13 +1,16 @@
 use std::task::{Context, Poll};
 use actix_web::{
     dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    error::InternalError,
     Error, HttpMessage,
 };
-use futures::future::{ok, Ready};
-use futures::Future;
-use std::pin::Pin;
+use futures::future::{ok, LocalBoxFuture, Ready};
+use actix_web::http::header::{HeaderName, HeaderValue};
 use uuid::Uuid;
 
+pub(crate) const X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+#[derive(Default)]
 pub struct RequestId;
 
 impl RequestId {
@@ -45,7 +48,7 @@ where
 {
     type Response = ServiceResponse<B>;
     type Error = Error;
-    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
 
     fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
         self.service.poll_ready(cx)
@@ -53,7 +56,7 @@ where
 
     fn call(&self, mut req: ServiceRequest) -> Self::Future {
         // Generate Request ID
-        let request_id = if let Some(existing_id) = req.headers().get("X-Request-ID") {
+        let request_id = if let Some(existing_id) = req.headers().get(X_REQUEST_ID) {
             existing_id.to_str().unwrap_or_default().to_string()
         } else {
             Uuid::new_v4().to_string()
@@ -63,23 +66,32 @@ where
         req.extensions_mut().insert(request_id.clone());
 
         // Request header'ına ekle
-        req.headers_mut().insert(
-            "X-Request-ID",
-            actix_web::http::header::HeaderValue::from_str(&request_id).unwrap(),
-        );
+        if let Ok(value) = HeaderValue::from_str(&request_id) {
+            req.headers_mut().insert(X_REQUEST_ID, value);
+        }
 
         let fut = self.service.call(req);
 
         Box::pin(async move {
-            let mut res = fut.await?;
-            
-            // Response header'ına da ekle
-            res.headers_mut().insert(
-                "X-Request-ID",
-                actix_web::http::header::HeaderValue::from_str(&request_id).unwrap(),
-            );
-
-            Ok(res)
+            let value = HeaderValue::from_str(&request_id).ok();
+            match fut.await {
+                Ok(mut res) => {
+                    // Response header'ına da ekle
+                    if let Some(value) = value {
+                        res.headers_mut().insert(X_REQUEST_ID, value);
+                    }
+                    Ok(res)
+                }
+                // Errors of the middleware further in, such as a rejected
+                // API key, are rendered here so they carry the id as well
+                Err(err) => {
+                    let mut response = err.error_response();
+                    if let Some(value) = value {
+                        response.headers_mut().insert(X_REQUEST_ID, value);
+                    }
+                    Err(InternalError::from_response(err, response).into())
+                }
+            }
         })
     }
 } 
\ No newline at end of file
@@ -1,91 +1,289 @@
+pub mod admin;
+pub mod graphql;
+pub mod health;
+pub mod http_cache;
+pub mod lifecycle;
 pub mod runes;
+pub mod stream;
 pub mod webhook;
+pub mod websocket;
 pub mod middleware;
 pub mod docs;
+pub mod encoding;
 
-use actix_web::{web, App, HttpServer, HttpResponse};
-use std::sync::Arc;
-use utoipa_swagger_ui::SwaggerUi;
+use actix_web::{middleware::Condition, web, App, HttpServer, HttpResponse};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing_appender::non_blocking::WorkerGuard;
+use utoipa::OpenApi;
+use utoipa_swagger_ui::{Config, SwaggerUi};
 
 use crate::services::{
-    node::connection::NodeConnection,
+    auth::{
+        signing::{RequestVerifier, SigningConfig},
+        ApiKeyStore,
+    },
+    health::{DependencyCheck, HealthChecker, HealthConfig},
+    node::{backend::NodeBackend, sync::SyncService},
     cache::RunesCache,
-    rate_limit::{RateLimiter, RateLimitConfig, RateLimitMetrics},
+    catalog::RuneCatalog,
+    events::{
+        bus::EventBus,
+        watcher::{BlockWatcher, WatcherConfig},
+    },
+    rate_limit::RateLimiter,
     metrics::{register_metrics, metrics_handler},
     logging::{init_logging, LoggingConfig},
+    webhook::manager::WebhookManager,
 };
 
 use self::{
+    admin::handlers::AdminApiContext,
+    graphql::{
+        handlers::GraphQLApiContext,
+        schema::{build_schema, GraphQLConfig},
+    },
+    health::handlers::HealthApiContext,
+    http_cache::HttpCacheConfig,
+    lifecycle::{shutdown_signal, LifecycleConfig, Supervisor},
     runes::handlers::RunesApiContext,
+    stream::handlers::StreamApiContext,
+    webhook::handlers::WebhookApiContext,
+    websocket::handlers::{WebSocketApiContext, WebSocketConfig},
     middleware::{
+        auth::ApiKeyAuth,
+        signature::SignatureAuth,
         rate_limit::RateLimitMiddleware,
         request_id::RequestId,
         error_handler::ErrorHandler,
         metrics::MetricsMiddleware,
         logging::LoggingMiddleware,
     },
-    docs::{ApiDoc, SecurityAddon, RateLimitHeaders},
+    docs::ApiDoc,
 };
 
+/// How often the rune listing is reloaded from the node
+const CATALOG_REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Events kept for `Last-Event-ID` resumes of the event stream
+const EVENT_REPLAY_CAPACITY: usize = 1024;
+
 pub struct ApiServer {
-    node: Arc<NodeConnection>,
+    node: Arc<dyn NodeBackend>,
     cache: Arc<RunesCache>,
+    catalog: Arc<RuneCatalog>,
+    events: Arc<EventBus>,
+    watcher: Arc<BlockWatcher>,
     rate_limiter: Arc<RateLimiter>,
+    api_keys: Option<Arc<ApiKeyStore>>,
+    signing_config: Option<SigningConfig>,
+    webhook_manager: Arc<WebhookManager>,
+    websocket_config: WebSocketConfig,
+    graphql_config: GraphQLConfig,
+    sync: Option<Arc<SyncService>>,
+    readiness_checks: Vec<Arc<dyn DependencyCheck>>,
+    health_config: HealthConfig,
+    http_cache: HttpCacheConfig,
+    lifecycle: LifecycleConfig,
+    /// Flushes the log file when dropped, taken once `run` has shut down
+    log_guard: Mutex<Option<WorkerGuard>>,
 }
 
 impl ApiServer {
     pub fn new(
-        node: Arc<NodeConnection>,
+        node: Arc<dyn NodeBackend>,
         cache: Arc<RunesCache>,
         rate_limiter: Arc<RateLimiter>,
     ) -> Result<Self, Box<dyn std::error::Error>> {
         // Loglama sistemini başlat
-        init_logging(LoggingConfig::default())?;
+        let log_guard = init_logging(LoggingConfig::default())?;
         
         // Metrikleri kaydet
         register_metrics();
         
+        let events = Arc::new(EventBus::new(EVENT_REPLAY_CAPACITY));
+
         Ok(Self {
+            catalog: Arc::new(RuneCatalog::new(node.clone(), CATALOG_REFRESH_INTERVAL)),
+            watcher: Arc::new(BlockWatcher::new(node.clone(), events.clone(), WatcherConfig::default())),
+            events,
             node,
             cache,
             rate_limiter,
+            api_keys: None,
+            signing_config: None,
+            webhook_manager: Arc::new(WebhookManager::new(Arc::new(
+                metrics::register_counter!("webhook_delivery_failures_total"),
+            ))),
+            websocket_config: WebSocketConfig::default(),
+            graphql_config: GraphQLConfig::default(),
+            sync: None,
+            readiness_checks: Vec::new(),
+            health_config: HealthConfig::default(),
+            http_cache: HttpCacheConfig::default(),
+            lifecycle: LifecycleConfig::default(),
+            log_guard: Mutex::new(log_guard),
         })
     }
 
+    /// Requires API keys from `store` on every non-public route.
+    /// Without a store the API is open, as before keys existed.
+    pub fn with_api_keys(mut self, store: Arc<ApiKeyStore>) -> Self {
+        self.api_keys = Some(store);
+        self
+    }
+
+    /// Requires HMAC signed requests on privileged routes, webhook management
+    /// and the admin API. Only takes effect together with `with_api_keys`.
+    pub fn with_request_signing(mut self, config: SigningConfig) -> Self {
+        self.signing_config = Some(config);
+        self
+    }
+
+    /// Authentication and limits of `/api/v1/ws`
+    pub fn with_websocket_config(mut self, config: WebSocketConfig) -> Self {
+        self.websocket_config = config;
+        self
+    }
+
+    /// Poll interval, confirmation depth and transaction scanning of the
+    /// watcher publishing to `/api/v1/stream` and `/api/v1/ws`
+    pub fn with_watcher_config(mut self, config: WatcherConfig) -> Self {
+        self.watcher = Arc::new(BlockWatcher::new(self.node.clone(), self.events.clone(), config));
+        self
+    }
+
+    /// Depth and complexity limits of `/api/v1/graphql`
+    pub fn with_graphql_config(mut self, config: GraphQLConfig) -> Self {
+        self.graphql_config = config;
+        self
+    }
+
+    /// Sync whose lag behind the node tip `/health/ready` reports and that
+    /// `/api/v1/admin/sync` pauses, resumes and rewinds. `run` starts it and
+    /// persists its progress on shutdown.
+    pub fn with_sync_service(mut self, sync: Arc<SyncService>) -> Self {
+        self.sync = Some(sync);
+        self
+    }
+
+    /// Adds a dependency `/health/ready` checks, e.g. the database
+    pub fn with_readiness_check(mut self, check: Arc<dyn DependencyCheck>) -> Self {
+        self.readiness_checks.push(check);
+        self
+    }
+
+    /// Sync lag threshold and check timeout of `/health/ready`
+    pub fn with_health_config(mut self, config: HealthConfig) -> Self {
+        self.health_config = config;
+        self
+    }
+
+    /// Finality depth and max-ages behind the `Cache-Control` of the runes API
+    pub fn with_http_cache(mut self, config: HttpCacheConfig) -> Self {
+        self.http_cache = config;
+        self
+    }
+
+    /// Shutdown deadline and the intervals of supervised background work
+    pub fn with_lifecycle_config(mut self, config: LifecycleConfig) -> Self {
+        self.lifecycle = config;
+        self
+    }
+
+    pub fn webhook_manager(&self) -> Arc<WebhookManager> {
+        self.webhook_manager.clone()
+    }
+
+    /// Bus behind `/api/v1/stream`, other producers can publish to it as well
+    pub fn events(&self) -> Arc<EventBus> {
+        self.events.clone()
+    }
+
+    fn health_checker(&self) -> Arc<HealthChecker> {
+        let mut checker = HealthChecker::new(self.node.clone(), self.health_config.clone());
+        if let Some(sync) = &self.sync {
+            checker = checker.with_sync(sync.clone());
+        }
+        for check in &self.readiness_checks {
+            checker = checker.with_dependency(check.clone());
+        }
+        Arc::new(checker)
+    }
+
+    /// Serves until SIGTERM or SIGINT, then shuts down gracefully.
+    ///
+    /// New connections are refused, in-flight requests and webhook deliveries
+    /// get `LifecycleConfig::shutdown_timeout` to finish, the sync progress is
+    /// persisted and the log file is flushed. Log lines written after `run`
+    /// returns only reach the console.
     pub async fn run(&self, bind_address: &str) -> std::io::Result<()> {
         let node = self.node.clone();
         let cache = self.cache.clone();
+        let catalog = self.catalog.clone();
+        let events = self.events.clone();
         let rate_limiter = self.rate_limiter.clone();
+        let webhook_manager = self.webhook_manager.clone();
+        let api_keys = self.api_keys.clone();
+        let http_cache = self.http_cache.clone();
+        let sync = self.sync.clone();
+        let watcher = self.watcher.clone();
+        // Shared by all workers so a nonce is only accepted once
+        let verifier = match (&api_keys, &self.signing_config) {
+            (Some(store), Some(config)) => {
+                Some(Arc::new(RequestVerifier::new(store.clone(), config.clone())))
+            }
+            _ => None,
+        };
+        let mut websocket_config = self.websocket_config.clone();
+        if websocket_config.api_keys.is_none() {
+            // In-band `auth` messages are checked against the same keys
+            websocket_config.api_keys = api_keys.clone();
+        }
+        let health = self.health_checker();
+        let schema = build_schema(node.clone(), catalog.clone(), &self.graphql_config);
 
         // OpenAPI dokümantasyonunu oluştur
-        let openapi = ApiDoc::openapi()
-            .merge_with(&SecurityAddon)
-            .merge_with(&RateLimitHeaders);
+        let openapi = ApiDoc::openapi();
 
         tracing::info!("Starting API server on {}", bind_address);
 
-        HttpServer::new(move || {
+        let server = HttpServer::new(move || {
             App::new()
-                // Middleware sıralaması önemli
-                .wrap(ErrorHandler::new())  // En dıştaki middleware
-                .wrap(RequestId::new())     // Request ID'yi ekle
-                .wrap(LoggingMiddleware)    // Loglamayı ekle
-                .wrap(MetricsMiddleware)    // Metrikleri topla
+                // Middleware order matters, the last one wrapped runs outermost.
                 .wrap(RateLimitMiddleware::new(rate_limiter.clone()))
+                .wrap(Condition::new(
+                    verifier.is_some(),
+                    SignatureAuth::new(verifier.clone().unwrap_or_else(|| {
+                        Arc::new(RequestVerifier::new(Arc::default(), SigningConfig::default()))
+                    })),
+                ))
+                // Rate limiting and signatures need the key id, so keys are
+                // checked first. Rejected keys are still logged, counted and
+                // answered with a request id.
+                .wrap(Condition::new(
+                    api_keys.is_some(),
+                    ApiKeyAuth::new(api_keys.clone().unwrap_or_default()),
+                ))
+                .wrap(MetricsMiddleware)    // Metrikleri topla
+                .wrap(LoggingMiddleware)    // Loglamayı ekle
+                .wrap(ErrorHandler::new())  // Needs the request id
+                .wrap(RequestId::new())     // En dıştaki middleware
                 // Swagger UI'ı ekle
                 .service(
                     SwaggerUi::new("/swagger-ui/{_:.*}")
                         .url("/api-docs/openapi.json", openapi.clone())
-                        .config(|c| {
-                            c.docExpansion("list")
-                             .defaultModelsExpandDepth(3)
-                             .defaultModelExpandDepth(3)
-                             .displayRequestDuration(true)
-                             .filter(true)
-                             .showExtensions(true)
-                             .showCommonExtensions(true)
-                             .tryItOutEnabled(true)
-                        })
+                        .config(
+                            Config::default()
+                                .doc_expansion("list")
+                                .default_models_expand_depth(3)
+                                .default_model_expand_depth(3)
+                                .display_request_duration(true)
+                                .filter(true)
+                                .show_extensions(true)
+                                .show_common_extensions(true)
+                                .try_it_out_enabled(true)
+                        )
                 )
                 // Metrik endpoint'i
                 .route("/metrics", web::get().to(|| async {
@@ -96,12 +294,93 @@ impl ApiServer {
                 .app_data(web::Data::new(RunesApiContext {
                     node: node.clone(),
                     cache: cache.clone(),
+                    catalog: catalog.clone(),
+                    http_cache: http_cache.clone(),
+                }))
+                .app_data(web::Data::new(WebhookApiContext {
+                    webhook_manager: webhook_manager.clone(),
+                }))
+                .app_data(web::Data::new(StreamApiContext {
+                    events: events.clone(),
                 }))
+                .app_data(web::Data::new(WebSocketApiContext {
+                    events: events.clone(),
+                    config: websocket_config.clone(),
+                }))
+                .app_data(web::Data::new(GraphQLApiContext {
+                    schema: schema.clone(),
+                    node: node.clone(),
+                    cache: cache.clone(),
+                    http_cache: http_cache.clone(),
+                }))
+                .app_data(web::Data::new(HealthApiContext {
+                    checker: health.clone(),
+                }))
+                .configure(health::routes::configure_routes)
                 .configure(runes::routes::configure_routes)
                 .configure(webhook::routes::configure_routes)
+                .configure(stream::routes::configure_routes)
+                .configure(websocket::routes::configure_routes)
+                .configure(graphql::routes::configure_routes)
+                // Operator routes only exist behind keys with the `admin` scope
+                .configure(|cfg| {
+                    if api_keys.is_some() {
+                        cfg.app_data(web::Data::new(AdminApiContext {
+                            cache: cache.clone(),
+                            rate_limiter: rate_limiter.clone(),
+                            webhook_manager: webhook_manager.clone(),
+                            sync: sync.clone(),
+                            catalog: Some(catalog.clone()),
+                            watcher: Some(watcher.clone()),
+                        }))
+                        .configure(admin::routes::configure_routes);
+                    }
+                })
         })
+        // Signals are handled below, so the supervised services stop with the server
+        .disable_signals()
+        .shutdown_timeout(self.lifecycle.shutdown_timeout.as_secs())
         .bind(bind_address)?
-        .run()
-        .await
+        .run();
+        let handle = server.handle();
+
+        let mut supervisor = Supervisor::new(
+            self.lifecycle.clone(),
+            self.rate_limiter.clone(),
+            self.webhook_manager.clone(),
+        );
+        if let Some(sync) = &self.sync {
+            supervisor = supervisor.with_sync(sync.clone());
+        }
+        if let Some(store) = &self.api_keys {
+            supervisor = supervisor.with_api_keys(store.clone());
+        }
+        supervisor.manage(self.catalog.clone().start());
+        supervisor.manage(self.watcher.clone().start());
+        supervisor.start();
+
+        tokio::pin!(server);
+        let result = tokio::select! {
+            result = &mut server => {
+                tracing::error!("API server stopped unexpectedly");
+                supervisor.shutdown(tokio::time::Instant::now()).await;
+                result
+            }
+            _ = shutdown_signal() => {
+                tracing::info!(
+                    "Shutting down, waiting up to {:?} for in-flight work",
+                    self.lifecycle.shutdown_timeout
+                );
+                let deadline = tokio::time::Instant::now() + self.lifecycle.shutdown_timeout;
+                // Requests and webhook deliveries drain side by side under one deadline
+                tokio::join!(handle.stop(true), supervisor.shutdown(deadline));
+                server.await
+            }
+        };
+
+        tracing::info!("API server stopped");
+        // Dropping the guard writes out what is still buffered for the log file
+        drop(self.log_guard.lock().ok().and_then(|mut guard| guard.take()));
+        result
     }
 } 
\ No newline at end of file
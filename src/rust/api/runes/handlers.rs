@@ -1,34 +1,215 @@
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{http::StatusCode, web, HttpRequest, Responder};
+use futures::{stream, StreamExt};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::sync::Arc;
-use utoipa::ToSchema;
+use std::time::{SystemTime, UNIX_EPOCH};
+use utoipa::{IntoParams, ToSchema};
 
+use crate::api::{
+    encoding::ResponseFormat,
+    http_cache::{cached_response, CachePolicy, HttpCacheConfig},
+};
 use crate::services::{
-    node::connection::NodeConnection,
+    node::backend::NodeBackend,
     cache::RunesCache,
+    catalog::RuneCatalog,
 };
 use crate::types::{
-    error::RuneError,
-    rune::{RunesTransactionResponse, RuneTransfer},
+    error::{RuneError, RuneResult},
+    rune::{
+        parse_spaced_rune, AddressBalances, OutputRunes, RuneEntry, RuneInfo,
+        RunesTransactionResponse,
+    },
+    runestone::{rune_to_u128, RuneId},
 };
 
 pub struct RunesApiContext {
-    pub node: Arc<NodeConnection>,
+    pub node: Arc<dyn NodeBackend>,
     pub cache: Arc<RunesCache>,
+    pub catalog: Arc<RuneCatalog>,
+    pub http_cache: HttpCacheConfig,
 }
 
-#[derive(Debug, Deserialize, ToSchema)]
+/// Largest page `list_runes` serves
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// Most transaction ids accepted by one batch request
+pub const MAX_BATCH_SIZE: usize = 100;
+
+/// Batch lookups in flight at once
+const BATCH_CONCURRENCY: usize = 8;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct BatchTransactionRequest {
+    /// Up to `MAX_BATCH_SIZE` ids, duplicates are looked up once
     #[schema(example = "['tx1', 'tx2']")]
     pub transaction_ids: Vec<String>,
+    /// Resolve confirmations against the current node tip instead of
+    /// returning the count from when the transaction was cached
+    #[serde(default)]
     #[schema(default = false)]
     pub include_confirmations: bool,
 }
 
-#[derive(Debug, Serialize, ToSchema)]
+/// Why a single batch item failed, using the codes of `ErrorResponse`
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BatchItemError {
+    pub code: String,
+    pub message: String,
+}
+
+impl From<&RuneError> for BatchItemError {
+    fn from(error: &RuneError) -> Self {
+        BatchItemError {
+            code: error.error_code(),
+            message: error.to_string(),
+        }
+    }
+}
+
+/// Outcome of one id, either `transaction` or `error` is set
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BatchTransactionResult {
+    pub transaction_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<RunesTransactionResponse>,
+    /// Confirmations at the current tip, only with `include_confirmations`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirmations: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<BatchItemError>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct BatchTransactionResponse {
-    pub transactions: Vec<RunesTransactionResponse>,
-    pub failed_transactions: Vec<String>,
+    /// One result per distinct id, in request order
+    pub results: Vec<BatchTransactionResult>,
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RuneSort {
+    /// Etching order
+    #[default]
+    Etching,
+    Mints,
+    Burned,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+fn default_page() -> u32 {
+    1
+}
+
+fn default_page_size() -> u32 {
+    20
+}
+
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListRunesQuery {
+    /// Rune name or name prefix, spacers and case are ignored. At most 28 letters
+    pub q: Option<String>,
+    /// Only runes that can (or cannot) be minted in the next block
+    pub mintable: Option<bool>,
+    /// Only runes with (or without) a premine
+    pub premine: Option<bool>,
+    pub turbo: Option<bool>,
+    #[serde(default)]
+    pub sort: RuneSort,
+    #[serde(default)]
+    pub order: SortOrder,
+    /// 1-based page number
+    #[serde(default = "default_page")]
+    #[param(default = 1, minimum = 1)]
+    pub page: u32,
+    #[serde(default = "default_page_size")]
+    #[param(default = 20, minimum = 1, maximum = 100)]
+    pub page_size: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RuneListResponse {
+    pub items: Vec<RuneInfo>,
+    /// Number of runes matching the filters
+    pub total: usize,
+    pub page: u32,
+    pub page_size: u32,
+}
+
+impl ListRunesQuery {
+    fn name_prefix(&self) -> RuneResult<Option<String>> {
+        name_prefix(self.q.as_deref())
+    }
+
+    fn matches(&self, entry: &RuneEntry, prefix: Option<&str>, height: u64) -> bool {
+        prefix.is_none_or(|prefix| entry.name.starts_with(prefix))
+            && self.mintable.is_none_or(|m| entry.mintable_at(height + 1) == m)
+            && self.premine.is_none_or(|p| (entry.premine > 0) == p)
+            && self.turbo.is_none_or(|t| entry.turbo == t)
+    }
+}
+
+/// Rune search term without spacers, uppercased
+pub(crate) fn name_prefix(q: Option<&str>) -> RuneResult<Option<String>> {
+    let Some(q) = q.map(str::trim).filter(|q| !q.is_empty()) else {
+        return Ok(None);
+    };
+
+    let (name, _) = parse_spaced_rune(q)?;
+    let name = name.to_ascii_uppercase();
+    if !name.chars().all(|c| c.is_ascii_uppercase()) {
+        return Err(RuneError::InvalidRequest(format!("Invalid rune name: {}", q)));
+    }
+    Ok(Some(name))
+}
+
+/// Checks that `tx_id` is a 64 character hex transaction id
+pub fn validate_tx_id(tx_id: &str) -> RuneResult<()> {
+    if tx_id.len() == 64 && tx_id.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(())
+    } else {
+        Err(RuneError::InvalidTransaction(format!("Invalid transaction ID: {}", tx_id)))
+    }
+}
+
+/// Rough shape check for base58 and bech32 addresses, the node does the real validation
+pub fn validate_address(address: &str) -> RuneResult<()> {
+    if (26..=90).contains(&address.len()) && address.chars().all(|c| c.is_ascii_alphanumeric()) {
+        Ok(())
+    } else {
+        Err(RuneError::InvalidRequest(format!("Invalid address: {}", address)))
+    }
+}
+
+/// Normalizes a `block:tx` id or a spaced rune name into the key used by the
+/// cache and the node: the id itself or the name without spacers. Anything
+/// else, including names past `MAX_RUNE_LENGTH` letters, is `INVALID_RUNE`
+pub fn rune_key(rune: &str) -> RuneResult<String> {
+    if let Ok(id) = rune.parse::<RuneId>() {
+        return Ok(id.to_string());
+    }
+
+    let (name, _) = parse_spaced_rune(rune)?;
+    rune_to_u128(&name).map_err(|_| RuneError::InvalidRune(format!("Invalid rune name: {}", rune)))?;
+    Ok(name)
+}
+
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
 }
 
 /// Get transaction details by ID
@@ -51,26 +232,23 @@ pub struct BatchTransactionResponse {
     tag = "transactions"
 )]
 pub async fn get_transaction(
+    req: HttpRequest,
     tx_id: web::Path<String>,
     context: web::Data<RunesApiContext>,
+    format: ResponseFormat,
 ) -> impl Responder {
-    // Önce cache'i kontrol et
-    if let Some(cached_tx) = context.cache.get_transaction(&tx_id).await {
-        return HttpResponse::Ok().json(cached_tx.as_ref());
+    if let Err(e) = validate_tx_id(&tx_id) {
+        return format.error_response(&e);
     }
 
-    // Cache'de yoksa node'dan al
-    match context.node.get_transaction(&tx_id).await {
+    match fetch_transaction(&context, &tx_id).await {
         Ok(tx) => {
-            // Başarılı sonucu cache'e kaydet
-            if let Err(e) = context.cache.set_transaction(tx_id.to_string(), tx.clone()).await {
-                tracing::error!("Failed to cache transaction {}: {}", tx_id, e);
-            }
-            HttpResponse::Ok().json(tx)
+            let policy = context.http_cache.transaction_policy(&tx);
+            cached_response(&req, format, &tx, policy, &context.http_cache)
         }
         Err(e) => {
             tracing::error!("Failed to get transaction {}: {}", tx_id, e);
-            HttpResponse::InternalServerError().json(e.to_string())
+            format.error_response(&e)
         }
     }
 }
@@ -81,8 +259,8 @@ pub async fn get_transaction(
     path = "/api/v1/runes/transactions/batch",
     request_body = BatchTransactionRequest,
     responses(
-        (status = 200, description = "Transactions retrieved successfully", body = BatchTransactionResponse),
-        (status = 400, description = "Invalid request format", body = ErrorResponse),
+        (status = 200, description = "Per-transaction results", body = BatchTransactionResponse),
+        (status = 400, description = "Invalid request format, empty batch or more than 100 ids", body = ErrorResponse),
         (status = 429, description = "Too many requests", body = ErrorResponse),
         (status = 503, description = "Node connection error", body = ErrorResponse),
     ),
@@ -94,36 +272,113 @@ pub async fn get_transaction(
 pub async fn get_batch_transactions(
     request: web::Json<BatchTransactionRequest>,
     context: web::Data<RunesApiContext>,
+    format: ResponseFormat,
 ) -> impl Responder {
-    let mut response = BatchTransactionResponse {
-        transactions: Vec::new(),
-        failed_transactions: Vec::new(),
+    let mut seen = HashSet::new();
+    let tx_ids: Vec<&String> = request
+        .transaction_ids
+        .iter()
+        .filter(|tx_id| seen.insert(tx_id.as_str()))
+        .collect();
+
+    if tx_ids.is_empty() || tx_ids.len() > MAX_BATCH_SIZE {
+        let error = RuneError::InvalidRequest(format!(
+            "transaction_ids must contain between 1 and {} distinct ids, got {}",
+            MAX_BATCH_SIZE,
+            tx_ids.len()
+        ));
+        return format.error_response(&error);
+    }
+
+    let tip = if request.include_confirmations {
+        match context.node.get_block_height().await {
+            Ok(height) => Some(height),
+            Err(e) => {
+                tracing::error!("Failed to get block height for batch: {}", e);
+                return format.error_response(&e);
+            }
+        }
+    } else {
+        None
+    };
+
+    let results: Vec<BatchTransactionResult> = stream::iter(tx_ids)
+        .map(|tx_id| {
+            let context = context.clone();
+            async move {
+                let outcome = fetch_transaction(&context, tx_id).await;
+                batch_result(tx_id, outcome, tip)
+            }
+        })
+        .buffered(BATCH_CONCURRENCY)
+        .collect()
+        .await;
+
+    let failed = results.iter().filter(|result| result.error.is_some()).count();
+    let response = BatchTransactionResponse {
+        succeeded: results.len() - failed,
+        failed,
+        results,
     };
+    format.respond(StatusCode::OK, &response)
+}
 
-    for tx_id in &request.transaction_ids {
-        // Önce cache'i kontrol et
-        if let Some(cached_tx) = context.cache.get_transaction(tx_id).await {
-            response.transactions.push(cached_tx.as_ref().clone());
-            continue;
+/// Cache first, then the node, caching what the node returns.
+///
+/// A cached copy keeps the confirmations it had when it was cached, so only
+/// final transactions are served from the cache. Anything younger would be
+/// sent with a short max-age while its data is already stale.
+async fn fetch_transaction(
+    context: &RunesApiContext,
+    tx_id: &str,
+) -> RuneResult<RunesTransactionResponse> {
+    validate_tx_id(tx_id)?;
+
+    if let Some(cached_tx) = context.cache.get_transaction(tx_id).await {
+        if context.http_cache.transaction_policy(&cached_tx) == CachePolicy::Immutable {
+            return Ok(cached_tx.as_ref().clone());
         }
+    }
+
+    let tx = context.node.get_transaction(tx_id).await?;
+    if let Err(e) = context.cache.set_transaction(tx_id.to_string(), tx.clone()).await {
+        tracing::error!("Failed to cache transaction {}: {}", tx_id, e);
+    }
+    Ok(tx)
+}
 
-        // Cache'de yoksa node'dan al
-        match context.node.get_transaction(tx_id).await {
-            Ok(tx) => {
-                // Başarılı sonucu cache'e kaydet
-                if let Err(e) = context.cache.set_transaction(tx_id.to_string(), tx.clone()).await {
-                    tracing::error!("Failed to cache transaction {}: {}", tx_id, e);
-                }
-                response.transactions.push(tx);
+fn batch_result(
+    tx_id: &str,
+    outcome: RuneResult<RunesTransactionResponse>,
+    tip: Option<u64>,
+) -> BatchTransactionResult {
+    match outcome {
+        Ok(mut tx) => {
+            let confirmations = tip.map(|tip| match tx.block_height {
+                Some(height) if tip >= u64::from(height) => (tip - u64::from(height) + 1) as u32,
+                _ => 0,
+            });
+            if let Some(confirmations) = confirmations {
+                tx.confirmation_count = confirmations;
             }
-            Err(e) => {
-                tracing::error!("Failed to get transaction {}: {}", tx_id, e);
-                response.failed_transactions.push(tx_id.clone());
+
+            BatchTransactionResult {
+                transaction_id: tx_id.to_string(),
+                transaction: Some(tx),
+                confirmations,
+                error: None,
+            }
+        }
+        Err(e) => {
+            tracing::error!("Failed to get transaction {}: {}", tx_id, e);
+            BatchTransactionResult {
+                transaction_id: tx_id.to_string(),
+                transaction: None,
+                confirmations: None,
+                error: Some(BatchItemError::from(&e)),
             }
         }
     }
-
-    HttpResponse::Ok().json(response)
 }
 
 /// Get all Rune transfers for a specific address
@@ -145,12 +400,20 @@ pub async fn get_batch_transactions(
     tag = "transactions"
 )]
 pub async fn get_address_transfers(
+    req: HttpRequest,
     address: web::Path<String>,
     context: web::Data<RunesApiContext>,
+    format: ResponseFormat,
 ) -> impl Responder {
+    if let Err(e) = validate_address(&address) {
+        return format.error_response(&e);
+    }
+
+    let policy = context.http_cache.default_policy();
+
     // Önce cache'i kontrol et
     if let Some(cached_transfers) = context.cache.get_address_transfers(&address).await {
-        return HttpResponse::Ok().json(cached_transfers.as_ref());
+        return cached_response(&req, format, cached_transfers.as_ref(), policy, &context.http_cache);
     }
 
     // Cache'de yoksa node'dan al
@@ -160,11 +423,238 @@ pub async fn get_address_transfers(
             if let Err(e) = context.cache.set_address_transfers(address.to_string(), transfers.clone()).await {
                 tracing::error!("Failed to cache address transfers {}: {}", address, e);
             }
-            HttpResponse::Ok().json(transfers)
+            cached_response(&req, format, &transfers, policy, &context.http_cache)
         }
         Err(e) => {
             tracing::error!("Failed to get address transfers {}: {}", address, e);
-            HttpResponse::InternalServerError().json(e.to_string())
+            format.error_response(&e)
         }
     }
-} 
\ No newline at end of file
+}
+
+/// Get rune details by id or name
+#[utoipa::path(
+    get,
+    path = "/api/v1/runes/{rune}",
+    responses(
+        (status = 200, description = "Rune found successfully", body = RuneInfo),
+        (status = 400, description = "Invalid rune id or name (`INVALID_RUNE`)", body = ErrorResponse),
+        (status = 404, description = "Rune not found", body = ErrorResponse),
+        (status = 429, description = "Too many requests", body = ErrorResponse),
+        (status = 501, description = "Node has no rune index", body = ErrorResponse),
+        (status = 503, description = "Node connection error", body = ErrorResponse),
+    ),
+    params(
+        ("rune" = String, Path, description = "Rune id (`block:tx`) or name, with or without spacers", example = "UNCOMMON•GOODS")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "runes"
+)]
+pub async fn get_rune(
+    req: HttpRequest,
+    rune: web::Path<String>,
+    context: web::Data<RunesApiContext>,
+    format: ResponseFormat,
+) -> impl Responder {
+    let key = match rune_key(&rune) {
+        Ok(key) => key,
+        Err(e) => return format.error_response(&e),
+    };
+    let policy = context.http_cache.default_policy();
+
+    if let Some(cached_rune) = context.cache.get_rune(&key).await {
+        return cached_response(&req, format, cached_rune.as_ref(), policy, &context.http_cache);
+    }
+
+    let lookup = tokio::try_join!(context.node.get_rune(&key), context.node.get_block_height());
+    match lookup {
+        Ok((entry, height)) => {
+            let info = RuneInfo::from_entry(&entry, height, unix_now());
+            if let Err(e) = context.cache.set_rune(info.clone()).await {
+                tracing::error!("Failed to cache rune {}: {}", key, e);
+            }
+            cached_response(&req, format, &info, policy, &context.http_cache)
+        }
+        Err(e) => {
+            tracing::error!("Failed to get rune {}: {}", key, e);
+            format.error_response(&e)
+        }
+    }
+}
+
+/// List and search etched runes
+#[utoipa::path(
+    get,
+    path = "/api/v1/runes",
+    params(ListRunesQuery),
+    responses(
+        (status = 200, description = "Runes listed successfully", body = RuneListResponse),
+        (status = 400, description = "Invalid search or paging parameters", body = ErrorResponse),
+        (status = 429, description = "Too many requests", body = ErrorResponse),
+        (status = 503, description = "Rune catalog is still loading", body = ErrorResponse),
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "runes"
+)]
+pub async fn list_runes(
+    req: HttpRequest,
+    query: web::Query<ListRunesQuery>,
+    context: web::Data<RunesApiContext>,
+    format: ResponseFormat,
+) -> impl Responder {
+    if query.page == 0 || query.page_size == 0 || query.page_size > MAX_PAGE_SIZE {
+        let error = RuneError::InvalidRequest(format!(
+            "page must be at least 1 and page_size between 1 and {}",
+            MAX_PAGE_SIZE
+        ));
+        return format.error_response(&error);
+    }
+
+    let prefix = match query.name_prefix() {
+        Ok(prefix) => prefix,
+        Err(e) => return format.error_response(&e),
+    };
+
+    let snapshot = match context.catalog.snapshot().await {
+        Ok(snapshot) => snapshot,
+        Err(e) => return format.error_response(&e),
+    };
+
+    let mut matches: Vec<&RuneEntry> = snapshot
+        .entries
+        .iter()
+        .filter(|entry| query.matches(entry, prefix.as_deref(), snapshot.height))
+        .collect();
+
+    match query.sort {
+        RuneSort::Etching => matches.sort_by_key(|entry| entry.number),
+        RuneSort::Mints => matches.sort_by_key(|entry| entry.mints),
+        RuneSort::Burned => matches.sort_by_key(|entry| entry.burned),
+    }
+    if query.order == SortOrder::Desc {
+        matches.reverse();
+    }
+
+    let total = matches.len();
+    let offset = (query.page as usize - 1) * query.page_size as usize;
+    let items = matches
+        .into_iter()
+        .skip(offset)
+        .take(query.page_size as usize)
+        .map(|entry| RuneInfo::from_entry(entry, snapshot.height, snapshot.refreshed_at))
+        .collect();
+
+    let response = RuneListResponse {
+        items,
+        total,
+        page: query.page,
+        page_size: query.page_size,
+    };
+    cached_response(
+        &req,
+        format,
+        &response,
+        context.http_cache.default_policy(),
+        &context.http_cache,
+    )
+}
+
+/// Get the rune balances of an address
+#[utoipa::path(
+    get,
+    path = "/api/v1/runes/address/{address}/balances",
+    responses(
+        (status = 200, description = "Balances retrieved successfully", body = AddressBalances),
+        (status = 400, description = "Invalid address format", body = ErrorResponse),
+        (status = 429, description = "Too many requests", body = ErrorResponse),
+        (status = 501, description = "Node has no rune index", body = ErrorResponse),
+        (status = 503, description = "Node connection error", body = ErrorResponse),
+    ),
+    params(
+        ("address" = String, Path, description = "Bitcoin address to lookup")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "runes"
+)]
+pub async fn get_address_balances(
+    req: HttpRequest,
+    address: web::Path<String>,
+    context: web::Data<RunesApiContext>,
+    format: ResponseFormat,
+) -> impl Responder {
+    if let Err(e) = validate_address(&address) {
+        return format.error_response(&e);
+    }
+
+    match context.node.get_address_outputs(&address).await {
+        Ok(outputs) => cached_response(
+            &req,
+            format,
+            &AddressBalances::from_outputs(&address, &outputs),
+            context.http_cache.default_policy(),
+            &context.http_cache,
+        ),
+        Err(e) => {
+            tracing::error!("Failed to get address balances {}: {}", address, e);
+            format.error_response(&e)
+        }
+    }
+}
+
+/// Get the runes on a transaction output
+#[utoipa::path(
+    get,
+    path = "/api/v1/runes/output/{txid}:{vout}",
+    responses(
+        (status = 200, description = "Output found successfully", body = OutputRunes),
+        (status = 400, description = "Invalid outpoint", body = ErrorResponse),
+        (status = 404, description = "Output not found", body = ErrorResponse),
+        (status = 429, description = "Too many requests", body = ErrorResponse),
+        (status = 501, description = "Node has no rune index", body = ErrorResponse),
+        (status = 503, description = "Node connection error", body = ErrorResponse),
+    ),
+    params(
+        ("txid" = String, Path, description = "Transaction ID of the output"),
+        ("vout" = u32, Path, description = "Output index")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "runes"
+)]
+pub async fn get_output(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    context: web::Data<RunesApiContext>,
+    format: ResponseFormat,
+) -> impl Responder {
+    let (txid, vout) = path.into_inner();
+    if let Err(e) = validate_tx_id(&txid) {
+        return format.error_response(&e);
+    }
+    let Ok(vout) = vout.parse::<u32>() else {
+        let error = RuneError::InvalidRequest(format!("Invalid output index: {}", vout));
+        return format.error_response(&error);
+    };
+
+    let outpoint = format!("{}:{}", txid, vout);
+    match context.node.get_output(&outpoint).await {
+        Ok(output) => cached_response(
+            &req,
+            format,
+            &OutputRunes::from(&output),
+            context.http_cache.default_policy(),
+            &context.http_cache,
+        ),
+        Err(e) => {
+            tracing::error!("Failed to get output {}: {}", outpoint, e);
+            format.error_response(&e)
+        }
+    }
+}
@@ -1,11 +1,23 @@
-use actix_web::web;
-use super::handlers::{get_transaction, get_batch_transactions, get_address_transfers};
+use actix_web::{middleware::Compress, web};
+use crate::api::encoding::{json_config, path_config, query_config};
+use super::handlers::{
+    get_transaction, get_batch_transactions, get_address_transfers, get_address_balances,
+    get_output, get_rune, list_runes,
+};
 
 pub fn configure_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/api/v1/runes")
+            .wrap(Compress::default())
+            .app_data(json_config())
+            .app_data(query_config())
+            .app_data(path_config())
+            .route("", web::get().to(list_runes))
             .route("/transaction/{tx_id}", web::get().to(get_transaction))
             .route("/transactions/batch", web::post().to(get_batch_transactions))
             .route("/address/{address}/transfers", web::get().to(get_address_transfers))
+            .route("/address/{address}/balances", web::get().to(get_address_balances))
+            .route("/output/{txid}:{vout}", web::get().to(get_output))
+            .route("/{rune}", web::get().to(get_rune))
     );
 } 
\ No newline at end of file
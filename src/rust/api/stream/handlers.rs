@@ -0,0 +1,148 @@
+use actix_web::{web, web::Bytes, HttpRequest, HttpResponse, Responder, ResponseError};
+use futures::{future, stream, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use utoipa::IntoParams;
+
+use crate::services::events::bus::{ChainEvent, EventBus, EventFilter, EventSubscription};
+use crate::types::error::{RuneError, RuneResult};
+
+/// Comment line sent while no events flow, keeps proxies from closing the connection
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+pub struct StreamApiContext {
+    pub events: Arc<EventBus>,
+}
+
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StreamQuery {
+    /// Comma separated event types: block, transfer, etching, mint, confirmation, reorg
+    #[param(example = "transfer,mint")]
+    pub types: Option<String>,
+    /// Only events about this rune, by id or name
+    pub rune: Option<String>,
+    /// Only events touching this address
+    pub address: Option<String>,
+}
+
+impl StreamQuery {
+    fn filter(&self) -> RuneResult<EventFilter> {
+        let types = match self.types.as_deref().filter(|types| !types.is_empty()) {
+            Some(types) => Some(
+                types
+                    .split(',')
+                    .map(|event_type| event_type.trim().parse())
+                    .collect::<RuneResult<_>>()?,
+            ),
+            None => None,
+        };
+
+        Ok(EventFilter {
+            types,
+            rune: self.rune.clone(),
+            address: self.address.clone(),
+        })
+    }
+}
+
+fn last_event_id(req: &HttpRequest) -> RuneResult<Option<u64>> {
+    match req.headers().get("Last-Event-ID") {
+        Some(value) => value
+            .to_str()
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+            .map(Some)
+            .ok_or_else(|| RuneError::InvalidRequest("Invalid Last-Event-ID header".to_string())),
+        None => Ok(None),
+    }
+}
+
+fn format_event(event: &ChainEvent) -> Bytes {
+    let data = serde_json::to_string(event).unwrap_or_default();
+    Bytes::from(format!(
+        "id: {}\nevent: {}\ndata: {}\n\n",
+        event.id,
+        event.event_type().as_str(),
+        data
+    ))
+}
+
+/// Replayed events followed by live ones, interleaved with keep-alive comments.
+///
+/// Ends when the subscriber lags behind the bus, the client then reconnects
+/// with `Last-Event-ID` and catches up from the replay buffer.
+fn event_stream(
+    subscription: EventSubscription,
+    filter: EventFilter,
+) -> impl Stream<Item = Result<Bytes, actix_web::Error>> {
+    let replay = stream::iter(subscription.replay.into_iter().map(Some));
+
+    let keep_alive = tokio::time::interval_at(
+        tokio::time::Instant::now() + KEEP_ALIVE_INTERVAL,
+        KEEP_ALIVE_INTERVAL,
+    );
+    let live = stream::unfold(
+        (subscription.receiver, keep_alive),
+        |(mut receiver, mut keep_alive)| async move {
+            tokio::select! {
+                event = receiver.recv() => match event {
+                    Ok(event) => Some((Some(event), (receiver, keep_alive))),
+                    Err(_) => None,
+                },
+                _ = keep_alive.tick() => Some((None, (receiver, keep_alive))),
+            }
+        },
+    );
+
+    replay.chain(live).filter_map(move |event| {
+        future::ready(match event {
+            Some(event) if filter.matches(&event) => Some(Ok(format_event(&event))),
+            Some(_) => None,
+            None => Some(Ok(Bytes::from_static(b": keep-alive\n\n"))),
+        })
+    })
+}
+
+/// Stream chain and rune events as Server-Sent Events
+#[utoipa::path(
+    get,
+    path = "/api/v1/stream",
+    params(
+        StreamQuery,
+        ("Last-Event-ID" = Option<u64>, Header, description = "Resume after this event id from the replay buffer")
+    ),
+    responses(
+        (status = 200, description = "Event stream, one `ChainEvent` per `data` line", content_type = "text/event-stream", body = ChainEvent),
+        (status = 400, description = "Unknown event type or invalid Last-Event-ID", body = ErrorResponse),
+        (status = 429, description = "Too many requests", body = ErrorResponse),
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "stream"
+)]
+pub async fn stream_events(
+    req: HttpRequest,
+    query: web::Query<StreamQuery>,
+    context: web::Data<StreamApiContext>,
+) -> impl Responder {
+    let filter = match query.filter() {
+        Ok(filter) => filter,
+        Err(e) => return e.error_response(),
+    };
+    let last_event_id = match last_event_id(&req) {
+        Ok(last_event_id) => last_event_id,
+        Err(e) => return e.error_response(),
+    };
+
+    let subscription = context.events.subscribe(last_event_id);
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        // Stops nginx from buffering the stream
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(event_stream(subscription, filter))
+}
@@ -0,0 +1,6 @@
+use actix_web::web;
+use super::handlers::stream_events;
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/api/v1/stream", web::get().to(stream_events));
+}
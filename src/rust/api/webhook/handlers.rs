@@ -1,12 +1,13 @@
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{http::StatusCode, web, Responder};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use utoipa::ToSchema;
 
+use crate::api::encoding::ResponseFormat;
 use crate::services::webhook::manager::{WebhookManager, WebhookConfig, WebhookEventType};
 use crate::types::error::RuneError;
 
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct RegisterWebhookRequest {
     #[schema(example = "https://example.com/webhook")]
     pub url: String,
@@ -14,8 +15,10 @@ pub struct RegisterWebhookRequest {
     pub secret: Option<String>,
     #[schema(example = json!(["TransactionConfirmed", "RuneTransfer"]))]
     pub events: Vec<WebhookEventType>,
+    #[serde(default = "default_max_retries")]
     #[schema(default = 3)]
     pub max_retries: u32,
+    #[serde(default = "default_retry_delay")]
     #[schema(default = 1000)]
     pub retry_delay: u64,
 }
@@ -49,13 +52,14 @@ pub struct WebhookApiContext {
         (status = 429, description = "Too many requests", body = ErrorResponse),
     ),
     security(
-        ("api_key" = [])
+        ("api_key" = [], "request_signature" = [])
     ),
     tag = "webhooks"
 )]
 pub async fn register_webhook(
     request: web::Json<RegisterWebhookRequest>,
     context: web::Data<WebhookApiContext>,
+    format: ResponseFormat,
 ) -> impl Responder {
     let config = WebhookConfig {
         url: request.url.clone(),
@@ -66,13 +70,13 @@ pub async fn register_webhook(
     };
 
     match context.webhook_manager.register_webhook(config).await {
-        Ok(_) => HttpResponse::Ok().json(WebhookResponse {
+        Ok(_) => format.respond(StatusCode::OK, &WebhookResponse {
             success: true,
             message: Some("Webhook registered successfully".to_string()),
         }),
         Err(e) => {
             tracing::error!("Failed to register webhook: {}", e);
-            HttpResponse::BadRequest().json(WebhookResponse {
+            format.respond(StatusCode::BAD_REQUEST, &WebhookResponse {
                 success: false,
                 message: Some(e.to_string()),
             })
@@ -93,31 +97,30 @@ pub async fn register_webhook(
         ("url" = String, Path, description = "URL of the webhook to unregister")
     ),
     security(
-        ("api_key" = [])
+        ("api_key" = [], "request_signature" = [])
     ),
     tag = "webhooks"
 )]
 pub async fn unregister_webhook(
     url: web::Path<String>,
     context: web::Data<WebhookApiContext>,
+    format: ResponseFormat,
 ) -> impl Responder {
     match context.webhook_manager.unregister_webhook(&url).await {
-        Ok(_) => HttpResponse::Ok().json(WebhookResponse {
+        Ok(_) => format.respond(StatusCode::OK, &WebhookResponse {
             success: true,
             message: Some("Webhook unregistered successfully".to_string()),
         }),
         Err(e) => {
             tracing::error!("Failed to unregister webhook: {}", e);
-            match e {
-                RuneError::ConfigError(_) => HttpResponse::NotFound().json(WebhookResponse {
-                    success: false,
-                    message: Some(e.to_string()),
-                }),
-                _ => HttpResponse::InternalServerError().json(WebhookResponse {
-                    success: false,
-                    message: Some(e.to_string()),
-                }),
-            }
+            let status = match e {
+                RuneError::ConfigError(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            format.respond(status, &WebhookResponse {
+                success: false,
+                message: Some(e.to_string()),
+            })
         }
     }
 } 
\ No newline at end of file
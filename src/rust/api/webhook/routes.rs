@@ -1,9 +1,13 @@
-use actix_web::web;
+use actix_web::{middleware::Compress, web};
+use crate::api::encoding::{json_config, path_config};
 use super::handlers::{register_webhook, unregister_webhook};
 
 pub fn configure_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/api/v1/webhooks")
+            .wrap(Compress::default())
+            .app_data(json_config())
+            .app_data(path_config())
             .route("", web::post().to(register_webhook))
             .route("/{url}", web::delete().to(unregister_webhook))
     );
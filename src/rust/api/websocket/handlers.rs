@@ -0,0 +1,139 @@
+use actix_web::{web, HttpMessage, HttpRequest, Responder, ResponseError};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use utoipa::IntoParams;
+
+use crate::services::{
+    auth::{ApiKeyId, ApiKeyScope, ApiKeyStore},
+    events::bus::EventBus,
+};
+use crate::types::error::RuneResult;
+use super::session::WebSocketSession;
+
+/// Largest client message accepted, subscribe requests are a few hundred bytes
+const MAX_MESSAGE_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct WebSocketConfig {
+    /// Keys accepted from clients, which need the `events:read` scope.
+    /// Connections are open to everyone without a store.
+    pub api_keys: Option<Arc<ApiKeyStore>>,
+    pub max_subscriptions: usize,
+    /// How often the server pings the client
+    pub heartbeat_interval: Duration,
+    /// Connections without any frame from the client for this long are closed
+    pub client_timeout: Duration,
+    /// Time a connection opened without a key has to send an `auth` message
+    pub auth_timeout: Duration,
+    /// Clients not taking a message within this time are disconnected as slow consumers
+    pub send_timeout: Duration,
+}
+
+impl Default for WebSocketConfig {
+    fn default() -> Self {
+        Self {
+            api_keys: None,
+            max_subscriptions: 20,
+            heartbeat_interval: Duration::from_secs(10),
+            client_timeout: Duration::from_secs(30),
+            auth_timeout: Duration::from_secs(10),
+            send_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+impl WebSocketConfig {
+    pub fn requires_auth(&self) -> bool {
+        self.api_keys.is_some()
+    }
+
+    pub fn authenticate(&self, api_key: &str) -> RuneResult<()> {
+        match &self.api_keys {
+            Some(store) => store.authenticate(api_key, ApiKeyScope::EventsRead).map(drop),
+            None => Ok(()),
+        }
+    }
+}
+
+pub struct WebSocketApiContext {
+    pub events: Arc<EventBus>,
+    pub config: WebSocketConfig,
+}
+
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct WebSocketQuery {
+    /// API key for clients that cannot set headers, e.g. browsers
+    pub api_key: Option<String>,
+}
+
+/// Open a WebSocket for live block, address and rune transfer events
+///
+/// Clients send JSON text frames tagged by `type`: `auth` with an `api_key`,
+/// `subscribe` with a `Channel`, `unsubscribe` with a `subscription` id, and
+/// `ping`. The server answers with `authenticated`, `subscribed`,
+/// `unsubscribed`, `pong` or `error`, and pushes `event` messages carrying the
+/// `subscription` id and a `ChainEvent`. A numeric `id` on a client message
+/// is copied onto its reply. Without an API key on the upgrade request the
+/// first message has to be `auth`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/ws",
+    params(
+        WebSocketQuery,
+    ),
+    responses(
+        (status = 101, description = "Switched to the WebSocket protocol"),
+        (status = 400, description = "Not a WebSocket upgrade request"),
+        (status = 401, description = "Invalid API key", body = ErrorResponse),
+        (status = 403, description = "API key lacks the events:read scope", body = ErrorResponse),
+        (status = 429, description = "Too many requests", body = ErrorResponse),
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "websocket"
+)]
+pub async fn connect(
+    req: HttpRequest,
+    body: web::Payload,
+    query: web::Query<WebSocketQuery>,
+    context: web::Data<WebSocketApiContext>,
+) -> impl Responder {
+    let api_key = req
+        .headers()
+        .get("X-API-Key")
+        .and_then(|value| value.to_str().ok())
+        .or(query.api_key.as_deref());
+
+    // ApiKeyAuth has already checked the key when it runs in front of the handler
+    let verified = req.extensions().contains::<ApiKeyId>();
+    let authenticated = match api_key {
+        _ if verified => true,
+        Some(api_key) => match context.config.authenticate(api_key) {
+            Ok(()) => true,
+            Err(e) => return e.error_response(),
+        },
+        None => !context.config.requires_auth(),
+    };
+
+    let (response, session, messages) = match actix_ws::handle(&req, body) {
+        Ok(handshake) => handshake,
+        Err(e) => return e.error_response(),
+    };
+    let messages = messages
+        .max_frame_size(MAX_MESSAGE_SIZE)
+        .aggregate_continuations()
+        .max_continuation_size(MAX_MESSAGE_SIZE);
+
+    let session = WebSocketSession::new(
+        session,
+        context.events.subscribe(None).receiver,
+        context.config.clone(),
+        authenticated,
+    );
+    actix_web::rt::spawn(session.run(messages));
+
+    response
+}
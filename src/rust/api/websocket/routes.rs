@@ -0,0 +1,6 @@
+use actix_web::web;
+use super::handlers::connect;
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/api/v1/ws", web::get().to(connect));
+}
@@ -0,0 +1,260 @@
+use actix_ws::{AggregatedMessage, AggregatedMessageStream, CloseCode, CloseReason, Session};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::time::Instant;
+
+use crate::services::events::{
+    bus::{ChainEvent, EventFilter},
+    protocol::{Channel, ClientMessage, Envelope, ServerMessage},
+};
+use crate::types::error::RuneError;
+use super::handlers::WebSocketConfig;
+
+/// Why the session loop stopped
+enum Disconnect {
+    /// The connection is gone, nothing left to send
+    Gone,
+    /// Close the connection with this reason
+    Close(CloseReason),
+}
+
+impl Disconnect {
+    fn close(code: CloseCode, description: &str) -> Self {
+        Disconnect::Close(CloseReason {
+            code,
+            description: Some(description.to_string()),
+        })
+    }
+
+    fn slow_consumer() -> Self {
+        Disconnect::close(CloseCode::Policy, "Slow consumer")
+    }
+}
+
+/// What woke the session loop up
+enum Wakeup {
+    Message(Option<Result<AggregatedMessage, actix_ws::ProtocolError>>),
+    Event(Result<Arc<ChainEvent>, RecvError>),
+    Heartbeat,
+    AuthTimeout,
+}
+
+/// One client connection, forwarding bus events to its subscriptions.
+///
+/// The session shares a single bus receiver between all of its subscriptions.
+/// A client that falls so far behind that the receiver lags, or does not take
+/// a message within `send_timeout`, is disconnected instead of buffered.
+pub struct WebSocketSession {
+    session: Session,
+    events: broadcast::Receiver<Arc<ChainEvent>>,
+    config: WebSocketConfig,
+    authenticated: bool,
+    subscriptions: BTreeMap<u32, EventFilter>,
+    next_subscription: u32,
+    last_seen: Instant,
+}
+
+impl WebSocketSession {
+    pub fn new(
+        session: Session,
+        events: broadcast::Receiver<Arc<ChainEvent>>,
+        config: WebSocketConfig,
+        authenticated: bool,
+    ) -> Self {
+        Self {
+            session,
+            events,
+            config,
+            authenticated,
+            subscriptions: BTreeMap::new(),
+            next_subscription: 1,
+            last_seen: Instant::now(),
+        }
+    }
+
+    pub async fn run(mut self, mut messages: AggregatedMessageStream) {
+        let mut heartbeat = tokio::time::interval_at(
+            Instant::now() + self.config.heartbeat_interval,
+            self.config.heartbeat_interval,
+        );
+        let auth_deadline = tokio::time::sleep(self.config.auth_timeout);
+        tokio::pin!(auth_deadline);
+
+        let disconnect = loop {
+            let wakeup = tokio::select! {
+                message = messages.recv() => Wakeup::Message(message),
+                event = self.events.recv() => Wakeup::Event(event),
+                _ = heartbeat.tick() => Wakeup::Heartbeat,
+                _ = &mut auth_deadline, if !self.authenticated => Wakeup::AuthTimeout,
+            };
+
+            let result = match wakeup {
+                Wakeup::Message(Some(Ok(message))) => {
+                    self.last_seen = Instant::now();
+                    self.handle_message(message).await
+                }
+                Wakeup::Message(Some(Err(e))) => {
+                    Err(Disconnect::close(CloseCode::Protocol, &e.to_string()))
+                }
+                Wakeup::Message(None) => Err(Disconnect::Gone),
+                Wakeup::Event(Ok(event)) => self.dispatch(&event).await,
+                Wakeup::Event(Err(RecvError::Lagged(skipped))) => {
+                    tracing::warn!("Closing WebSocket of slow consumer, {} events behind", skipped);
+                    Err(Disconnect::slow_consumer())
+                }
+                Wakeup::Event(Err(RecvError::Closed)) => {
+                    Err(Disconnect::close(CloseCode::Away, "Server shutting down"))
+                }
+                Wakeup::Heartbeat => self.heartbeat().await,
+                Wakeup::AuthTimeout => {
+                    Err(Disconnect::close(CloseCode::Policy, "Authentication timeout"))
+                }
+            };
+
+            if let Err(disconnect) = result {
+                break disconnect;
+            }
+        };
+
+        if let Disconnect::Close(reason) = disconnect {
+            let _ = self.session.close(Some(reason)).await;
+        }
+    }
+
+    async fn handle_message(&mut self, message: AggregatedMessage) -> Result<(), Disconnect> {
+        match message {
+            AggregatedMessage::Text(text) => match serde_json::from_str::<Envelope<ClientMessage>>(&text) {
+                Ok(request) => self.handle_client_message(request.id, request.message).await,
+                Err(e) => {
+                    // Still answer with the id when only the rest is malformed
+                    let id = serde_json::from_str::<serde_json::Value>(&text)
+                        .ok()
+                        .and_then(|value| value.get("id")?.as_u64());
+                    let error = RuneError::InvalidRequest(format!("Invalid message: {}", e));
+                    self.reply(id, ServerMessage::from(&error)).await
+                }
+            },
+            AggregatedMessage::Binary(_) => {
+                let error = RuneError::InvalidRequest("Binary messages are not supported".to_string());
+                self.reply(None, ServerMessage::from(&error)).await
+            }
+            AggregatedMessage::Ping(bytes) => {
+                self.session.pong(&bytes).await.map_err(|_| Disconnect::Gone)
+            }
+            AggregatedMessage::Pong(_) => Ok(()),
+            AggregatedMessage::Close(reason) => Err(match reason {
+                Some(reason) => Disconnect::Close(reason),
+                None => Disconnect::close(CloseCode::Normal, "Closed by client"),
+            }),
+        }
+    }
+
+    async fn handle_client_message(&mut self, id: Option<u64>, message: ClientMessage) -> Result<(), Disconnect> {
+        match message {
+            ClientMessage::Auth { api_key } => {
+                let result = match self.authenticated {
+                    true => Ok(()),
+                    false => self.config.authenticate(&api_key),
+                };
+                if let Err(error) = result {
+                    self.reply(id, ServerMessage::from(&error)).await?;
+                    return Err(Disconnect::close(CloseCode::Policy, "Authentication failed"));
+                }
+
+                self.authenticated = true;
+                self.reply(id, ServerMessage::Authenticated).await
+            }
+            ClientMessage::Subscribe { .. }
+                if self.authenticated
+                    && self.subscriptions.len() >= self.config.max_subscriptions =>
+            {
+                let error = ServerMessage::Error {
+                    code: "SUBSCRIPTION_LIMIT_EXCEEDED".to_string(),
+                    message: format!(
+                        "At most {} subscriptions per connection",
+                        self.config.max_subscriptions
+                    ),
+                };
+                self.reply(id, error).await
+            }
+            ClientMessage::Subscribe { channel } => match self.subscribe(&channel) {
+                Ok(subscription) => {
+                    self.reply(id, ServerMessage::Subscribed { subscription, channel }).await
+                }
+                Err(error) => self.reply(id, ServerMessage::from(&error)).await,
+            },
+            ClientMessage::Unsubscribe { subscription } => {
+                let reply = match self.subscriptions.remove(&subscription) {
+                    Some(_) => ServerMessage::Unsubscribed { subscription },
+                    None => ServerMessage::from(&RuneError::NotFound(format!(
+                        "Subscription {} not found",
+                        subscription
+                    ))),
+                };
+                self.reply(id, reply).await
+            }
+            ClientMessage::Ping => self.reply(id, ServerMessage::Pong).await,
+        }
+    }
+
+    fn subscribe(&mut self, channel: &Channel) -> Result<u32, RuneError> {
+        if !self.authenticated {
+            return Err(RuneError::Unauthorized("Authenticate before subscribing".to_string()));
+        }
+
+        if let Channel::Address { address } = channel {
+            if address.trim().is_empty() {
+                return Err(RuneError::InvalidRequest("Address must not be empty".to_string()));
+            }
+        }
+
+        let subscription = self.next_subscription;
+        self.next_subscription += 1;
+        self.subscriptions.insert(subscription, channel.filter());
+        Ok(subscription)
+    }
+
+    /// Sends the event once for every subscription it matches
+    async fn dispatch(&mut self, event: &Arc<ChainEvent>) -> Result<(), Disconnect> {
+        let matching: Vec<u32> = self
+            .subscriptions
+            .iter()
+            .filter(|(_, filter)| filter.matches(event))
+            .map(|(subscription, _)| *subscription)
+            .collect();
+
+        for subscription in matching {
+            self.send(&ServerMessage::Event {
+                subscription,
+                event: event.as_ref().clone(),
+            })
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn heartbeat(&mut self) -> Result<(), Disconnect> {
+        if self.last_seen.elapsed() > self.config.client_timeout {
+            return Err(Disconnect::close(CloseCode::Policy, "Heartbeat timeout"));
+        }
+        self.session.ping(b"").await.map_err(|_| Disconnect::Gone)
+    }
+
+    /// Answers the request sent with `id`
+    async fn reply(&mut self, id: Option<u64>, message: ServerMessage) -> Result<(), Disconnect> {
+        self.send(&Envelope { id, message }).await
+    }
+
+    async fn send<T: Serialize>(&mut self, message: &T) -> Result<(), Disconnect> {
+        let text = serde_json::to_string(message)
+            .map_err(|e| Disconnect::close(CloseCode::Error, &e.to_string()))?;
+
+        match tokio::time::timeout(self.config.send_timeout, self.session.text(text)).await {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(_)) => Err(Disconnect::Gone),
+            Err(_) => Err(Disconnect::slow_consumer()),
+        }
+    }
+}
@@ -1,6 +1,38 @@
+use futures::{SinkExt, Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::RwLock;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, Semaphore};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{Error as WsError, Message as WsMessage},
+    MaybeTlsStream, WebSocketStream,
+};
+
+use services::events::{
+    bus::ChainEvent,
+    protocol::{Channel, ClientMessage, Envelope, ServerMessage},
+};
+
+pub mod services;
+pub mod types;
+
+/// REST API server, enabled with the `server` feature
+#[cfg(feature = "server")]
+pub mod api;
+
+#[cfg(any(test, feature = "test-support"))]
+pub mod testing;
+
+#[cfg(feature = "server")]
+pub use api::ApiServer;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RunesTransactionResponse {
@@ -29,6 +61,7 @@ pub enum TransferType {
     Mint,
     Transfer,
     Burn,
+    Etch,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -44,16 +77,188 @@ pub enum NetworkType {
     Testnet,
 }
 
+/// Credentials used when talking to a node over HTTP
+#[derive(Debug, Clone, Default)]
+pub enum RpcAuth {
+    #[default]
+    None,
+    Basic {
+        username: String,
+        password: String,
+    },
+    /// Path to a bitcoind `.cookie` file, re-read whenever the node rejects it
+    CookieFile(PathBuf),
+}
+
+/// Connection pool settings for the shared HTTP client
+#[derive(Debug, Clone)]
+pub struct HttpClientConfig {
+    pub connect_timeout: Duration,
+    pub pool_idle_timeout: Option<Duration>,
+    pub pool_max_idle_per_host: usize,
+    pub tcp_keepalive: Option<Duration>,
+    /// Requests in flight per client, JSON-RPC and REST backends alike
+    pub max_concurrent_requests: Option<usize>,
+}
+
+impl Default for HttpClientConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(10),
+            pool_idle_timeout: Some(Duration::from_secs(90)),
+            pool_max_idle_per_host: 32,
+            tcp_keepalive: Some(Duration::from_secs(60)),
+            max_concurrent_requests: None,
+        }
+    }
+}
+
+impl HttpClientConfig {
+    /// Builds a pooled HTTP client that can be shared between calls
+    ///
+    /// # Errors
+    /// Returns an error if the TLS backend cannot be initialised
+    pub fn build_client(&self, timeout: Duration) -> Result<reqwest::Client, Error> {
+        reqwest::Client::builder()
+            .timeout(timeout)
+            .connect_timeout(self.connect_timeout)
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .tcp_keepalive(self.tcp_keepalive)
+            .build()
+            .map_err(|e| Error::NetworkError(format!("Failed to create HTTP client: {e}")))
+    }
+}
+
+/// Resolves [`RpcAuth`] into request credentials, caching the cookie file contents
+#[derive(Debug, Default)]
+pub struct RpcCredentials {
+    auth: RpcAuth,
+    cookie: RwLock<Option<(String, String)>>,
+}
+
+impl RpcCredentials {
+    #[must_use]
+    pub fn new(auth: RpcAuth) -> Self {
+        Self {
+            auth,
+            cookie: RwLock::new(None),
+        }
+    }
+
+    /// Adds the configured credentials to a request
+    ///
+    /// # Errors
+    /// Returns an error if the cookie file cannot be read or is malformed
+    pub fn apply(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::RequestBuilder, Error> {
+        match &self.auth {
+            RpcAuth::None => Ok(request),
+            RpcAuth::Basic { username, password } => {
+                Ok(request.basic_auth(username, Some(password)))
+            }
+            RpcAuth::CookieFile(path) => {
+                let cached = self
+                    .cookie
+                    .read()
+                    .map_err(|_| Error::NodeConnectionError("Cookie lock poisoned".to_string()))?
+                    .clone();
+                let (username, password) = match cached {
+                    Some(credentials) => credentials,
+                    None => {
+                        let credentials = read_cookie_file(path)?;
+                        *self.cookie.write().map_err(|_| {
+                            Error::NodeConnectionError("Cookie lock poisoned".to_string())
+                        })? = Some(credentials.clone());
+                        credentials
+                    }
+                };
+                Ok(request.basic_auth(username, Some(password)))
+            }
+        }
+    }
+
+    /// Drops the cached cookie so the next request re-reads it from disk.
+    /// Returns `true` if retrying with fresh credentials can help.
+    pub fn invalidate(&self) -> bool {
+        match &self.auth {
+            RpcAuth::CookieFile(_) => {
+                if let Ok(mut cookie) = self.cookie.write() {
+                    *cookie = None;
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+fn read_cookie_file(path: &Path) -> Result<(String, String), Error> {
+    let contents = std::fs::read_to_string(path).map_err(|e| {
+        Error::NodeConnectionError(format!(
+            "Failed to read cookie file {}: {e}",
+            path.display()
+        ))
+    })?;
+
+    contents
+        .trim()
+        .split_once(':')
+        .map(|(username, password)| (username.to_string(), password.to_string()))
+        .ok_or_else(|| {
+            Error::NodeConnectionError(format!("Malformed cookie file {}", path.display()))
+        })
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RpcClientConfig {
+    pub url: String,
+    pub timeout: Duration,
+    pub auth: RpcAuth,
+    pub http: HttpClientConfig,
+}
+
 #[derive(Debug)]
 pub struct RpcClient {
     url: String,
-    timeout: u64,
+    client: reqwest::Client,
+    credentials: RpcCredentials,
+    permits: Option<Semaphore>,
 }
 
 impl RpcClient {
+    /// # Panics
+    /// Panics if the HTTP client cannot be created
     #[must_use]
     pub fn new(url: String, timeout: u64) -> Self {
-        Self { url, timeout }
+        Self::with_config(RpcClientConfig {
+            url,
+            timeout: Duration::from_secs(timeout),
+            ..RpcClientConfig::default()
+        })
+        .expect("Failed to create HTTP client")
+    }
+
+    /// Creates a client with authentication and connection pool settings
+    ///
+    /// # Errors
+    /// Returns an error if the HTTP client cannot be created
+    pub fn with_config(config: RpcClientConfig) -> Result<Self, Error> {
+        let client = config.http.build_client(config.timeout)?;
+        Ok(Self::with_http_client(config, client))
+    }
+
+    /// Creates a client that shares an existing connection pool
+    #[must_use]
+    pub fn with_http_client(config: RpcClientConfig, client: reqwest::Client) -> Self {
+        Self {
+            url: config.url,
+            client,
+            credentials: RpcCredentials::new(config.auth),
+            permits: config.http.max_concurrent_requests.map(Semaphore::new),
+        }
     }
 
     /// Makes an RPC call to the node
@@ -68,7 +273,31 @@ impl RpcClient {
         method: &str,
         params: Vec<String>,
     ) -> Result<T, Error> {
-        let client = reqwest::Client::new();
+        let params = params.into_iter().map(serde_json::Value::String).collect();
+        self.call_with_params(method, params).await
+    }
+
+    /// Makes an RPC call with arbitrary JSON parameters, e.g. numbers or booleans
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - The network request fails
+    /// - The response cannot be parsed
+    /// - The node returns an error
+    pub async fn call_with_params<T: serde::de::DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Vec<serde_json::Value>,
+    ) -> Result<T, Error> {
+        let _permit = match &self.permits {
+            Some(permits) => Some(
+                permits
+                    .acquire()
+                    .await
+                    .map_err(|e| Error::NetworkError(e.to_string()))?,
+            ),
+            None => None,
+        };
 
         let request_body = serde_json::json!({
             "jsonrpc": "2.0",
@@ -77,28 +306,31 @@ impl RpcClient {
             "id": 1
         });
 
-        let response = client
-            .post(&self.url)
-            .timeout(std::time::Duration::from_secs(self.timeout))
-            .json(&request_body)
-            .send()
-            .await
-            .map_err(|e| Error::NetworkError(e.to_string()))?;
+        let mut response = self.send(&request_body).await?;
 
-        if !response.status().is_success() {
-            return Err(Error::NetworkError(format!(
-                "HTTP error: {}",
-                response.status()
-            )));
+        // bitcoind rewrites its cookie on restart, so re-read it once before giving up
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED && self.credentials.invalidate() {
+            response = self.send(&request_body).await?;
         }
 
-        let response_body: serde_json::Value = response
-            .json()
-            .await
-            .map_err(|e| Error::ParseError(e.to_string()))?;
+        // bitcoind reports RPC errors with a non-2xx status and a JSON body
+        let status = response.status();
+        let response_body: serde_json::Value = match response.json().await {
+            Ok(body) => body,
+            Err(_) if !status.is_success() => {
+                return Err(Error::NetworkError(format!("HTTP error: {status}")));
+            }
+            Err(e) => return Err(Error::ParseError(e.to_string())),
+        };
 
-        if let Some(error) = response_body.get("error") {
-            return Err(Error::InvalidTransaction(error.to_string()));
+        if let Some(error) = response_body.get("error").filter(|e| !e.is_null()) {
+            let error: JsonRpcErrorObject = serde_json::from_value(error.clone())
+                .map_err(|_| Error::ParseError(format!("Malformed JSON-RPC error: {error}")))?;
+            return Err(Error::from_rpc_error(error.code, error.message));
+        }
+
+        if !status.is_success() {
+            return Err(Error::NetworkError(format!("HTTP error: {status}")));
         }
 
         let result = response_body
@@ -108,6 +340,15 @@ impl RpcClient {
         serde_json::from_value(result.clone()).map_err(|e| Error::ParseError(e.to_string()))
     }
 
+    async fn send(&self, body: &serde_json::Value) -> Result<reqwest::Response, Error> {
+        self.credentials
+            .apply(self.client.post(&self.url))?
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| Error::NetworkError(e.to_string()))
+    }
+
     /// Checks if the RPC node is healthy and responding
     ///
     /// # Errors
@@ -147,13 +388,59 @@ impl RunesAPI {
     }
 }
 
-#[derive(Debug)]
+/// First reconnect delay when `reconnect_interval` is not set, in milliseconds
+const DEFAULT_RECONNECT_INTERVAL_MS: u64 = 1000;
+const DEFAULT_MAX_RECONNECT_ATTEMPTS: u32 = 5;
+/// Upper bound of the doubling delay between reconnect attempts
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+/// Events buffered for a caller that is not polling the client
+const WEBSOCKET_EVENT_BUFFER: usize = 256;
+
+#[derive(Debug, Clone)]
 pub struct WebSocketConfig {
+    /// Server endpoint, e.g. `ws://localhost:8080/api/v1/ws?api_key=...`
     pub url: String,
+    /// Key sent in an `auth` message after every (re)connect, before the
+    /// subscriptions. Leave unset when the key is part of `url`.
+    pub api_key: Option<String>,
+    /// Delay before the first reconnect attempt in milliseconds, doubled for
+    /// every further attempt
     pub reconnect_interval: Option<u64>,
+    /// Attempts after a failed or dropped connection before giving up
     pub max_reconnect_attempts: Option<u32>,
 }
 
+impl WebSocketConfig {
+    fn reconnect_delay(&self, attempt: u32) -> Duration {
+        let interval = self
+            .reconnect_interval
+            .unwrap_or(DEFAULT_RECONNECT_INTERVAL_MS);
+        let factor = 1u64 << attempt.saturating_sub(1).min(16);
+        Duration::from_millis(interval.saturating_mul(factor)).min(MAX_RECONNECT_DELAY)
+    }
+
+    fn max_attempts(&self) -> u32 {
+        self.max_reconnect_attempts
+            .unwrap_or(DEFAULT_MAX_RECONNECT_ATTEMPTS)
+    }
+}
+
+/// Something received on a [`WebSocketClient`]
+#[derive(Debug, Clone)]
+pub enum WebSocketEvent {
+    /// An event of one of the subscribed channels
+    Chain { channel: Channel, event: ChainEvent },
+    /// The server refused a subscription, it is no longer active. A refused
+    /// `api_key` ends the client with an `Error::WebSocketError` instead.
+    Rejected {
+        channel: Channel,
+        code: String,
+        message: String,
+    },
+    /// The connection dropped and was re-established, subscriptions were re-sent
+    Reconnected,
+}
+
 #[derive(Debug)]
 pub struct WebSocketService {
     config: WebSocketConfig,
@@ -167,19 +454,344 @@ impl WebSocketService {
 
     /// Connects to the WebSocket endpoint
     ///
+    /// The returned client keeps the connection alive in a background task,
+    /// reconnecting with backoff and re-sending its subscriptions when the
+    /// connection drops. Once the reconnect attempts are exhausted it yields
+    /// a final `Error::WebSocketError` and ends.
+    ///
     /// # Errors
     /// Returns an error if:
     /// - The connection cannot be established
     /// - The maximum reconnection attempts are exceeded
-    pub fn connect(&self) -> Result<(), Error> {
-        // Config will be used for WebSocket connection implementation
-        // Connection will be established using config values
-        todo!("Implement WebSocket connection using config: url={}, reconnect_interval={:?}, max_attempts={:?}", 
-            self.config.url,
-            self.config.reconnect_interval,
-            self.config.max_reconnect_attempts
-        )
+    pub async fn connect(&self) -> Result<WebSocketClient, Error> {
+        let socket = open_socket(&self.config, true).await?;
+
+        let (commands, command_receiver) = mpsc::unbounded_channel();
+        let (event_sender, events) = mpsc::channel(WEBSOCKET_EVENT_BUFFER);
+
+        let connection = WebSocketConnection {
+            config: self.config.clone(),
+            commands: command_receiver,
+            events: event_sender,
+            channels: Vec::new(),
+            subscriptions: HashMap::new(),
+            pending: BTreeMap::new(),
+            next_id: 0,
+        };
+        tokio::spawn(connection.run(socket));
+
+        Ok(WebSocketClient { commands, events })
+    }
+}
+
+/// Handle of a live WebSocket connection, a `Stream` of its events.
+///
+/// Dropping the client closes the connection.
+#[derive(Debug)]
+pub struct WebSocketClient {
+    commands: mpsc::UnboundedSender<WebSocketCommand>,
+    events: mpsc::Receiver<Result<WebSocketEvent, Error>>,
+}
+
+impl WebSocketClient {
+    /// Subscribes to a channel, kept across reconnects until unsubscribed
+    ///
+    /// # Errors
+    /// Returns an error if the connection was given up
+    pub fn subscribe(&self, channel: Channel) -> Result<(), Error> {
+        self.command(WebSocketCommand::Subscribe(channel))
+    }
+
+    /// # Errors
+    /// Returns an error if the connection was given up
+    pub fn unsubscribe(&self, channel: Channel) -> Result<(), Error> {
+        self.command(WebSocketCommand::Unsubscribe(channel))
+    }
+
+    fn command(&self, command: WebSocketCommand) -> Result<(), Error> {
+        self.commands
+            .send(command)
+            .map_err(|_| Error::WebSocketError("Connection closed".to_string()))
+    }
+}
+
+impl Stream for WebSocketClient {
+    type Item = Result<WebSocketEvent, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.events.poll_recv(cx)
+    }
+}
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+#[derive(Debug)]
+enum WebSocketCommand {
+    Subscribe(Channel),
+    Unsubscribe(Channel),
+}
+
+/// Client message awaiting its reply
+enum PendingReply {
+    Auth,
+    Subscribe(Channel),
+    Unsubscribe(u32),
+}
+
+/// Opens the socket, retrying with backoff. Rejected handshakes such as an
+/// invalid API key are not retried.
+async fn open_socket(config: &WebSocketConfig, immediate: bool) -> Result<Socket, Error> {
+    let first_attempt = u32::from(!immediate);
+    let mut attempt = first_attempt;
+
+    loop {
+        if attempt > 0 {
+            tokio::time::sleep(config.reconnect_delay(attempt)).await;
+        }
+
+        let error = match connect_async(config.url.as_str()).await {
+            Ok((socket, _)) => return Ok(socket),
+            Err(WsError::Http(response)) => {
+                return Err(Error::WebSocketError(format!(
+                    "Handshake with {} rejected with status {}",
+                    config.url,
+                    response.status()
+                )));
+            }
+            Err(e) => e,
+        };
+
+        if attempt >= config.max_attempts() {
+            return Err(Error::WebSocketError(format!(
+                "Failed to connect to {} after {} attempts: {}",
+                config.url,
+                attempt - first_attempt + 1,
+                error
+            )));
+        }
+        tracing::debug!("WebSocket connection attempt {} failed: {}", attempt, error);
+        attempt += 1;
+    }
+}
+
+struct WebSocketConnection {
+    config: WebSocketConfig,
+    commands: mpsc::UnboundedReceiver<WebSocketCommand>,
+    events: mpsc::Sender<Result<WebSocketEvent, Error>>,
+    /// Channels the caller subscribed to, re-sent after reconnecting
+    channels: Vec<Channel>,
+    /// Server side subscription ids of the current connection
+    subscriptions: HashMap<u32, Channel>,
+    /// Requests awaiting their reply, by the `id` they were sent with
+    pending: BTreeMap<u64, PendingReply>,
+    next_id: u64,
+}
+
+impl WebSocketConnection {
+    async fn run(mut self, mut socket: Socket) {
+        loop {
+            match self.serve(&mut socket).await {
+                Ok(()) => {
+                    let _ = socket.close(None).await;
+                    return;
+                }
+                Err(e) => tracing::warn!("WebSocket connection lost: {}", e),
+            }
+
+            self.subscriptions.clear();
+            self.pending.clear();
+            if self.events.is_closed() {
+                return;
+            }
+
+            socket = match open_socket(&self.config, false).await {
+                Ok(socket) => socket,
+                Err(e) => {
+                    let _ = self.events.send(Err(e)).await;
+                    return;
+                }
+            };
+            let _ = self.events.send(Ok(WebSocketEvent::Reconnected)).await;
+        }
+    }
+
+    /// Runs one connection, returns `Ok` once the client was dropped or the
+    /// server refused the API key
+    async fn serve(&mut self, socket: &mut Socket) -> Result<(), Error> {
+        if let Some(api_key) = self.config.api_key.clone() {
+            self.send_request(socket, PendingReply::Auth, ClientMessage::Auth { api_key })
+                .await?;
+        }
+        for channel in self.channels.clone() {
+            self.send_subscribe(socket, channel).await?;
+        }
+
+        loop {
+            tokio::select! {
+                command = self.commands.recv() => match command {
+                    Some(command) => self.handle_command(socket, command).await?,
+                    None => return Ok(()),
+                },
+                message = socket.next() => match message {
+                    Some(Ok(WsMessage::Text(text))) => {
+                        if self.handle_message(socket, &text).await?.is_break() {
+                            return Ok(());
+                        }
+                    }
+                    Some(Ok(WsMessage::Close(frame))) => {
+                        return Err(Error::WebSocketError(match frame {
+                            Some(frame) => format!("Closed by server: {} {}", frame.code, frame.reason),
+                            None => "Closed by server".to_string(),
+                        }));
+                    }
+                    // Pings are answered by the socket itself
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => return Err(Error::WebSocketError(e.to_string())),
+                    None => return Err(Error::WebSocketError("Connection closed".to_string())),
+                },
+            }
+        }
+    }
+
+    async fn handle_command(&mut self, socket: &mut Socket, command: WebSocketCommand) -> Result<(), Error> {
+        match command {
+            WebSocketCommand::Subscribe(channel) => {
+                if !self.channels.contains(&channel) {
+                    self.channels.push(channel.clone());
+                    // A subscribe still awaiting its reply is kept once acknowledged
+                    if !self.is_pending(&channel) {
+                        self.send_subscribe(socket, channel).await?;
+                    }
+                }
+            }
+            WebSocketCommand::Unsubscribe(channel) => {
+                self.channels.retain(|active| active != &channel);
+
+                let subscription = self
+                    .subscriptions
+                    .iter()
+                    .find(|(_, active)| **active == channel)
+                    .map(|(subscription, _)| *subscription);
+                // Still pending subscriptions are dropped once acknowledged
+                if let Some(subscription) = subscription {
+                    self.send_unsubscribe(socket, subscription).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Breaks once the connection has to be given up
+    async fn handle_message(&mut self, socket: &mut Socket, text: &str) -> Result<ControlFlow<()>, Error> {
+        let Envelope { id, message } = match serde_json::from_str::<Envelope<ServerMessage>>(text) {
+            Ok(message) => message,
+            Err(e) => {
+                tracing::debug!("Ignoring unknown WebSocket message: {}", e);
+                return Ok(ControlFlow::Continue(()));
+            }
+        };
+
+        match message {
+            ServerMessage::Subscribed { subscription, channel } => {
+                self.settle(id, |reply| matches!(reply, PendingReply::Subscribe(pending) if *pending == channel));
+                let duplicate = self.subscriptions.values().any(|active| *active == channel);
+                if self.channels.contains(&channel) && !duplicate {
+                    self.subscriptions.insert(subscription, channel);
+                } else {
+                    self.send_unsubscribe(socket, subscription).await?;
+                }
+            }
+            ServerMessage::Unsubscribed { subscription } => {
+                self.settle(id, |reply| matches!(reply, PendingReply::Unsubscribe(pending) if *pending == subscription));
+            }
+            ServerMessage::Event { subscription, event } => {
+                if let Some(channel) = self.subscriptions.get(&subscription) {
+                    let channel = channel.clone();
+                    let _ = self
+                        .events
+                        .send(Ok(WebSocketEvent::Chain { channel, event }))
+                        .await;
+                }
+            }
+            // Errors without the id of a request answer none of them
+            ServerMessage::Error { code, message } => match id.and_then(|id| self.pending.remove(&id)) {
+                Some(PendingReply::Subscribe(channel)) => {
+                    self.channels.retain(|active| active != &channel);
+                    let _ = self
+                        .events
+                        .send(Ok(WebSocketEvent::Rejected { channel, code, message }))
+                        .await;
+                }
+                // Reconnecting would only be refused again
+                Some(PendingReply::Auth) => {
+                    let _ = self
+                        .events
+                        .send(Err(Error::WebSocketError(format!(
+                            "Authentication rejected: {} {}",
+                            code, message
+                        ))))
+                        .await;
+                    return Ok(ControlFlow::Break(()));
+                }
+                _ => tracing::warn!("WebSocket server error {}: {}", code, message),
+            },
+            ServerMessage::Authenticated => {
+                self.settle(id, |reply| matches!(reply, PendingReply::Auth));
+            }
+            ServerMessage::Pong => {}
+        }
+        Ok(ControlFlow::Continue(()))
+    }
+
+    fn is_pending(&self, channel: &Channel) -> bool {
+        self.pending
+            .values()
+            .any(|reply| matches!(reply, PendingReply::Subscribe(pending) if pending == channel))
+    }
+
+    /// Drops the request a reply answers, found by `id` or, when the server
+    /// sent none back, by what the reply acknowledges
+    fn settle(&mut self, id: Option<u64>, answers: impl Fn(&PendingReply) -> bool) {
+        let id = id.or_else(|| {
+            self.pending
+                .iter()
+                .find(|(_, reply)| answers(reply))
+                .map(|(id, _)| *id)
+        });
+        if let Some(id) = id {
+            self.pending.remove(&id);
+        }
     }
+
+    async fn send_request(
+        &mut self,
+        socket: &mut Socket,
+        reply: PendingReply,
+        message: ClientMessage,
+    ) -> Result<(), Error> {
+        self.next_id += 1;
+        self.pending.insert(self.next_id, reply);
+        send_message(socket, &Envelope { id: Some(self.next_id), message }).await
+    }
+
+    async fn send_subscribe(&mut self, socket: &mut Socket, channel: Channel) -> Result<(), Error> {
+        let reply = PendingReply::Subscribe(channel.clone());
+        self.send_request(socket, reply, ClientMessage::Subscribe { channel }).await
+    }
+
+    async fn send_unsubscribe(&mut self, socket: &mut Socket, subscription: u32) -> Result<(), Error> {
+        self.subscriptions.remove(&subscription);
+        let reply = PendingReply::Unsubscribe(subscription);
+        self.send_request(socket, reply, ClientMessage::Unsubscribe { subscription }).await
+    }
+}
+
+async fn send_message(socket: &mut Socket, message: &Envelope<ClientMessage>) -> Result<(), Error> {
+    let text = serde_json::to_string(message).map_err(|e| Error::ParseError(e.to_string()))?;
+    socket
+        .send(WsMessage::Text(text))
+        .await
+        .map_err(|e| Error::WebSocketError(e.to_string()))
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -198,11 +810,32 @@ pub enum TransactionType {
     Burn,
 }
 
+/// bitcoind `RPC_INVALID_ADDRESS_OR_KEY`, returned for unknown transactions and blocks
+pub const RPC_INVALID_ADDRESS_OR_KEY: i64 = -5;
+/// bitcoind `RPC_INVALID_PARAMETER`
+pub const RPC_INVALID_PARAMETER: i64 = -8;
+/// bitcoind `RPC_VERIFY_ERROR`, general error while validating a transaction
+pub const RPC_VERIFY_ERROR: i64 = -25;
+/// bitcoind `RPC_VERIFY_REJECTED`, transaction rejected by network rules
+pub const RPC_VERIFY_REJECTED: i64 = -26;
+/// bitcoind `RPC_IN_WARMUP`, the node is still loading its block index
+pub const RPC_IN_WARMUP: i64 = -28;
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcErrorObject {
+    code: i64,
+    message: String,
+}
+
 #[derive(Debug)]
 pub enum Error {
     InvalidTransaction(String),
     NetworkError(String),
-    JsonRpcError(String, String),
+    JsonRpcError(i64, String),
+    NotFound(String),
+    InvalidParameter(String),
+    NodeWarmingUp(String),
+    TransactionRejected(String),
     DatabaseError(String),
     NodeConnectionError(String),
     ParseError(String),
@@ -215,6 +848,10 @@ impl std::fmt::Display for Error {
             Error::InvalidTransaction(msg) => write!(f, "Invalid transaction: {msg}"),
             Error::NetworkError(msg) => write!(f, "Network error: {msg}"),
             Error::JsonRpcError(code, msg) => write!(f, "JSON-RPC error {code}: {msg}"),
+            Error::NotFound(msg) => write!(f, "Not found: {msg}"),
+            Error::InvalidParameter(msg) => write!(f, "Invalid parameter: {msg}"),
+            Error::NodeWarmingUp(msg) => write!(f, "Node is warming up: {msg}"),
+            Error::TransactionRejected(msg) => write!(f, "Transaction rejected: {msg}"),
             Error::DatabaseError(msg) => write!(f, "Database error: {msg}"),
             Error::NodeConnectionError(msg) => write!(f, "Node connection error: {msg}"),
             Error::ParseError(msg) => write!(f, "Parse error: {msg}"),
@@ -225,10 +862,24 @@ impl std::fmt::Display for Error {
 
 impl std::error::Error for Error {}
 
+impl Error {
+    /// Maps a JSON-RPC error object returned by the node to a typed error
+    #[must_use]
+    pub fn from_rpc_error(code: i64, message: String) -> Self {
+        match code {
+            RPC_INVALID_ADDRESS_OR_KEY => Error::NotFound(message),
+            RPC_INVALID_PARAMETER => Error::InvalidParameter(message),
+            RPC_IN_WARMUP => Error::NodeWarmingUp(message),
+            RPC_VERIFY_ERROR | RPC_VERIFY_REJECTED => Error::TransactionRejected(message),
+            _ => Error::JsonRpcError(code, message),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use wiremock::matchers::{method, path};
+    use wiremock::matchers::{basic_auth, method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
     #[tokio::test]
@@ -254,6 +905,145 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_rpc_client_basic_auth() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(basic_auth("rpcuser", "rpcpassword"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "result": { "blocks": 100 },
+                "id": 1
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let client = RpcClient::with_config(RpcClientConfig {
+            url: mock_server.uri(),
+            timeout: Duration::from_secs(5),
+            auth: RpcAuth::Basic {
+                username: "rpcuser".to_string(),
+                password: "rpcpassword".to_string(),
+            },
+            ..RpcClientConfig::default()
+        })
+        .unwrap();
+
+        assert!(client.health_check().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_rpc_client_rereads_cookie_after_restart() {
+        let mock_server = MockServer::start().await;
+        let cookie_path = std::env::temp_dir().join(format!(".cookie-{}", std::process::id()));
+        std::fs::write(&cookie_path, "__cookie__:first").unwrap();
+
+        let ok = ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "jsonrpc": "2.0",
+            "result": { "blocks": 100 },
+            "id": 1
+        }));
+
+        Mock::given(method("POST"))
+            .and(basic_auth("__cookie__", "first"))
+            .respond_with(ok.clone())
+            .mount(&mock_server)
+            .await;
+
+        let client = RpcClient::with_config(RpcClientConfig {
+            url: mock_server.uri(),
+            timeout: Duration::from_secs(5),
+            auth: RpcAuth::CookieFile(cookie_path.clone()),
+            ..RpcClientConfig::default()
+        })
+        .unwrap();
+        assert!(client.health_check().await.is_ok());
+
+        // Node restarts with a fresh cookie
+        mock_server.reset().await;
+        std::fs::write(&cookie_path, "__cookie__:second").unwrap();
+        Mock::given(method("POST"))
+            .and(basic_auth("__cookie__", "second"))
+            .respond_with(ok)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&mock_server)
+            .await;
+
+        let result = client.health_check().await;
+        std::fs::remove_file(&cookie_path).unwrap();
+        assert!(result.is_ok());
+    }
+
+    async fn rpc_error_response(status: u16, code: i64, message: &str) -> Error {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(status).set_body_json(serde_json::json!({
+                    "result": null,
+                    "error": { "code": code, "message": message },
+                    "id": 1
+                })),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let client = RpcClient::new(mock_server.uri(), 5);
+        client
+            .call::<serde_json::Value>("getrawtransaction", vec!["tx".to_string()])
+            .await
+            .unwrap_err()
+    }
+
+    #[tokio::test]
+    async fn test_rpc_error_codes() {
+        assert!(matches!(
+            rpc_error_response(500, -5, "No such mempool or blockchain transaction").await,
+            Error::NotFound(_)
+        ));
+        assert!(matches!(
+            rpc_error_response(500, -8, "parameter 1 must be hexadecimal").await,
+            Error::InvalidParameter(_)
+        ));
+        assert!(matches!(
+            rpc_error_response(500, -28, "Loading block index...").await,
+            Error::NodeWarmingUp(_)
+        ));
+        assert!(matches!(
+            rpc_error_response(500, -26, "min relay fee not met").await,
+            Error::TransactionRejected(_)
+        ));
+        assert!(matches!(
+            rpc_error_response(500, -25, "bad-txns-inputs-missingorspent").await,
+            Error::TransactionRejected(_)
+        ));
+        assert!(matches!(
+            rpc_error_response(404, -32601, "Method not found").await,
+            Error::JsonRpcError(-32601, _)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rpc_null_error_is_success() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "result": { "blocks": 100 },
+                "error": null,
+                "id": 1
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let client = RpcClient::new(mock_server.uri(), 5);
+        assert!(client.health_check().await.is_ok());
+    }
+
     #[tokio::test]
     async fn test_runes_api_get_transaction() {
         let mock_server = MockServer::start().await;
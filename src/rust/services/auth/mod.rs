@@ -0,0 +1,321 @@
+pub mod signing;
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+use crate::types::error::{RuneError, RuneResult};
+
+/// Prefix of generated keys, makes them recognisable in configs and secret scanners
+const KEY_PREFIX: &str = "rsk_";
+
+/// Prefix of generated request signing secrets
+const SIGNING_SECRET_PREFIX: &str = "rss_";
+
+/// What a key is allowed to access
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ApiKeyScope {
+    /// Rune, transaction, address and block lookups over REST and GraphQL
+    #[serde(rename = "runes:read")]
+    RunesRead,
+    /// The event stream and WebSocket subscriptions
+    #[serde(rename = "events:read")]
+    EventsRead,
+    /// Registering and removing webhooks
+    #[serde(rename = "webhooks:write")]
+    WebhooksWrite,
+    /// Everything, including operator endpoints
+    #[serde(rename = "admin")]
+    Admin,
+}
+
+impl fmt::Display for ApiKeyScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ApiKeyScope::RunesRead => "runes:read",
+            ApiKeyScope::EventsRead => "events:read",
+            ApiKeyScope::WebhooksWrite => "webhooks:write",
+            ApiKeyScope::Admin => "admin",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Id of the key a request was authenticated with, stored in the request extensions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiKeyId(pub String);
+
+/// A key as seen by operators, the key itself is never kept
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiKey {
+    pub id: String,
+    pub name: String,
+    pub scopes: HashSet<ApiKeyScope>,
+    pub created_at: u64,
+    pub expires_at: Option<u64>,
+    pub enabled: bool,
+    pub last_used_at: Option<u64>,
+    /// Whether the key can sign requests to privileged endpoints
+    pub has_signing_secret: bool,
+}
+
+impl ApiKey {
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    pub fn has_scope(&self, scope: ApiKeyScope) -> bool {
+        self.scopes.contains(&scope) || self.scopes.contains(&ApiKeyScope::Admin)
+    }
+}
+
+struct StoredKey {
+    key: ApiKey,
+    hash: String,
+    /// HMAC secret for signed requests. Unlike the key it has to be kept as is
+    /// to verify signatures.
+    signing_secret: Option<String>,
+    /// Unix seconds of the last successful use, 0 if never used
+    last_used_at: AtomicU64,
+}
+
+impl StoredKey {
+    fn snapshot(&self) -> ApiKey {
+        let last_used_at = self.last_used_at.load(Ordering::Relaxed);
+        ApiKey {
+            last_used_at: (last_used_at > 0).then_some(last_used_at),
+            has_signing_secret: self.signing_secret.is_some(),
+            ..self.key.clone()
+        }
+    }
+}
+
+/// API keys with their scopes, expiry and usage.
+///
+/// Only SHA-256 hashes of the keys are stored. Keys are random and long, so a
+/// plain hash is enough to make a leaked store useless for authenticating.
+#[derive(Default)]
+pub struct ApiKeyStore {
+    keys: DashMap<String, StoredKey>,
+    /// Key hash to key id
+    ids: DashMap<String, String>,
+}
+
+impl fmt::Debug for ApiKeyStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApiKeyStore")
+            .field("keys", &self.keys.len())
+            .finish()
+    }
+}
+
+fn hash_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+impl ApiKeyStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Generates a new key. The returned key is shown once, only its hash is kept.
+    pub fn create_key(
+        &self,
+        name: &str,
+        scopes: impl IntoIterator<Item = ApiKeyScope>,
+        ttl: Option<Duration>,
+    ) -> (ApiKey, String) {
+        let key = format!(
+            "{}{}{}",
+            KEY_PREFIX,
+            Uuid::new_v4().simple(),
+            Uuid::new_v4().simple()
+        );
+        let expires_at = ttl.map(|ttl| unix_now() + ttl.as_secs());
+        let info = self.insert(Uuid::new_v4().to_string(), name, &key, scopes, expires_at);
+        (info, key)
+    }
+
+    /// Adds a key issued elsewhere, e.g. read from the configuration
+    pub fn import_key(
+        &self,
+        id: &str,
+        name: &str,
+        key: &str,
+        scopes: impl IntoIterator<Item = ApiKeyScope>,
+        expires_at: Option<u64>,
+    ) -> RuneResult<ApiKey> {
+        if key.trim().is_empty() {
+            return Err(RuneError::ConfigError("API key must not be empty".to_string()));
+        }
+        if self.keys.contains_key(id) {
+            return Err(RuneError::ConfigError(format!("API key {} already exists", id)));
+        }
+        if self.ids.contains_key(&hash_key(key)) {
+            return Err(RuneError::ConfigError(format!(
+                "API key {} duplicates an existing key",
+                id
+            )));
+        }
+        Ok(self.insert(id.to_string(), name, key, scopes, expires_at))
+    }
+
+    fn insert(
+        &self,
+        id: String,
+        name: &str,
+        key: &str,
+        scopes: impl IntoIterator<Item = ApiKeyScope>,
+        expires_at: Option<u64>,
+    ) -> ApiKey {
+        let hash = hash_key(key);
+        let info = ApiKey {
+            id: id.clone(),
+            name: name.to_string(),
+            scopes: scopes.into_iter().collect(),
+            created_at: unix_now(),
+            expires_at,
+            enabled: true,
+            last_used_at: None,
+            has_signing_secret: false,
+        };
+        self.ids.insert(hash.clone(), id.clone());
+        self.keys.insert(
+            id,
+            StoredKey {
+                key: info.clone(),
+                hash,
+                signing_secret: None,
+                last_used_at: AtomicU64::new(0),
+            },
+        );
+        info
+    }
+
+    /// Checks `key` and that it grants `scope`, recording the use on success.
+    ///
+    /// Unknown, disabled and expired keys are `Unauthorized`, a valid key
+    /// without the scope is `Forbidden`.
+    pub fn authenticate(&self, key: &str, scope: ApiKeyScope) -> RuneResult<ApiKey> {
+        let invalid = || RuneError::Unauthorized("Invalid API key".to_string());
+
+        let id = self.ids.get(&hash_key(key)).ok_or_else(invalid)?.clone();
+        let stored = self.keys.get(&id).ok_or_else(invalid)?;
+
+        let now = unix_now();
+        if !stored.key.enabled {
+            return Err(RuneError::Unauthorized("API key is disabled".to_string()));
+        }
+        if stored.key.is_expired(now) {
+            return Err(RuneError::Unauthorized("API key has expired".to_string()));
+        }
+        if !stored.key.has_scope(scope) {
+            return Err(RuneError::Forbidden(format!(
+                "API key lacks the {} scope",
+                scope
+            )));
+        }
+
+        stored.last_used_at.store(now, Ordering::Relaxed);
+        Ok(stored.snapshot())
+    }
+
+    /// Whether `key` is known, enabled and not expired, regardless of scopes
+    pub fn is_key_valid(&self, key: &str) -> bool {
+        let Some(id) = self.ids.get(&hash_key(key)) else {
+            return false;
+        };
+        self.keys
+            .get(id.value())
+            .is_some_and(|stored| stored.key.enabled && !stored.key.is_expired(unix_now()))
+    }
+
+    pub fn get_key(&self, id: &str) -> Option<ApiKey> {
+        self.keys.get(id).map(|stored| stored.snapshot())
+    }
+
+    pub fn list_keys(&self) -> Vec<ApiKey> {
+        let mut keys: Vec<_> = self.keys.iter().map(|stored| stored.snapshot()).collect();
+        keys.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.id.cmp(&b.id)));
+        keys
+    }
+
+    /// Enables or disables a key without forgetting it
+    pub fn set_enabled(&self, id: &str, enabled: bool) -> RuneResult<()> {
+        let mut stored = self
+            .keys
+            .get_mut(id)
+            .ok_or_else(|| RuneError::NotFound(format!("API key {} not found", id)))?;
+        stored.key.enabled = enabled;
+        Ok(())
+    }
+
+    /// Generates a signing secret for the key, replacing any previous one.
+    /// Like a new key it is shown once.
+    pub fn issue_signing_secret(&self, id: &str) -> RuneResult<String> {
+        let secret = format!(
+            "{}{}{}",
+            SIGNING_SECRET_PREFIX,
+            Uuid::new_v4().simple(),
+            Uuid::new_v4().simple()
+        );
+        self.set_signing_secret(id, &secret)?;
+        Ok(secret)
+    }
+
+    /// Sets a signing secret issued elsewhere, e.g. read from the configuration
+    pub fn set_signing_secret(&self, id: &str, secret: &str) -> RuneResult<()> {
+        if secret.trim().is_empty() {
+            return Err(RuneError::ConfigError("Signing secret must not be empty".to_string()));
+        }
+        let mut stored = self
+            .keys
+            .get_mut(id)
+            .ok_or_else(|| RuneError::NotFound(format!("API key {} not found", id)))?;
+        stored.signing_secret = Some(secret.to_string());
+        Ok(())
+    }
+
+    pub(crate) fn signing_secret(&self, id: &str) -> Option<String> {
+        self.keys.get(id).and_then(|stored| stored.signing_secret.clone())
+    }
+
+    pub fn remove_key(&self, id: &str) -> RuneResult<ApiKey> {
+        let (_, stored) = self
+            .keys
+            .remove(id)
+            .ok_or_else(|| RuneError::NotFound(format!("API key {} not found", id)))?;
+        self.ids.remove(&stored.hash);
+        Ok(stored.snapshot())
+    }
+
+    /// Drops expired keys, returns how many were removed
+    pub fn cleanup_expired_keys(&self) -> usize {
+        let now = unix_now();
+        let expired: Vec<String> = self
+            .keys
+            .iter()
+            .filter(|stored| stored.key.is_expired(now))
+            .map(|stored| stored.key.id.clone())
+            .collect();
+
+        for id in &expired {
+            if self.remove_key(id).is_ok() {
+                tracing::debug!("Removed expired API key {}", id);
+            }
+        }
+        expired.len()
+    }
+}
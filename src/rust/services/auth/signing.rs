@@ -0,0 +1,169 @@
+use dashmap::DashMap;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::types::error::{RuneError, RuneResult};
+use super::ApiKeyStore;
+
+pub const SIGNATURE_HEADER: &str = "X-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Signature-Timestamp";
+pub const NONCE_HEADER: &str = "X-Signature-Nonce";
+
+/// Nonces shorter than this are too easy to reuse by accident
+const MIN_NONCE_LEN: usize = 8;
+const MAX_NONCE_LEN: usize = 128;
+
+#[derive(Debug, Clone)]
+pub struct SigningConfig {
+    /// How far a request timestamp may be from the server clock, either way
+    pub max_clock_skew: Duration,
+    /// Nonces remembered at most, requests beyond that are refused until old
+    /// nonces leave the skew window
+    pub max_nonces: usize,
+}
+
+impl Default for SigningConfig {
+    fn default() -> Self {
+        Self {
+            max_clock_skew: Duration::from_secs(300),
+            max_nonces: 100_000,
+        }
+    }
+}
+
+/// The parts of a request covered by its signature
+pub struct SignedRequest<'a> {
+    pub method: &'a str,
+    /// Path including the query string, if any
+    pub path: &'a str,
+    pub timestamp: u64,
+    pub nonce: &'a str,
+    pub body: &'a [u8],
+}
+
+impl SignedRequest<'_> {
+    /// `METHOD\npath\ntimestamp\nnonce\nhex(sha256(body))`
+    pub fn canonical_string(&self) -> String {
+        format!(
+            "{}\n{}\n{}\n{}\n{}",
+            self.method.to_ascii_uppercase(),
+            self.path,
+            self.timestamp,
+            self.nonce,
+            hex::encode(Sha256::digest(self.body))
+        )
+    }
+
+    fn mac(&self, secret: &str) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .expect("HMAC can take key of any size");
+        mac.update(self.canonical_string().as_bytes());
+        mac
+    }
+
+    /// Hex encoded HMAC-SHA256 of the canonical string, the `X-Signature` value
+    pub fn sign(&self, secret: &str) -> String {
+        hex::encode(self.mac(secret).finalize().into_bytes())
+    }
+
+    fn verify(&self, secret: &str, signature: &str) -> bool {
+        match hex::decode(signature) {
+            Ok(signature) => self.mac(secret).verify_slice(&signature).is_ok(),
+            Err(_) => false,
+        }
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Verifies signed requests against the signing secrets of an `ApiKeyStore`.
+///
+/// A nonce is remembered until its timestamp leaves the skew window, after
+/// which the timestamp check alone rejects a replay.
+pub struct RequestVerifier {
+    store: Arc<ApiKeyStore>,
+    config: SigningConfig,
+    /// `key id:nonce` to the unix second it can be forgotten
+    nonces: DashMap<String, u64>,
+    last_prune: AtomicU64,
+}
+
+impl RequestVerifier {
+    pub fn new(store: Arc<ApiKeyStore>, config: SigningConfig) -> Self {
+        Self {
+            store,
+            config,
+            nonces: DashMap::new(),
+            last_prune: AtomicU64::new(0),
+        }
+    }
+
+    /// Checks a request signed with the secret of key `key_id`.
+    ///
+    /// Bad signatures, stale timestamps and reused nonces are `Unauthorized`,
+    /// a key without a signing secret is `Forbidden`.
+    pub fn verify(&self, key_id: &str, request: &SignedRequest<'_>, signature: &str) -> RuneResult<()> {
+        let secret = self.store.signing_secret(key_id).ok_or_else(|| {
+            RuneError::Forbidden("API key is not allowed to sign requests".to_string())
+        })?;
+
+        let now = unix_now();
+        let skew = self.config.max_clock_skew.as_secs();
+        if request.timestamp.abs_diff(now) > skew {
+            return Err(RuneError::Unauthorized(
+                "Request timestamp is outside the allowed clock skew".to_string(),
+            ));
+        }
+        if !(MIN_NONCE_LEN..=MAX_NONCE_LEN).contains(&request.nonce.len()) {
+            return Err(RuneError::Unauthorized(format!(
+                "Request nonce must be {} to {} characters",
+                MIN_NONCE_LEN, MAX_NONCE_LEN
+            )));
+        }
+        if !request.verify(&secret, signature) {
+            return Err(RuneError::Unauthorized("Invalid request signature".to_string()));
+        }
+
+        // Only requests with a valid signature use up their nonce
+        self.remember_nonce(key_id, request.nonce, request.timestamp + skew, now)
+    }
+
+    fn remember_nonce(&self, key_id: &str, nonce: &str, forget_at: u64, now: u64) -> RuneResult<()> {
+        if self.nonces.len() >= self.config.max_nonces {
+            self.prune(now);
+            if self.nonces.len() >= self.config.max_nonces {
+                return Err(RuneError::RateLimitExceeded);
+            }
+        } else if self.last_prune.swap(now, Ordering::Relaxed) < now {
+            // At most once a second, expired nonces are dropped
+            self.prune(now);
+        }
+
+        match self.nonces.entry(format!("{}:{}", key_id, nonce)) {
+            dashmap::mapref::entry::Entry::Occupied(_) => Err(RuneError::Unauthorized(
+                "Request nonce has already been used".to_string(),
+            )),
+            dashmap::mapref::entry::Entry::Vacant(entry) => {
+                entry.insert(forget_at);
+                Ok(())
+            }
+        }
+    }
+
+    fn prune(&self, now: u64) {
+        self.nonces.retain(|_, forget_at| *forget_at >= now);
+    }
+
+    /// Nonces currently remembered
+    pub fn nonce_count(&self) -> usize {
+        self.nonces.len()
+    }
+}
@@ -1,20 +1,29 @@
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
-use cached::{Cached, TimedCache, SizedCache};
+use cached::{Cached, TimedSizedCache};
 use tokio::sync::RwLock;
-use serde::{Serialize, de::DeserializeOwned};
+use serde::Serialize;
 
+#[cfg(feature = "metrics")]
+use crate::services::metrics::{CACHE_HITS_TOTAL, CACHE_MISSES_TOTAL, CACHE_SIZE};
 use crate::types::{
     error::RuneError,
-    rune::{RunesTransactionResponse, RuneTransfer},
+    rune::{RuneInfo, RunesTransactionResponse, RuneTransfer},
 };
 
+const TRANSACTION_CACHE: &str = "transaction";
+const ADDRESS_CACHE: &str = "address";
+const RUNE_CACHE: &str = "rune";
+
 #[derive(Debug, Clone)]
 pub struct CacheConfig {
     pub transaction_cache_size: usize,
     pub transaction_ttl: Duration,
     pub address_cache_size: usize,
     pub address_ttl: Duration,
+    pub rune_cache_size: usize,
+    pub rune_ttl: Duration,
 }
 
 impl Default for CacheConfig {
@@ -24,32 +33,52 @@ impl Default for CacheConfig {
             transaction_ttl: Duration::from_secs(300), // 5 minutes
             address_cache_size: 5_000,
             address_ttl: Duration::from_secs(60),      // 1 minute
+            rune_cache_size: 5_000,
+            rune_ttl: Duration::from_secs(30),         // mint counts move every block
         }
     }
 }
 
 pub struct RunesCache {
-    transaction_cache: Arc<RwLock<TimedCache<String, Arc<RunesTransactionResponse>>>>,
-    address_cache: Arc<RwLock<TimedCache<String, Arc<Vec<RuneTransfer>>>>>,
+    transaction_cache: Arc<RwLock<TimedSizedCache<String, Arc<RunesTransactionResponse>>>>,
+    address_cache: Arc<RwLock<TimedSizedCache<String, Arc<Vec<RuneTransfer>>>>>,
+    rune_cache: Arc<RwLock<TimedSizedCache<String, Arc<RuneInfo>>>>,
     metrics: Arc<CacheMetrics>,
 }
 
+/// Counters behind `RunesCache::get_metrics`, also exported to Prometheus
+#[derive(Debug, Default)]
 pub struct CacheMetrics {
-    hits: metrics::Counter,
-    misses: metrics::Counter,
-    evictions: metrics::Counter,
+    pub hits: AtomicU64,
+    pub misses: AtomicU64,
+    pub evictions: AtomicU64,
+}
+
+impl CacheMetrics {
+    fn record_lookup(&self, cache_type: &str, hit: bool) {
+        if hit {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+        publish_lookup(cache_type, hit);
+    }
 }
 
 impl RunesCache {
     pub fn new(config: CacheConfig, metrics: Arc<CacheMetrics>) -> Self {
         Self {
-            transaction_cache: Arc::new(RwLock::new(TimedCache::with_lifespan_and_capacity(
-                config.transaction_ttl.as_secs() as u64,
+            transaction_cache: Arc::new(RwLock::new(TimedSizedCache::with_size_and_lifespan(
                 config.transaction_cache_size,
+                config.transaction_ttl.as_secs(),
             ))),
-            address_cache: Arc::new(RwLock::new(TimedCache::with_lifespan_and_capacity(
-                config.address_ttl.as_secs() as u64,
+            address_cache: Arc::new(RwLock::new(TimedSizedCache::with_size_and_lifespan(
                 config.address_cache_size,
+                config.address_ttl.as_secs(),
+            ))),
+            rune_cache: Arc::new(RwLock::new(TimedSizedCache::with_size_and_lifespan(
+                config.rune_cache_size,
+                config.rune_ttl.as_secs(),
             ))),
             metrics,
         }
@@ -59,14 +88,10 @@ impl RunesCache {
         &self,
         tx_id: &str,
     ) -> Option<Arc<RunesTransactionResponse>> {
-        let cache = self.transaction_cache.read().await;
+        // Lookups refresh the LRU order and drop expired entries, so they need the write lock
+        let mut cache = self.transaction_cache.write().await;
         let result = cache.cache_get(tx_id).cloned();
-        
-        match result {
-            Some(_) => self.metrics.hits.increment(1),
-            None => self.metrics.misses.increment(1),
-        }
-        
+        self.metrics.record_lookup(TRANSACTION_CACHE, result.is_some());
         result
     }
 
@@ -76,9 +101,7 @@ impl RunesCache {
         response: RunesTransactionResponse,
     ) -> Result<(), RuneError> {
         let mut cache = self.transaction_cache.write().await;
-        if cache.cache_set(tx_id, Arc::new(response)).is_some() {
-            self.metrics.evictions.increment(1);
-        }
+        self.insert(&mut cache, TRANSACTION_CACHE, tx_id, Arc::new(response));
         Ok(())
     }
 
@@ -86,14 +109,9 @@ impl RunesCache {
         &self,
         address: &str,
     ) -> Option<Arc<Vec<RuneTransfer>>> {
-        let cache = self.address_cache.read().await;
+        let mut cache = self.address_cache.write().await;
         let result = cache.cache_get(address).cloned();
-        
-        match result {
-            Some(_) => self.metrics.hits.increment(1),
-            None => self.metrics.misses.increment(1),
-        }
-        
+        self.metrics.record_lookup(ADDRESS_CACHE, result.is_some());
         result
     }
 
@@ -103,46 +121,134 @@ impl RunesCache {
         transfers: Vec<RuneTransfer>,
     ) -> Result<(), RuneError> {
         let mut cache = self.address_cache.write().await;
-        if cache.cache_set(address, Arc::new(transfers)).is_some() {
-            self.metrics.evictions.increment(1);
-        }
+        self.insert(&mut cache, ADDRESS_CACHE, address, Arc::new(transfers));
+        Ok(())
+    }
+
+    /// Looks a rune up by `block:tx` id or plain name
+    pub async fn get_rune(&self, rune: &str) -> Option<Arc<RuneInfo>> {
+        let mut cache = self.rune_cache.write().await;
+        let result = cache.cache_get(rune).cloned();
+        self.metrics.record_lookup(RUNE_CACHE, result.is_some());
+        result
+    }
+
+    /// Caches `info` under both its id and its plain name
+    pub async fn set_rune(&self, info: RuneInfo) -> Result<(), RuneError> {
+        let info = Arc::new(info);
+        let mut cache = self.rune_cache.write().await;
+        self.insert(&mut cache, RUNE_CACHE, info.id.clone(), info.clone());
+        self.insert(&mut cache, RUNE_CACHE, info.metadata.name.clone(), info);
         Ok(())
     }
 
     pub async fn invalidate_transaction(&self, tx_id: &str) {
         let mut cache = self.transaction_cache.write().await;
         cache.cache_remove(tx_id);
+        publish_size(TRANSACTION_CACHE, cache.cache_size());
     }
 
     pub async fn invalidate_address(&self, address: &str) {
         let mut cache = self.address_cache.write().await;
         cache.cache_remove(address);
+        publish_size(ADDRESS_CACHE, cache.cache_size());
+    }
+
+    pub async fn invalidate_rune(&self, rune: &str) {
+        let mut cache = self.rune_cache.write().await;
+        if let Some(info) = cache.cache_remove(rune) {
+            cache.cache_remove(&info.id);
+            cache.cache_remove(&info.metadata.name);
+        }
+        publish_size(RUNE_CACHE, cache.cache_size());
+    }
+
+    /// Drops what a rewind to `height` makes stale: transactions confirmed
+    /// from `height` on or still pending, and every address and rune entry,
+    /// whose transfers and mint counts cannot be dated to a block
+    pub async fn invalidate_from_height(&self, height: u64) {
+        let mut tx_cache = self.transaction_cache.write().await;
+        let stale: Vec<String> = tx_cache
+            .key_order()
+            .zip(tx_cache.value_order())
+            .filter(|(_, (_, tx))| tx.block_height.is_none_or(|block| u64::from(block) >= height))
+            .map(|(tx_id, _)| tx_id.clone())
+            .collect();
+        for tx_id in stale {
+            tx_cache.cache_remove(&tx_id);
+        }
+        publish_size(TRANSACTION_CACHE, tx_cache.cache_size());
+        drop(tx_cache);
+
+        self.address_cache.write().await.cache_clear();
+        self.rune_cache.write().await.cache_clear();
+        publish_size(ADDRESS_CACHE, 0);
+        publish_size(RUNE_CACHE, 0);
     }
 
     pub async fn clear_all(&self) {
         let mut tx_cache = self.transaction_cache.write().await;
         let mut addr_cache = self.address_cache.write().await;
-        
+        let mut rune_cache = self.rune_cache.write().await;
+
         tx_cache.cache_clear();
         addr_cache.cache_clear();
+        rune_cache.cache_clear();
+        publish_size(TRANSACTION_CACHE, 0);
+        publish_size(ADDRESS_CACHE, 0);
+        publish_size(RUNE_CACHE, 0);
     }
 
     pub async fn get_metrics(&self) -> CacheStats {
         CacheStats {
             transaction_cache_size: self.transaction_cache.read().await.cache_size(),
             address_cache_size: self.address_cache.read().await.cache_size(),
-            hits: self.metrics.hits.get_count(),
-            misses: self.metrics.misses.get_count(),
-            evictions: self.metrics.evictions.get_count(),
+            rune_cache_size: self.rune_cache.read().await.cache_size(),
+            hits: self.metrics.hits.load(Ordering::Relaxed),
+            misses: self.metrics.misses.load(Ordering::Relaxed),
+            evictions: self.metrics.evictions.load(Ordering::Relaxed),
         }
     }
+
+    fn insert<V>(&self, cache: &mut TimedSizedCache<String, V>, cache_type: &str, key: String, value: V) {
+        let size_before = cache.cache_size();
+        let replaced = cache.cache_set(key, value).is_some();
+
+        // A new key that did not grow the cache pushed out the least recently used entry
+        if !replaced && size_before > 0 && cache.cache_size() == size_before {
+            self.metrics.evictions.fetch_add(1, Ordering::Relaxed);
+        }
+        publish_size(cache_type, cache.cache_size());
+    }
 }
 
+#[cfg(feature = "metrics")]
+fn publish_lookup(cache_type: &str, hit: bool) {
+    if hit {
+        CACHE_HITS_TOTAL.with_label_values(&[cache_type]).inc();
+    } else {
+        CACHE_MISSES_TOTAL.with_label_values(&[cache_type]).inc();
+    }
+}
+
+#[cfg(not(feature = "metrics"))]
+fn publish_lookup(_cache_type: &str, _hit: bool) {}
+
+#[cfg(feature = "metrics")]
+fn publish_size(cache_type: &str, size: usize) {
+    CACHE_SIZE.with_label_values(&[cache_type]).set(size as i64);
+}
+
+#[cfg(not(feature = "metrics"))]
+fn publish_size(_cache_type: &str, _size: usize) {}
+
 #[derive(Debug, Serialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
 pub struct CacheStats {
     pub transaction_cache_size: usize,
     pub address_cache_size: usize,
+    pub rune_cache_size: usize,
     pub hits: u64,
     pub misses: u64,
     pub evictions: u64,
-} 
\ No newline at end of file
+}
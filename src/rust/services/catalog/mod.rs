@@ -0,0 +1,114 @@
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+
+use crate::services::node::backend::NodeBackend;
+use crate::types::{
+    error::{RuneError, RuneResult},
+    rune::RuneEntry,
+};
+
+/// Every etched rune as of one refresh of the catalog
+#[derive(Debug, Clone)]
+pub struct CatalogSnapshot {
+    /// Newest etching first, in the order the index lists them
+    pub entries: Vec<RuneEntry>,
+    /// Node tip when the refresh started
+    pub height: u64,
+    /// Unix time the refresh finished
+    pub refreshed_at: u64,
+}
+
+/// In-memory listing of all runes known to the node's rune index.
+///
+/// Indexes only serve the listing page by page, newest first, so searching,
+/// filtering and sorting all run against a snapshot that is rebuilt every
+/// `refresh_interval`.
+pub struct RuneCatalog {
+    node: Arc<dyn NodeBackend>,
+    snapshot: RwLock<Option<Arc<CatalogSnapshot>>>,
+    refresh_interval: Duration,
+}
+
+impl RuneCatalog {
+    pub fn new(node: Arc<dyn NodeBackend>, refresh_interval: Duration) -> Self {
+        Self {
+            node,
+            snapshot: RwLock::new(None),
+            refresh_interval,
+        }
+    }
+
+    /// Walks every page of the node's rune listing and swaps in the result,
+    /// returning the number of runes found
+    pub async fn refresh(&self) -> RuneResult<usize> {
+        let height = self.node.get_block_height().await?;
+
+        let mut entries = Vec::new();
+        let mut page = None;
+        loop {
+            let runes = self.node.get_runes(page).await?;
+            entries.extend(runes.entries);
+
+            match runes.next {
+                Some(next) => page = Some(next),
+                None => break,
+            }
+        }
+
+        let count = entries.len();
+        let refreshed_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        *self.snapshot.write().await = Some(Arc::new(CatalogSnapshot {
+            entries,
+            height,
+            refreshed_at,
+        }));
+        Ok(count)
+    }
+
+    /// Drops the runes etched from `height` on. The others keep the mint
+    /// counts of the last refresh until the next one.
+    pub async fn rewind_to(&self, height: u64) {
+        let mut snapshot = self.snapshot.write().await;
+        if let Some(current) = snapshot.as_ref() {
+            *snapshot = Some(Arc::new(CatalogSnapshot {
+                entries: current.entries.iter().filter(|entry| entry.block < height).cloned().collect(),
+                height: current.height.min(height.saturating_sub(1)),
+                refreshed_at: current.refreshed_at,
+            }));
+        }
+    }
+
+    /// Latest snapshot, or `NodeWarmingUp` until the first refresh completed
+    pub async fn snapshot(&self) -> RuneResult<Arc<CatalogSnapshot>> {
+        self.snapshot
+            .read()
+            .await
+            .clone()
+            .ok_or_else(|| RuneError::NodeWarmingUp("Rune catalog is still loading".to_string()))
+    }
+
+    /// Refreshes the catalog in the background every `refresh_interval`.
+    ///
+    /// Stops for good when the backend has no rune index.
+    pub fn start(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                match self.refresh().await {
+                    Ok(count) => tracing::debug!("Rune catalog refreshed with {} runes", count),
+                    Err(RuneError::UnsupportedOperation(msg)) => {
+                        tracing::info!("Rune catalog disabled: {}", msg);
+                        return;
+                    }
+                    Err(e) => tracing::warn!("Failed to refresh rune catalog: {}", e),
+                }
+                tokio::time::sleep(self.refresh_interval).await;
+            }
+        })
+    }
+}
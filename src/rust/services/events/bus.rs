@@ -0,0 +1,311 @@
+use std::collections::{HashSet, VecDeque};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::types::{
+    error::RuneError,
+    rune::{parse_spaced_rune, TransferType},
+    runestone::RuneId,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ChainEventType {
+    Block,
+    Transfer,
+    Etching,
+    Mint,
+    Confirmation,
+    Reorg,
+}
+
+impl ChainEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChainEventType::Block => "block",
+            ChainEventType::Transfer => "transfer",
+            ChainEventType::Etching => "etching",
+            ChainEventType::Mint => "mint",
+            ChainEventType::Confirmation => "confirmation",
+            ChainEventType::Reorg => "reorg",
+        }
+    }
+}
+
+impl FromStr for ChainEventType {
+    type Err = RuneError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "block" => Ok(ChainEventType::Block),
+            "transfer" => Ok(ChainEventType::Transfer),
+            "etching" => Ok(ChainEventType::Etching),
+            "mint" => Ok(ChainEventType::Mint),
+            "confirmation" => Ok(ChainEventType::Confirmation),
+            "reorg" => Ok(ChainEventType::Reorg),
+            other => Err(RuneError::InvalidRequest(format!("Unknown event type: {}", other))),
+        }
+    }
+}
+
+/// What happened on chain; amounts are strings like in the REST responses
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChainEventData {
+    Block {
+        height: u64,
+        hash: String,
+        timestamp: u64,
+        tx_count: usize,
+    },
+    Transfer {
+        txid: String,
+        /// Spaced rune name, or the id when the node cannot name the rune
+        rune: String,
+        /// `block:tx` id, absent when the node cannot look it up
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rune_id: Option<String>,
+        from_address: String,
+        to_address: String,
+        amount: String,
+        transfer_type: TransferType,
+        block_height: u64,
+        /// `false` when the node only read the runestone, without checking
+        /// input balances, mint caps or height windows; see `RunestoneDecoder`
+        #[serde(default)]
+        verified: bool,
+    },
+    Etching {
+        txid: String,
+        rune_id: String,
+        /// Spaced rune name
+        rune: String,
+        block_height: u64,
+    },
+    Mint {
+        txid: String,
+        /// Spaced rune name, or the id when the node cannot name the rune
+        rune: String,
+        /// `block:tx` id, absent when the node cannot look it up
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rune_id: Option<String>,
+        to_address: String,
+        amount: String,
+        block_height: u64,
+        /// `false` when the node only read the runestone, without checking
+        /// input balances, mint caps or height windows; see `RunestoneDecoder`
+        #[serde(default)]
+        verified: bool,
+    },
+    /// A rune transaction reached a new confirmation count
+    Confirmation {
+        txid: String,
+        block_height: u64,
+        confirmations: u64,
+    },
+    /// Blocks published before left the chain. Events of the new branch
+    /// follow from `fork_height + 1`, including blocks at heights already
+    /// published.
+    Reorg {
+        /// Last block both branches share
+        fork_height: u64,
+        old_tip_height: u64,
+        old_tip_hash: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct ChainEvent {
+    /// Increasing id, usable as `Last-Event-ID`
+    pub id: u64,
+    /// Unix time the event was published
+    pub published_at: u64,
+    #[serde(flatten)]
+    pub data: ChainEventData,
+}
+
+impl ChainEvent {
+    pub fn event_type(&self) -> ChainEventType {
+        match self.data {
+            ChainEventData::Block { .. } => ChainEventType::Block,
+            ChainEventData::Transfer { .. } => ChainEventType::Transfer,
+            ChainEventData::Etching { .. } => ChainEventType::Etching,
+            ChainEventData::Mint { .. } => ChainEventType::Mint,
+            ChainEventData::Confirmation { .. } => ChainEventType::Confirmation,
+            ChainEventData::Reorg { .. } => ChainEventType::Reorg,
+        }
+    }
+
+    /// Rune the event is about, by spaced name
+    pub fn rune(&self) -> Option<&str> {
+        match &self.data {
+            ChainEventData::Transfer { rune, .. }
+            | ChainEventData::Etching { rune, .. }
+            | ChainEventData::Mint { rune, .. } => Some(rune),
+            ChainEventData::Block { .. }
+            | ChainEventData::Confirmation { .. }
+            | ChainEventData::Reorg { .. } => None,
+        }
+    }
+
+    /// `block:tx` id of the rune the event is about, when known
+    pub fn rune_id(&self) -> Option<&str> {
+        match &self.data {
+            ChainEventData::Etching { rune_id, .. } => Some(rune_id),
+            ChainEventData::Transfer { rune_id, .. } | ChainEventData::Mint { rune_id, .. } => {
+                rune_id.as_deref()
+            }
+            _ => None,
+        }
+    }
+
+    /// Addresses whose balances the event touches
+    pub fn addresses(&self) -> Vec<&str> {
+        match &self.data {
+            ChainEventData::Transfer { from_address, to_address, .. } => {
+                vec![from_address, to_address]
+            }
+            ChainEventData::Mint { to_address, .. } => vec![to_address],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Subscriber side selection of events. Every set field has to match, so a
+/// `rune` or `address` filter drops events that do not mention one.
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    pub types: Option<HashSet<ChainEventType>>,
+    /// `block:tx` rune id, or a name whose spacers are ignored
+    pub rune: Option<String>,
+    pub address: Option<String>,
+}
+
+impl EventFilter {
+    pub fn matches(&self, event: &ChainEvent) -> bool {
+        if let Some(types) = &self.types {
+            if !types.contains(&event.event_type()) {
+                return false;
+            }
+        }
+
+        if let Some(rune) = &self.rune {
+            let matches = if rune.parse::<RuneId>().is_ok() {
+                event.rune_id() == Some(rune.as_str()) || event.rune() == Some(rune.as_str())
+            } else {
+                event.rune().is_some_and(|event_rune| {
+                    match (parse_spaced_rune(event_rune), parse_spaced_rune(rune)) {
+                        (Ok((event_rune, _)), Ok((rune, _))) => event_rune == rune,
+                        _ => false,
+                    }
+                })
+            };
+            if !matches {
+                return false;
+            }
+        }
+
+        match &self.address {
+            Some(address) => event.addresses().contains(&address.as_str()),
+            None => true,
+        }
+    }
+}
+
+/// Events published after `EventBus::subscribe`, preceded by the buffered
+/// ones the subscriber missed
+pub struct EventSubscription {
+    pub replay: Vec<Arc<ChainEvent>>,
+    pub receiver: broadcast::Receiver<Arc<ChainEvent>>,
+}
+
+struct BusState {
+    next_id: u64,
+    history: VecDeque<Arc<ChainEvent>>,
+}
+
+/// Fan-out of chain events to live subscribers with a bounded replay buffer
+/// for clients resuming from an event id
+pub struct EventBus {
+    sender: broadcast::Sender<Arc<ChainEvent>>,
+    state: Mutex<BusState>,
+    capacity: usize,
+}
+
+impl EventBus {
+    /// Keeps the last `capacity` events for replay, slower subscribers lag
+    /// once that many events are queued for them
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+
+        Self {
+            sender,
+            state: Mutex::new(BusState {
+                next_id: 1,
+                history: VecDeque::with_capacity(capacity),
+            }),
+            capacity,
+        }
+    }
+
+    pub fn publish(&self, data: ChainEventData) -> Arc<ChainEvent> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        let event = Arc::new(ChainEvent {
+            id: state.next_id,
+            published_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            data,
+        });
+        state.next_id += 1;
+
+        if self.capacity > 0 {
+            if state.history.len() == self.capacity {
+                state.history.pop_front();
+            }
+            state.history.push_back(event.clone());
+        }
+
+        // Sending under the lock keeps subscribe's replay and live events gapless
+        let _ = self.sender.send(event.clone());
+        event
+    }
+
+    /// Subscribes to new events, replaying buffered events after
+    /// `last_event_id`. Events older than the buffer are lost.
+    pub fn subscribe(&self, last_event_id: Option<u64>) -> EventSubscription {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        let replay = match last_event_id {
+            Some(last_id) => state
+                .history
+                .iter()
+                .filter(|event| event.id > last_id)
+                .cloned()
+                .collect(),
+            None => Vec::new(),
+        };
+
+        EventSubscription {
+            replay,
+            receiver: self.sender.subscribe(),
+        }
+    }
+
+    pub fn last_event_id(&self) -> Option<u64> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.history.back().map(|event| event.id)
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
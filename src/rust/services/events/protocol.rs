@@ -0,0 +1,96 @@
+use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
+
+use crate::types::error::RuneError;
+use super::bus::{ChainEvent, ChainEventType, EventFilter};
+
+/// What a WebSocket subscription follows
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+#[serde(tag = "channel", rename_all = "snake_case")]
+pub enum Channel {
+    /// Every new block, and reorgs replacing published ones
+    Blocks,
+    /// Transfers and mints touching `address`
+    Address { address: String },
+    /// Transfers and mints of `rune`, or of every rune
+    RuneTransfers {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rune: Option<String>,
+    },
+}
+
+impl Channel {
+    pub fn filter(&self) -> EventFilter {
+        let transfers = || Some(HashSet::from([ChainEventType::Transfer, ChainEventType::Mint]));
+
+        match self {
+            Channel::Blocks => EventFilter {
+                types: Some(HashSet::from([ChainEventType::Block, ChainEventType::Reorg])),
+                ..Default::default()
+            },
+            Channel::Address { address } => EventFilter {
+                types: transfers(),
+                address: Some(address.clone()),
+                ..Default::default()
+            },
+            Channel::RuneTransfers { rune } => EventFilter {
+                types: transfers(),
+                rune: rune.clone(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// A message with the `id` pairing a client request with its reply. The
+/// server copies the `id` of a request onto its reply, errors included, so
+/// an error without one answers no particular request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Envelope<T> {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    #[serde(flatten)]
+    pub message: T,
+}
+
+/// Messages a WebSocket client sends, as JSON text frames
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// Authenticates a connection opened without an API key
+    Auth { api_key: String },
+    Subscribe {
+        #[serde(flatten)]
+        channel: Channel,
+    },
+    Unsubscribe { subscription: u32 },
+    Ping,
+}
+
+/// Messages the server sends, as JSON text frames
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Authenticated,
+    Subscribed {
+        /// Id to unsubscribe with, also tags the events of the subscription
+        subscription: u32,
+        #[serde(flatten)]
+        channel: Channel,
+    },
+    Unsubscribed { subscription: u32 },
+    Event { subscription: u32, event: ChainEvent },
+    Pong,
+    /// `code` uses the values of `ErrorResponse::code`
+    Error { code: String, message: String },
+}
+
+impl From<&RuneError> for ServerMessage {
+    fn from(error: &RuneError) -> Self {
+        ServerMessage::Error {
+            code: error.error_code(),
+            message: error.to_string(),
+        }
+    }
+}
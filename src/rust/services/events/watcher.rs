@@ -0,0 +1,338 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+use crate::services::node::backend::NodeBackend;
+use crate::types::{
+    error::{RuneError, RuneResult},
+    rune::{RuneTransfer, TransferType},
+};
+use super::bus::{ChainEventData, EventBus};
+
+/// Hashes of published blocks kept to find where a reorg forked off
+const REORG_WINDOW: usize = 100;
+
+#[derive(Debug, Clone)]
+pub struct WatcherConfig {
+    pub poll_interval: Duration,
+    /// Confirmation events are published until a rune transaction has this many
+    pub confirmation_depth: u64,
+    /// Reads the rune transfers and mints of new blocks with
+    /// `NodeBackend::get_block_transactions`, one request per block. Off by
+    /// default; only Bitcoin Core lists them, with other backends only
+    /// etchings are published either way.
+    pub scan_transactions: bool,
+}
+
+impl Default for WatcherConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_secs(10),
+            confirmation_depth: 6,
+            scan_transactions: false,
+        }
+    }
+}
+
+#[derive(Default)]
+struct WatchState {
+    last_height: Option<u64>,
+    /// `(height, hash)` of the last published blocks, oldest first
+    recent: VecDeque<(u64, String)>,
+    /// Rune transactions of recent blocks still gaining confirmations
+    pending: VecDeque<(u64, Vec<String>)>,
+    /// `block:tx` ids by spaced name for backends that only name runes,
+    /// `None` when the node cannot look them up
+    rune_ids: HashMap<String, Option<String>>,
+}
+
+/// Block read from the node, published only once all of it was read
+struct ScannedBlock {
+    height: u64,
+    hash: String,
+    events: Vec<ChainEventData>,
+    rune_txs: Vec<String>,
+}
+
+/// Polls the node for new blocks and publishes what they contain to an `EventBus`
+pub struct BlockWatcher {
+    node: Arc<dyn NodeBackend>,
+    bus: Arc<EventBus>,
+    config: WatcherConfig,
+    state: Mutex<WatchState>,
+}
+
+impl BlockWatcher {
+    pub fn new(node: Arc<dyn NodeBackend>, bus: Arc<EventBus>, config: WatcherConfig) -> Self {
+        Self {
+            node,
+            bus,
+            config,
+            state: Mutex::new(WatchState::default()),
+        }
+    }
+
+    /// Publishes the blocks mined since the last poll, starting with the
+    /// current tip on the first one. Returns the number of new blocks.
+    ///
+    /// When blocks published before left the chain, a reorg event is
+    /// published first and the new branch follows from the fork.
+    pub async fn poll(&self) -> RuneResult<u64> {
+        let mut state = self.state.lock().await;
+        let tip = self.node.get_block_height().await?;
+        self.check_reorg(tip, &mut state).await?;
+
+        let start = match state.last_height {
+            Some(last) if tip <= last => return Ok(0),
+            Some(last) => last + 1,
+            None => tip,
+        };
+
+        let mut published = 0;
+        for height in start..=tip {
+            let Some(block) = self.scan_block(height, &mut state).await? else {
+                // The chain changed under this poll, the next one rewinds
+                break;
+            };
+            self.publish_block(block, &mut state);
+            published += 1;
+        }
+        Ok(published)
+    }
+
+    /// Publishes the blocks from `height` on again, starting with the next poll.
+    ///
+    /// Subscribers first get a reorg event forking off below `height`, so they
+    /// drop what they have from those blocks. Nothing happens before the first
+    /// poll or when `height` was not published yet.
+    pub async fn rewind_to(&self, height: u64) {
+        let mut state = self.state.lock().await;
+        let Some(old_tip_height) = state.last_height.filter(|last| height <= *last) else {
+            return;
+        };
+
+        let fork_height = height.saturating_sub(1);
+        tracing::info!("Rewinding the block watcher to block {}", height);
+        self.bus.publish(ChainEventData::Reorg {
+            fork_height,
+            old_tip_height,
+            old_tip_hash: state.recent.back().map(|(_, hash)| hash.clone()).unwrap_or_default(),
+        });
+
+        state.recent.retain(|(height, _)| *height <= fork_height);
+        state.pending.retain(|(height, _)| *height <= fork_height);
+        state.last_height = Some(fork_height);
+    }
+
+    pub fn start(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = self.poll().await {
+                    tracing::warn!("Block watcher poll failed: {}", e);
+                }
+                tokio::time::sleep(self.config.poll_interval).await;
+            }
+        })
+    }
+
+    /// Compares the hashes of recently published blocks with the node's
+    /// chain and rewinds to the last one still on it
+    async fn check_reorg(&self, tip: u64, state: &mut WatchState) -> RuneResult<()> {
+        let Some((old_tip_height, old_tip_hash)) = state.recent.back().cloned() else {
+            return Ok(());
+        };
+
+        let mut fork_height = None;
+        for (height, hash) in state.recent.iter().rev() {
+            if *height <= tip && self.node.get_block_hash(*height).await? == *hash {
+                fork_height = Some(*height);
+                break;
+            }
+        }
+        if fork_height == Some(old_tip_height) {
+            return Ok(());
+        }
+
+        let fork_height = fork_height.unwrap_or_else(|| {
+            let oldest = state.recent.front().map_or(0, |(height, _)| *height);
+            tracing::warn!(
+                "Reorg goes deeper than the {} blocks watched, resuming below block {}",
+                REORG_WINDOW,
+                oldest
+            );
+            oldest.saturating_sub(1)
+        });
+
+        tracing::info!("Reorg from block {} back to {}", old_tip_height, fork_height);
+        self.bus.publish(ChainEventData::Reorg {
+            fork_height,
+            old_tip_height,
+            old_tip_hash,
+        });
+
+        state.recent.retain(|(height, _)| *height <= fork_height);
+        state.pending.retain(|(height, _)| *height <= fork_height);
+        state.last_height = Some(fork_height);
+        Ok(())
+    }
+
+    /// Reads the events of the block at `height` without publishing them.
+    /// `None` when it does not extend the last published block.
+    async fn scan_block(&self, height: u64, state: &mut WatchState) -> RuneResult<Option<ScannedBlock>> {
+        let hash = self.node.get_block_hash(height).await?;
+        let block = self.node.get_block(&hash).await?;
+
+        if let (Some(previous), Some((last_height, last_hash))) =
+            (&block.previous_block_hash, state.recent.back())
+        {
+            if *last_height + 1 == height && previous != last_hash {
+                return Ok(None);
+            }
+        }
+
+        let mut events = vec![ChainEventData::Block {
+            height,
+            hash: block.hash.clone(),
+            timestamp: block.timestamp,
+            tx_count: block.tx_ids.len(),
+        }];
+        let mut rune_txs = Vec::new();
+
+        match self.node.get_block_etchings(height).await {
+            Ok(etchings) => {
+                for entry in etchings {
+                    state
+                        .rune_ids
+                        .insert(entry.spaced_name.clone(), Some(entry.id.clone()));
+                    events.push(ChainEventData::Etching {
+                        txid: entry.etching.clone(),
+                        rune_id: entry.id,
+                        rune: entry.spaced_name,
+                        block_height: height,
+                    });
+                    rune_txs.push(entry.etching);
+                }
+            }
+            Err(RuneError::UnsupportedOperation(_)) => {}
+            Err(e) => return Err(e),
+        }
+
+        let transactions = if self.config.scan_transactions {
+            match self.node.get_block_transactions(&block.hash).await {
+                Ok(transactions) => transactions,
+                Err(RuneError::UnsupportedOperation(_)) => Vec::new(),
+                Err(e) => return Err(e),
+            }
+        } else {
+            Vec::new()
+        };
+
+        for tx in transactions {
+            if tx.runes.is_empty() {
+                continue;
+            }
+
+            for transfer in tx.runes {
+                let rune_id = self.rune_id(&transfer, state).await?;
+                let verified = transfer
+                    .metadata
+                    .as_ref()
+                    .and_then(|metadata| metadata.get("verified"))
+                    .and_then(|verified| verified.as_bool())
+                    .unwrap_or(true);
+                let data = match transfer.transfer_type {
+                    TransferType::Mint => ChainEventData::Mint {
+                        txid: tx.transaction_id.clone(),
+                        rune: transfer.rune_id,
+                        rune_id,
+                        to_address: transfer.to_address,
+                        amount: transfer.amount.to_string(),
+                        block_height: height,
+                        verified,
+                    },
+                    transfer_type => ChainEventData::Transfer {
+                        txid: tx.transaction_id.clone(),
+                        rune: transfer.rune_id,
+                        rune_id,
+                        from_address: transfer.from_address,
+                        to_address: transfer.to_address,
+                        amount: transfer.amount.to_string(),
+                        transfer_type,
+                        block_height: height,
+                        verified,
+                    },
+                };
+                events.push(data);
+            }
+            if !rune_txs.contains(&tx.transaction_id) {
+                rune_txs.push(tx.transaction_id);
+            }
+        }
+
+        Ok(Some(ScannedBlock {
+            height,
+            hash: block.hash,
+            events,
+            rune_txs,
+        }))
+    }
+
+    /// `block:tx` id of the transferred rune, from the transfer itself or
+    /// looked up by name
+    async fn rune_id(&self, transfer: &RuneTransfer, state: &mut WatchState) -> RuneResult<Option<String>> {
+        let id = transfer
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.get("id"))
+            .and_then(|id| id.as_str());
+        if let Some(id) = id {
+            return Ok(Some(id.to_string()));
+        }
+
+        if let Some(id) = state.rune_ids.get(&transfer.rune_id) {
+            return Ok(id.clone());
+        }
+        let id = match self.node.get_rune(&transfer.rune_id).await {
+            Ok(entry) => Some(entry.id),
+            Err(RuneError::UnsupportedOperation(_) | RuneError::NotFound(_)) => None,
+            Err(e) => return Err(e),
+        };
+        state.rune_ids.insert(transfer.rune_id.clone(), id.clone());
+        Ok(id)
+    }
+
+    fn publish_block(&self, block: ScannedBlock, state: &mut WatchState) {
+        for data in block.events {
+            self.bus.publish(data);
+        }
+
+        // Every earlier rune transaction gained a confirmation with this block
+        let height = block.height;
+        for (block_height, txids) in &state.pending {
+            let confirmations = height - block_height + 1;
+            for txid in txids {
+                self.bus.publish(ChainEventData::Confirmation {
+                    txid: txid.clone(),
+                    block_height: *block_height,
+                    confirmations,
+                });
+            }
+        }
+
+        if !block.rune_txs.is_empty() {
+            state.pending.push_back((height, block.rune_txs));
+        }
+        let depth = self.config.confirmation_depth;
+        state
+            .pending
+            .retain(|(block_height, _)| height - block_height + 1 < depth);
+
+        state.recent.push_back((height, block.hash));
+        if state.recent.len() > REORG_WINDOW {
+            state.recent.pop_front();
+        }
+        state.last_height = Some(height);
+    }
+}
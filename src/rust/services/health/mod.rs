@@ -0,0 +1,217 @@
+use async_trait::async_trait;
+use futures::future::join_all;
+use serde::Serialize;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::services::node::{backend::NodeBackend, sync::SyncService};
+use crate::types::error::{RuneError, RuneResult};
+
+#[derive(Debug, Clone)]
+pub struct HealthConfig {
+    /// Blocks the sync may trail the node tip by before the instance stops being ready
+    pub max_sync_lag: u64,
+    /// Longest a single dependency check may take before it counts as failed
+    pub check_timeout: Duration,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            max_sync_lag: 6,
+            check_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+/// A dependency the instance cannot serve requests without, e.g. a database
+#[async_trait]
+pub trait DependencyCheck: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn check(&self) -> RuneResult<()>;
+}
+
+/// Runs `SELECT 1` against the pool
+#[cfg(feature = "postgres")]
+pub struct DatabaseCheck {
+    pool: sqlx::PgPool,
+}
+
+#[cfg(feature = "postgres")]
+impl DatabaseCheck {
+    pub fn new(pool: sqlx::PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[cfg(feature = "postgres")]
+#[async_trait]
+impl DependencyCheck for DatabaseCheck {
+    fn name(&self) -> &str {
+        "database"
+    }
+
+    async fn check(&self) -> RuneResult<()> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct CheckResult {
+    #[cfg_attr(feature = "server", schema(example = "node"))]
+    pub name: String,
+    pub healthy: bool,
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct SyncLag {
+    pub current_height: u64,
+    pub node_height: u64,
+    pub lag: u64,
+    pub max_lag: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct ReadinessReport {
+    pub ready: bool,
+    pub checks: Vec<CheckResult>,
+    /// Node tip, when the node answered
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_height: Option<u64>,
+    /// Only present when a sync service is tracked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sync: Option<SyncLag>,
+}
+
+/// Decides whether the instance should receive traffic.
+///
+/// The node has to answer its health check, every registered dependency has
+/// to pass and, when a `SyncService` is tracked, its height may not trail the
+/// node tip by more than `max_sync_lag` blocks.
+pub struct HealthChecker {
+    node: Arc<dyn NodeBackend>,
+    sync: Option<Arc<SyncService>>,
+    dependencies: Vec<Arc<dyn DependencyCheck>>,
+    config: HealthConfig,
+}
+
+impl HealthChecker {
+    pub fn new(node: Arc<dyn NodeBackend>, config: HealthConfig) -> Self {
+        Self {
+            node,
+            sync: None,
+            dependencies: Vec::new(),
+            config,
+        }
+    }
+
+    pub fn with_sync(mut self, sync: Arc<SyncService>) -> Self {
+        self.sync = Some(sync);
+        self
+    }
+
+    pub fn with_dependency(mut self, check: Arc<dyn DependencyCheck>) -> Self {
+        self.dependencies.push(check);
+        self
+    }
+
+    /// Runs `check` under the configured timeout, measuring how long it took
+    async fn timed<T>(&self, check: impl Future<Output = RuneResult<T>>) -> (RuneResult<T>, u64) {
+        let started = Instant::now();
+        let result = match tokio::time::timeout(self.config.check_timeout, check).await {
+            Ok(result) => result,
+            Err(_) => Err(RuneError::NodeConnectionError(format!(
+                "Timed out after {:?}",
+                self.config.check_timeout
+            ))),
+        };
+        (result, started.elapsed().as_millis() as u64)
+    }
+
+    pub async fn readiness(&self) -> ReadinessReport {
+        let node = self.timed(self.node.health_check());
+        let dependencies = join_all(self.dependencies.iter().map(|dependency| async move {
+            let (result, latency_ms) = self.timed(dependency.check()).await;
+            CheckResult {
+                name: dependency.name().to_string(),
+                healthy: result.is_ok(),
+                latency_ms,
+                error: result.err().map(|e| e.to_string()),
+            }
+        }));
+        let ((node, latency_ms), dependencies) = tokio::join!(node, dependencies);
+
+        let node = node.and_then(|status| match status.is_connected {
+            true => Ok(status),
+            false => Err(RuneError::NodeConnectionError("Node reports it is not connected".to_string())),
+        });
+        let node_height = node.as_ref().ok().map(|status| status.block_height);
+
+        let mut checks = vec![CheckResult {
+            name: "node".to_string(),
+            healthy: node.is_ok(),
+            latency_ms,
+            error: node.err().map(|e| e.to_string()),
+        }];
+        checks.extend(dependencies);
+
+        let mut sync = None;
+        if let Some(service) = &self.sync {
+            let (check, lag) = self.sync_check(service, node_height).await;
+            checks.push(check);
+            sync = lag;
+        }
+
+        ReadinessReport {
+            ready: checks.iter().all(|check| check.healthy),
+            checks,
+            node_height,
+            sync,
+        }
+    }
+
+    async fn sync_check(
+        &self,
+        service: &SyncService,
+        node_height: Option<u64>,
+    ) -> (CheckResult, Option<SyncLag>) {
+        let started = Instant::now();
+        let status = service.get_sync_status().await;
+
+        let lag = match (status, node_height) {
+            (Ok(status), Some(node_height)) => Ok(SyncLag {
+                current_height: status.current_height,
+                node_height,
+                lag: node_height.saturating_sub(status.current_height),
+                max_lag: self.config.max_sync_lag,
+            }),
+            (Err(e), _) => Err(e.to_string()),
+            (_, None) => Err("Node tip unknown".to_string()),
+        };
+        let error = match &lag {
+            Ok(lag) if lag.lag > lag.max_lag => Some(format!(
+                "Sync is {} blocks behind the node, at most {} allowed",
+                lag.lag, lag.max_lag
+            )),
+            Ok(_) => None,
+            Err(e) => Some(e.clone()),
+        };
+
+        let check = CheckResult {
+            name: "sync".to_string(),
+            healthy: error.is_none(),
+            latency_ms: started.elapsed().as_millis() as u64,
+            error,
+        };
+        (check, lag.ok())
+    }
+}
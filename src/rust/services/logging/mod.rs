@@ -1,15 +1,19 @@
-use std::sync::Arc;
-use tracing::{Level, Subscriber};
+use std::path::Path;
+use tracing::Level;
+use tracing_appender::non_blocking::WorkerGuard;
 use tracing_subscriber::{
     fmt::{self, format::FmtSpan},
+    layer::{Layered, SubscriberExt},
     EnvFilter,
-    layer::SubscriberExt,
+    Layer,
     Registry,
 };
 use tracing_bunyan_formatter::{BunyanFormattingLayer, JsonStorageLayer};
 use tracing_log::LogTracer;
 use tracing_appender::rolling::{RollingFileAppender, Rotation};
 
+type BoxedLayer = Box<dyn Layer<Layered<EnvFilter, Registry>> + Send + Sync>;
+
 pub struct LoggingConfig {
     pub log_level: Level,
     pub log_file_path: String,
@@ -28,28 +32,29 @@ impl Default for LoggingConfig {
     }
 }
 
-pub fn init_logging(config: LoggingConfig) -> Result<(), Box<dyn std::error::Error>> {
+/// Installs the global subscriber.
+///
+/// The returned guard flushes the JSON log file when dropped, so it has to
+/// live as long as the process.
+pub fn init_logging(config: LoggingConfig) -> Result<Option<WorkerGuard>, Box<dyn std::error::Error>> {
     // Log kütüphanesini tracing'e yönlendir
     LogTracer::init()?;
 
-    // Dosyaya yazma için appender oluştur
-    let file_appender = RollingFileAppender::new(
-        Rotation::DAILY,
-        "logs",
-        "runes-sdk.log",
-    );
-
-    let mut layers = Vec::new();
+    let mut layers: Vec<BoxedLayer> = Vec::new();
+    let mut guard = None;
 
     // JSON formatında loglama katmanı
     if config.json_logging {
-        let (non_blocking, _guard) = tracing_appender::non_blocking(file_appender);
-        let bunyan_formatting_layer = BunyanFormattingLayer::new(
-            "runes-sdk".into(),
-            non_blocking,
-        );
-        layers.push(Arc::new(JsonStorageLayer));
-        layers.push(Arc::new(bunyan_formatting_layer));
+        let path = Path::new(&config.log_file_path);
+        let directory = path.parent().unwrap_or_else(|| Path::new("."));
+        let file_name = path.file_name().unwrap_or_else(|| "runes-sdk.log".as_ref());
+
+        // Dosyaya yazma için appender oluştur
+        let file_appender = RollingFileAppender::new(Rotation::DAILY, directory, file_name);
+        let (non_blocking, worker_guard) = tracing_appender::non_blocking(file_appender);
+        layers.push(JsonStorageLayer.boxed());
+        layers.push(BunyanFormattingLayer::new("runes-sdk".into(), non_blocking).boxed());
+        guard = Some(worker_guard);
     }
 
     // Konsol loglama katmanı
@@ -62,7 +67,7 @@ pub fn init_logging(config: LoggingConfig) -> Result<(), Box<dyn std::error::Err
             .with_line_number(true)
             .with_span_events(FmtSpan::CLOSE)
             .pretty();
-        layers.push(Arc::new(console_layer));
+        layers.push(console_layer.boxed());
     }
 
     // Log seviyesi filtresi
@@ -71,16 +76,13 @@ pub fn init_logging(config: LoggingConfig) -> Result<(), Box<dyn std::error::Err
 
     // Subscriber'ı oluştur ve kaydet
     let subscriber = Registry::default()
-        .with(env_filter);
-
-    for layer in layers {
-        subscriber = subscriber.with(layer);
-    }
+        .with(env_filter)
+        .with(layers);
 
     // Global subscriber'ı ayarla
     tracing::subscriber::set_global_default(subscriber)?;
 
-    Ok(())
+    Ok(guard)
 }
 
 // Log makroları
@@ -1,6 +1,5 @@
-use std::sync::Arc;
 use prometheus::{
-    Counter, Gauge, Histogram, HistogramOpts, HistogramVec,
+    opts, Encoder, GaugeVec, Histogram, HistogramOpts, HistogramVec,
     IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
     Registry, TextEncoder,
 };
@@ -63,6 +62,52 @@ lazy_static! {
         &["method"]
     ).unwrap();
     
+    pub static ref NODE_HEDGED_REQUESTS_TOTAL: IntCounterVec = IntCounterVec::new(
+        opts!("node_hedged_requests_total", "Total number of node requests duplicated to a second backend"),
+        &["method"]
+    ).unwrap();
+    
+    pub static ref NODE_HEDGE_WINS_TOTAL: IntCounterVec = IntCounterVec::new(
+        opts!("node_hedge_wins_total", "Total number of hedged requests answered first by the second backend"),
+        &["method"]
+    ).unwrap();
+    
+    // Node pool metrics
+    pub static ref NODE_HEALTHY: IntGaugeVec = IntGaugeVec::new(
+        opts!("node_healthy", "Whether a pooled node is healthy (1) or not (0)"),
+        &["node", "backend"]
+    ).unwrap();
+    
+    pub static ref NODE_LAGGING: IntGaugeVec = IntGaugeVec::new(
+        opts!("node_lagging", "Whether a pooled node is behind the best known tip (1) or not (0)"),
+        &["node"]
+    ).unwrap();
+    
+    pub static ref NODE_BLOCK_HEIGHT: IntGaugeVec = IntGaugeVec::new(
+        opts!("node_block_height", "Tip height reported by a pooled node"),
+        &["node"]
+    ).unwrap();
+    
+    pub static ref NODE_LATENCY_SECONDS: GaugeVec = GaugeVec::new(
+        opts!("node_latency_seconds", "Smoothed response latency of a pooled node"),
+        &["node"]
+    ).unwrap();
+    
+    pub static ref NODE_FAILOVERS_TOTAL: IntCounterVec = IntCounterVec::new(
+        opts!("node_failovers_total", "Total number of requests retried on another node"),
+        &["node", "method"]
+    ).unwrap();
+    
+    pub static ref NODE_CIRCUIT_STATE: IntGaugeVec = IntGaugeVec::new(
+        opts!("node_circuit_state", "Circuit breaker state per node (0 closed, 1 half-open, 2 open)"),
+        &["node"]
+    ).unwrap();
+    
+    pub static ref NODE_CIRCUIT_REJECTIONS_TOTAL: IntCounterVec = IntCounterVec::new(
+        opts!("node_circuit_rejections_total", "Total number of node calls rejected by an open circuit"),
+        &["node"]
+    ).unwrap();
+    
     // Webhook metrikleri
     pub static ref WEBHOOK_DELIVERIES_TOTAL: IntCounterVec = IntCounterVec::new(
         opts!("webhook_deliveries_total", "Total number of webhook deliveries"),
@@ -82,7 +127,14 @@ lazy_static! {
     ).unwrap();
 }
 
+static REGISTER: std::sync::Once = std::sync::Once::new();
+
+/// Registers every metric with `REGISTRY`, safe to call more than once
 pub fn register_metrics() {
+    REGISTER.call_once(register_all);
+}
+
+fn register_all() {
     // HTTP metrikleri
     REGISTRY.register(Box::new(HTTP_REQUESTS_TOTAL.clone())).unwrap();
     REGISTRY.register(Box::new(HTTP_REQUEST_DURATION_SECONDS.clone())).unwrap();
@@ -97,6 +149,17 @@ pub fn register_metrics() {
     REGISTRY.register(Box::new(NODE_REQUESTS_TOTAL.clone())).unwrap();
     REGISTRY.register(Box::new(NODE_REQUEST_FAILURES_TOTAL.clone())).unwrap();
     REGISTRY.register(Box::new(NODE_REQUEST_DURATION_SECONDS.clone())).unwrap();
+    REGISTRY.register(Box::new(NODE_HEDGED_REQUESTS_TOTAL.clone())).unwrap();
+    REGISTRY.register(Box::new(NODE_HEDGE_WINS_TOTAL.clone())).unwrap();
+    
+    // Node pool metrics
+    REGISTRY.register(Box::new(NODE_HEALTHY.clone())).unwrap();
+    REGISTRY.register(Box::new(NODE_LAGGING.clone())).unwrap();
+    REGISTRY.register(Box::new(NODE_BLOCK_HEIGHT.clone())).unwrap();
+    REGISTRY.register(Box::new(NODE_LATENCY_SECONDS.clone())).unwrap();
+    REGISTRY.register(Box::new(NODE_FAILOVERS_TOTAL.clone())).unwrap();
+    REGISTRY.register(Box::new(NODE_CIRCUIT_STATE.clone())).unwrap();
+    REGISTRY.register(Box::new(NODE_CIRCUIT_REJECTIONS_TOTAL.clone())).unwrap();
     
     // Webhook metrikleri
     REGISTRY.register(Box::new(WEBHOOK_DELIVERIES_TOTAL.clone())).unwrap();
@@ -0,0 +1,17 @@
+pub mod cache;
+pub mod catalog;
+pub mod events;
+pub mod health;
+pub mod node;
+
+#[cfg(feature = "metrics")]
+pub mod metrics;
+
+#[cfg(feature = "server")]
+pub mod auth;
+#[cfg(feature = "server")]
+pub mod logging;
+#[cfg(feature = "server")]
+pub mod rate_limit;
+#[cfg(feature = "server")]
+pub mod webhook;
@@ -0,0 +1,140 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use reqwest::Client as HttpClient;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{
+    error::{RuneError, RuneResult},
+    rune::{RuneEntry, RuneOutput, RunePage, RunesTransactionResponse, RuneTransfer},
+};
+use super::{
+    bitcoind::BitcoindBackend,
+    connection::{NodeConfig, NodeStatus},
+    esplora::EsploraBackend,
+    ord::OrdBackend,
+};
+
+/// Which kind of upstream server `NodeConfig::rpc_url` points at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackendKind {
+    /// Bitcoin Core JSON-RPC
+    #[default]
+    Bitcoind,
+    /// Esplora / electrs REST API
+    Esplora,
+    /// `ord server` JSON API
+    Ord,
+}
+
+impl BackendKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BackendKind::Bitcoind => "bitcoind",
+            BackendKind::Esplora => "esplora",
+            BackendKind::Ord => "ord",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockInfo {
+    pub hash: String,
+    pub height: u64,
+    pub previous_block_hash: Option<String>,
+    pub timestamp: u64,
+    pub tx_ids: Vec<String>,
+}
+
+/// Common interface over the node implementations the SDK can talk to.
+///
+/// The API and sync services only depend on this trait, the concrete
+/// backend is picked from `NodeConfig::backend`.
+#[async_trait]
+pub trait NodeBackend: Send + Sync {
+    fn kind(&self) -> BackendKind;
+
+    async fn health_check(&self) -> RuneResult<NodeStatus>;
+
+    async fn get_block_height(&self) -> RuneResult<u64>;
+
+    async fn get_block_hash(&self, height: u64) -> RuneResult<String>;
+
+    async fn get_block(&self, hash: &str) -> RuneResult<BlockInfo>;
+
+    /// Returns the raw transaction as a hex string
+    async fn get_raw_transaction(&self, tx_id: &str) -> RuneResult<String>;
+
+    /// Raw transaction `tx_id` confirmed in the block `block_hash`. Bitcoin
+    /// Core needs the hash to find it without `-txindex`, other nodes ignore it.
+    async fn get_raw_block_transaction(&self, tx_id: &str, _block_hash: &str) -> RuneResult<String> {
+        self.get_raw_transaction(tx_id).await
+    }
+
+    async fn get_transaction(&self, tx_id: &str) -> RuneResult<RunesTransactionResponse>;
+
+    async fn get_address_transfers(&self, address: &str) -> RuneResult<Vec<RuneTransfer>>;
+
+    /// Looks a rune up by `block:tx` id or plain name.
+    ///
+    /// Only rune indexers can answer this, so other backends keep the default.
+    async fn get_rune(&self, _rune: &str) -> RuneResult<RuneEntry> {
+        Err(unsupported(self.kind(), "Rune lookup"))
+    }
+
+    /// Fetches a page of etched runes, newest first; `None` is the first page
+    async fn get_runes(&self, _page: Option<u32>) -> RuneResult<RunePage> {
+        Err(unsupported(self.kind(), "Rune listing"))
+    }
+
+    /// Runes carried by the `txid:vout` output, spent or not
+    async fn get_output(&self, _outpoint: &str) -> RuneResult<RuneOutput> {
+        Err(unsupported(self.kind(), "Output lookup"))
+    }
+
+    /// Unspent outputs of `address` that carry runes
+    async fn get_address_outputs(&self, _address: &str) -> RuneResult<Vec<RuneOutput>> {
+        Err(unsupported(self.kind(), "Address balance lookup"))
+    }
+
+    /// Transactions of the block `hash` that move runes, read with one
+    /// request for the whole block
+    async fn get_block_transactions(&self, _hash: &str) -> RuneResult<Vec<RunesTransactionResponse>> {
+        Err(unsupported(self.kind(), "Block transaction lookup"))
+    }
+
+    /// Runes etched in the block at `height`
+    async fn get_block_etchings(&self, _height: u64) -> RuneResult<Vec<RuneEntry>> {
+        Err(unsupported(self.kind(), "Etching lookup"))
+    }
+
+}
+
+/// Builds the backend selected by `config.backend`, sharing `client`'s connection pool
+pub fn create_backend(config: &NodeConfig, client: Arc<HttpClient>) -> Arc<dyn NodeBackend> {
+    match config.backend {
+        BackendKind::Bitcoind => Arc::new(BitcoindBackend::new(config, client.as_ref().clone())),
+        BackendKind::Esplora => Arc::new(EsploraBackend::new(config, client)),
+        BackendKind::Ord => Arc::new(OrdBackend::new(config, client)),
+    }
+}
+
+/// Error returned by backends for operations their server cannot answer
+pub fn unsupported(kind: BackendKind, operation: &str) -> RuneError {
+    RuneError::UnsupportedOperation(format!(
+        "{} is not supported by the {} backend",
+        operation,
+        kind.as_str()
+    ))
+}
+
+/// Errors caused by the node rather than the request itself
+pub(crate) fn is_node_failure(error: &RuneError) -> bool {
+    matches!(
+        error,
+        RuneError::NodeConnectionError(_)
+            | RuneError::NodeResponseError(_)
+            | RuneError::NodeSyncError(_)
+            | RuneError::NodeWarmingUp(_)
+    )
+}
@@ -0,0 +1,254 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::types::{
+    error::{RuneError, RuneResult},
+    rune::{NetworkType, RunesTransactionResponse, RuneTransfer, TransactionStatus},
+};
+use crate::{RpcClient, RpcClientConfig};
+use super::{
+    backend::{unsupported, BackendKind, BlockInfo, NodeBackend},
+    connection::{NodeConfig, NodeStatus},
+    decoder::{RunestoneDecoder, TxOutput},
+};
+
+/// Bitcoin Core backend speaking JSON-RPC
+pub struct BitcoindBackend {
+    rpc: RpcClient,
+    network: NetworkType,
+    decoder: RunestoneDecoder,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlockchainInfo {
+    blocks: u64,
+    verificationprogress: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct VerboseBlock {
+    hash: String,
+    height: u64,
+    previousblockhash: Option<String>,
+    time: u64,
+    tx: Vec<String>,
+}
+
+/// `getblock` with verbosity 2, its transactions carry no block fields
+#[derive(Debug, Deserialize)]
+struct TransactionBlock {
+    hash: String,
+    height: u64,
+    /// -1 once the block left the main chain
+    confirmations: i64,
+    time: u64,
+    tx: Vec<VerboseTransaction>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlockHeader {
+    height: u64,
+}
+
+/// Block confirming a transaction
+struct Confirmation<'a> {
+    hash: &'a str,
+    height: u64,
+    confirmations: i64,
+    time: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct VerboseTransaction {
+    txid: String,
+    blockhash: Option<String>,
+    confirmations: Option<i64>,
+    time: Option<u64>,
+    #[serde(default)]
+    vout: Vec<VerboseTxOut>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VerboseTxOut {
+    #[serde(rename = "scriptPubKey")]
+    script_pub_key: ScriptPubKey,
+}
+
+#[derive(Debug, Deserialize)]
+struct ScriptPubKey {
+    hex: String,
+    address: Option<String>,
+}
+
+impl BitcoindBackend {
+    pub fn new(config: &NodeConfig, client: reqwest::Client) -> Self {
+        let rpc = RpcClient::with_http_client(
+            RpcClientConfig {
+                url: config.rpc_url.clone(),
+                timeout: config.timeout,
+                auth: config.auth(),
+                http: config.http.clone(),
+            },
+            client,
+        );
+
+        Self {
+            rpc,
+            network: config.network,
+            decoder: RunestoneDecoder::new(),
+        }
+    }
+
+    pub fn rpc(&self) -> &RpcClient {
+        &self.rpc
+    }
+
+    async fn transaction_response(
+        &self,
+        tx: &VerboseTransaction,
+        block: Option<Confirmation<'_>>,
+    ) -> RuneResult<RunesTransactionResponse> {
+        let confirmation_count = block
+            .as_ref()
+            .map_or(0, |block| u32::try_from(block.confirmations).unwrap_or(0));
+
+        // Bitcoin Core has no notion of rune balances, so they are read from the runestone
+        let outputs = tx
+            .vout
+            .iter()
+            .map(|out| TxOutput::from_hex(&out.script_pub_key.hex, out.script_pub_key.address.clone()))
+            .collect::<RuneResult<Vec<_>>>()?;
+        let runes = self
+            .decoder
+            .transfers(self, &tx.txid, block.as_ref().map(|block| block.hash), &outputs)
+            .await?;
+
+        Ok(RunesTransactionResponse {
+            transaction_id: tx.txid.clone(),
+            runes,
+            block_height: block.as_ref().map(|block| block.height as u32),
+            confirmation_count,
+            timestamp: block.map_or(0, |block| block.time),
+            network_type: self.network,
+            status: if confirmation_count > 0 {
+                TransactionStatus::Confirmed
+            } else {
+                TransactionStatus::Pending
+            },
+        })
+    }
+}
+
+#[async_trait]
+impl NodeBackend for BitcoindBackend {
+    fn kind(&self) -> BackendKind {
+        BackendKind::Bitcoind
+    }
+
+    async fn health_check(&self) -> RuneResult<NodeStatus> {
+        let info: BlockchainInfo = self.rpc.call_with_params("getblockchaininfo", vec![]).await?;
+        let peer_count: u32 = self.rpc.call_with_params("getconnectioncount", vec![]).await?;
+
+        Ok(NodeStatus {
+            is_connected: true,
+            block_height: info.blocks,
+            peer_count,
+            sync_progress: info.verificationprogress,
+        })
+    }
+
+    async fn get_block_height(&self) -> RuneResult<u64> {
+        Ok(self.rpc.call_with_params("getblockcount", vec![]).await?)
+    }
+
+    async fn get_block_hash(&self, height: u64) -> RuneResult<String> {
+        Ok(self.rpc.call_with_params("getblockhash", vec![json!(height)]).await?)
+    }
+
+    async fn get_block(&self, hash: &str) -> RuneResult<BlockInfo> {
+        let block: VerboseBlock = self
+            .rpc
+            .call_with_params("getblock", vec![json!(hash), json!(1)])
+            .await?;
+
+        Ok(BlockInfo {
+            hash: block.hash,
+            height: block.height,
+            previous_block_hash: block.previousblockhash,
+            timestamp: block.time,
+            tx_ids: block.tx,
+        })
+    }
+
+    async fn get_raw_transaction(&self, tx_id: &str) -> RuneResult<String> {
+        Ok(self
+            .rpc
+            .call_with_params("getrawtransaction", vec![json!(tx_id), json!(false)])
+            .await?)
+    }
+
+    async fn get_raw_block_transaction(&self, tx_id: &str, block_hash: &str) -> RuneResult<String> {
+        Ok(self
+            .rpc
+            .call_with_params("getrawtransaction", vec![json!(tx_id), json!(false), json!(block_hash)])
+            .await?)
+    }
+
+    async fn get_transaction(&self, tx_id: &str) -> RuneResult<RunesTransactionResponse> {
+        let tx: VerboseTransaction = self
+            .rpc
+            .call_with_params("getrawtransaction", vec![json!(tx_id), json!(true)])
+            .await?;
+
+        let block = match &tx.blockhash {
+            Some(hash) => {
+                let header: BlockHeader = self
+                    .rpc
+                    .call_with_params("getblockheader", vec![json!(hash), json!(true)])
+                    .await?;
+                Some(Confirmation {
+                    hash,
+                    height: header.height,
+                    confirmations: tx.confirmations.unwrap_or(0),
+                    time: tx.time.unwrap_or(0),
+                })
+            }
+            None => None,
+        };
+
+        self.transaction_response(&tx, block).await
+    }
+
+    async fn get_block_transactions(&self, hash: &str) -> RuneResult<Vec<RunesTransactionResponse>> {
+        let block: TransactionBlock = self
+            .rpc
+            .call_with_params("getblock", vec![json!(hash), json!(2)])
+            .await?;
+
+        let mut transactions = Vec::new();
+        for tx in &block.tx {
+            let confirmation = Confirmation {
+                hash: &block.hash,
+                height: block.height,
+                confirmations: block.confirmations,
+                time: block.time,
+            };
+            match self.transaction_response(tx, Some(confirmation)).await {
+                Ok(response) if !response.runes.is_empty() => transactions.push(response),
+                Ok(_) => {}
+                // A rune its runestone names may be gone, the rest of the block still counts
+                Err(RuneError::NotFound(e)) => {
+                    tracing::warn!("Skipping transaction {} of block {}: {}", tx.txid, block.hash, e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(transactions)
+    }
+
+    async fn get_address_transfers(&self, _address: &str) -> RuneResult<Vec<RuneTransfer>> {
+        // Bitcoin Core keeps no address index
+        Err(unsupported(self.kind(), "Address lookup"))
+    }
+}
@@ -0,0 +1,361 @@
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use async_trait::async_trait;
+use serde::Serialize;
+
+#[cfg(feature = "metrics")]
+use crate::services::metrics::{NODE_CIRCUIT_REJECTIONS_TOTAL, NODE_CIRCUIT_STATE};
+use crate::types::{
+    error::{RuneError, RuneResult},
+    rune::{RuneEntry, RuneOutput, RunePage, RunesTransactionResponse, RuneTransfer},
+};
+use super::{
+    backend::{is_node_failure, BackendKind, BlockInfo, NodeBackend},
+    connection::NodeStatus,
+};
+
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerConfig {
+    /// Number of recent calls the failure rate is computed over
+    pub window_size: usize,
+    /// Calls required in the window before the circuit may open
+    pub minimum_calls: usize,
+    /// Failure rate between 0.0 and 1.0 that opens the circuit
+    pub failure_rate_threshold: f64,
+    /// How long the circuit stays open before probe requests are let through
+    pub open_duration: Duration,
+    /// Successful probes required to close the circuit again
+    pub half_open_probes: u32,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            window_size: 20,
+            minimum_calls: 10,
+            failure_rate_threshold: 0.5,
+            open_duration: Duration::from_secs(30),
+            half_open_probes: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+#[cfg(feature = "metrics")]
+impl CircuitState {
+    fn metric_value(&self) -> i64 {
+        match self {
+            CircuitState::Closed => 0,
+            CircuitState::HalfOpen => 1,
+            CircuitState::Open => 2,
+        }
+    }
+}
+
+/// Circuit breaker state as reported by health endpoints
+#[derive(Debug, Clone, Serialize)]
+pub struct CircuitStatus {
+    pub name: String,
+    pub state: CircuitState,
+    pub failure_rate: f64,
+    pub recent_calls: usize,
+}
+
+enum Inner {
+    Closed { outcomes: VecDeque<bool> },
+    Open { until: Instant },
+    HalfOpen { in_flight: u32, successes: u32 },
+}
+
+/// Closed / open / half-open state machine keyed on the recent failure rate
+pub struct CircuitBreaker {
+    name: String,
+    config: CircuitBreakerConfig,
+    inner: Mutex<Inner>,
+}
+
+/// Admission to call the node, must be completed with `record`
+pub struct CircuitPermit<'a> {
+    breaker: &'a CircuitBreaker,
+    probe: bool,
+    recorded: bool,
+}
+
+impl CircuitPermit<'_> {
+    pub fn record(mut self, success: bool) {
+        self.recorded = true;
+        self.breaker.on_result(self.probe, success);
+    }
+}
+
+impl Drop for CircuitPermit<'_> {
+    fn drop(&mut self) {
+        // A cancelled probe must give its slot back
+        if !self.recorded && self.probe {
+            let mut inner = self.breaker.inner.lock().unwrap();
+            if let Inner::HalfOpen { in_flight, .. } = &mut *inner {
+                *in_flight = in_flight.saturating_sub(1);
+            }
+        }
+    }
+}
+
+impl CircuitBreaker {
+    pub fn new(name: impl Into<String>, config: CircuitBreakerConfig) -> Self {
+        let breaker = Self {
+            name: name.into(),
+            config,
+            inner: Mutex::new(Inner::Closed {
+                outcomes: VecDeque::new(),
+            }),
+        };
+        breaker.publish(CircuitState::Closed);
+        breaker
+    }
+
+    pub fn state(&self) -> CircuitState {
+        let mut inner = self.inner.lock().unwrap();
+        self.refresh(&mut inner)
+    }
+
+    pub fn status(&self) -> CircuitStatus {
+        let mut inner = self.inner.lock().unwrap();
+        let state = self.refresh(&mut inner);
+        let (failure_rate, recent_calls) = match &*inner {
+            Inner::Closed { outcomes } => (failure_rate(outcomes), outcomes.len()),
+            _ => (1.0, 0),
+        };
+
+        CircuitStatus {
+            name: self.name.clone(),
+            state,
+            failure_rate,
+            recent_calls,
+        }
+    }
+
+    /// Admits a call, or fails fast with `NodeConnectionError` while the circuit is open
+    pub fn try_acquire(&self) -> RuneResult<CircuitPermit<'_>> {
+        let mut inner = self.inner.lock().unwrap();
+        let probe = match self.refresh(&mut inner) {
+            CircuitState::Closed => false,
+            CircuitState::HalfOpen => match &mut *inner {
+                Inner::HalfOpen { in_flight, successes }
+                    if *in_flight + *successes < self.config.half_open_probes =>
+                {
+                    *in_flight += 1;
+                    true
+                }
+                _ => return Err(self.reject()),
+            },
+            CircuitState::Open => return Err(self.reject()),
+        };
+
+        Ok(CircuitPermit {
+            breaker: self,
+            probe,
+            recorded: false,
+        })
+    }
+
+    /// Runs `call` through the breaker, counting only node-side errors as failures
+    pub async fn call<T, Fut>(&self, call: Fut) -> RuneResult<T>
+    where
+        Fut: Future<Output = RuneResult<T>>,
+    {
+        let permit = self.try_acquire()?;
+        let result = call.await;
+        permit.record(!matches!(&result, Err(e) if is_node_failure(e)));
+        result
+    }
+
+    /// Moves an expired open circuit to half-open
+    fn refresh(&self, inner: &mut Inner) -> CircuitState {
+        match inner {
+            Inner::Closed { .. } => CircuitState::Closed,
+            Inner::HalfOpen { .. } => CircuitState::HalfOpen,
+            Inner::Open { until } if Instant::now() >= *until => {
+                *inner = Inner::HalfOpen {
+                    in_flight: 0,
+                    successes: 0,
+                };
+                self.publish(CircuitState::HalfOpen);
+                CircuitState::HalfOpen
+            }
+            Inner::Open { .. } => CircuitState::Open,
+        }
+    }
+
+    fn on_result(&self, probe: bool, success: bool) {
+        let mut inner = self.inner.lock().unwrap();
+        let next = match &mut *inner {
+            Inner::Closed { outcomes } if !probe => {
+                outcomes.push_back(success);
+                while outcomes.len() > self.config.window_size {
+                    outcomes.pop_front();
+                }
+
+                let tripped = outcomes.len() >= self.config.minimum_calls
+                    && failure_rate(outcomes) >= self.config.failure_rate_threshold;
+                if !tripped {
+                    return;
+                }
+                tracing::warn!(
+                    "Circuit for node {} opened at {:.0}% failures",
+                    self.name,
+                    failure_rate(outcomes) * 100.0
+                );
+                self.open()
+            }
+            Inner::HalfOpen { in_flight, successes } if probe => {
+                *in_flight = in_flight.saturating_sub(1);
+                if !success {
+                    tracing::warn!("Probe to node {} failed, circuit reopened", self.name);
+                    self.open()
+                } else {
+                    *successes += 1;
+                    if *successes < self.config.half_open_probes {
+                        return;
+                    }
+                    tracing::info!("Circuit for node {} closed", self.name);
+                    self.publish(CircuitState::Closed);
+                    Inner::Closed {
+                        outcomes: VecDeque::new(),
+                    }
+                }
+            }
+            // Results of calls admitted under a previous state are stale
+            _ => return,
+        };
+        *inner = next;
+    }
+
+    fn open(&self) -> Inner {
+        self.publish(CircuitState::Open);
+        Inner::Open {
+            until: Instant::now() + self.config.open_duration,
+        }
+    }
+
+    fn reject(&self) -> RuneError {
+        #[cfg(feature = "metrics")]
+        NODE_CIRCUIT_REJECTIONS_TOTAL
+            .with_label_values(&[&self.name])
+            .inc();
+        RuneError::NodeConnectionError(format!("Circuit open for node {}", self.name))
+    }
+
+    #[cfg(feature = "metrics")]
+    fn publish(&self, state: CircuitState) {
+        NODE_CIRCUIT_STATE
+            .with_label_values(&[&self.name])
+            .set(state.metric_value());
+    }
+
+    #[cfg(not(feature = "metrics"))]
+    fn publish(&self, _state: CircuitState) {}
+}
+
+fn failure_rate(outcomes: &VecDeque<bool>) -> f64 {
+    if outcomes.is_empty() {
+        return 0.0;
+    }
+    outcomes.iter().filter(|success| !**success).count() as f64 / outcomes.len() as f64
+}
+
+/// Backend wrapper that stops calling a failing node until it recovers
+pub struct CircuitBreakerBackend {
+    inner: Arc<dyn NodeBackend>,
+    breaker: Arc<CircuitBreaker>,
+}
+
+impl CircuitBreakerBackend {
+    pub fn new(
+        name: impl Into<String>,
+        inner: Arc<dyn NodeBackend>,
+        config: CircuitBreakerConfig,
+    ) -> Self {
+        Self::with_breaker(inner, Arc::new(CircuitBreaker::new(name, config)))
+    }
+
+    pub fn with_breaker(inner: Arc<dyn NodeBackend>, breaker: Arc<CircuitBreaker>) -> Self {
+        Self { inner, breaker }
+    }
+
+    pub fn breaker(&self) -> Arc<CircuitBreaker> {
+        self.breaker.clone()
+    }
+}
+
+#[async_trait]
+impl NodeBackend for CircuitBreakerBackend {
+    fn kind(&self) -> BackendKind {
+        self.inner.kind()
+    }
+
+    async fn health_check(&self) -> RuneResult<NodeStatus> {
+        self.breaker.call(self.inner.health_check()).await
+    }
+
+    async fn get_block_height(&self) -> RuneResult<u64> {
+        self.breaker.call(self.inner.get_block_height()).await
+    }
+
+    async fn get_block_hash(&self, height: u64) -> RuneResult<String> {
+        self.breaker.call(self.inner.get_block_hash(height)).await
+    }
+
+    async fn get_block(&self, hash: &str) -> RuneResult<BlockInfo> {
+        self.breaker.call(self.inner.get_block(hash)).await
+    }
+
+    async fn get_raw_transaction(&self, tx_id: &str) -> RuneResult<String> {
+        self.breaker.call(self.inner.get_raw_transaction(tx_id)).await
+    }
+
+    async fn get_raw_block_transaction(&self, tx_id: &str, block_hash: &str) -> RuneResult<String> {
+        self.breaker.call(self.inner.get_raw_block_transaction(tx_id, block_hash)).await
+    }
+
+    async fn get_transaction(&self, tx_id: &str) -> RuneResult<RunesTransactionResponse> {
+        self.breaker.call(self.inner.get_transaction(tx_id)).await
+    }
+
+    async fn get_block_transactions(&self, hash: &str) -> RuneResult<Vec<RunesTransactionResponse>> {
+        self.breaker.call(self.inner.get_block_transactions(hash)).await
+    }
+
+    async fn get_address_transfers(&self, address: &str) -> RuneResult<Vec<RuneTransfer>> {
+        self.breaker.call(self.inner.get_address_transfers(address)).await
+    }
+
+    async fn get_rune(&self, rune: &str) -> RuneResult<RuneEntry> {
+        self.breaker.call(self.inner.get_rune(rune)).await
+    }
+
+    async fn get_runes(&self, page: Option<u32>) -> RuneResult<RunePage> {
+        self.breaker.call(self.inner.get_runes(page)).await
+    }
+
+    async fn get_output(&self, outpoint: &str) -> RuneResult<RuneOutput> {
+        self.breaker.call(self.inner.get_output(outpoint)).await
+    }
+
+    async fn get_address_outputs(&self, address: &str) -> RuneResult<Vec<RuneOutput>> {
+        self.breaker.call(self.inner.get_address_outputs(address)).await
+    }
+
+    async fn get_block_etchings(&self, height: u64) -> RuneResult<Vec<RuneEntry>> {
+        self.breaker.call(self.inner.get_block_etchings(height)).await
+    }
+
+}
@@ -1,21 +1,76 @@
+use std::future::Future;
+use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use async_trait::async_trait;
 use reqwest::Client as HttpClient;
-use crate::types::error::RuneError;
+use serde::{Deserialize, Serialize};
+use crate::types::{
+    error::{RuneError, RuneResult},
+    rune::{NetworkType, RuneEntry, RuneOutput, RunePage, RunesTransactionResponse, RuneTransfer},
+};
+use crate::{HttpClientConfig, RpcAuth};
 use metrics::{Counter, Gauge, Histogram};
+use super::{
+    backend::{create_backend, BackendKind, BlockInfo, NodeBackend},
+    breaker::{CircuitBreaker, CircuitBreakerBackend, CircuitBreakerConfig, CircuitStatus},
+};
 
 #[derive(Debug, Clone)]
 pub struct NodeConfig {
+    /// Base URL of the node, interpreted according to `backend`
     pub rpc_url: String,
+    pub backend: BackendKind,
+    pub network: NetworkType,
     pub username: Option<String>,
     pub password: Option<String>,
+    /// bitcoind `.cookie` file, takes precedence over `username`/`password`
+    pub cookie_file: Option<PathBuf>,
     pub timeout: Duration,
+    /// Health checks `connect` makes before giving up
     pub max_retries: u32,
+    pub http: HttpClientConfig,
+    /// Wraps the backend in a circuit breaker when set
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+}
+
+impl Default for NodeConfig {
+    fn default() -> Self {
+        Self {
+            rpc_url: "http://localhost:8332".to_string(),
+            backend: BackendKind::Bitcoind,
+            network: NetworkType::Mainnet,
+            username: None,
+            password: None,
+            cookie_file: None,
+            timeout: Duration::from_secs(30),
+            max_retries: 3,
+            http: HttpClientConfig::default(),
+            circuit_breaker: Some(CircuitBreakerConfig::default()),
+        }
+    }
+}
+
+impl NodeConfig {
+    pub fn auth(&self) -> RpcAuth {
+        if let Some(path) = &self.cookie_file {
+            return RpcAuth::CookieFile(path.clone());
+        }
+
+        match &self.username {
+            Some(username) => RpcAuth::Basic {
+                username: username.clone(),
+                password: self.password.clone().unwrap_or_default(),
+            },
+            None => RpcAuth::None,
+        }
+    }
 }
 
 pub struct NodeConnection {
     config: NodeConfig,
-    client: Arc<HttpClient>,
+    backend: Arc<dyn NodeBackend>,
+    circuit: Option<Arc<CircuitBreaker>>,
     metrics: Arc<MetricsCollector>,
 }
 
@@ -26,7 +81,7 @@ pub struct MetricsCollector {
     pub active_connections: Gauge,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NodeStatus {
     pub is_connected: bool,
     pub block_height: u64,
@@ -36,86 +91,169 @@ pub struct NodeStatus {
 
 impl NodeConnection {
     pub fn new(config: NodeConfig, metrics: Arc<MetricsCollector>) -> Self {
-        let client = HttpClient::builder()
-            .timeout(config.timeout)
-            .build()
+        let client = config.http
+            .build_client(config.timeout)
             .expect("Failed to create HTTP client");
 
+        Self::with_client(config, Arc::new(client), metrics)
+    }
+
+    /// Creates a connection that reuses an existing pooled HTTP client
+    pub fn with_client(
+        config: NodeConfig,
+        client: Arc<HttpClient>,
+        metrics: Arc<MetricsCollector>,
+    ) -> Self {
+        let backend = create_backend(&config, client);
+        Self::with_backend(config, backend, metrics)
+    }
+
+    /// Wraps an already constructed backend with retries, metrics and the
+    /// configured circuit breaker
+    pub fn with_backend(
+        config: NodeConfig,
+        backend: Arc<dyn NodeBackend>,
+        metrics: Arc<MetricsCollector>,
+    ) -> Self {
+        let (backend, circuit) = match &config.circuit_breaker {
+            Some(breaker_config) => {
+                let breaker = Arc::new(CircuitBreaker::new(
+                    config.rpc_url.clone(),
+                    breaker_config.clone(),
+                ));
+                let backend: Arc<dyn NodeBackend> =
+                    Arc::new(CircuitBreakerBackend::with_breaker(backend, breaker.clone()));
+                (backend, Some(breaker))
+            }
+            None => (backend, None),
+        };
+
         Self {
             config,
-            client: Arc::new(client),
+            backend,
+            circuit,
             metrics,
         }
     }
 
+    pub fn backend(&self) -> Arc<dyn NodeBackend> {
+        self.backend.clone()
+    }
+
+    /// Circuit breaker state, `None` when the breaker is disabled
+    pub fn circuit_status(&self) -> Option<CircuitStatus> {
+        self.circuit.as_ref().map(|breaker| breaker.status())
+    }
+
+    /// Health-checks the node until it answers, up to `max_retries` times.
+    ///
+    /// Waits 1s, 2s, 4s and so on before each retry, not after the last
+    /// attempt. Only a successful check counts towards `active_connections`.
+    /// When all attempts fail the error is a `NodeConnectionError` reading
+    /// "Max retries exceeded: " and the last failure.
     pub async fn connect(&self) -> Result<(), RuneError> {
-        let _metrics_guard = self.metrics.active_connections.increment(1.0);
-        
+        let mut last_error = None;
+
         for attempt in 0..self.config.max_retries {
+            if attempt > 0 {
+                tokio::time::sleep(Duration::from_secs(2_u64.pow(attempt - 1))).await;
+            }
+
             match self.health_check().await {
                 Ok(_) => {
-                    tracing::info!("Successfully connected to node");
+                    self.metrics.active_connections.increment(1.0);
+                    tracing::info!("Successfully connected to {} node", self.backend.kind().as_str());
                     return Ok(());
                 }
                 Err(e) => {
-                    if attempt == self.config.max_retries - 1 {
-                        return Err(e);
-                    }
                     tracing::warn!("Connection attempt {} failed: {}", attempt + 1, e);
-                    tokio::time::sleep(Duration::from_secs(2_u64.pow(attempt))).await;
+                    last_error = Some(e);
                 }
             }
         }
 
-        Err(RuneError::NodeConnectionError("Max retries exceeded".to_string()))
+        Err(RuneError::NodeConnectionError(match last_error {
+            Some(e) => format!("Max retries exceeded: {}", e),
+            None => "Max retries exceeded".to_string(),
+        }))
     }
 
-    pub async fn health_check(&self) -> Result<NodeStatus, RuneError> {
-        let timer = self.metrics.response_time.start_timer();
-        
-        let response = self.client
-            .get(&format!("{}/health", self.config.rpc_url))
-            .send()
-            .await
-            .map_err(RuneError::NetworkError)?;
-
-        timer.observe_duration();
+    async fn observe<T, F>(&self, request: F) -> RuneResult<T>
+    where
+        F: Future<Output = RuneResult<T>>,
+    {
+        let started = Instant::now();
+        let result = request.await;
+        self.metrics.response_time.record(started.elapsed().as_secs_f64());
 
-        if !response.status().is_success() {
+        if result.is_err() {
             self.metrics.error_counter.increment(1);
-            return Err(RuneError::NodeConnectionError(
-                format!("Health check failed with status: {}", response.status())
-            ));
         }
+        result
+    }
+}
 
-        let status: NodeStatus = response
-            .json()
-            .await
-            .map_err(|e| RuneError::NodeConnectionError(format!("Failed to parse status: {}", e)))?;
+#[async_trait]
+impl NodeBackend for NodeConnection {
+    fn kind(&self) -> BackendKind {
+        self.backend.kind()
+    }
 
-        Ok(status)
+    async fn health_check(&self) -> RuneResult<NodeStatus> {
+        self.observe(self.backend.health_check()).await
     }
 
-    pub async fn get_block_height(&self) -> Result<u64, RuneError> {
-        let response = self.client
-            .get(&format!("{}/blocks/tip/height", self.config.rpc_url))
-            .send()
-            .await
-            .map_err(RuneError::NetworkError)?;
+    async fn get_block_height(&self) -> RuneResult<u64> {
+        self.observe(self.backend.get_block_height()).await
+    }
 
-        if !response.status().is_success() {
-            return Err(RuneError::NodeConnectionError(
-                "Failed to get block height".to_string()
-            ));
-        }
+    async fn get_block_hash(&self, height: u64) -> RuneResult<String> {
+        self.observe(self.backend.get_block_hash(height)).await
+    }
+
+    async fn get_block(&self, hash: &str) -> RuneResult<BlockInfo> {
+        self.observe(self.backend.get_block(hash)).await
+    }
+
+    async fn get_raw_transaction(&self, tx_id: &str) -> RuneResult<String> {
+        self.observe(self.backend.get_raw_transaction(tx_id)).await
+    }
+
+    async fn get_raw_block_transaction(&self, tx_id: &str, block_hash: &str) -> RuneResult<String> {
+        self.observe(self.backend.get_raw_block_transaction(tx_id, block_hash)).await
+    }
+
+    async fn get_transaction(&self, tx_id: &str) -> RuneResult<RunesTransactionResponse> {
+        self.metrics.transaction_counter.increment(1);
+        self.observe(self.backend.get_transaction(tx_id)).await
+    }
 
-        let height = response
-            .text()
-            .await
-            .map_err(|e| RuneError::NodeConnectionError(format!("Failed to parse height: {}", e)))?
-            .parse::<u64>()
-            .map_err(|e| RuneError::NodeConnectionError(format!("Invalid height format: {}", e)))?;
+    async fn get_block_transactions(&self, hash: &str) -> RuneResult<Vec<RunesTransactionResponse>> {
+        self.observe(self.backend.get_block_transactions(hash)).await
+    }
+
+    async fn get_address_transfers(&self, address: &str) -> RuneResult<Vec<RuneTransfer>> {
+        self.observe(self.backend.get_address_transfers(address)).await
+    }
+
+    async fn get_rune(&self, rune: &str) -> RuneResult<RuneEntry> {
+        self.observe(self.backend.get_rune(rune)).await
+    }
+
+    async fn get_runes(&self, page: Option<u32>) -> RuneResult<RunePage> {
+        self.observe(self.backend.get_runes(page)).await
+    }
 
-        Ok(height)
+    async fn get_output(&self, outpoint: &str) -> RuneResult<RuneOutput> {
+        self.observe(self.backend.get_output(outpoint)).await
     }
-} 
\ No newline at end of file
+
+    async fn get_address_outputs(&self, address: &str) -> RuneResult<Vec<RuneOutput>> {
+        self.observe(self.backend.get_address_outputs(address)).await
+    }
+
+    async fn get_block_etchings(&self, height: u64) -> RuneResult<Vec<RuneEntry>> {
+        self.observe(self.backend.get_block_etchings(height)).await
+    }
+
+}
@@ -0,0 +1,364 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use cached::{Cached, SizedCache};
+
+use crate::types::{
+    error::{RuneError, RuneResult},
+    rune::{format_spaced_rune, RuneTransfer, TransferType},
+    runestone::{is_runestone, reserved_rune, Etching, RuneId, Runestone},
+};
+use super::backend::NodeBackend;
+
+/// Etchings remembered by id, looked up again once evicted
+const ETCHING_CACHE_SIZE: usize = 10_000;
+
+const OP_RETURN: u8 = 0x6a;
+
+/// Output of the transaction handed to `RunestoneDecoder::transfers`
+#[derive(Debug, Clone)]
+pub struct TxOutput {
+    pub script_pubkey: Vec<u8>,
+    pub address: Option<String>,
+}
+
+impl TxOutput {
+    pub fn from_hex(script_pubkey: &str, address: Option<String>) -> RuneResult<Self> {
+        Ok(Self {
+            script_pubkey: decode_hex(script_pubkey)?,
+            address,
+        })
+    }
+
+    fn is_op_return(&self) -> bool {
+        self.script_pubkey.first() == Some(&OP_RETURN)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EtchedRune {
+    id: Option<RuneId>,
+    spaced_name: String,
+    divisibility: u8,
+    symbol: Option<char>,
+    mint_amount: Option<u128>,
+}
+
+impl EtchedRune {
+    /// `None` for an unnamed etching that is not confirmed yet, its name
+    /// comes from its position in the chain
+    fn new(etching: &Etching, id: Option<RuneId>) -> Option<Self> {
+        let name = match (&etching.rune, id) {
+            (Some(name), _) => name.clone(),
+            (None, Some(id)) => reserved_rune(id),
+            (None, None) => return None,
+        };
+
+        Some(Self {
+            id,
+            spaced_name: format_spaced_rune(&name, etching.spacers.unwrap_or_default()),
+            divisibility: etching.divisibility.unwrap_or_default(),
+            symbol: etching.symbol,
+            mint_amount: etching.terms.as_ref().and_then(|terms| terms.amount),
+        })
+    }
+}
+
+/// Reads rune movements from the runestones of raw transactions, for
+/// backends whose node knows nothing about runes.
+///
+/// Input balances are not tracked, so only what a runestone states
+/// explicitly is reported: the premine, mints and edicts with an amount.
+/// Edicts of amount 0 are only followed for runes the transaction etches
+/// or mints. Mint caps and height windows are not checked either, so a
+/// transfer may claim more than its inputs hold and a mint may be one the
+/// rune no longer allows. Every transfer carries `"verified": false` in its
+/// metadata, and the watcher publishes it on the mint and transfer events.
+pub struct RunestoneDecoder {
+    etchings: Mutex<SizedCache<RuneId, Option<EtchedRune>>>,
+}
+
+impl Default for RunestoneDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RunestoneDecoder {
+    pub fn new() -> Self {
+        Self {
+            etchings: Mutex::new(SizedCache::with_size(ETCHING_CACHE_SIZE)),
+        }
+    }
+
+    /// Transfers stated by the runestone of transaction `txid`, empty for
+    /// transactions without one and for cenotaphs.
+    ///
+    /// `block_hash` is the block confirming the transaction; a rune it
+    /// etches only gets its id once confirmed.
+    pub async fn transfers(
+        &self,
+        node: &dyn NodeBackend,
+        txid: &str,
+        block_hash: Option<&str>,
+        outputs: &[TxOutput],
+    ) -> RuneResult<Vec<RuneTransfer>> {
+        // Only the first runestone output counts, if it cannot be read the
+        // transaction is a cenotaph and burns its runes
+        let Some(runestone) = outputs
+            .iter()
+            .find(|output| is_runestone(&output.script_pubkey))
+            .and_then(|output| Runestone::decipher(&output.script_pubkey))
+        else {
+            return Ok(Vec::new());
+        };
+
+        let in_range = |output: u32| (output as usize) <= outputs.len();
+        if runestone.pointer.is_some_and(|pointer| pointer as usize >= outputs.len())
+            || !runestone.edicts.iter().all(|edict| in_range(edict.output))
+        {
+            return Ok(Vec::new());
+        }
+
+        // Runes the transaction creates, keyed like its edicts with 0:0 for
+        // the rune it etches
+        let etched_id = RuneId { block: 0, tx: 0 };
+        let mut runes: HashMap<RuneId, EtchedRune> = HashMap::new();
+        let mut issued: HashMap<RuneId, (u128, TransferType)> = HashMap::new();
+
+        if let Some(etching) = &runestone.etching {
+            let id = match block_hash {
+                Some(hash) => self.position(node, txid, hash).await?,
+                None => None,
+            };
+            if let Some(rune) = EtchedRune::new(etching, id) {
+                runes.insert(etched_id, rune);
+                if let Some(premine) = etching.premine.filter(|premine| *premine > 0) {
+                    issued.insert(etched_id, (premine, TransferType::Etch));
+                }
+            }
+        }
+
+        if let Some(id) = runestone.mint {
+            if let Some(rune) = self.etched(node, id).await? {
+                if let Some(amount) = rune.mint_amount {
+                    issued.insert(id, (amount, TransferType::Mint));
+                }
+                runes.insert(id, rune);
+            }
+        }
+
+        for edict in &runestone.edicts {
+            if edict.id != etched_id && !runes.contains_key(&edict.id) {
+                if let Some(rune) = self.etched(node, edict.id).await? {
+                    runes.insert(edict.id, rune);
+                }
+            }
+        }
+
+        let mut allocations = Vec::new();
+        for edict in &runestone.edicts {
+            if !runes.contains_key(&edict.id) {
+                continue;
+            }
+
+            if edict.output as usize == outputs.len() {
+                // Split across every output that is not an OP_RETURN
+                let targets: Vec<usize> = (0..outputs.len())
+                    .filter(|&vout| !outputs[vout].is_op_return())
+                    .collect();
+                if targets.is_empty() {
+                    continue;
+                }
+                let amounts: Vec<u128> = if edict.amount == 0 {
+                    let available = issued.get(&edict.id).map_or(0, |(amount, _)| *amount);
+                    let share = available / targets.len() as u128;
+                    let remainder = (available % targets.len() as u128) as usize;
+                    (0..targets.len())
+                        .map(|i| share + u128::from(i < remainder))
+                        .collect()
+                } else {
+                    vec![edict.amount; targets.len()]
+                };
+                for (vout, amount) in targets.into_iter().zip(amounts) {
+                    allocate(&mut allocations, &mut issued, edict.id, vout, amount);
+                }
+            } else {
+                let amount = match edict.amount {
+                    0 => issued.get(&edict.id).map_or(0, |(amount, _)| *amount),
+                    amount => amount,
+                };
+                allocate(&mut allocations, &mut issued, edict.id, edict.output as usize, amount);
+            }
+        }
+
+        // Whatever is left of the premine and mint goes to the pointer, or
+        // the first output that is not an OP_RETURN
+        let default_output = runestone
+            .pointer
+            .map(|pointer| pointer as usize)
+            .or_else(|| outputs.iter().position(|output| !output.is_op_return()));
+        if let Some(vout) = default_output {
+            let mut leftover: Vec<(RuneId, (u128, TransferType))> = issued.drain().collect();
+            leftover.sort_by_key(|(id, _)| *id);
+            for (id, (amount, transfer_type)) in leftover {
+                if amount > 0 {
+                    allocations.push((id, vout, amount, transfer_type));
+                }
+            }
+        }
+
+        Ok(allocations
+            .into_iter()
+            .filter(|(_, _, amount, _)| *amount > 0)
+            .map(|(id, vout, amount, transfer_type)| {
+                let rune = &runes[&id];
+                let output = &outputs[vout];
+                let transfer_type = if output.is_op_return() {
+                    TransferType::Burn
+                } else {
+                    transfer_type
+                };
+
+                let mut metadata = HashMap::new();
+                if let Some(id) = rune.id {
+                    metadata.insert("id".to_string(), serde_json::json!(id.to_string()));
+                }
+                metadata.insert("divisibility".to_string(), serde_json::json!(rune.divisibility));
+                metadata.insert("symbol".to_string(), serde_json::json!(rune.symbol));
+                metadata.insert("vout".to_string(), serde_json::json!(vout));
+                metadata.insert("verified".to_string(), serde_json::json!(false));
+
+                RuneTransfer {
+                    rune_id: rune.spaced_name.clone(),
+                    from_address: String::new(),
+                    to_address: output.address.clone().unwrap_or_default(),
+                    amount,
+                    transfer_type,
+                    fee: None,
+                    metadata: Some(metadata),
+                }
+            })
+            .collect())
+    }
+
+    /// `block:tx` of `txid` in the block `block_hash`
+    async fn position(&self, node: &dyn NodeBackend, txid: &str, block_hash: &str) -> RuneResult<Option<RuneId>> {
+        let block = node.get_block(block_hash).await?;
+        Ok(block
+            .tx_ids
+            .iter()
+            .position(|id| id == txid)
+            .map(|tx| RuneId {
+                block: block.height,
+                tx: tx as u32,
+            }))
+    }
+
+    /// Rune etched at `id`, `None` when no etching is there
+    async fn etched(&self, node: &dyn NodeBackend, id: RuneId) -> RuneResult<Option<EtchedRune>> {
+        if let Some(rune) = self.etchings.lock().unwrap().cache_get(&id) {
+            return Ok(rune.clone());
+        }
+
+        let rune = match self.lookup(node, id).await {
+            Ok(rune) => rune,
+            // Edicts may name blocks and transactions that do not exist
+            Err(RuneError::NotFound(_) | RuneError::InvalidRequest(_)) => None,
+            Err(e) => return Err(e),
+        };
+        self.etchings.lock().unwrap().cache_set(id, rune.clone());
+        Ok(rune)
+    }
+
+    async fn lookup(&self, node: &dyn NodeBackend, id: RuneId) -> RuneResult<Option<EtchedRune>> {
+        let hash = node.get_block_hash(id.block).await?;
+        let block = node.get_block(&hash).await?;
+        let Some(txid) = block.tx_ids.get(id.tx as usize) else {
+            return Ok(None);
+        };
+
+        let raw = decode_hex(&node.get_raw_block_transaction(txid, &hash).await?)?;
+        let etching = output_scripts(&raw)
+            .ok_or_else(|| RuneError::NodeResponseError(format!("Malformed transaction {}", txid)))?
+            .into_iter()
+            .find(|script| is_runestone(script))
+            .and_then(|script| Runestone::decipher(&script))
+            .and_then(|runestone| runestone.etching);
+
+        Ok(etching.and_then(|etching| EtchedRune::new(&etching, Some(id))))
+    }
+}
+
+/// Moves `amount` of rune `id` to `vout`, taking it from what the
+/// transaction issued first and counting the rest as transferred in
+fn allocate(
+    allocations: &mut Vec<(RuneId, usize, u128, TransferType)>,
+    issued: &mut HashMap<RuneId, (u128, TransferType)>,
+    id: RuneId,
+    vout: usize,
+    amount: u128,
+) {
+    let mut remaining = amount;
+    if let Some((available, transfer_type)) = issued.get_mut(&id) {
+        let taken = remaining.min(*available);
+        *available -= taken;
+        remaining -= taken;
+        allocations.push((id, vout, taken, *transfer_type));
+    }
+    allocations.push((id, vout, remaining, TransferType::Transfer));
+}
+
+/// Output scripts of a serialized transaction, with or without witness data
+fn output_scripts(raw: &[u8]) -> Option<Vec<Vec<u8>>> {
+    let mut reader = raw.get(4..)?;
+
+    // Segwit marker and flag come before the inputs
+    if reader.first() == Some(&0) {
+        reader = reader.get(2..)?;
+    }
+
+    let inputs = read_compact_size(&mut reader)?;
+    for _ in 0..inputs {
+        reader = reader.get(36..)?;
+        let script_length = read_compact_size(&mut reader)?;
+        reader = reader.get(script_length as usize + 4..)?;
+    }
+
+    let count = read_compact_size(&mut reader)?;
+    let mut scripts = Vec::new();
+    for _ in 0..count {
+        reader = reader.get(8..)?;
+        let script_length = read_compact_size(&mut reader)? as usize;
+        scripts.push(reader.get(..script_length)?.to_vec());
+        reader = &reader[script_length..];
+    }
+    Some(scripts)
+}
+
+fn read_compact_size(reader: &mut &[u8]) -> Option<u64> {
+    let (&prefix, rest) = reader.split_first()?;
+    let (n, length) = match prefix {
+        0xfd => (u64::from(u16::from_le_bytes(rest.get(..2)?.try_into().ok()?)), 2),
+        0xfe => (u64::from(u32::from_le_bytes(rest.get(..4)?.try_into().ok()?)), 4),
+        0xff => (u64::from_le_bytes(rest.get(..8)?.try_into().ok()?), 8),
+        n => (u64::from(n), 0),
+    };
+    *reader = &rest[length..];
+    Some(n)
+}
+
+fn decode_hex(hex: &str) -> RuneResult<Vec<u8>> {
+    let invalid = || RuneError::NodeResponseError(format!("Invalid hex: {}", hex));
+    if !hex.len().is_multiple_of(2) {
+        return Err(invalid());
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(invalid)
+        })
+        .collect()
+}
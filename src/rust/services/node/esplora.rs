@@ -0,0 +1,301 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use async_trait::async_trait;
+use reqwest::Client as HttpClient;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{
+    error::{RuneError, RuneResult},
+    rune::{NetworkType, RunesTransactionResponse, RuneTransfer, TransactionStatus},
+};
+use crate::RpcCredentials;
+use super::{
+    backend::{unsupported, BackendKind, BlockInfo, NodeBackend},
+    connection::{NodeConfig, NodeStatus},
+    decoder::{RunestoneDecoder, TxOutput},
+    rest::RestClient,
+};
+
+/// Transactions returned per page by the block and address endpoints
+pub const ESPLORA_PAGE_SIZE: u32 = 25;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EsploraBlock {
+    pub id: String,
+    pub height: u64,
+    pub previousblockhash: Option<String>,
+    pub timestamp: u64,
+    #[serde(default)]
+    pub tx_count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EsploraTxStatus {
+    pub confirmed: bool,
+    pub block_height: Option<u64>,
+    pub block_hash: Option<String>,
+    pub block_time: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EsploraTxIn {
+    pub txid: String,
+    pub vout: u32,
+    pub prevout: Option<EsploraTxOut>,
+    #[serde(default)]
+    pub is_coinbase: bool,
+    pub sequence: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EsploraTxOut {
+    pub scriptpubkey: String,
+    pub scriptpubkey_type: String,
+    pub scriptpubkey_address: Option<String>,
+    pub value: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EsploraTransaction {
+    pub txid: String,
+    pub version: i32,
+    pub locktime: u32,
+    pub vin: Vec<EsploraTxIn>,
+    pub vout: Vec<EsploraTxOut>,
+    pub size: u32,
+    pub weight: u32,
+    pub fee: u64,
+    pub status: EsploraTxStatus,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EsploraUtxo {
+    pub txid: String,
+    pub vout: u32,
+    pub value: u64,
+    pub status: EsploraTxStatus,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EsploraMempool {
+    pub count: u64,
+    pub vsize: u64,
+    pub total_fee: u64,
+    /// `(feerate, vsize)` pairs, highest feerate first
+    pub fee_histogram: Vec<(f64, u64)>,
+}
+
+/// Client for the Esplora / electrs REST API
+pub struct EsploraClient {
+    rest: RestClient,
+}
+
+impl EsploraClient {
+    pub fn new(base_url: &str, client: Arc<HttpClient>, credentials: RpcCredentials) -> Self {
+        Self {
+            rest: RestClient::new(base_url, client, credentials),
+        }
+    }
+
+    /// Caps the requests in flight to the server, see `HttpClientConfig::max_concurrent_requests`
+    pub fn with_max_concurrent_requests(mut self, limit: Option<usize>) -> Self {
+        self.rest = self.rest.with_max_concurrent_requests(limit);
+        self
+    }
+
+    pub async fn tip_height(&self) -> RuneResult<u64> {
+        self.rest
+            .get_text("/blocks/tip/height")
+            .await?
+            .parse::<u64>()
+            .map_err(|e| RuneError::NodeResponseError(format!("Invalid height format: {}", e)))
+    }
+
+    pub async fn block_hash(&self, height: u64) -> RuneResult<String> {
+        self.rest.get_text(&format!("/block-height/{}", height)).await
+    }
+
+    pub async fn block(&self, hash: &str) -> RuneResult<EsploraBlock> {
+        self.rest.get_json(&format!("/block/{}", hash)).await
+    }
+
+    pub async fn block_txids(&self, hash: &str) -> RuneResult<Vec<String>> {
+        self.rest.get_json(&format!("/block/{}/txids", hash)).await
+    }
+
+    /// Returns up to `ESPLORA_PAGE_SIZE` transactions of a block starting at
+    /// index `start`, which must be a multiple of the page size
+    pub async fn block_txs(&self, hash: &str, start: u32) -> RuneResult<Vec<EsploraTransaction>> {
+        if !start.is_multiple_of(ESPLORA_PAGE_SIZE) {
+            return Err(RuneError::InvalidRequest(format!(
+                "Block transaction index {} is not a multiple of {}",
+                start, ESPLORA_PAGE_SIZE
+            )));
+        }
+        self.rest.get_json(&format!("/block/{}/txs/{}", hash, start)).await
+    }
+
+    pub async fn tx(&self, txid: &str) -> RuneResult<EsploraTransaction> {
+        self.rest.get_json(&format!("/tx/{}", txid)).await
+    }
+
+    pub async fn tx_hex(&self, txid: &str) -> RuneResult<String> {
+        self.rest.get_text(&format!("/tx/{}/hex", txid)).await
+    }
+
+    pub async fn tx_status(&self, txid: &str) -> RuneResult<EsploraTxStatus> {
+        self.rest.get_json(&format!("/tx/{}/status", txid)).await
+    }
+
+    pub async fn address_utxos(&self, address: &str) -> RuneResult<Vec<EsploraUtxo>> {
+        self.rest.get_json(&format!("/address/{}/utxo", address)).await
+    }
+
+    /// Mempool transactions of an address followed by its newest confirmed ones
+    pub async fn address_txs(&self, address: &str) -> RuneResult<Vec<EsploraTransaction>> {
+        self.rest.get_json(&format!("/address/{}/txs", address)).await
+    }
+
+    /// Confirmed transactions of an address, newest first. Pass the last txid
+    /// of the previous page to continue paging
+    pub async fn address_txs_chain(
+        &self,
+        address: &str,
+        last_seen_txid: Option<&str>,
+    ) -> RuneResult<Vec<EsploraTransaction>> {
+        let path = match last_seen_txid {
+            Some(txid) => format!("/address/{}/txs/chain/{}", address, txid),
+            None => format!("/address/{}/txs/chain", address),
+        };
+        self.rest.get_json(&path).await
+    }
+
+    pub async fn mempool(&self) -> RuneResult<EsploraMempool> {
+        self.rest.get_json("/mempool").await
+    }
+
+    /// Fee rate in sat/vB keyed by confirmation target in blocks
+    pub async fn fee_estimates(&self) -> RuneResult<BTreeMap<u16, f64>> {
+        let estimates: BTreeMap<String, f64> = self.rest.get_json("/fee-estimates").await?;
+
+        estimates
+            .into_iter()
+            .map(|(target, rate)| {
+                target
+                    .parse::<u16>()
+                    .map(|target| (target, rate))
+                    .map_err(|e| {
+                        RuneError::NodeResponseError(format!("Invalid confirmation target: {}", e))
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Esplora / electrs REST backend
+pub struct EsploraBackend {
+    client: EsploraClient,
+    network: NetworkType,
+    decoder: RunestoneDecoder,
+}
+
+impl EsploraBackend {
+    pub fn new(config: &NodeConfig, client: Arc<HttpClient>) -> Self {
+        Self {
+            client: EsploraClient::new(&config.rpc_url, client, RpcCredentials::new(config.auth()))
+                .with_max_concurrent_requests(config.http.max_concurrent_requests),
+            network: config.network,
+            decoder: RunestoneDecoder::new(),
+        }
+    }
+
+    pub fn client(&self) -> &EsploraClient {
+        &self.client
+    }
+}
+
+#[async_trait]
+impl NodeBackend for EsploraBackend {
+    fn kind(&self) -> BackendKind {
+        BackendKind::Esplora
+    }
+
+    async fn health_check(&self) -> RuneResult<NodeStatus> {
+        let block_height = self.get_block_height().await?;
+
+        // Esplora does not expose peer or sync information
+        Ok(NodeStatus {
+            is_connected: true,
+            block_height,
+            peer_count: 0,
+            sync_progress: 1.0,
+        })
+    }
+
+    async fn get_block_height(&self) -> RuneResult<u64> {
+        self.client.tip_height().await
+    }
+
+    async fn get_block_hash(&self, height: u64) -> RuneResult<String> {
+        self.client.block_hash(height).await
+    }
+
+    async fn get_block(&self, hash: &str) -> RuneResult<BlockInfo> {
+        let block = self.client.block(hash).await?;
+        let tx_ids = self.client.block_txids(hash).await?;
+
+        Ok(BlockInfo {
+            hash: block.id,
+            height: block.height,
+            previous_block_hash: block.previousblockhash,
+            timestamp: block.timestamp,
+            tx_ids,
+        })
+    }
+
+    async fn get_raw_transaction(&self, tx_id: &str) -> RuneResult<String> {
+        self.client.tx_hex(tx_id).await
+    }
+
+    async fn get_transaction(&self, tx_id: &str) -> RuneResult<RunesTransactionResponse> {
+        let tx = self.client.tx(tx_id).await?;
+
+        let confirmation_count = match tx.status.block_height {
+            Some(height) if tx.status.confirmed => {
+                let tip = self.get_block_height().await?;
+                (tip.saturating_sub(height) + 1) as u32
+            }
+            _ => 0,
+        };
+
+        // Esplora knows nothing about runes, so they are read from the runestone
+        let outputs = tx
+            .vout
+            .into_iter()
+            .map(|out| TxOutput::from_hex(&out.scriptpubkey, out.scriptpubkey_address))
+            .collect::<RuneResult<Vec<_>>>()?;
+        let runes = self
+            .decoder
+            .transfers(self, &tx.txid, tx.status.block_hash.as_deref(), &outputs)
+            .await?;
+
+        Ok(RunesTransactionResponse {
+            transaction_id: tx.txid,
+            runes,
+            block_height: tx.status.block_height.map(|h| h as u32),
+            confirmation_count,
+            timestamp: tx.status.block_time.unwrap_or(0),
+            network_type: self.network,
+            status: if tx.status.confirmed {
+                TransactionStatus::Confirmed
+            } else {
+                TransactionStatus::Pending
+            },
+        })
+    }
+
+    async fn get_address_transfers(&self, _address: &str) -> RuneResult<Vec<RuneTransfer>> {
+        // Esplora indexes addresses but knows nothing about runes
+        Err(unsupported(self.kind(), "Rune transfer lookup"))
+    }
+}
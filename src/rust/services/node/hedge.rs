@@ -0,0 +1,251 @@
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use async_trait::async_trait;
+use prometheus::core::Metric;
+
+use crate::services::metrics::{
+    NODE_HEDGED_REQUESTS_TOTAL, NODE_HEDGE_WINS_TOTAL, NODE_REQUESTS_TOTAL,
+    NODE_REQUEST_DURATION_SECONDS, NODE_REQUEST_FAILURES_TOTAL,
+};
+use crate::types::{
+    error::RuneResult,
+    rune::{RuneEntry, RuneOutput, RunePage, RunesTransactionResponse, RuneTransfer},
+};
+use super::{
+    backend::{BackendKind, BlockInfo, NodeBackend},
+    connection::NodeStatus,
+};
+
+#[derive(Debug, Clone)]
+pub struct HedgeConfig {
+    /// `NodeBackend` method names that may be hedged, e.g. `get_raw_transaction`
+    pub methods: HashSet<String>,
+    /// Latency percentile of the method after which the hedge is sent
+    pub percentile: f64,
+    /// Observations required before the percentile is trusted
+    pub min_samples: u64,
+    /// Delay used until enough samples have been recorded
+    pub default_delay: Duration,
+    pub min_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for HedgeConfig {
+    fn default() -> Self {
+        Self {
+            methods: HashSet::new(),
+            percentile: 0.95,
+            min_samples: 50,
+            default_delay: Duration::from_millis(250),
+            min_delay: Duration::from_millis(10),
+            max_delay: Duration::from_secs(2),
+        }
+    }
+}
+
+impl HedgeConfig {
+    /// Opts `method` into hedging
+    pub fn hedge(mut self, method: &str) -> Self {
+        self.methods.insert(method.to_string());
+        self
+    }
+}
+
+/// Sends slow read-only calls to a second backend and keeps the first answer.
+///
+/// Every call is timed into `NODE_REQUEST_DURATION_SECONDS`; for opted-in
+/// methods the hedge fires once the primary has been outstanding longer than
+/// the configured percentile of that histogram.
+pub struct HedgedBackend {
+    primary: Arc<dyn NodeBackend>,
+    secondary: Arc<dyn NodeBackend>,
+    config: HedgeConfig,
+}
+
+impl HedgedBackend {
+    pub fn new(
+        primary: Arc<dyn NodeBackend>,
+        secondary: Arc<dyn NodeBackend>,
+        config: HedgeConfig,
+    ) -> Self {
+        Self {
+            primary,
+            secondary,
+            config,
+        }
+    }
+
+    /// Current hedge delay for `method`, derived from its latency histogram
+    pub fn hedge_delay(&self, method: &str) -> Duration {
+        let metric = NODE_REQUEST_DURATION_SECONDS
+            .with_label_values(&[method])
+            .metric();
+        let histogram = metric.get_histogram();
+
+        let samples = histogram.get_sample_count();
+        if samples < self.config.min_samples.max(1) {
+            return self.config.default_delay;
+        }
+
+        let target = (samples as f64 * self.config.percentile).ceil() as u64;
+        let upper_bound = histogram
+            .get_bucket()
+            .iter()
+            .find(|bucket| bucket.get_cumulative_count() >= target)
+            .map(|bucket| bucket.get_upper_bound())
+            .unwrap_or_else(|| self.config.max_delay.as_secs_f64());
+
+        Duration::from_secs_f64(upper_bound).clamp(self.config.min_delay, self.config.max_delay)
+    }
+
+    async fn observe<T, Fut>(method: &str, request: Fut) -> RuneResult<T>
+    where
+        Fut: Future<Output = RuneResult<T>>,
+    {
+        let started = Instant::now();
+        NODE_REQUESTS_TOTAL.with_label_values(&[method]).inc();
+
+        let result = request.await;
+        NODE_REQUEST_DURATION_SECONDS
+            .with_label_values(&[method])
+            .observe(started.elapsed().as_secs_f64());
+        if result.is_err() {
+            NODE_REQUEST_FAILURES_TOTAL.with_label_values(&[method]).inc();
+        }
+        result
+    }
+
+    async fn request<T, F, Fut>(&self, method: &str, call: F) -> RuneResult<T>
+    where
+        F: Fn(Arc<dyn NodeBackend>) -> Fut,
+        Fut: Future<Output = RuneResult<T>>,
+    {
+        let primary = Self::observe(method, call(self.primary.clone()));
+        if !self.config.methods.contains(method) {
+            return primary.await;
+        }
+
+        tokio::pin!(primary);
+        tokio::select! {
+            result = &mut primary => return result,
+            _ = tokio::time::sleep(self.hedge_delay(method)) => {}
+        }
+
+        NODE_HEDGED_REQUESTS_TOTAL.with_label_values(&[method]).inc();
+        let hedge = Self::observe(method, call(self.secondary.clone()));
+        tokio::pin!(hedge);
+
+        // First successful answer wins, an error waits for the other side
+        tokio::select! {
+            result = &mut primary => match result {
+                Ok(value) => Ok(value),
+                Err(_) => {
+                    let result = hedge.await;
+                    if result.is_ok() {
+                        NODE_HEDGE_WINS_TOTAL.with_label_values(&[method]).inc();
+                    }
+                    result
+                }
+            },
+            result = &mut hedge => match result {
+                Ok(value) => {
+                    NODE_HEDGE_WINS_TOTAL.with_label_values(&[method]).inc();
+                    Ok(value)
+                }
+                Err(_) => primary.await,
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl NodeBackend for HedgedBackend {
+    fn kind(&self) -> BackendKind {
+        self.primary.kind()
+    }
+
+    async fn health_check(&self) -> RuneResult<NodeStatus> {
+        self.request("health_check", |node| async move { node.health_check().await })
+            .await
+    }
+
+    async fn get_block_height(&self) -> RuneResult<u64> {
+        self.request("get_block_height", |node| async move { node.get_block_height().await })
+            .await
+    }
+
+    async fn get_block_hash(&self, height: u64) -> RuneResult<String> {
+        self.request("get_block_hash", |node| async move { node.get_block_hash(height).await })
+            .await
+    }
+
+    async fn get_block(&self, hash: &str) -> RuneResult<BlockInfo> {
+        self.request("get_block", |node| async move { node.get_block(hash).await })
+            .await
+    }
+
+    async fn get_raw_transaction(&self, tx_id: &str) -> RuneResult<String> {
+        self.request("get_raw_transaction", |node| async move {
+            node.get_raw_transaction(tx_id).await
+        })
+        .await
+    }
+
+    async fn get_raw_block_transaction(&self, tx_id: &str, block_hash: &str) -> RuneResult<String> {
+        self.request("get_raw_block_transaction", |node| async move {
+            node.get_raw_block_transaction(tx_id, block_hash).await
+        })
+        .await
+    }
+
+    async fn get_transaction(&self, tx_id: &str) -> RuneResult<RunesTransactionResponse> {
+        self.request("get_transaction", |node| async move { node.get_transaction(tx_id).await })
+            .await
+    }
+
+    async fn get_block_transactions(&self, hash: &str) -> RuneResult<Vec<RunesTransactionResponse>> {
+        self.request("get_block_transactions", |node| async move {
+            node.get_block_transactions(hash).await
+        })
+        .await
+    }
+
+    async fn get_address_transfers(&self, address: &str) -> RuneResult<Vec<RuneTransfer>> {
+        self.request("get_address_transfers", |node| async move {
+            node.get_address_transfers(address).await
+        })
+        .await
+    }
+
+    async fn get_rune(&self, rune: &str) -> RuneResult<RuneEntry> {
+        self.request("get_rune", |node| async move { node.get_rune(rune).await })
+            .await
+    }
+
+    async fn get_runes(&self, page: Option<u32>) -> RuneResult<RunePage> {
+        self.request("get_runes", |node| async move { node.get_runes(page).await })
+            .await
+    }
+
+    async fn get_output(&self, outpoint: &str) -> RuneResult<RuneOutput> {
+        self.request("get_output", |node| async move { node.get_output(outpoint).await })
+            .await
+    }
+
+    async fn get_address_outputs(&self, address: &str) -> RuneResult<Vec<RuneOutput>> {
+        self.request("get_address_outputs", |node| async move {
+            node.get_address_outputs(address).await
+        })
+        .await
+    }
+
+    async fn get_block_etchings(&self, height: u64) -> RuneResult<Vec<RuneEntry>> {
+        self.request("get_block_etchings", |node| async move {
+            node.get_block_etchings(height).await
+        })
+        .await
+    }
+
+}
@@ -0,0 +1,14 @@
+pub mod backend;
+pub mod bitcoind;
+pub mod breaker;
+pub mod connection;
+pub mod decoder;
+pub mod esplora;
+pub mod ord;
+pub mod pool;
+pub mod rest;
+pub mod sync;
+
+/// Hedge delays come from the Prometheus latency histograms
+#[cfg(feature = "metrics")]
+pub mod hedge;
@@ -0,0 +1,475 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use async_trait::async_trait;
+use futures::{stream, StreamExt, TryStreamExt};
+use reqwest::Client as HttpClient;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::types::{
+    error::{RuneError, RuneResult},
+    rune::{
+        parse_spaced_rune, NetworkType, RuneBalance, RuneEntry, RuneOutput, RunePage, RuneTerms,
+        RunesTransactionResponse, RuneTransfer, TransactionStatus, TransferType,
+    },
+};
+use crate::RpcCredentials;
+use super::{
+    backend::{unsupported, BackendKind, BlockInfo, NodeBackend},
+    connection::{NodeConfig, NodeStatus},
+    rest::RestClient,
+};
+
+/// Output lookups `OrdBackend` runs in parallel for one address or transaction
+const OUTPUT_LOOKUP_CONCURRENCY: usize = 8;
+
+/// Index status reported by `ord server`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrdStatus {
+    pub chain: String,
+    pub height: Option<u64>,
+    #[serde(default)]
+    pub rune_index: bool,
+    #[serde(default)]
+    pub runes: u64,
+    #[serde(default)]
+    pub unrecoverably_reorged: bool,
+}
+
+/// Block summary from `/block/{height}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrdBlock {
+    pub hash: String,
+    pub height: u64,
+    pub best_height: u64,
+    /// Spaced names of the runes etched in this block
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub runes: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OrdBlockInfo {
+    hash: String,
+    height: u64,
+    previous_blockhash: Option<String>,
+    timestamp: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct OrdTransaction {
+    txid: String,
+    etching: Option<String>,
+    transaction: OrdTransactionBody,
+}
+
+#[derive(Debug, Deserialize)]
+struct OrdTransactionBody {
+    output: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OrdOutput {
+    outpoint: Option<String>,
+    address: Option<String>,
+    #[serde(default)]
+    value: u64,
+    #[serde(default)]
+    spent: bool,
+    #[serde(default)]
+    confirmations: u32,
+    /// `null` for outputs without runes
+    #[serde(default, deserialize_with = "null_as_default")]
+    runes: BTreeMap<String, OrdPile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OrdAddress {
+    #[serde(default, deserialize_with = "null_as_default")]
+    outputs: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OrdPile {
+    amount: u128,
+    divisibility: u8,
+    symbol: Option<char>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OrdRuneResponse {
+    entry: OrdRuneEntry,
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OrdRunesResponse {
+    entries: Vec<(String, OrdRuneEntry)>,
+    prev: Option<u32>,
+    next: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OrdRuneEntry {
+    block: u64,
+    #[serde(default)]
+    burned: u128,
+    divisibility: u8,
+    etching: String,
+    #[serde(default)]
+    mints: u128,
+    number: u64,
+    #[serde(default)]
+    premine: u128,
+    spaced_rune: String,
+    symbol: Option<char>,
+    terms: Option<OrdTerms>,
+    timestamp: u64,
+    #[serde(default)]
+    turbo: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct OrdTerms {
+    amount: Option<u128>,
+    cap: Option<u128>,
+    #[serde(default)]
+    height: (Option<u64>, Option<u64>),
+    #[serde(default)]
+    offset: (Option<u64>, Option<u64>),
+}
+
+/// Treats an explicit `null` like a missing field
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+impl OrdRuneEntry {
+    fn into_entry(self, id: String) -> RuneResult<RuneEntry> {
+        let (name, spacers) = parse_spaced_rune(&self.spaced_rune)
+            .map_err(|e| RuneError::NodeResponseError(e.to_string()))?;
+
+        Ok(RuneEntry {
+            id,
+            name,
+            spaced_name: self.spaced_rune,
+            number: self.number,
+            etching: self.etching,
+            block: self.block,
+            divisibility: self.divisibility,
+            symbol: self.symbol,
+            spacers,
+            premine: self.premine,
+            terms: self.terms.map(|terms| RuneTerms {
+                amount: terms.amount,
+                cap: terms.cap,
+                height_start: terms.height.0,
+                height_end: terms.height.1,
+                offset_start: terms.offset.0,
+                offset_end: terms.offset.1,
+            }),
+            turbo: self.turbo,
+            mints: self.mints,
+            burned: self.burned,
+            timestamp: self.timestamp,
+            holders: None,
+        })
+    }
+}
+
+impl OrdOutput {
+    fn into_output(self, outpoint: &str) -> RuneOutput {
+        RuneOutput {
+            outpoint: self.outpoint.unwrap_or_else(|| outpoint.to_string()),
+            address: self.address,
+            value: self.value,
+            spent: self.spent,
+            confirmations: self.confirmations,
+            runes: self
+                .runes
+                .into_iter()
+                .map(|(rune, pile)| RuneBalance {
+                    rune,
+                    amount: pile.amount,
+                    divisibility: pile.divisibility,
+                    symbol: pile.symbol,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Client for the JSON API of an `ord server`
+pub struct OrdClient {
+    rest: RestClient,
+}
+
+impl OrdClient {
+    pub fn new(base_url: &str, client: Arc<HttpClient>, credentials: RpcCredentials) -> Self {
+        Self {
+            rest: RestClient::new(base_url, client, credentials),
+        }
+    }
+
+    /// Caps the requests in flight to the server, see `HttpClientConfig::max_concurrent_requests`
+    pub fn with_max_concurrent_requests(mut self, limit: Option<usize>) -> Self {
+        self.rest = self.rest.with_max_concurrent_requests(limit);
+        self
+    }
+
+    pub async fn status(&self) -> RuneResult<OrdStatus> {
+        self.rest.get_json("/status").await
+    }
+
+    /// Looks a rune up by spaced name, plain name or `block:tx` id
+    pub async fn rune(&self, rune: &str) -> RuneResult<RuneEntry> {
+        let response: OrdRuneResponse = self.rest.get_json(&format!("/rune/{}", rune)).await?;
+        response.entry.into_entry(response.id)
+    }
+
+    /// Fetches a page of etched runes, newest first; `None` is the first page
+    pub async fn runes(&self, page: Option<u32>) -> RuneResult<RunePage> {
+        let path = match page {
+            Some(page) => format!("/runes/{}", page),
+            None => "/runes".to_string(),
+        };
+        let response: OrdRunesResponse = self.rest.get_json(&path).await?;
+
+        Ok(RunePage {
+            entries: response
+                .entries
+                .into_iter()
+                .map(|(id, entry)| entry.into_entry(id))
+                .collect::<RuneResult<_>>()?,
+            prev: response.prev,
+            next: response.next,
+        })
+    }
+
+    pub async fn output(&self, outpoint: &str) -> RuneResult<RuneOutput> {
+        let output: OrdOutput = self.rest.get_json(&format!("/output/{}", outpoint)).await?;
+        Ok(output.into_output(outpoint))
+    }
+
+    /// Outpoints currently owned by `address`
+    pub async fn address_outputs(&self, address: &str) -> RuneResult<Vec<String>> {
+        let address: OrdAddress = self.rest.get_json(&format!("/address/{}", address)).await?;
+        Ok(address.outputs)
+    }
+
+    pub async fn block(&self, height: u64) -> RuneResult<OrdBlock> {
+        self.rest.get_json(&format!("/block/{}", height)).await
+    }
+
+    pub async fn block_height(&self) -> RuneResult<u64> {
+        self.rest
+            .get_text("/blockheight")
+            .await?
+            .parse::<u64>()
+            .map_err(|e| RuneError::NodeResponseError(format!("Invalid height format: {}", e)))
+    }
+
+    /// Compares an entry from our own index with ord's view of the same rune,
+    /// returning the names of the fields that disagree
+    pub async fn cross_check(&self, local: &RuneEntry) -> RuneResult<Vec<&'static str>> {
+        let remote = self.rune(&local.id).await?;
+
+        let checks = [
+            ("spaced_name", local.spaced_name == remote.spaced_name),
+            ("etching", local.etching == remote.etching),
+            ("block", local.block == remote.block),
+            ("divisibility", local.divisibility == remote.divisibility),
+            ("symbol", local.symbol == remote.symbol),
+            ("premine", local.premine == remote.premine),
+            ("terms", local.terms == remote.terms),
+            ("turbo", local.turbo == remote.turbo),
+            ("mints", local.mints == remote.mints),
+            ("burned", local.burned == remote.burned),
+        ];
+
+        let mismatches: Vec<_> = checks
+            .into_iter()
+            .filter(|(_, matches)| !matches)
+            .map(|(field, _)| field)
+            .collect();
+
+        if !mismatches.is_empty() {
+            tracing::warn!("Rune {} differs from ord in {:?}", local.id, mismatches);
+        }
+        Ok(mismatches)
+    }
+
+    async fn block_info(&self, block: &str) -> RuneResult<OrdBlockInfo> {
+        self.rest.get_json(&format!("/r/blockinfo/{}", block)).await
+    }
+}
+
+/// `ord server` backend using its JSON API
+pub struct OrdBackend {
+    client: OrdClient,
+    network: NetworkType,
+}
+
+impl OrdBackend {
+    pub fn new(config: &NodeConfig, client: Arc<HttpClient>) -> Self {
+        Self {
+            client: OrdClient::new(&config.rpc_url, client, RpcCredentials::new(config.auth()))
+                .with_max_concurrent_requests(config.http.max_concurrent_requests),
+            network: config.network,
+        }
+    }
+
+    pub fn client(&self) -> &OrdClient {
+        &self.client
+    }
+}
+
+#[async_trait]
+impl NodeBackend for OrdBackend {
+    fn kind(&self) -> BackendKind {
+        BackendKind::Ord
+    }
+
+    async fn health_check(&self) -> RuneResult<NodeStatus> {
+        let block_height = self.get_block_height().await?;
+
+        Ok(NodeStatus {
+            is_connected: true,
+            block_height,
+            peer_count: 0,
+            sync_progress: 1.0,
+        })
+    }
+
+    async fn get_block_height(&self) -> RuneResult<u64> {
+        self.client.block_height().await
+    }
+
+    async fn get_block_hash(&self, height: u64) -> RuneResult<String> {
+        self.client.rest.get_text(&format!("/blockhash/{}", height)).await
+    }
+
+    async fn get_block(&self, hash: &str) -> RuneResult<BlockInfo> {
+        let block = self.client.block_info(hash).await?;
+
+        // ord does not list transaction ids for a block
+        Ok(BlockInfo {
+            hash: block.hash,
+            height: block.height,
+            previous_block_hash: block.previous_blockhash,
+            timestamp: block.timestamp,
+            tx_ids: Vec::new(),
+        })
+    }
+
+    async fn get_raw_transaction(&self, _tx_id: &str) -> RuneResult<String> {
+        Err(unsupported(self.kind(), "Raw transaction lookup"))
+    }
+
+    async fn get_transaction(&self, tx_id: &str) -> RuneResult<RunesTransactionResponse> {
+        let tx: OrdTransaction = self.client.rest.get_json(&format!("/tx/{}", tx_id)).await?;
+
+        let outpoints = (0..tx.transaction.output.len()).map(|vout| format!("{}:{}", tx.txid, vout));
+        let outputs: Vec<RuneOutput> = stream::iter(outpoints)
+            .map(|outpoint| async move { self.client.output(&outpoint).await })
+            .buffered(OUTPUT_LOOKUP_CONCURRENCY)
+            .try_collect()
+            .await?;
+        let confirmation_count = outputs.last().map_or(0, |output| output.confirmations);
+
+        let mut runes = Vec::new();
+        for (vout, output) in outputs.into_iter().enumerate() {
+            for balance in output.runes {
+                // Runes of the etching transaction's outputs can only come from its premine
+                let transfer_type = if tx.etching.as_deref() == Some(balance.rune.as_str()) {
+                    TransferType::Etch
+                } else {
+                    TransferType::Transfer
+                };
+
+                let mut metadata = HashMap::new();
+                metadata.insert("divisibility".to_string(), serde_json::json!(balance.divisibility));
+                metadata.insert("symbol".to_string(), serde_json::json!(balance.symbol));
+                metadata.insert("vout".to_string(), serde_json::json!(vout));
+
+                runes.push(RuneTransfer {
+                    rune_id: balance.rune,
+                    from_address: String::new(),
+                    to_address: output.address.clone().unwrap_or_default(),
+                    amount: balance.amount,
+                    transfer_type,
+                    fee: None,
+                    metadata: Some(metadata),
+                });
+            }
+        }
+
+        let (block_height, timestamp) = if confirmation_count > 0 {
+            let tip = self.get_block_height().await?;
+            let height = (tip + 1).saturating_sub(confirmation_count as u64);
+            let block = self.client.block_info(&height.to_string()).await?;
+            (Some(height as u32), block.timestamp)
+        } else {
+            (None, 0)
+        };
+
+        Ok(RunesTransactionResponse {
+            transaction_id: tx.txid,
+            runes,
+            block_height,
+            confirmation_count,
+            timestamp,
+            network_type: self.network,
+            status: if confirmation_count > 0 {
+                TransactionStatus::Confirmed
+            } else {
+                TransactionStatus::Pending
+            },
+        })
+    }
+
+    async fn get_address_transfers(&self, _address: &str) -> RuneResult<Vec<RuneTransfer>> {
+        // ord exposes balances per address, not a transfer history
+        Err(unsupported(self.kind(), "Address transfer history"))
+    }
+
+    async fn get_rune(&self, rune: &str) -> RuneResult<RuneEntry> {
+        self.client.rune(rune).await
+    }
+
+    async fn get_runes(&self, page: Option<u32>) -> RuneResult<RunePage> {
+        self.client.runes(page).await
+    }
+
+    async fn get_output(&self, outpoint: &str) -> RuneResult<RuneOutput> {
+        self.client.output(outpoint).await
+    }
+
+    async fn get_address_outputs(&self, address: &str) -> RuneResult<Vec<RuneOutput>> {
+        let outpoints = self.client.address_outputs(address).await?;
+
+        // ord has no bulk output lookup, keep a few requests in flight
+        let outputs: Vec<RuneOutput> = stream::iter(outpoints)
+            .map(|outpoint| async move { self.client.output(&outpoint).await })
+            .buffered(OUTPUT_LOOKUP_CONCURRENCY)
+            .try_collect()
+            .await?;
+
+        Ok(outputs
+            .into_iter()
+            .filter(|output| !output.spent && !output.runes.is_empty())
+            .collect())
+    }
+
+    async fn get_block_etchings(&self, height: u64) -> RuneResult<Vec<RuneEntry>> {
+        let block = self.client.block(height).await?;
+
+        let mut etchings = Vec::with_capacity(block.runes.len());
+        for rune in &block.runes {
+            etchings.push(self.client.rune(rune).await?);
+        }
+        Ok(etchings)
+    }
+}
@@ -0,0 +1,404 @@
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use async_trait::async_trait;
+use reqwest::Client as HttpClient;
+use serde::Serialize;
+use tokio::task::JoinHandle;
+
+#[cfg(feature = "metrics")]
+use crate::services::metrics::{
+    NODE_BLOCK_HEIGHT, NODE_FAILOVERS_TOTAL, NODE_HEALTHY, NODE_LAGGING, NODE_LATENCY_SECONDS,
+};
+use crate::types::{
+    error::{RuneError, RuneResult},
+    rune::{RuneEntry, RuneOutput, RunePage, RunesTransactionResponse, RuneTransfer},
+};
+use super::{
+    backend::{create_backend, is_node_failure, BackendKind, BlockInfo, NodeBackend},
+    connection::{NodeConfig, NodeStatus},
+};
+
+/// Weight of the newest sample in the smoothed latency
+const LATENCY_SMOOTHING: f64 = 0.3;
+
+#[derive(Debug, Clone)]
+pub struct PoolConfig {
+    pub health_check_interval: Duration,
+    /// Blocks a node may trail the best known tip before it is deprioritised
+    pub max_block_lag: u64,
+    /// Consecutive request failures after which a node is marked unhealthy
+    pub failure_threshold: u32,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            health_check_interval: Duration::from_secs(15),
+            max_block_lag: 2,
+            failure_threshold: 3,
+        }
+    }
+}
+
+/// Point in time view of a pooled node, as reported to metrics and health endpoints
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolNodeStatus {
+    pub name: String,
+    pub backend: BackendKind,
+    pub healthy: bool,
+    pub lagging: bool,
+    pub latency_ms: Option<f64>,
+    pub consecutive_failures: u32,
+    pub last_status: Option<NodeStatus>,
+    pub last_error: Option<String>,
+}
+
+struct PoolMember {
+    name: String,
+    backend: Arc<dyn NodeBackend>,
+    state: RwLock<MemberState>,
+}
+
+#[derive(Default)]
+struct MemberState {
+    unhealthy: bool,
+    lagging: bool,
+    latency: Option<f64>,
+    consecutive_failures: u32,
+    last_status: Option<NodeStatus>,
+    last_error: Option<String>,
+}
+
+impl PoolMember {
+    fn record_success(&self, elapsed: Duration) {
+        let mut state = self.state.write().unwrap();
+        let sample = elapsed.as_secs_f64();
+        state.latency = Some(match state.latency {
+            Some(latency) => latency * (1.0 - LATENCY_SMOOTHING) + sample * LATENCY_SMOOTHING,
+            None => sample,
+        });
+        state.consecutive_failures = 0;
+        state.unhealthy = false;
+    }
+
+    fn record_failure(&self, error: &RuneError, threshold: u32) {
+        let mut state = self.state.write().unwrap();
+        state.consecutive_failures += 1;
+        state.last_error = Some(error.to_string());
+        if state.consecutive_failures >= threshold {
+            state.unhealthy = true;
+        }
+    }
+
+    fn status(&self) -> PoolNodeStatus {
+        let state = self.state.read().unwrap();
+        PoolNodeStatus {
+            name: self.name.clone(),
+            backend: self.backend.kind(),
+            healthy: !state.unhealthy,
+            lagging: state.lagging,
+            latency_ms: state.latency.map(|latency| latency * 1000.0),
+            consecutive_failures: state.consecutive_failures,
+            last_status: state.last_status.clone(),
+            last_error: state.last_error.clone(),
+        }
+    }
+
+    #[cfg(feature = "metrics")]
+    fn publish_metrics(&self) {
+        let status = self.status();
+        NODE_HEALTHY
+            .with_label_values(&[&self.name, status.backend.as_str()])
+            .set(status.healthy as i64);
+        NODE_LAGGING
+            .with_label_values(&[&self.name])
+            .set(status.lagging as i64);
+        if let Some(node_status) = &status.last_status {
+            NODE_BLOCK_HEIGHT
+                .with_label_values(&[&self.name])
+                .set(node_status.block_height as i64);
+        }
+        if let Some(latency_ms) = status.latency_ms {
+            NODE_LATENCY_SECONDS
+                .with_label_values(&[&self.name])
+                .set(latency_ms / 1000.0);
+        }
+    }
+
+    #[cfg(not(feature = "metrics"))]
+    fn publish_metrics(&self) {}
+}
+
+/// Spreads node calls over several backends.
+///
+/// Requests go to the healthiest, fastest node first and are retried on the
+/// next one when it fails with a connectivity or node-side error. Nodes that
+/// trail the best known tip by more than `max_block_lag` are only used when
+/// nothing better is available.
+pub struct NodePool {
+    members: Vec<PoolMember>,
+    config: PoolConfig,
+}
+
+impl NodePool {
+    pub fn new(nodes: Vec<(String, Arc<dyn NodeBackend>)>, config: PoolConfig) -> Self {
+        let members = nodes
+            .into_iter()
+            .map(|(name, backend)| PoolMember {
+                name,
+                backend,
+                state: RwLock::new(MemberState::default()),
+            })
+            .collect();
+
+        Self { members, config }
+    }
+
+    /// Builds one backend per config, named after its `rpc_url`
+    pub fn from_configs(
+        configs: &[NodeConfig],
+        client: Arc<HttpClient>,
+        config: PoolConfig,
+    ) -> Self {
+        let nodes = configs
+            .iter()
+            .map(|node| (node.rpc_url.clone(), create_backend(node, client.clone())))
+            .collect();
+
+        Self::new(nodes, config)
+    }
+
+    pub fn status(&self) -> Vec<PoolNodeStatus> {
+        self.members.iter().map(PoolMember::status).collect()
+    }
+
+    /// Probes every node, refreshes lag detection and publishes per-node metrics
+    pub async fn check_health(&self) {
+        let probes: Vec<_> = self
+            .members
+            .iter()
+            .map(|member| {
+                let backend = member.backend.clone();
+                tokio::spawn(async move {
+                    let started = Instant::now();
+                    let result = backend.health_check().await;
+                    (result, started.elapsed())
+                })
+            })
+            .collect();
+
+        let mut results = Vec::with_capacity(probes.len());
+        for probe in probes {
+            results.push(match probe.await {
+                Ok(result) => result,
+                Err(e) => (Err(RuneError::InternalError(e.to_string())), Duration::ZERO),
+            });
+        }
+
+        let best_height = results
+            .iter()
+            .filter_map(|(result, _)| result.as_ref().ok())
+            .filter(|status| status.is_connected)
+            .map(|status| status.block_height)
+            .max();
+
+        for (member, (result, elapsed)) in self.members.iter().zip(results) {
+            match result {
+                Ok(status) if status.is_connected => {
+                    member.record_success(elapsed);
+                    let mut state = member.state.write().unwrap();
+                    state.lagging = best_height
+                        .map(|best| best.saturating_sub(status.block_height) > self.config.max_block_lag)
+                        .unwrap_or(false);
+                    if state.lagging {
+                        tracing::warn!(
+                            "Node {} is lagging at height {}",
+                            member.name,
+                            status.block_height
+                        );
+                    }
+                    state.last_status = Some(status);
+                }
+                Ok(status) => {
+                    let mut state = member.state.write().unwrap();
+                    state.unhealthy = true;
+                    state.last_error = Some("Node reports it is not connected".to_string());
+                    state.last_status = Some(status);
+                }
+                Err(e) => {
+                    tracing::warn!("Health check for node {} failed: {}", member.name, e);
+                    // A failed probe is conclusive, no need to wait for the threshold
+                    member.record_failure(&e, 1);
+                }
+            }
+            member.publish_metrics();
+        }
+    }
+
+    /// Runs `check_health` every `health_check_interval` until the handle is aborted
+    pub fn spawn_health_checks(self: &Arc<Self>) -> JoinHandle<()> {
+        let pool = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(pool.config.health_check_interval);
+            loop {
+                interval.tick().await;
+                pool.check_health().await;
+            }
+        })
+    }
+
+    /// Members ordered by preference: healthy before unhealthy, in sync before
+    /// lagging, then by smoothed latency. Ties keep the configured order.
+    fn candidates(&self) -> Vec<&PoolMember> {
+        let mut candidates: Vec<(&PoolMember, (bool, bool, f64))> = self
+            .members
+            .iter()
+            .map(|member| {
+                let state = member.state.read().unwrap();
+                let key = (state.unhealthy, state.lagging, state.latency.unwrap_or(f64::MAX));
+                (member, key)
+            })
+            .collect();
+
+        candidates.sort_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        candidates.into_iter().map(|(member, _)| member).collect()
+    }
+
+    async fn route<T, F, Fut>(&self, method: &str, call: F) -> RuneResult<T>
+    where
+        F: Fn(Arc<dyn NodeBackend>) -> Fut,
+        Fut: Future<Output = RuneResult<T>>,
+    {
+        let mut last_error = None;
+
+        for member in self.candidates() {
+            #[cfg(feature = "metrics")]
+            if last_error.is_some() {
+                NODE_FAILOVERS_TOTAL
+                    .with_label_values(&[&member.name, method])
+                    .inc();
+            }
+
+            let started = Instant::now();
+            match call(member.backend.clone()).await {
+                Ok(value) => {
+                    member.record_success(started.elapsed());
+                    member.publish_metrics();
+                    return Ok(value);
+                }
+                Err(e) if should_fail_over(&e) => {
+                    tracing::warn!("{} on node {} failed: {}", method, member.name, e);
+                    if !matches!(e, RuneError::UnsupportedOperation(_)) {
+                        member.record_failure(&e, self.config.failure_threshold);
+                        member.publish_metrics();
+                    }
+                    last_error = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            RuneError::NodeConnectionError("No nodes configured".to_string())
+        }))
+    }
+}
+
+/// Another backend may succeed where this one failed or lacks the operation
+fn should_fail_over(error: &RuneError) -> bool {
+    is_node_failure(error) || matches!(error, RuneError::UnsupportedOperation(_))
+}
+
+#[async_trait]
+impl NodeBackend for NodePool {
+    fn kind(&self) -> BackendKind {
+        self.candidates()
+            .first()
+            .map(|member| member.backend.kind())
+            .unwrap_or_default()
+    }
+
+    async fn health_check(&self) -> RuneResult<NodeStatus> {
+        self.route("health_check", |node| async move { node.health_check().await })
+            .await
+    }
+
+    async fn get_block_height(&self) -> RuneResult<u64> {
+        self.route("get_block_height", |node| async move { node.get_block_height().await })
+            .await
+    }
+
+    async fn get_block_hash(&self, height: u64) -> RuneResult<String> {
+        self.route("get_block_hash", |node| async move { node.get_block_hash(height).await })
+            .await
+    }
+
+    async fn get_block(&self, hash: &str) -> RuneResult<BlockInfo> {
+        self.route("get_block", |node| async move { node.get_block(hash).await })
+            .await
+    }
+
+    async fn get_raw_transaction(&self, tx_id: &str) -> RuneResult<String> {
+        self.route("get_raw_transaction", |node| async move {
+            node.get_raw_transaction(tx_id).await
+        })
+        .await
+    }
+
+    async fn get_raw_block_transaction(&self, tx_id: &str, block_hash: &str) -> RuneResult<String> {
+        self.route("get_raw_block_transaction", |node| async move {
+            node.get_raw_block_transaction(tx_id, block_hash).await
+        })
+        .await
+    }
+
+    async fn get_transaction(&self, tx_id: &str) -> RuneResult<RunesTransactionResponse> {
+        self.route("get_transaction", |node| async move { node.get_transaction(tx_id).await })
+            .await
+    }
+
+    async fn get_block_transactions(&self, hash: &str) -> RuneResult<Vec<RunesTransactionResponse>> {
+        self.route("get_block_transactions", |node| async move {
+            node.get_block_transactions(hash).await
+        })
+        .await
+    }
+
+    async fn get_address_transfers(&self, address: &str) -> RuneResult<Vec<RuneTransfer>> {
+        self.route("get_address_transfers", |node| async move {
+            node.get_address_transfers(address).await
+        })
+        .await
+    }
+
+    async fn get_rune(&self, rune: &str) -> RuneResult<RuneEntry> {
+        self.route("get_rune", |node| async move { node.get_rune(rune).await })
+            .await
+    }
+
+    async fn get_runes(&self, page: Option<u32>) -> RuneResult<RunePage> {
+        self.route("get_runes", |node| async move { node.get_runes(page).await })
+            .await
+    }
+
+    async fn get_output(&self, outpoint: &str) -> RuneResult<RuneOutput> {
+        self.route("get_output", |node| async move { node.get_output(outpoint).await })
+            .await
+    }
+
+    async fn get_address_outputs(&self, address: &str) -> RuneResult<Vec<RuneOutput>> {
+        self.route("get_address_outputs", |node| async move {
+            node.get_address_outputs(address).await
+        })
+        .await
+    }
+
+    async fn get_block_etchings(&self, height: u64) -> RuneResult<Vec<RuneEntry>> {
+        self.route("get_block_etchings", |node| async move {
+            node.get_block_etchings(height).await
+        })
+        .await
+    }
+
+}
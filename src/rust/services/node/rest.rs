@@ -0,0 +1,107 @@
+use std::sync::Arc;
+use reqwest::{header::ACCEPT, Client as HttpClient, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+use crate::types::error::{RuneError, RuneResult};
+use crate::RpcCredentials;
+
+/// Authenticated GET client shared by the REST based backends
+pub struct RestClient {
+    base_url: String,
+    client: Arc<HttpClient>,
+    credentials: RpcCredentials,
+    permits: Option<Semaphore>,
+}
+
+impl RestClient {
+    pub fn new(base_url: &str, client: Arc<HttpClient>, credentials: RpcCredentials) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            client,
+            credentials,
+            permits: None,
+        }
+    }
+
+    /// Caps the requests in flight, like `RpcClient` does for JSON-RPC.
+    /// A permit is held until the response body has been read.
+    pub fn with_max_concurrent_requests(mut self, limit: Option<usize>) -> Self {
+        self.permits = limit.map(Semaphore::new);
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Sends a GET request and maps non-success statuses to `RuneError`
+    pub async fn get(&self, path: &str) -> RuneResult<Response> {
+        let _permit = self.acquire().await?;
+        self.fetch(path).await
+    }
+
+    pub async fn get_json<T: DeserializeOwned>(&self, path: &str) -> RuneResult<T> {
+        let _permit = self.acquire().await?;
+        self.fetch(path)
+            .await?
+            .json()
+            .await
+            .map_err(|e| RuneError::NodeResponseError(format!("Failed to parse {}: {}", path, e)))
+    }
+
+    pub async fn get_text(&self, path: &str) -> RuneResult<String> {
+        let _permit = self.acquire().await?;
+        self.fetch(path)
+            .await?
+            .text()
+            .await
+            .map(|text| text.trim().to_string())
+            .map_err(|e| RuneError::NodeResponseError(format!("Failed to read {}: {}", path, e)))
+    }
+
+    async fn acquire(&self) -> RuneResult<Option<SemaphorePermit<'_>>> {
+        match &self.permits {
+            Some(permits) => permits
+                .acquire()
+                .await
+                .map(Some)
+                .map_err(|e| RuneError::NodeConnectionError(e.to_string())),
+            None => Ok(None),
+        }
+    }
+
+    async fn fetch(&self, path: &str) -> RuneResult<Response> {
+        let url = format!("{}{}", self.base_url, path);
+        let mut response = self.send(&url).await?;
+
+        // The node may have restarted with a new cookie, retry once
+        if response.status() == StatusCode::UNAUTHORIZED && self.credentials.invalidate() {
+            response = self.send(&url).await?;
+        }
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let body = response.text().await.unwrap_or_default();
+        Err(match status {
+            StatusCode::NOT_FOUND => RuneError::NotFound(format!("{} not found", path)),
+            StatusCode::BAD_REQUEST => RuneError::InvalidRequest(body),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                RuneError::NodeConnectionError(format!("Authentication failed: {}", status))
+            }
+            _ => RuneError::NodeResponseError(format!("{} returned {}: {}", path, status, body)),
+        })
+    }
+
+    async fn send(&self, url: &str) -> RuneResult<Response> {
+        self.credentials
+            .apply(self.client.get(url).header(ACCEPT, "application/json"))
+            .map_err(|e| RuneError::NodeConnectionError(e.to_string()))?
+            .send()
+            .await
+            .map_err(|e| RuneError::NodeConnectionError(e.to_string()))
+    }
+}
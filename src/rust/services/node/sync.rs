@@ -1,32 +1,49 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 use crate::types::error::RuneError;
-use super::connection::NodeConnection;
+use super::backend::NodeBackend;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
 pub struct SyncStatus {
     pub current_height: u64,
     pub target_height: u64,
     pub is_syncing: bool,
+    /// Stopped by `pause`, `start_sync` leaves it stopped until `resume`
+    pub paused: bool,
     pub progress: f64,
     pub estimated_time_remaining: Option<u64>,
 }
 
+/// What `persist_progress` writes to the checkpoint file
+#[derive(Debug, Serialize, Deserialize)]
+struct SyncCheckpoint {
+    current_height: u64,
+}
+
 pub struct SyncService {
-    node: Arc<NodeConnection>,
+    node: Arc<dyn NodeBackend>,
     status: Arc<RwLock<SyncStatus>>,
     sync_interval: tokio::time::Duration,
+    /// Bumped whenever the sync is stopped or repositioned, a loop started
+    /// under an older generation exits without writing its block
+    generation: Arc<AtomicU64>,
+    checkpoint: Option<PathBuf>,
 }
 
 impl SyncService {
     pub fn new(
-        node: Arc<NodeConnection>,
+        node: Arc<dyn NodeBackend>,
         sync_interval: tokio::time::Duration,
     ) -> Self {
         let initial_status = SyncStatus {
             current_height: 0,
             target_height: 0,
             is_syncing: false,
+            paused: false,
             progress: 0.0,
             estimated_time_remaining: None,
         };
@@ -35,70 +52,211 @@ impl SyncService {
             node,
             status: Arc::new(RwLock::new(initial_status)),
             sync_interval,
+            generation: Arc::new(AtomicU64::new(0)),
+            checkpoint: None,
+        }
+    }
+
+    /// Keeps the sync height in the file at `path` across restarts.
+    ///
+    /// A height found there is where the sync picks up, `persist_progress`
+    /// writes the current one back. A missing or unreadable file starts the
+    /// sync from scratch.
+    pub fn with_checkpoint(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        match std::fs::read(&path) {
+            Ok(contents) => match serde_json::from_slice::<SyncCheckpoint>(&contents) {
+                Ok(checkpoint) => {
+                    tracing::info!("Resuming sync from block {}", checkpoint.current_height);
+                    // Not yet shared, no other handle can be holding the lock
+                    if let Ok(mut status) = self.status.try_write() {
+                        status.current_height = checkpoint.current_height;
+                    }
+                }
+                Err(e) => tracing::warn!("Ignoring sync checkpoint {}: {}", path.display(), e),
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => tracing::warn!("Ignoring sync checkpoint {}: {}", path.display(), e),
         }
+        self.checkpoint = Some(path);
+        self
+    }
+
+    /// Writes the current height to the checkpoint file, if there is one.
+    ///
+    /// Goes through a temporary file, so a crash halfway leaves the previous
+    /// checkpoint in place.
+    pub async fn persist_progress(&self) -> Result<(), RuneError> {
+        let Some(path) = &self.checkpoint else {
+            return Ok(());
+        };
+
+        let checkpoint = SyncCheckpoint {
+            current_height: self.status.read().await.current_height,
+        };
+        let contents = serde_json::to_vec(&checkpoint)
+            .map_err(|e| RuneError::SerializationError(e.to_string()))?;
+
+        let mut temporary = path.clone().into_os_string();
+        temporary.push(".tmp");
+        let written = match tokio::fs::write(&temporary, contents).await {
+            Ok(()) => tokio::fs::rename(&temporary, path).await,
+            Err(e) => Err(e),
+        };
+        written.map_err(|e| {
+            RuneError::NodeSyncError(format!("Could not write checkpoint {}: {}", path.display(), e))
+        })?;
+
+        tracing::info!("Sync progress saved at block {}", checkpoint.current_height);
+        Ok(())
     }
 
+    /// Records the node tip as the target and keeps syncing towards it in the background.
+    ///
+    /// Returns once the target is set, a failure to fetch it leaves the sync
+    /// stopped. The spawned task fetches the hash of each block and clears
+    /// `is_syncing` when the target is reached or a fetch fails.
     pub async fn start_sync(&self) -> Result<(), RuneError> {
         let mut status = self.status.write().await;
-        if status.is_syncing {
+        if status.is_syncing || status.paused {
             return Ok(());
         }
 
-        status.is_syncing = true;
         status.target_height = self.node.get_block_height().await?;
+        status.is_syncing = true;
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
         drop(status);
 
-        self.sync_blocks().await?;
+        let node = self.node.clone();
+        let status = self.status.clone();
+        let sync_interval = self.sync_interval;
+        let current = self.generation.clone();
+        tokio::spawn(async move {
+            if let Err(e) = Self::sync_blocks(node, status.clone(), sync_interval, current.clone(), generation).await {
+                tracing::error!("Block sync stopped: {}", e);
+                let mut status = status.write().await;
+                if current.load(Ordering::SeqCst) == generation {
+                    status.is_syncing = false;
+                }
+            }
+        });
+
         Ok(())
     }
 
     pub async fn stop_sync(&self) -> Result<(), RuneError> {
         let mut status = self.status.write().await;
         status.is_syncing = false;
+        self.generation.fetch_add(1, Ordering::SeqCst);
         Ok(())
     }
 
+    /// Stops the sync until `resume`, the block in flight is not recorded
+    pub async fn pause(&self) -> Result<(), RuneError> {
+        let mut status = self.status.write().await;
+        status.paused = true;
+        status.is_syncing = false;
+        status.estimated_time_remaining = None;
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Lifts a `pause` and syncs on from the current height
+    pub async fn resume(&self) -> Result<(), RuneError> {
+        self.status.write().await.paused = false;
+        self.start_sync().await
+    }
+
+    /// Moves the sync cursor back so blocks from `height` on are walked again.
+    ///
+    /// Only the cursor moves. `POST /api/v1/admin/sync/rewind` also rewinds
+    /// the runes cache, the rune catalog and the block watcher. Restarts the
+    /// sync unless it is paused, in which case `resume` picks up from `height`.
+    pub async fn rewind_to(&self, height: u64) -> Result<(), RuneError> {
+        let tip = self.node.get_block_height().await?;
+        if height > tip {
+            return Err(RuneError::InvalidRequest(format!(
+                "Height {} is above the node tip {}",
+                height, tip
+            )));
+        }
+
+        let mut status = self.status.write().await;
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        status.current_height = height.saturating_sub(1);
+        status.target_height = tip;
+        status.is_syncing = false;
+        status.progress = Self::progress(status.current_height, tip);
+        status.estimated_time_remaining = None;
+        drop(status);
+
+        tracing::info!("Rewinding the sync to block {}", height);
+        self.start_sync().await
+    }
+
     pub async fn get_sync_status(&self) -> Result<SyncStatus, RuneError> {
         Ok(self.status.read().await.clone())
     }
 
-    async fn sync_blocks(&self) -> Result<(), RuneError> {
-        while {
-            let status = self.status.read().await;
-            status.is_syncing && status.current_height < status.target_height
-        } {
+    fn progress(current_height: u64, target_height: u64) -> f64 {
+        if target_height == 0 {
+            1.0
+        } else {
+            current_height as f64 / target_height as f64
+        }
+    }
+
+    async fn sync_blocks(
+        node: Arc<dyn NodeBackend>,
+        status: Arc<RwLock<SyncStatus>>,
+        sync_interval: tokio::time::Duration,
+        current: Arc<AtomicU64>,
+        generation: u64,
+    ) -> Result<(), RuneError> {
+        let superseded = || current.load(Ordering::SeqCst) != generation;
+
+        loop {
             let current_height = {
-                let status = self.status.read().await;
+                let status = status.read().await;
+                if !status.is_syncing || superseded() {
+                    return Ok(());
+                }
+                if status.current_height >= status.target_height {
+                    break;
+                }
                 status.current_height
             };
 
-            // Sync logic here
-            // For example:
-            // 1. Get block at current_height + 1
-            // 2. Process block data
-            // 3. Update current_height
+            let next_height = current_height + 1;
+            let hash = node.get_block_hash(next_height).await?;
+            tracing::debug!("Synced block {} ({})", next_height, hash);
 
-            let mut status = self.status.write().await;
-            status.current_height += 1;
-            status.progress = status.current_height as f64 / status.target_height as f64;
+            let mut status = status.write().await;
+            // Stopped or repositioned while the block was fetched
+            if superseded() {
+                return Ok(());
+            }
+            status.current_height = next_height;
+            status.progress = Self::progress(status.current_height, status.target_height);
 
             // Update estimated time remaining based on sync speed
             // This is a simplified calculation
-            if status.current_height > 0 {
-                let blocks_remaining = status.target_height - status.current_height;
-                status.estimated_time_remaining = Some(blocks_remaining * 2); // Assume 2 seconds per block
-            }
+            let blocks_remaining = status.target_height - status.current_height;
+            status.estimated_time_remaining = Some(blocks_remaining * 2); // Assume 2 seconds per block
 
             drop(status);
 
-            tokio::time::sleep(self.sync_interval).await;
+            tokio::time::sleep(sync_interval).await;
         }
 
-        let mut status = self.status.write().await;
+        let mut status = status.write().await;
+        if superseded() {
+            return Ok(());
+        }
         status.is_syncing = false;
         status.progress = 1.0;
         status.estimated_time_remaining = None;
 
         Ok(())
     }
-} 
\ No newline at end of file
+}
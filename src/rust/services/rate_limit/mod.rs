@@ -1,3 +1,4 @@
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
@@ -57,6 +58,12 @@ impl TokenBucket {
             false
         }
     }
+
+    /// Tokens the bucket holds at `now`, without taking any
+    fn available(&self, now: Instant) -> f64 {
+        let elapsed = now.duration_since(self.last_update).as_secs_f64();
+        (self.tokens + elapsed * self.tokens_per_sec).min(self.max_tokens)
+    }
 }
 
 pub struct RateLimiter {
@@ -65,19 +72,34 @@ pub struct RateLimiter {
     metrics: Arc<RateLimitMetrics>,
 }
 
+/// Counters behind `RateLimiter::get_metrics`
+#[derive(Debug, Default)]
 pub struct RateLimitMetrics {
-    allowed_requests: metrics::Counter,
-    rejected_requests: metrics::Counter,
-    current_buckets: metrics::Gauge,
+    pub allowed_requests: AtomicU64,
+    pub rejected_requests: AtomicU64,
+    pub current_buckets: AtomicI64,
 }
 
 #[derive(Debug, Serialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
 pub struct RateLimitStats {
     pub allowed_requests: u64,
     pub rejected_requests: u64,
     pub current_buckets: i64,
 }
 
+/// Snapshot of one client's bucket
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct RateLimitBucket {
+    pub key: String,
+    /// Requests the client can make right now
+    pub tokens: f64,
+    pub max_tokens: f64,
+    /// Seconds since the client last made a request
+    pub idle_secs: u64,
+}
+
 impl RateLimiter {
     pub fn new(config: RateLimitConfig, metrics: Arc<RateLimitMetrics>) -> Self {
         Self {
@@ -91,7 +113,7 @@ impl RateLimiter {
         let bucket = self.buckets
             .entry(key.to_string())
             .or_insert_with(|| {
-                self.metrics.current_buckets.increment(1);
+                self.metrics.current_buckets.fetch_add(1, Ordering::Relaxed);
                 Arc::new(RwLock::new(TokenBucket::new(
                     self.config.max_requests,
                     self.config.window_size,
@@ -102,10 +124,10 @@ impl RateLimiter {
 
         let mut bucket = bucket.write().await;
         if bucket.try_acquire(cost as f64) {
-            self.metrics.allowed_requests.increment(1);
+            self.metrics.allowed_requests.fetch_add(1, Ordering::Relaxed);
             Ok(())
         } else {
-            self.metrics.rejected_requests.increment(1);
+            self.metrics.rejected_requests.fetch_add(1, Ordering::Relaxed);
             Err(RuneError::RateLimitExceeded)
         }
     }
@@ -115,11 +137,11 @@ impl RateLimiter {
         self.buckets.retain(|_, bucket| {
             let last_update = bucket.try_read()
                 .map(|b| b.last_update)
-                .unwrap_or_else(|| now);
+                .unwrap_or(now);
             
             let should_retain = now.duration_since(last_update) < max_age;
             if !should_retain {
-                self.metrics.current_buckets.decrement(1);
+                self.metrics.current_buckets.fetch_sub(1, Ordering::Relaxed);
             }
             should_retain
         });
@@ -127,9 +149,61 @@ impl RateLimiter {
 
     pub async fn get_metrics(&self) -> RateLimitStats {
         RateLimitStats {
-            allowed_requests: self.metrics.allowed_requests.get_count(),
-            rejected_requests: self.metrics.rejected_requests.get_count(),
-            current_buckets: self.metrics.current_buckets.get_value(),
+            allowed_requests: self.metrics.allowed_requests.load(Ordering::Relaxed),
+            rejected_requests: self.metrics.rejected_requests.load(Ordering::Relaxed),
+            current_buckets: self.metrics.current_buckets.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Snapshots of all buckets, the most throttled clients first
+    pub async fn buckets(&self) -> Vec<RateLimitBucket> {
+        let entries: Vec<_> = self.buckets
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+
+        let mut buckets = Vec::with_capacity(entries.len());
+        for (key, bucket) in entries {
+            buckets.push(Self::snapshot(key, &*bucket.read().await));
         }
+        buckets.sort_by(|a, b| a.tokens.total_cmp(&b.tokens).then_with(|| a.key.cmp(&b.key)));
+        buckets
     }
-} 
\ No newline at end of file
+
+    pub async fn bucket(&self, key: &str) -> Option<RateLimitBucket> {
+        let bucket = self.buckets.get(key)?.value().clone();
+        let bucket = bucket.read().await;
+        Some(Self::snapshot(key.to_string(), &bucket))
+    }
+
+    /// Drops the bucket of `key`, so the client starts over with a full one.
+    /// Returns whether there was a bucket.
+    pub fn reset_bucket(&self, key: &str) -> bool {
+        let removed = self.buckets.remove(key).is_some();
+        if removed {
+            self.metrics.current_buckets.fetch_sub(1, Ordering::Relaxed);
+        }
+        removed
+    }
+
+    /// Drops every bucket and returns how many there were
+    pub fn reset_all(&self) -> usize {
+        let mut removed = 0;
+        self.buckets.retain(|_, _| {
+            removed += 1;
+            false
+        });
+        self.metrics.current_buckets.fetch_sub(removed as i64, Ordering::Relaxed);
+        removed
+    }
+
+    fn snapshot(key: String, bucket: &TokenBucket) -> RateLimitBucket {
+        let now = Instant::now();
+        RateLimitBucket {
+            key,
+            tokens: bucket.available(now),
+            max_tokens: bucket.max_tokens,
+            idle_secs: now.duration_since(bucket.last_update).as_secs(),
+        }
+    }
+}
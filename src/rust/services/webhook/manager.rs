@@ -1,6 +1,9 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
-use tokio::sync::RwLock;
+use tokio::sync::{Notify, RwLock};
 use reqwest::Client;
 use crate::types::error::RuneError;
 
@@ -13,7 +16,7 @@ pub struct WebhookConfig {
     pub retry_delay: u64, // milliseconds
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, utoipa::ToSchema)]
 pub enum WebhookEventType {
     TransactionConfirmed,
     TransactionFailed,
@@ -21,17 +24,70 @@ pub enum WebhookEventType {
     BlockSynced,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct WebhookEvent {
     pub event_type: WebhookEventType,
     pub timestamp: u64,
     pub payload: serde_json::Value,
 }
 
+/// Outcome of the deliveries to one webhook URL, each counted once after its retries
+#[derive(Debug, Clone, Default, Serialize, utoipa::ToSchema)]
+pub struct DeliveryHealth {
+    pub delivered: u64,
+    pub failed: u64,
+    /// Failed deliveries since the last successful one
+    pub consecutive_failures: u32,
+    /// Unix timestamp in seconds
+    pub last_success_at: Option<u64>,
+    /// Unix timestamp in seconds
+    pub last_failure_at: Option<u64>,
+    pub last_error: Option<String>,
+}
+
+/// A registered webhook as listed to operators
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct WebhookStatus {
+    pub url: String,
+    pub events: Vec<WebhookEventType>,
+    pub max_retries: u32,
+    pub retry_delay: u64,
+    /// Whether deliveries are signed, the secret itself is never listed
+    pub signed: bool,
+    /// No delivery failed since the last successful one
+    pub healthy: bool,
+    pub health: DeliveryHealth,
+}
+
 pub struct WebhookManager {
     config: Arc<RwLock<Vec<WebhookConfig>>>,
     client: Client,
     metrics: Arc<metrics::Counter>,
+    health: DashMap<String, DeliveryHealth>,
+    /// `send_event` calls that have not returned yet
+    in_flight: AtomicUsize,
+    idle: Notify,
+    /// Set by `drain`, later events are refused
+    closed: AtomicBool,
+}
+
+/// Counts a `send_event` call as in flight until dropped, also when the
+/// caller gives up on it
+struct InFlight<'a>(&'a WebhookManager);
+
+impl<'a> InFlight<'a> {
+    fn new(manager: &'a WebhookManager) -> Self {
+        manager.in_flight.fetch_add(1, Ordering::SeqCst);
+        Self(manager)
+    }
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        if self.0.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.idle.notify_waiters();
+        }
+    }
 }
 
 impl WebhookManager {
@@ -40,6 +96,10 @@ impl WebhookManager {
             config: Arc::new(RwLock::new(Vec::new())),
             client: Client::new(),
             metrics,
+            health: DashMap::new(),
+            in_flight: AtomicUsize::new(0),
+            idle: Notify::new(),
+            closed: AtomicBool::new(false),
         }
     }
 
@@ -57,6 +117,9 @@ impl WebhookManager {
         let mut configs = self.config.write().await;
         if let Some(pos) = configs.iter().position(|c| c.url == url) {
             configs.remove(pos);
+            if !configs.iter().any(|c| c.url == url) {
+                self.health.remove(url);
+            }
             Ok(())
         } else {
             Err(RuneError::ConfigError("Webhook not found".to_string()))
@@ -64,6 +127,12 @@ impl WebhookManager {
     }
 
     pub async fn send_event(&self, event: WebhookEvent) -> Result<(), RuneError> {
+        // Counted before the check, so `drain` either waits for this call or it is refused
+        let _in_flight = InFlight::new(self);
+        if self.closed.load(Ordering::SeqCst) {
+            return Err(RuneError::WebhookError("Shutting down, event not delivered".to_string()));
+        }
+
         let configs = self.config.read().await;
         let mut tasks = Vec::new();
 
@@ -84,7 +153,80 @@ impl WebhookManager {
         Ok(())
     }
 
+    /// Refuses new events and waits up to `timeout` for the ones being
+    /// delivered, retries included. Returns how many were still in flight.
+    pub async fn drain(&self, timeout: Duration) -> usize {
+        self.closed.store(true, Ordering::SeqCst);
+
+        let idle = async {
+            loop {
+                let notified = self.idle.notified();
+                tokio::pin!(notified);
+                // Registered before the check so a wake-up in between is not lost
+                notified.as_mut().enable();
+                if self.in_flight.load(Ordering::SeqCst) == 0 {
+                    return;
+                }
+                notified.await;
+            }
+        };
+        let _ = tokio::time::timeout(timeout, idle).await;
+
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Registered webhooks with the health of their deliveries
+    pub async fn list_webhooks(&self) -> Vec<WebhookStatus> {
+        let configs = self.config.read().await;
+        configs
+            .iter()
+            .map(|config| {
+                let health = self.health
+                    .get(&config.url)
+                    .map(|health| health.clone())
+                    .unwrap_or_default();
+                WebhookStatus {
+                    url: config.url.clone(),
+                    events: config.events.clone(),
+                    max_retries: config.max_retries,
+                    retry_delay: config.retry_delay,
+                    signed: config.secret.is_some(),
+                    healthy: health.consecutive_failures == 0,
+                    health,
+                }
+            })
+            .collect()
+    }
+
     async fn send_to_endpoint(&self, config: WebhookConfig, event: WebhookEvent) -> Result<(), RuneError> {
+        let result = self.deliver(&config, &event).await;
+        self.record_delivery(&config.url, &result);
+        result
+    }
+
+    fn record_delivery(&self, url: &str, result: &Result<(), RuneError>) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+
+        let mut health = self.health.entry(url.to_string()).or_default();
+        match result {
+            Ok(()) => {
+                health.delivered += 1;
+                health.consecutive_failures = 0;
+                health.last_success_at = Some(now);
+            }
+            Err(e) => {
+                health.failed += 1;
+                health.consecutive_failures += 1;
+                health.last_failure_at = Some(now);
+                health.last_error = Some(e.to_string());
+            }
+        }
+    }
+
+    async fn deliver(&self, config: &WebhookConfig, event: &WebhookEvent) -> Result<(), RuneError> {
         let mut last_error = None;
 
         for attempt in 0..config.max_retries {
@@ -96,11 +238,11 @@ impl WebhookManager {
 
             let mut request = self.client
                 .post(&config.url)
-                .json(&event);
+                .json(event);
 
             // Eğer secret varsa, HMAC imzası ekle
             if let Some(secret) = &config.secret {
-                let signature = self.generate_signature(secret, &event);
+                let signature = self.generate_signature(secret, event);
                 request = request.header("X-Webhook-Signature", signature);
             }
 
@@ -0,0 +1,989 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use wiremock::{http::Method, matchers::any, Mock, MockServer, Request, Respond, ResponseTemplate};
+
+use crate::services::node::{backend::BackendKind, connection::NodeConfig};
+use crate::types::runestone::Runestone;
+
+const GENESIS_TIME: u64 = 1_231_006_505;
+const BLOCK_INTERVAL: u64 = 600;
+const COINBASE_VALUE: u64 = 312_500_000;
+const ESPLORA_PAGE_SIZE: usize = 25;
+const SEQUENCE_RBF: u32 = 0xffff_fffd;
+
+/// Failure modes the fake node can be switched into
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeFailure {
+    /// Every request answers `503 Service Unavailable` without a body
+    Unavailable,
+    /// Requests are answered normally after the given delay
+    Delay(Duration),
+    /// bitcoind reports RPC error -28, Esplora answers 503
+    WarmingUp,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FakeOutPoint {
+    pub txid: String,
+    pub vout: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct FakeTxOut {
+    pub value: u64,
+    pub script_pubkey: Vec<u8>,
+    pub address: Option<String>,
+}
+
+/// Transaction builder for the fake chain.
+///
+/// Only the fields the SDK looks at are modelled; addresses are mapped to a
+/// synthetic P2WPKH script so outputs can be looked up by address again.
+#[derive(Debug, Clone)]
+pub struct FakeTransaction {
+    inputs: Vec<FakeOutPoint>,
+    outputs: Vec<FakeTxOut>,
+    coinbase: Option<Vec<u8>>,
+}
+
+impl Default for FakeTransaction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FakeTransaction {
+    pub fn new() -> Self {
+        Self {
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            coinbase: None,
+        }
+    }
+
+    fn coinbase(height: u64, nonce: u64) -> Self {
+        let mut script_sig = Vec::new();
+        push_script_int(&mut script_sig, height);
+        push_script_int(&mut script_sig, nonce);
+
+        Self {
+            inputs: Vec::new(),
+            outputs: vec![FakeTxOut {
+                value: COINBASE_VALUE,
+                script_pubkey: address_script("coinbase"),
+                address: Some("coinbase".to_string()),
+            }],
+            coinbase: Some(script_sig),
+        }
+    }
+
+    pub fn spend(mut self, txid: &str, vout: u32) -> Self {
+        self.inputs.push(FakeOutPoint {
+            txid: txid.to_string(),
+            vout,
+        });
+        self
+    }
+
+    pub fn pay(mut self, address: &str, value: u64) -> Self {
+        self.outputs.push(FakeTxOut {
+            value,
+            script_pubkey: address_script(address),
+            address: Some(address.to_string()),
+        });
+        self
+    }
+
+    /// Adds an `OP_RETURN` output carrying `runestone`
+    pub fn runestone(mut self, runestone: &Runestone) -> Self {
+        self.outputs.push(FakeTxOut {
+            value: 0,
+            script_pubkey: runestone.encipher().expect("runestone must be valid"),
+            address: None,
+        });
+        self
+    }
+
+    pub fn inputs(&self) -> &[FakeOutPoint] {
+        &self.inputs
+    }
+
+    pub fn outputs(&self) -> &[FakeTxOut] {
+        &self.outputs
+    }
+
+    pub fn is_coinbase(&self) -> bool {
+        self.coinbase.is_some()
+    }
+
+    /// Legacy (non-witness) serialization
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        buffer.extend_from_slice(&2i32.to_le_bytes());
+
+        match &self.coinbase {
+            Some(script_sig) => {
+                write_compact_size(&mut buffer, 1);
+                buffer.extend_from_slice(&[0u8; 32]);
+                buffer.extend_from_slice(&u32::MAX.to_le_bytes());
+                write_compact_size(&mut buffer, script_sig.len() as u64);
+                buffer.extend_from_slice(script_sig);
+                buffer.extend_from_slice(&u32::MAX.to_le_bytes());
+            }
+            None => {
+                write_compact_size(&mut buffer, self.inputs.len() as u64);
+                for input in &self.inputs {
+                    buffer.extend_from_slice(&hash_from_hex(&input.txid));
+                    buffer.extend_from_slice(&input.vout.to_le_bytes());
+                    write_compact_size(&mut buffer, 0);
+                    buffer.extend_from_slice(&SEQUENCE_RBF.to_le_bytes());
+                }
+            }
+        }
+
+        write_compact_size(&mut buffer, self.outputs.len() as u64);
+        for output in &self.outputs {
+            buffer.extend_from_slice(&output.value.to_le_bytes());
+            write_compact_size(&mut buffer, output.script_pubkey.len() as u64);
+            buffer.extend_from_slice(&output.script_pubkey);
+        }
+
+        buffer.extend_from_slice(&0u32.to_le_bytes());
+        buffer
+    }
+
+    pub fn txid(&self) -> String {
+        hash_to_hex(&sha256d(&self.serialize()))
+    }
+}
+
+/// Block appended to the fake chain
+#[derive(Debug, Clone)]
+pub struct MinedBlock {
+    pub hash: String,
+    pub height: u64,
+    /// Coinbase first, then the block's transactions in order
+    pub txids: Vec<String>,
+}
+
+struct FakeBlock {
+    hash: String,
+    height: u64,
+    previous_hash: Option<String>,
+    time: u64,
+    merkle_root: [u8; 32],
+    nonce: u32,
+    txs: Vec<(String, FakeTransaction)>,
+}
+
+impl FakeBlock {
+    fn header(&self) -> Vec<u8> {
+        let mut header = Vec::with_capacity(80);
+        header.extend_from_slice(&0x2000_0000i32.to_le_bytes());
+        match &self.previous_hash {
+            Some(hash) => header.extend_from_slice(&hash_from_hex(hash)),
+            None => header.extend_from_slice(&[0u8; 32]),
+        }
+        header.extend_from_slice(&self.merkle_root);
+        header.extend_from_slice(&(self.time as u32).to_le_bytes());
+        header.extend_from_slice(&0x207f_ffffu32.to_le_bytes());
+        header.extend_from_slice(&self.nonce.to_le_bytes());
+        header
+    }
+
+    fn serialize(&self) -> Vec<u8> {
+        let mut buffer = self.header();
+        write_compact_size(&mut buffer, self.txs.len() as u64);
+        for (_, tx) in &self.txs {
+            buffer.extend_from_slice(&tx.serialize());
+        }
+        buffer
+    }
+}
+
+struct TxLocation<'a> {
+    tx: &'a FakeTransaction,
+    block: Option<&'a FakeBlock>,
+}
+
+#[derive(Default)]
+struct ChainState {
+    blocks: Vec<FakeBlock>,
+    mempool: Vec<(String, FakeTransaction)>,
+    failure: Option<NodeFailure>,
+    nonce: u64,
+    /// Like bitcoind without `-txindex`, confirmed transactions are only
+    /// found when `getrawtransaction` is given their block hash
+    without_txindex: bool,
+}
+
+impl ChainState {
+    fn tip(&self) -> &FakeBlock {
+        self.blocks.last().expect("chain always has a genesis block")
+    }
+
+    fn height(&self) -> u64 {
+        self.tip().height
+    }
+
+    fn block_by_hash(&self, hash: &str) -> Option<&FakeBlock> {
+        self.blocks.iter().find(|block| block.hash == hash)
+    }
+
+    fn confirmations(&self, block: &FakeBlock) -> u64 {
+        self.height() - block.height + 1
+    }
+
+    fn find_tx(&self, txid: &str) -> Option<TxLocation<'_>> {
+        for block in &self.blocks {
+            if let Some((_, tx)) = block.txs.iter().find(|(id, _)| id == txid) {
+                return Some(TxLocation {
+                    tx,
+                    block: Some(block),
+                });
+            }
+        }
+
+        self.mempool
+            .iter()
+            .find(|(id, _)| id == txid)
+            .map(|(_, tx)| TxLocation { tx, block: None })
+    }
+
+    fn prevout(&self, outpoint: &FakeOutPoint) -> Option<&FakeTxOut> {
+        self.find_tx(&outpoint.txid)
+            .and_then(|location| location.tx.outputs.get(outpoint.vout as usize))
+    }
+
+    /// Confirmed transactions, newest first, followed by nothing from the mempool
+    fn confirmed_txs(&self) -> impl Iterator<Item = (&String, &FakeTransaction, &FakeBlock)> {
+        self.blocks
+            .iter()
+            .rev()
+            .flat_map(|block| block.txs.iter().rev().map(move |(id, tx)| (id, tx, block)))
+    }
+
+    fn touches_address(&self, tx: &FakeTransaction, address: &str) -> bool {
+        tx.outputs.iter().any(|out| out.address.as_deref() == Some(address))
+            || tx.inputs.iter().any(|input| {
+                self.prevout(input).and_then(|out| out.address.as_deref()) == Some(address)
+            })
+    }
+
+    fn is_spent(&self, txid: &str, vout: u32) -> bool {
+        let spends = |tx: &FakeTransaction| {
+            tx.inputs
+                .iter()
+                .any(|input| input.txid == txid && input.vout == vout)
+        };
+
+        self.blocks
+            .iter()
+            .flat_map(|block| block.txs.iter())
+            .chain(self.mempool.iter())
+            .any(|(_, tx)| spends(tx))
+    }
+
+    fn connect_block(&mut self, txs: Vec<FakeTransaction>) -> MinedBlock {
+        let height = self.blocks.last().map(|block| block.height + 1).unwrap_or(0);
+        let previous_hash = self.blocks.last().map(|block| block.hash.clone());
+        self.nonce += 1;
+
+        let coinbase = FakeTransaction::coinbase(height, self.nonce);
+        let txs: Vec<(String, FakeTransaction)> = std::iter::once(coinbase)
+            .chain(txs)
+            .map(|tx| (tx.txid(), tx))
+            .collect();
+
+        let confirmed: Vec<&String> = txs.iter().map(|(id, _)| id).collect();
+        self.mempool.retain(|(id, _)| !confirmed.contains(&id));
+
+        let mut block = FakeBlock {
+            hash: String::new(),
+            height,
+            previous_hash,
+            time: GENESIS_TIME + height * BLOCK_INTERVAL,
+            merkle_root: merkle_root(txs.iter().map(|(id, _)| hash_from_hex(id)).collect()),
+            nonce: self.nonce as u32,
+            txs,
+        };
+        block.hash = hash_to_hex(&sha256d(&block.header()));
+
+        let mined = MinedBlock {
+            hash: block.hash.clone(),
+            height,
+            txids: block.txs.iter().map(|(id, _)| id.clone()).collect(),
+        };
+        self.blocks.push(block);
+        mined
+    }
+}
+
+/// In-process node serving bitcoind JSON-RPC (`POST /`) and the Esplora REST
+/// API (`GET`) over one scripted chain.
+///
+/// ```ignore
+/// let node = FakeNode::start().await;
+/// let funding = node.mine(vec![FakeTransaction::new().pay("bc1qalice", 10_000)]);
+/// node.submit(FakeTransaction::new().spend(&funding.txids[1], 0).pay("bc1qbob", 9_000));
+/// node.reorg(1, vec![vec![]]);
+/// let backend = create_backend(&node.config(BackendKind::Esplora), client);
+/// ```
+pub struct FakeNode {
+    server: MockServer,
+    state: Arc<Mutex<ChainState>>,
+}
+
+impl FakeNode {
+    /// Starts the server with a chain holding only the genesis block
+    pub async fn start() -> Self {
+        let mut chain = ChainState::default();
+        chain.connect_block(Vec::new());
+        let state = Arc::new(Mutex::new(chain));
+
+        let server = MockServer::start().await;
+        Mock::given(any())
+            .respond_with(FakeNodeResponder {
+                state: state.clone(),
+            })
+            .mount(&server)
+            .await;
+
+        Self { server, state }
+    }
+
+    pub fn uri(&self) -> String {
+        self.server.uri()
+    }
+
+    /// Node config pointing at this server, without retries or circuit breaker
+    pub fn config(&self, backend: BackendKind) -> NodeConfig {
+        NodeConfig {
+            rpc_url: self.uri(),
+            backend,
+            max_retries: 1,
+            circuit_breaker: None,
+            ..Default::default()
+        }
+    }
+
+    pub fn height(&self) -> u64 {
+        self.state().height()
+    }
+
+    pub fn tip_hash(&self) -> String {
+        self.state().tip().hash.clone()
+    }
+
+    pub fn block_hash(&self, height: u64) -> Option<String> {
+        self.state()
+            .blocks
+            .get(height as usize)
+            .map(|block| block.hash.clone())
+    }
+
+    /// Mines one block with every mempool transaction followed by `txs`
+    pub fn mine(&self, txs: Vec<FakeTransaction>) -> MinedBlock {
+        let mut state = self.state();
+        let mut block_txs: Vec<FakeTransaction> =
+            state.mempool.iter().map(|(_, tx)| tx.clone()).collect();
+        block_txs.extend(txs);
+        state.connect_block(block_txs)
+    }
+
+    /// Mines `count` blocks holding only a coinbase
+    pub fn mine_empty(&self, count: u64) -> Vec<MinedBlock> {
+        let mut state = self.state();
+        (0..count).map(|_| state.connect_block(Vec::new())).collect()
+    }
+
+    /// Adds `tx` to the mempool and returns its txid
+    pub fn submit(&self, tx: FakeTransaction) -> String {
+        let txid = tx.txid();
+        self.state().mempool.push((txid.clone(), tx));
+        txid
+    }
+
+    pub fn mempool(&self) -> Vec<String> {
+        self.state().mempool.iter().map(|(id, _)| id.clone()).collect()
+    }
+
+    /// Disconnects the top `depth` blocks and connects `replacement` blocks in
+    /// their place. Disconnected transactions go back to the mempool unless a
+    /// replacement block includes them again.
+    pub fn reorg(&self, depth: u64, replacement: Vec<Vec<FakeTransaction>>) -> Vec<MinedBlock> {
+        let mut state = self.state();
+        assert!(depth <= state.height(), "cannot reorg the genesis block");
+
+        let keep = state.blocks.len() - depth as usize;
+        let disconnected = state.blocks.split_off(keep);
+        let returned: Vec<(String, FakeTransaction)> = disconnected
+            .into_iter()
+            .flat_map(|block| block.txs)
+            .filter(|(_, tx)| !tx.is_coinbase())
+            .collect();
+
+        let mut mempool = returned;
+        mempool.append(&mut state.mempool);
+        state.mempool = mempool;
+
+        replacement
+            .into_iter()
+            .map(|txs| state.connect_block(txs))
+            .collect()
+    }
+
+    /// Stops answering `getrawtransaction` for confirmed transactions
+    /// unless their block hash is passed, like bitcoind without `-txindex`
+    pub fn disable_txindex(&self) {
+        self.state().without_txindex = true;
+    }
+
+    pub fn fail(&self, failure: NodeFailure) {
+        self.state().failure = Some(failure);
+    }
+
+    pub fn recover(&self) {
+        self.state().failure = None;
+    }
+
+    fn state(&self) -> MutexGuard<'_, ChainState> {
+        self.state.lock().unwrap()
+    }
+}
+
+struct FakeNodeResponder {
+    state: Arc<Mutex<ChainState>>,
+}
+
+impl Respond for FakeNodeResponder {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let state = self.state.lock().unwrap();
+        let is_rpc = request.method == Method::Post;
+
+        let response = match &state.failure {
+            Some(NodeFailure::Unavailable) => return ResponseTemplate::new(503),
+            Some(NodeFailure::WarmingUp) if is_rpc => {
+                return rpc_error(request_id(request), -28, "Loading block index...")
+            }
+            Some(NodeFailure::WarmingUp) => {
+                return ResponseTemplate::new(503).set_body_string("Loading block index...")
+            }
+            _ if is_rpc => handle_rpc(&state, request),
+            _ => handle_rest(&state, request.url.path()),
+        };
+
+        match &state.failure {
+            Some(NodeFailure::Delay(delay)) => response.set_delay(*delay),
+            _ => response,
+        }
+    }
+}
+
+fn request_id(request: &Request) -> Value {
+    serde_json::from_slice::<Value>(&request.body)
+        .ok()
+        .and_then(|body| body.get("id").cloned())
+        .unwrap_or(Value::Null)
+}
+
+fn rpc_result(id: Value, result: Value) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({ "result": result, "error": null, "id": id }))
+}
+
+fn rpc_error(id: Value, code: i64, message: &str) -> ResponseTemplate {
+    let status = if code == -32601 { 404 } else { 500 };
+    ResponseTemplate::new(status).set_body_json(json!({
+        "result": null,
+        "error": { "code": code, "message": message },
+        "id": id
+    }))
+}
+
+fn handle_rpc(state: &ChainState, request: &Request) -> ResponseTemplate {
+    let body: Value = match serde_json::from_slice(&request.body) {
+        Ok(body) => body,
+        Err(_) => return rpc_error(Value::Null, -32700, "Parse error"),
+    };
+    let id = body.get("id").cloned().unwrap_or(Value::Null);
+    let params = body
+        .get("params")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    let param = |i: usize| params.get(i).cloned().unwrap_or(Value::Null);
+
+    let block_not_found = |id| rpc_error(id, -5, "Block not found");
+
+    match body.get("method").and_then(Value::as_str).unwrap_or_default() {
+        "getblockchaininfo" => rpc_result(id, json!({
+            "chain": "regtest",
+            "blocks": state.height(),
+            "headers": state.height(),
+            "bestblockhash": state.tip().hash,
+            "verificationprogress": 1.0,
+            "initialblockdownload": false
+        })),
+        "getblockcount" => rpc_result(id, json!(state.height())),
+        "getbestblockhash" => rpc_result(id, json!(state.tip().hash)),
+        "getconnectioncount" => rpc_result(id, json!(8)),
+        "getrawmempool" => rpc_result(
+            id,
+            json!(state.mempool.iter().map(|(txid, _)| txid).collect::<Vec<_>>()),
+        ),
+        "getblockhash" => match param(0).as_u64().and_then(|h| state.blocks.get(h as usize)) {
+            Some(block) => rpc_result(id, json!(block.hash)),
+            None => rpc_error(id, -8, "Block height out of range"),
+        },
+        "getblockheader" => match param(0).as_str().and_then(|h| state.block_by_hash(h)) {
+            Some(block) => rpc_result(id, rpc_block_header(state, block)),
+            None => block_not_found(id),
+        },
+        "getblock" => {
+            let Some(block) = param(0).as_str().and_then(|h| state.block_by_hash(h)) else {
+                return block_not_found(id);
+            };
+            match param(1).as_u64().unwrap_or(1) {
+                0 => rpc_result(id, json!(to_hex(&block.serialize()))),
+                verbosity => {
+                    let mut result = rpc_block_header(state, block);
+                    result["tx"] = if verbosity >= 2 {
+                        block
+                            .txs
+                            .iter()
+                            // Unlike getrawtransaction, without the block fields
+                            .map(|(txid, tx)| rpc_transaction(state, txid, tx, None))
+                            .collect()
+                    } else {
+                        block.txs.iter().map(|(txid, _)| json!(txid)).collect()
+                    };
+                    rpc_result(id, result)
+                }
+            }
+        }
+        "getrawtransaction" => {
+            let txid = param(0).as_str().unwrap_or_default().to_string();
+            let location = match param(2).as_str() {
+                Some(hash) => state.block_by_hash(hash).and_then(|block| {
+                    let (_, tx) = block.txs.iter().find(|(id, _)| *id == txid)?;
+                    Some(TxLocation { tx, block: Some(block) })
+                }),
+                None => state
+                    .find_tx(&txid)
+                    .filter(|location| location.block.is_none() || !state.without_txindex),
+            };
+            let Some(location) = location else {
+                return rpc_error(
+                    id,
+                    -5,
+                    "No such mempool or blockchain transaction. Use gettransaction for wallet transactions.",
+                );
+            };
+
+            let verbose = match param(1) {
+                Value::Bool(verbose) => verbose,
+                Value::Number(n) => n.as_u64().unwrap_or(0) > 0,
+                _ => false,
+            };
+            if verbose {
+                rpc_result(id, rpc_transaction(state, &txid, location.tx, location.block))
+            } else {
+                rpc_result(id, json!(to_hex(&location.tx.serialize())))
+            }
+        }
+        _ => rpc_error(id, -32601, "Method not found"),
+    }
+}
+
+fn rpc_block_header(state: &ChainState, block: &FakeBlock) -> Value {
+    let next = state.blocks.get(block.height as usize + 1);
+    json!({
+        "hash": block.hash,
+        "confirmations": state.confirmations(block),
+        "height": block.height,
+        "version": 0x2000_0000,
+        "merkleroot": hash_to_hex(&block.merkle_root),
+        "time": block.time,
+        "mediantime": block.time,
+        "nonce": block.nonce,
+        "bits": "207fffff",
+        "nTx": block.txs.len(),
+        "previousblockhash": block.previous_hash,
+        "nextblockhash": next.map(|next| next.hash.clone())
+    })
+}
+
+fn rpc_transaction(
+    state: &ChainState,
+    txid: &str,
+    tx: &FakeTransaction,
+    block: Option<&FakeBlock>,
+) -> Value {
+    let size = tx.serialize().len();
+    let vin: Vec<Value> = match &tx.coinbase {
+        Some(script_sig) => vec![json!({ "coinbase": to_hex(script_sig), "sequence": u32::MAX })],
+        None => tx
+            .inputs
+            .iter()
+            .map(|input| json!({
+                "txid": input.txid,
+                "vout": input.vout,
+                "scriptSig": { "asm": "", "hex": "" },
+                "sequence": SEQUENCE_RBF
+            }))
+            .collect(),
+    };
+    let vout: Vec<Value> = tx
+        .outputs
+        .iter()
+        .enumerate()
+        .map(|(n, output)| {
+            let mut script = json!({
+                "asm": "",
+                "hex": to_hex(&output.script_pubkey),
+                "type": if output.address.is_some() { "witness_v0_keyhash" } else { "nulldata" }
+            });
+            if let Some(address) = &output.address {
+                script["address"] = json!(address);
+            }
+            json!({
+                "value": output.value as f64 / 100_000_000.0,
+                "n": n,
+                "scriptPubKey": script
+            })
+        })
+        .collect();
+
+    let mut result = json!({
+        "txid": txid,
+        "hash": txid,
+        "version": 2,
+        "size": size,
+        "vsize": size,
+        "weight": size * 4,
+        "locktime": 0,
+        "vin": vin,
+        "vout": vout,
+        "hex": to_hex(&tx.serialize())
+    });
+    if let Some(block) = block {
+        result["blockhash"] = json!(block.hash);
+        result["confirmations"] = json!(state.confirmations(block));
+        result["time"] = json!(block.time);
+        result["blocktime"] = json!(block.time);
+    }
+    result
+}
+
+fn handle_rest(state: &ChainState, path: &str) -> ResponseTemplate {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let json_response = |value: Value| ResponseTemplate::new(200).set_body_json(value);
+    let text = |value: String| ResponseTemplate::new(200).set_body_string(value);
+    let not_found = |what: &str| ResponseTemplate::new(404).set_body_string(format!("{} not found", what));
+    let bad_request = |message: &str| ResponseTemplate::new(400).set_body_string(message.to_string());
+
+    match segments.as_slice() {
+        ["blocks", "tip", "height"] => text(state.height().to_string()),
+        ["blocks", "tip", "hash"] => text(state.tip().hash.clone()),
+        ["block-height", height] => match height.parse::<usize>().ok().and_then(|h| state.blocks.get(h)) {
+            Some(block) => text(block.hash.clone()),
+            None => not_found("Block"),
+        },
+        ["block", hash, rest @ ..] => {
+            let Some(block) = state.block_by_hash(hash) else {
+                return not_found("Block");
+            };
+            match rest {
+                [] => json_response(json!({
+                    "id": block.hash,
+                    "height": block.height,
+                    "version": 0x2000_0000,
+                    "timestamp": block.time,
+                    "mediantime": block.time,
+                    "tx_count": block.txs.len(),
+                    "size": block.serialize().len(),
+                    "weight": block.serialize().len() * 4,
+                    "merkle_root": hash_to_hex(&block.merkle_root),
+                    "previousblockhash": block.previous_hash,
+                    "nonce": block.nonce,
+                    "bits": 0x207f_ffffu32
+                })),
+                ["txids"] => json_response(json!(block
+                    .txs
+                    .iter()
+                    .map(|(txid, _)| txid)
+                    .collect::<Vec<_>>())),
+                ["txs", start] => {
+                    let Ok(start) = start.parse::<usize>() else {
+                        return bad_request("Invalid start index");
+                    };
+                    if start % ESPLORA_PAGE_SIZE != 0 {
+                        return bad_request("start index must be a multiple of 25");
+                    }
+                    if start >= block.txs.len() {
+                        return bad_request("start index out of range");
+                    }
+                    json_response(Value::Array(
+                        block
+                            .txs
+                            .iter()
+                            .skip(start)
+                            .take(ESPLORA_PAGE_SIZE)
+                            .map(|(txid, tx)| esplora_transaction(state, txid, tx, Some(block)))
+                            .collect(),
+                    ))
+                }
+                _ => not_found("Endpoint"),
+            }
+        }
+        ["tx", txid, rest @ ..] => {
+            let Some(location) = state.find_tx(txid) else {
+                return not_found("Transaction");
+            };
+            match rest {
+                [] => json_response(esplora_transaction(state, txid, location.tx, location.block)),
+                ["hex"] => text(to_hex(&location.tx.serialize())),
+                ["status"] => json_response(esplora_status(location.block)),
+                _ => not_found("Endpoint"),
+            }
+        }
+        ["address", address, rest @ ..] => match rest {
+            ["utxo"] => {
+                let outputs = state
+                    .blocks
+                    .iter()
+                    .flat_map(|block| block.txs.iter().map(move |(id, tx)| (id, tx, Some(block))))
+                    .chain(state.mempool.iter().map(|(id, tx)| (id, tx, None)));
+
+                let mut utxos = Vec::new();
+                for (txid, tx, block) in outputs {
+                    for (vout, output) in tx.outputs.iter().enumerate() {
+                        if output.address.as_deref() == Some(*address)
+                            && !state.is_spent(txid, vout as u32)
+                        {
+                            utxos.push(json!({
+                                "txid": txid,
+                                "vout": vout,
+                                "value": output.value,
+                                "status": esplora_status(block)
+                            }));
+                        }
+                    }
+                }
+                json_response(Value::Array(utxos))
+            }
+            ["txs"] => {
+                let mempool = state
+                    .mempool
+                    .iter()
+                    .rev()
+                    .filter(|(_, tx)| state.touches_address(tx, address))
+                    .map(|(txid, tx)| esplora_transaction(state, txid, tx, None));
+                let confirmed = state
+                    .confirmed_txs()
+                    .filter(|(_, tx, _)| state.touches_address(tx, address))
+                    .take(ESPLORA_PAGE_SIZE)
+                    .map(|(txid, tx, block)| esplora_transaction(state, txid, tx, Some(block)));
+                json_response(Value::Array(mempool.chain(confirmed).collect()))
+            }
+            ["txs", "chain", last_seen @ ..] => {
+                let mut confirmed = state
+                    .confirmed_txs()
+                    .filter(|(_, tx, _)| state.touches_address(tx, address))
+                    .peekable();
+                if let [last_seen] = last_seen {
+                    for (txid, _, _) in confirmed.by_ref() {
+                        if txid == last_seen {
+                            break;
+                        }
+                    }
+                }
+                json_response(Value::Array(
+                    confirmed
+                        .take(ESPLORA_PAGE_SIZE)
+                        .map(|(txid, tx, block)| esplora_transaction(state, txid, tx, Some(block)))
+                        .collect(),
+                ))
+            }
+            _ => not_found("Endpoint"),
+        },
+        ["mempool"] => {
+            let vsize: usize = state.mempool.iter().map(|(_, tx)| tx.serialize().len()).sum();
+            let total_fee: u64 = state.mempool.iter().map(|(_, tx)| fee(state, tx)).sum();
+            json_response(json!({
+                "count": state.mempool.len(),
+                "vsize": vsize,
+                "total_fee": total_fee,
+                "fee_histogram": []
+            }))
+        }
+        ["mempool", "txids"] => json_response(json!(state
+            .mempool
+            .iter()
+            .map(|(txid, _)| txid)
+            .collect::<Vec<_>>())),
+        ["fee-estimates"] => json_response(json!({ "1": 20.0, "3": 10.0, "6": 5.0, "144": 1.0 })),
+        _ => not_found("Endpoint"),
+    }
+}
+
+fn esplora_status(block: Option<&FakeBlock>) -> Value {
+    match block {
+        Some(block) => json!({
+            "confirmed": true,
+            "block_height": block.height,
+            "block_hash": block.hash,
+            "block_time": block.time
+        }),
+        None => json!({ "confirmed": false }),
+    }
+}
+
+fn esplora_output(output: &FakeTxOut) -> Value {
+    let mut value = json!({
+        "scriptpubkey": to_hex(&output.script_pubkey),
+        "scriptpubkey_type": if output.address.is_some() { "v0_p2wpkh" } else { "op_return" },
+        "value": output.value
+    });
+    if let Some(address) = &output.address {
+        value["scriptpubkey_address"] = json!(address);
+    }
+    value
+}
+
+fn esplora_transaction(
+    state: &ChainState,
+    txid: &str,
+    tx: &FakeTransaction,
+    block: Option<&FakeBlock>,
+) -> Value {
+    let vin: Vec<Value> = match &tx.coinbase {
+        Some(script_sig) => vec![json!({
+            "txid": "0".repeat(64),
+            "vout": u32::MAX,
+            "prevout": null,
+            "scriptsig": to_hex(script_sig),
+            "is_coinbase": true,
+            "sequence": u32::MAX
+        })],
+        None => tx
+            .inputs
+            .iter()
+            .map(|input| json!({
+                "txid": input.txid,
+                "vout": input.vout,
+                "prevout": state.prevout(input).map(esplora_output),
+                "scriptsig": "",
+                "is_coinbase": false,
+                "sequence": SEQUENCE_RBF
+            }))
+            .collect(),
+    };
+    let size = tx.serialize().len();
+
+    json!({
+        "txid": txid,
+        "version": 2,
+        "locktime": 0,
+        "vin": vin,
+        "vout": tx.outputs.iter().map(esplora_output).collect::<Vec<_>>(),
+        "size": size,
+        "weight": size * 4,
+        "fee": fee(state, tx),
+        "status": esplora_status(block)
+    })
+}
+
+fn fee(state: &ChainState, tx: &FakeTransaction) -> u64 {
+    if tx.is_coinbase() {
+        return 0;
+    }
+    let input: u64 = tx
+        .inputs
+        .iter()
+        .filter_map(|input| state.prevout(input))
+        .map(|output| output.value)
+        .sum();
+    let output: u64 = tx.outputs.iter().map(|output| output.value).sum();
+    input.saturating_sub(output)
+}
+
+fn sha256d(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(Sha256::digest(data)).into()
+}
+
+fn merkle_root(mut hashes: Vec<[u8; 32]>) -> [u8; 32] {
+    while hashes.len() > 1 {
+        if hashes.len() % 2 == 1 {
+            hashes.push(*hashes.last().unwrap());
+        }
+        hashes = hashes
+            .chunks(2)
+            .map(|pair| sha256d(&[pair[0], pair[1]].concat()))
+            .collect();
+    }
+    hashes[0]
+}
+
+/// Synthetic P2WPKH script so every address maps to a stable output script
+fn address_script(address: &str) -> Vec<u8> {
+    let digest = Sha256::digest(address.as_bytes());
+    let mut script = vec![0x00, 0x14];
+    script.extend_from_slice(&digest[..20]);
+    script
+}
+
+fn push_script_int(script: &mut Vec<u8>, n: u64) {
+    let mut bytes: Vec<u8> = n.to_le_bytes().into_iter().collect();
+    while bytes.len() > 1 && bytes[bytes.len() - 1] == 0 && bytes[bytes.len() - 2] & 0x80 == 0 {
+        bytes.pop();
+    }
+    if bytes == [0] {
+        bytes.clear();
+    }
+    script.push(bytes.len() as u8);
+    script.extend_from_slice(&bytes);
+}
+
+fn write_compact_size(buffer: &mut Vec<u8>, n: u64) {
+    match n {
+        0..=0xfc => buffer.push(n as u8),
+        0xfd..=0xffff => {
+            buffer.push(0xfd);
+            buffer.extend_from_slice(&(n as u16).to_le_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            buffer.push(0xfe);
+            buffer.extend_from_slice(&(n as u32).to_le_bytes());
+        }
+        _ => {
+            buffer.push(0xff);
+            buffer.extend_from_slice(&n.to_le_bytes());
+        }
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Txids and block hashes are displayed byte-reversed
+fn hash_to_hex(hash: &[u8; 32]) -> String {
+    let mut reversed = *hash;
+    reversed.reverse();
+    to_hex(&reversed)
+}
+
+fn hash_from_hex(hex: &str) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    for (i, byte) in hash.iter_mut().enumerate() {
+        *byte = hex
+            .get(i * 2..i * 2 + 2)
+            .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+            .expect("txid must be 32 bytes of hex");
+    }
+    hash.reverse();
+    hash
+}
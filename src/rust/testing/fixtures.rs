@@ -0,0 +1,92 @@
+#[cfg(feature = "server")]
+use std::sync::Arc;
+
+#[cfg(feature = "server")]
+use crate::services::auth::{ApiKeyScope, ApiKeyStore};
+use crate::services::events::bus::ChainEventData;
+use crate::types::rune::{RuneEntry, RuneTerms, TransferType};
+
+/// Turbo rune etched at 840000:1 with open mint terms, as ord reports it,
+/// so without a holder count
+pub fn uncommon_goods() -> RuneEntry {
+    RuneEntry {
+        id: "840000:1".to_string(),
+        name: "UNCOMMONGOODS".to_string(),
+        spaced_name: "UNCOMMON•GOODS".to_string(),
+        number: 0,
+        etching: "0".repeat(64),
+        block: 840_000,
+        divisibility: 2,
+        symbol: Some('⧉'),
+        spacers: 0b1000_0000,
+        premine: 500,
+        terms: Some(RuneTerms {
+            amount: Some(100),
+            cap: Some(u128::MAX),
+            height_start: Some(840_000),
+            height_end: Some(1_050_000),
+            offset_start: None,
+            offset_end: None,
+        }),
+        turbo: true,
+        mints: 30,
+        burned: 200,
+        timestamp: 1_713_571_767,
+        holders: None,
+    }
+}
+
+/// Fully premined rune etched at 840000:3, with a holder count
+pub fn dog_go_to_the_moon() -> RuneEntry {
+    RuneEntry {
+        id: "840000:3".to_string(),
+        name: "DOGGOTOTHEMOON".to_string(),
+        spaced_name: "DOG•GO•TO•THE•MOON".to_string(),
+        number: 1,
+        premine: 100_000_000_000,
+        terms: None,
+        turbo: false,
+        mints: 0,
+        burned: 5,
+        holders: Some(75_000),
+        ..uncommon_goods()
+    }
+}
+
+/// Keys `reader-key`, `listener-key` and `operator-key`, scoped to reading
+/// runes, reading events and administration
+#[cfg(feature = "server")]
+pub fn key_store() -> Arc<ApiKeyStore> {
+    let store = ApiKeyStore::new();
+    store
+        .import_key("reader", "Reader", "reader-key", [ApiKeyScope::RunesRead], None)
+        .unwrap();
+    store
+        .import_key("listener", "Listener", "listener-key", [ApiKeyScope::EventsRead], None)
+        .unwrap();
+    store
+        .import_key("operator", "Operator", "operator-key", [ApiKeyScope::Admin], None)
+        .unwrap();
+    Arc::new(store)
+}
+
+/// Transfer of 100 `rune` from `bc1qsender` to `to` at height 840000.
+/// Carries the rune id when `rune` names one of the fixture runes.
+pub fn transfer(rune: &str, to: &str) -> ChainEventData {
+    let rune_id = [uncommon_goods(), dog_go_to_the_moon()]
+        .into_iter()
+        .find(|entry| entry.spaced_name == rune)
+        .map(|entry| entry.id);
+
+    ChainEventData::Transfer {
+        txid: "a".repeat(64),
+        rune: rune.to_string(),
+        rune_id,
+        from_address: "bc1qsender".to_string(),
+        to_address: to.to_string(),
+        amount: "100".to_string(),
+        transfer_type: TransferType::Transfer,
+        block_height: 840_000,
+        verified: true,
+    }
+}
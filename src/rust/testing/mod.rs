@@ -0,0 +1,8 @@
+//! Test support shared by the SDK's own tests and downstream crates.
+//!
+//! Enabled with the `test-support` feature.
+
+pub mod fake_node;
+pub mod fixtures;
+
+pub use fake_node::{FakeNode, FakeTransaction, MinedBlock, NodeFailure};
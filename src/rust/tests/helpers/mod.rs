@@ -1,5 +1,6 @@
 use std::sync::Arc;
 use actix_web::{test, App, web};
+use async_trait::async_trait;
 use mockall::mock;
 use crate::{
     api::{
@@ -7,18 +8,29 @@ use crate::{
         webhook::handlers::WebhookApiContext,
     },
     services::{
-        node::connection::NodeConnection,
+        node::{
+            backend::{BackendKind, BlockInfo, NodeBackend},
+            connection::NodeStatus,
+        },
         cache::RunesCache,
         webhook::manager::WebhookManager,
     },
 };
 
-// Mock NodeConnection
+// Mock NodeBackend
 mock! {
-    pub NodeConnection {
-        pub async fn get_transaction(&self, tx_id: &str) -> Result<RunesTransactionResponse, RuneError>;
-        pub async fn get_batch_transactions(&self, tx_ids: &[String]) -> Result<Vec<RunesTransactionResponse>, RuneError>;
-        pub async fn get_address_transfers(&self, address: &str) -> Result<Vec<RuneTransfer>, RuneError>;
+    pub NodeBackend {}
+
+    #[async_trait]
+    impl NodeBackend for NodeBackend {
+        fn kind(&self) -> BackendKind;
+        async fn health_check(&self) -> RuneResult<NodeStatus>;
+        async fn get_block_height(&self) -> RuneResult<u64>;
+        async fn get_block_hash(&self, height: u64) -> RuneResult<String>;
+        async fn get_block(&self, hash: &str) -> RuneResult<BlockInfo>;
+        async fn get_raw_transaction(&self, tx_id: &str) -> RuneResult<String>;
+        async fn get_transaction(&self, tx_id: &str) -> RuneResult<RunesTransactionResponse>;
+        async fn get_address_transfers(&self, address: &str) -> RuneResult<Vec<RuneTransfer>>;
     }
 }
 
@@ -43,7 +55,7 @@ mock! {
 
 // Test application builder
 pub async fn create_test_app(
-    node: Option<MockNodeConnection>,
+    node: Option<MockNodeBackend>,
     cache: Option<MockRunesCache>,
     webhook_manager: Option<MockWebhookManager>,
 ) -> impl actix_web::dev::Service<
@@ -52,11 +64,9 @@ pub async fn create_test_app(
     Error = actix_web::Error,
 > {
     let node = Arc::new(node.unwrap_or_else(|| {
-        let mut mock = MockNodeConnection::new();
+        let mut mock = MockNodeBackend::new();
         mock.expect_get_transaction()
             .returning(|_| Ok(RunesTransactionResponse::default()));
-        mock.expect_get_batch_transactions()
-            .returning(|_| Ok(vec![RunesTransactionResponse::default()]));
         mock.expect_get_address_transfers()
             .returning(|_| Ok(vec![RuneTransfer::default()]));
         mock
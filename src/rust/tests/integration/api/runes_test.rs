@@ -5,7 +5,7 @@ use crate::{
         create_test_app,
         create_test_transaction,
         send_test_request,
-        MockNodeConnection,
+        MockNodeBackend,
         MockRunesCache,
     },
     types::error::RuneError,
@@ -14,7 +14,7 @@ use crate::{
 #[actix_web::test]
 async fn test_get_transaction_success() {
     // Mock node'u hazırla
-    let mut mock_node = MockNodeConnection::new();
+    let mut mock_node = MockNodeBackend::new();
     mock_node
         .expect_get_transaction()
         .with(mockall::predicate::eq("test_tx"))
@@ -72,7 +72,7 @@ async fn test_get_transaction_from_cache() {
 #[actix_web::test]
 async fn test_get_transaction_not_found() {
     // Mock node'u hazırla
-    let mut mock_node = MockNodeConnection::new();
+    let mut mock_node = MockNodeBackend::new();
     mock_node
         .expect_get_transaction()
         .with(mockall::predicate::eq("not_found_tx"))
@@ -97,7 +97,7 @@ async fn test_get_transaction_not_found() {
 #[actix_web::test]
 async fn test_get_batch_transactions() {
     // Mock node'u hazırla
-    let mut mock_node = MockNodeConnection::new();
+    let mut mock_node = MockNodeBackend::new();
     mock_node
         .expect_get_batch_transactions()
         .times(1)
@@ -128,14 +128,14 @@ async fn test_get_batch_transactions() {
     assert_eq!(response.status(), StatusCode::OK);
     
     let body: serde_json::Value = test::read_body_json(response).await;
-    assert_eq!(body["transactions"].as_array().unwrap().len(), 2);
-    assert_eq!(body["failed_transactions"].as_array().unwrap().len(), 0);
+    assert_eq!(body["results"].as_array().unwrap().len(), 2);
+    assert_eq!(body["failed"], 0);
 }
 
 #[actix_web::test]
 async fn test_get_address_transfers() {
     // Mock node'u hazırla
-    let mut mock_node = MockNodeConnection::new();
+    let mut mock_node = MockNodeBackend::new();
     mock_node
         .expect_get_address_transfers()
         .with(mockall::predicate::eq("test_address"))
@@ -1,8 +1,11 @@
 use std::fmt;
 use serde::{Serialize, Deserialize};
-use actix_web::{HttpResponse, ResponseError};
+use serde_json::json;
+#[cfg(feature = "server")]
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
 
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
 pub struct ErrorResponse {
     pub code: String,
     pub message: String,
@@ -17,12 +20,20 @@ pub enum RuneError {
     NodeConnectionError(String),
     NodeResponseError(String),
     NodeSyncError(String),
+    NodeWarmingUp(String),
+    UnsupportedOperation(String),
     
     // API ile ilgili hatalar
     InvalidTransaction(String),
     InvalidAddress(String),
+    InvalidRune(String),
     InvalidRequest(String),
+    NotFound(String),
+    TransactionRejected(String),
     RateLimitExceeded,
+    Unauthorized(String),
+    Forbidden(String),
+    NotAcceptable(String),
     
     // Cache ile ilgili hatalar
     CacheError(String),
@@ -44,10 +55,18 @@ impl fmt::Display for RuneError {
             RuneError::NodeConnectionError(msg) => write!(f, "Node connection error: {}", msg),
             RuneError::NodeResponseError(msg) => write!(f, "Node response error: {}", msg),
             RuneError::NodeSyncError(msg) => write!(f, "Node sync error: {}", msg),
+            RuneError::NodeWarmingUp(msg) => write!(f, "Node is warming up: {}", msg),
+            RuneError::UnsupportedOperation(msg) => write!(f, "Unsupported operation: {}", msg),
             RuneError::InvalidTransaction(msg) => write!(f, "Invalid transaction: {}", msg),
             RuneError::InvalidAddress(msg) => write!(f, "Invalid address: {}", msg),
+            RuneError::InvalidRune(msg) => write!(f, "Invalid rune: {}", msg),
             RuneError::InvalidRequest(msg) => write!(f, "Invalid request: {}", msg),
+            RuneError::NotFound(msg) => write!(f, "Not found: {}", msg),
+            RuneError::TransactionRejected(msg) => write!(f, "Transaction rejected: {}", msg),
             RuneError::RateLimitExceeded => write!(f, "Rate limit exceeded"),
+            RuneError::Unauthorized(msg) => write!(f, "Unauthorized: {}", msg),
+            RuneError::Forbidden(msg) => write!(f, "Forbidden: {}", msg),
+            RuneError::NotAcceptable(msg) => write!(f, "Not acceptable: {}", msg),
             RuneError::CacheError(msg) => write!(f, "Cache error: {}", msg),
             RuneError::WebhookError(msg) => write!(f, "Webhook error: {}", msg),
             RuneError::WebhookValidationError(msg) => write!(f, "Webhook validation error: {}", msg),
@@ -61,44 +80,70 @@ impl fmt::Display for RuneError {
 
 impl std::error::Error for RuneError {}
 
-impl ResponseError for RuneError {
-    fn error_response(&self) -> HttpResponse {
-        let error_response = ErrorResponse {
-            code: self.error_code(),
-            message: self.to_string(),
-            details: self.error_details(),
+impl From<&RuneError> for ErrorResponse {
+    fn from(error: &RuneError) -> Self {
+        ErrorResponse {
+            code: error.error_code(),
+            message: error.to_string(),
+            details: error.error_details(),
             request_id: None, // Request ID middleware tarafından eklenecek
-        };
+        }
+    }
+}
 
+#[cfg(feature = "server")]
+impl ResponseError for RuneError {
+    fn status_code(&self) -> StatusCode {
         match self {
-            RuneError::NodeConnectionError(_) => HttpResponse::ServiceUnavailable().json(error_response),
-            RuneError::NodeResponseError(_) => HttpResponse::BadGateway().json(error_response),
-            RuneError::NodeSyncError(_) => HttpResponse::ServiceUnavailable().json(error_response),
-            RuneError::InvalidTransaction(_) => HttpResponse::BadRequest().json(error_response),
-            RuneError::InvalidAddress(_) => HttpResponse::BadRequest().json(error_response),
-            RuneError::InvalidRequest(_) => HttpResponse::BadRequest().json(error_response),
-            RuneError::RateLimitExceeded => HttpResponse::TooManyRequests().json(error_response),
-            RuneError::CacheError(_) => HttpResponse::InternalServerError().json(error_response),
-            RuneError::WebhookError(_) => HttpResponse::BadRequest().json(error_response),
-            RuneError::WebhookValidationError(_) => HttpResponse::BadRequest().json(error_response),
-            RuneError::ConfigError(_) => HttpResponse::InternalServerError().json(error_response),
-            RuneError::DatabaseError(_) => HttpResponse::InternalServerError().json(error_response),
-            RuneError::SerializationError(_) => HttpResponse::InternalServerError().json(error_response),
-            RuneError::InternalError(_) => HttpResponse::InternalServerError().json(error_response),
+            RuneError::NodeConnectionError(_) => StatusCode::SERVICE_UNAVAILABLE,
+            RuneError::NodeResponseError(_) => StatusCode::BAD_GATEWAY,
+            RuneError::NodeSyncError(_) => StatusCode::SERVICE_UNAVAILABLE,
+            RuneError::NodeWarmingUp(_) => StatusCode::SERVICE_UNAVAILABLE,
+            RuneError::UnsupportedOperation(_) => StatusCode::NOT_IMPLEMENTED,
+            RuneError::InvalidTransaction(_) => StatusCode::BAD_REQUEST,
+            RuneError::InvalidAddress(_) => StatusCode::BAD_REQUEST,
+            RuneError::InvalidRune(_) => StatusCode::BAD_REQUEST,
+            RuneError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+            RuneError::NotFound(_) => StatusCode::NOT_FOUND,
+            RuneError::TransactionRejected(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            RuneError::RateLimitExceeded => StatusCode::TOO_MANY_REQUESTS,
+            RuneError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            RuneError::Forbidden(_) => StatusCode::FORBIDDEN,
+            RuneError::NotAcceptable(_) => StatusCode::NOT_ACCEPTABLE,
+            RuneError::CacheError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            RuneError::WebhookError(_) => StatusCode::BAD_REQUEST,
+            RuneError::WebhookValidationError(_) => StatusCode::BAD_REQUEST,
+            RuneError::ConfigError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            RuneError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            RuneError::SerializationError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            RuneError::InternalError(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(ErrorResponse::from(self))
+    }
 }
 
 impl RuneError {
-    fn error_code(&self) -> String {
+    /// Stable machine readable code, e.g. `NOT_FOUND`
+    pub fn error_code(&self) -> String {
         match self {
             RuneError::NodeConnectionError(_) => "NODE_CONNECTION_ERROR",
             RuneError::NodeResponseError(_) => "NODE_RESPONSE_ERROR",
             RuneError::NodeSyncError(_) => "NODE_SYNC_ERROR",
+            RuneError::NodeWarmingUp(_) => "NODE_WARMING_UP",
+            RuneError::UnsupportedOperation(_) => "UNSUPPORTED_OPERATION",
             RuneError::InvalidTransaction(_) => "INVALID_TRANSACTION",
             RuneError::InvalidAddress(_) => "INVALID_ADDRESS",
+            RuneError::InvalidRune(_) => "INVALID_RUNE",
             RuneError::InvalidRequest(_) => "INVALID_REQUEST",
+            RuneError::NotFound(_) => "NOT_FOUND",
+            RuneError::TransactionRejected(_) => "TRANSACTION_REJECTED",
             RuneError::RateLimitExceeded => "RATE_LIMIT_EXCEEDED",
+            RuneError::Unauthorized(_) => "UNAUTHORIZED",
+            RuneError::Forbidden(_) => "FORBIDDEN",
+            RuneError::NotAcceptable(_) => "NOT_ACCEPTABLE",
             RuneError::CacheError(_) => "CACHE_ERROR",
             RuneError::WebhookError(_) => "WEBHOOK_ERROR",
             RuneError::WebhookValidationError(_) => "WEBHOOK_VALIDATION_ERROR",
@@ -109,7 +154,7 @@ impl RuneError {
         }.to_string()
     }
 
-    fn error_details(&self) -> Option<serde_json::Value> {
+    pub fn error_details(&self) -> Option<serde_json::Value> {
         match self {
             RuneError::NodeConnectionError(msg) => Some(json!({
                 "reason": msg,
@@ -127,6 +172,15 @@ impl RuneError {
                 "reason": msg,
                 "suggestion": "Please check address format"
             })),
+            RuneError::InvalidRune(msg) => Some(json!({
+                "reason": msg,
+                "suggestion": "Please use a rune id like 840000:1 or a name of at most 28 letters"
+            })),
+            RuneError::NodeWarmingUp(msg) => Some(json!({
+                "reason": msg,
+                "suggestion": "The node is still starting up, please retry shortly",
+                "retry_after": 30 // seconds
+            })),
             RuneError::RateLimitExceeded => Some(json!({
                 "reason": "Too many requests",
                 "suggestion": "Please wait before making more requests",
@@ -137,5 +191,36 @@ impl RuneError {
     }
 }
 
+// Node client hatalarını API hatalarına dönüştür
+impl From<crate::Error> for RuneError {
+    fn from(error: crate::Error) -> Self {
+        match error {
+            crate::Error::InvalidTransaction(msg) => RuneError::InvalidTransaction(msg),
+            crate::Error::NetworkError(msg) => RuneError::NodeConnectionError(msg),
+            crate::Error::JsonRpcError(code, msg) => {
+                RuneError::NodeResponseError(format!("JSON-RPC error {}: {}", code, msg))
+            }
+            crate::Error::NotFound(msg) => RuneError::NotFound(msg),
+            crate::Error::InvalidParameter(msg) => RuneError::InvalidRequest(msg),
+            crate::Error::NodeWarmingUp(msg) => RuneError::NodeWarmingUp(msg),
+            crate::Error::TransactionRejected(msg) => RuneError::TransactionRejected(msg),
+            crate::Error::DatabaseError(msg) => RuneError::DatabaseError(msg),
+            crate::Error::NodeConnectionError(msg) => RuneError::NodeConnectionError(msg),
+            crate::Error::ParseError(msg) => RuneError::NodeResponseError(msg),
+            crate::Error::WebSocketError(msg) => RuneError::InternalError(msg),
+        }
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl From<sqlx::Error> for RuneError {
+    fn from(error: sqlx::Error) -> Self {
+        match error {
+            sqlx::Error::RowNotFound => RuneError::NotFound("Row not found".to_string()),
+            other => RuneError::DatabaseError(other.to_string()),
+        }
+    }
+}
+
 // Alias for Result type
 pub type RuneResult<T> = Result<T, RuneError>; 
\ No newline at end of file
@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-#[derive(Debug, Serialize, Deserialize)]
+use super::error::{RuneError, RuneResult};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
 pub struct RunesTransactionResponse {
     pub transaction_id: String,
     pub runes: Vec<RuneTransfer>,
@@ -12,33 +15,466 @@ pub struct RunesTransactionResponse {
     pub status: TransactionStatus,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
 pub struct RuneTransfer {
     pub rune_id: String,
     pub from_address: String,
     pub to_address: String,
-    pub amount: u64,
+    pub amount: u128,
     pub transfer_type: TransferType,
     pub fee: Option<u64>,
     pub metadata: Option<HashMap<String, serde_json::Value>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema, async_graphql::Enum))]
 pub enum TransferType {
     Mint,
     Transfer,
     Burn,
+    /// Premine the etching transaction allocates to its outputs
+    Etch,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema, async_graphql::Enum))]
 pub enum TransactionStatus {
     Pending,
     Confirmed,
     Failed,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema, async_graphql::Enum))]
 pub enum NetworkType {
+    #[default]
     Mainnet,
     Testnet,
-} 
\ No newline at end of file
+} 
+
+/// Etched rune and its running supply counters
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuneEntry {
+    /// `block:tx` of the etching transaction
+    pub id: String,
+    /// Rune name without spacers, e.g. `UNCOMMONGOODS`
+    pub name: String,
+    /// Rune name with spacers, e.g. `UNCOMMON•GOODS`
+    pub spaced_name: String,
+    pub number: u64,
+    pub etching: String,
+    pub block: u64,
+    pub divisibility: u8,
+    pub symbol: Option<char>,
+    pub spacers: u32,
+    pub premine: u128,
+    pub terms: Option<RuneTerms>,
+    pub turbo: bool,
+    pub mints: u128,
+    pub burned: u128,
+    pub timestamp: u64,
+    /// Number of addresses holding the rune, when the index tracks it
+    #[serde(default)]
+    pub holders: Option<u64>,
+}
+
+/// Open mint terms of a rune
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuneTerms {
+    pub amount: Option<u128>,
+    pub cap: Option<u128>,
+    pub height_start: Option<u64>,
+    pub height_end: Option<u64>,
+    pub offset_start: Option<u64>,
+    pub offset_end: Option<u64>,
+}
+
+impl RuneEntry {
+    /// Whether an open mint would be accepted in a block at `height`
+    pub fn mintable_at(&self, height: u64) -> bool {
+        let Some(terms) = &self.terms else {
+            return false;
+        };
+        if self.mints >= terms.cap.unwrap_or_default() {
+            return false;
+        }
+
+        let start = [terms.height_start, terms.offset_start.map(|o| self.block.saturating_add(o))]
+            .into_iter()
+            .flatten()
+            .max();
+        let end = [terms.height_end, terms.offset_end.map(|o| self.block.saturating_add(o))]
+            .into_iter()
+            .flatten()
+            .min();
+
+        start.is_none_or(|start| height >= start) && end.is_none_or(|end| height < end)
+    }
+
+    /// Premine plus everything minted so far, before burns
+    pub fn supply(&self) -> u128 {
+        let amount = self.terms.as_ref().and_then(|t| t.amount).unwrap_or_default();
+        self.premine.saturating_add(self.mints.saturating_mul(amount))
+    }
+
+    /// Supply once every allowed mint has happened
+    pub fn max_supply(&self) -> u128 {
+        let (amount, cap) = self
+            .terms
+            .as_ref()
+            .map(|t| (t.amount.unwrap_or_default(), t.cap.unwrap_or_default()))
+            .unwrap_or_default();
+        self.premine.saturating_add(cap.saturating_mul(amount))
+    }
+}
+
+/// Amount of a single rune held by an output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuneBalance {
+    /// Spaced rune name
+    pub rune: String,
+    pub amount: u128,
+    pub divisibility: u8,
+    pub symbol: Option<char>,
+}
+
+/// One page of the rune listing of an index, newest first
+#[derive(Debug, Clone)]
+pub struct RunePage {
+    pub entries: Vec<RuneEntry>,
+    pub prev: Option<u32>,
+    pub next: Option<u32>,
+}
+
+/// Runes carried by a transaction output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuneOutput {
+    /// `txid:vout`
+    pub outpoint: String,
+    pub address: Option<String>,
+    pub value: u64,
+    pub spent: bool,
+    pub confirmations: u32,
+    pub runes: Vec<RuneBalance>,
+}
+
+/// Renders a raw rune amount with `divisibility` decimal places, e.g. `12345` at 2 is `123.45`
+pub fn format_rune_amount(amount: u128, divisibility: u8) -> String {
+    let Some(unit) = 10u128.checked_pow(u32::from(divisibility)).filter(|_| divisibility > 0) else {
+        return amount.to_string();
+    };
+
+    let (whole, fraction) = (amount / unit, amount % unit);
+    if fraction == 0 {
+        return whole.to_string();
+    }
+
+    let fraction = format!("{:0width$}", fraction, width = divisibility as usize);
+    format!("{}.{}", whole, fraction.trim_end_matches('0'))
+}
+
+/// Letters in the longest rune name, `BCGDENLQRQWDSLRUGSNLBTMFIJAV`
+pub const MAX_RUNE_LENGTH: usize = 28;
+
+/// Splits a spaced rune name into its letters and spacer bitfield, rejecting
+/// names longer than `MAX_RUNE_LENGTH` letters
+pub fn parse_spaced_rune(spaced: &str) -> RuneResult<(String, u32)> {
+    let mut name = String::with_capacity(spaced.len());
+    let mut letters = 0;
+    let mut spacers = 0u32;
+
+    for c in spaced.chars() {
+        if c == '•' || c == '.' {
+            if letters > 0 {
+                spacers |= 1 << (letters - 1);
+            }
+        } else if letters == MAX_RUNE_LENGTH {
+            return Err(RuneError::InvalidRune(format!(
+                "Rune name is longer than {} letters: {}",
+                MAX_RUNE_LENGTH, spaced
+            )));
+        } else {
+            name.push(c);
+            letters += 1;
+        }
+    }
+
+    Ok((name, spacers))
+}
+
+/// Inverse of `parse_spaced_rune`, puts a `•` after every letter whose bit
+/// is set in `spacers`
+pub fn format_spaced_rune(name: &str, spacers: u32) -> String {
+    let mut spaced = String::with_capacity(name.len() * 2);
+    let last = name.chars().count().saturating_sub(1);
+
+    for (i, c) in name.chars().enumerate() {
+        spaced.push(c);
+        if i < last && i < 32 && spacers & (1 << i) != 0 {
+            spaced.push('•');
+        }
+    }
+    spaced
+}
+
+/// Rune details served by the API, grouped like the TypeScript `RuneInfo`.
+///
+/// Amounts are strings since they do not fit into a JavaScript number.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct RuneInfo {
+    /// `block:tx` of the etching transaction
+    pub id: String,
+    pub metadata: RuneMetadata,
+    pub attributes: RuneAttributes,
+    pub supply: RuneSupply,
+    pub stats: RuneStats,
+    pub etching: RuneEtching,
+    /// Timestamp of the etching block
+    pub created_at: u64,
+    /// When the entry was read from the index
+    pub updated_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct RuneMetadata {
+    pub id: String,
+    /// Rune name without spacers
+    pub name: String,
+    pub spaced_name: String,
+    pub number: u64,
+    #[cfg_attr(feature = "server", schema(value_type = Option<String>, example = "⧉"))]
+    pub symbol: Option<char>,
+    /// Divisibility of the rune
+    pub decimals: u8,
+    pub spacers: u32,
+    pub supply: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema, async_graphql::SimpleObject))]
+pub struct RuneAttributes {
+    pub transferable: bool,
+    pub burnable: bool,
+    /// Whether an open mint would land in the next block
+    pub mintable: bool,
+    pub max_supply: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema, async_graphql::SimpleObject))]
+pub struct RuneSupply {
+    pub total: String,
+    pub circulating: String,
+    pub burned: String,
+    pub premine: String,
+    pub mints: String,
+    pub last_updated: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema, async_graphql::SimpleObject))]
+pub struct RuneStats {
+    /// `None` when the node does not track holders
+    pub holders: Option<u64>,
+    pub last_updated: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema, async_graphql::SimpleObject))]
+pub struct RuneEtching {
+    pub txid: String,
+    pub block_height: u64,
+    pub premine: String,
+    pub terms: Option<RuneTermsInfo>,
+    pub turbo: bool,
+}
+
+/// `RuneTerms` with amounts as strings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema, async_graphql::SimpleObject))]
+pub struct RuneTermsInfo {
+    pub amount: Option<String>,
+    pub cap: Option<String>,
+    pub height_start: Option<u64>,
+    pub height_end: Option<u64>,
+    pub offset_start: Option<u64>,
+    pub offset_end: Option<u64>,
+}
+
+impl RuneInfo {
+    /// Builds the API view of `entry` as seen with the chain tip at `height`
+    pub fn from_entry(entry: &RuneEntry, height: u64, updated_at: u64) -> Self {
+        let total = entry.supply();
+
+        RuneInfo {
+            id: entry.id.clone(),
+            metadata: RuneMetadata {
+                id: entry.id.clone(),
+                name: entry.name.clone(),
+                spaced_name: entry.spaced_name.clone(),
+                number: entry.number,
+                symbol: entry.symbol,
+                decimals: entry.divisibility,
+                spacers: entry.spacers,
+                supply: total.to_string(),
+            },
+            attributes: RuneAttributes {
+                transferable: true,
+                burnable: true,
+                mintable: entry.mintable_at(height + 1),
+                max_supply: entry.max_supply().to_string(),
+            },
+            supply: RuneSupply {
+                total: total.to_string(),
+                circulating: total.saturating_sub(entry.burned).to_string(),
+                burned: entry.burned.to_string(),
+                premine: entry.premine.to_string(),
+                mints: entry.mints.to_string(),
+                last_updated: updated_at,
+            },
+            stats: RuneStats {
+                holders: entry.holders,
+                last_updated: updated_at,
+            },
+            etching: RuneEtching {
+                txid: entry.etching.clone(),
+                block_height: entry.block,
+                premine: entry.premine.to_string(),
+                terms: entry.terms.as_ref().map(|terms| RuneTermsInfo {
+                    amount: terms.amount.map(|a| a.to_string()),
+                    cap: terms.cap.map(|c| c.to_string()),
+                    height_start: terms.height_start,
+                    height_end: terms.height_end,
+                    offset_start: terms.offset_start,
+                    offset_end: terms.offset_end,
+                }),
+                turbo: entry.turbo,
+            },
+            created_at: entry.timestamp,
+            updated_at,
+        }
+    }
+}
+
+/// Amount of one rune, raw and formatted with its divisibility
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct RuneAmount {
+    /// Spaced rune name
+    pub rune: String,
+    /// Raw amount in the rune's smallest unit
+    pub amount: String,
+    /// `amount` with `divisibility` decimal places
+    pub formatted: String,
+    pub divisibility: u8,
+    #[cfg_attr(feature = "server", schema(value_type = Option<String>))]
+    pub symbol: Option<char>,
+}
+
+impl From<&RuneBalance> for RuneAmount {
+    fn from(balance: &RuneBalance) -> Self {
+        RuneAmount {
+            rune: balance.rune.clone(),
+            amount: balance.amount.to_string(),
+            formatted: format_rune_amount(balance.amount, balance.divisibility),
+            divisibility: balance.divisibility,
+            symbol: balance.symbol,
+        }
+    }
+}
+
+/// Runes on a single outpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct OutputRunes {
+    /// `txid:vout`
+    pub outpoint: String,
+    pub address: Option<String>,
+    /// Value of the output in sats
+    pub value: u64,
+    pub spent: bool,
+    pub confirmations: u32,
+    pub runes: Vec<RuneAmount>,
+}
+
+impl From<&RuneOutput> for OutputRunes {
+    fn from(output: &RuneOutput) -> Self {
+        OutputRunes {
+            outpoint: output.outpoint.clone(),
+            address: output.address.clone(),
+            value: output.value,
+            spent: output.spent,
+            confirmations: output.confirmations,
+            runes: output.runes.iter().map(RuneAmount::from).collect(),
+        }
+    }
+}
+
+/// Unspent output carrying part of an address balance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema, async_graphql::SimpleObject))]
+pub struct RuneUtxo {
+    pub outpoint: String,
+    pub value: u64,
+    pub confirmations: u32,
+    pub amount: String,
+    pub formatted: String,
+}
+
+/// Total of one rune held by an address and where it sits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct AddressRuneBalance {
+    #[serde(flatten)]
+    pub balance: RuneAmount,
+    pub utxos: Vec<RuneUtxo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct AddressBalances {
+    pub address: String,
+    /// One entry per rune, sorted by spaced name
+    pub balances: Vec<AddressRuneBalance>,
+}
+
+impl AddressBalances {
+    /// Sums the runes on the unspent `outputs` of `address` per rune
+    pub fn from_outputs(address: &str, outputs: &[RuneOutput]) -> Self {
+        let mut totals: BTreeMap<&str, (u128, &RuneBalance, Vec<RuneUtxo>)> = BTreeMap::new();
+
+        for output in outputs.iter().filter(|output| !output.spent) {
+            for balance in &output.runes {
+                let (total, _, utxos) = totals
+                    .entry(balance.rune.as_str())
+                    .or_insert_with(|| (0, balance, Vec::new()));
+
+                *total = total.saturating_add(balance.amount);
+                utxos.push(RuneUtxo {
+                    outpoint: output.outpoint.clone(),
+                    value: output.value,
+                    confirmations: output.confirmations,
+                    amount: balance.amount.to_string(),
+                    formatted: format_rune_amount(balance.amount, balance.divisibility),
+                });
+            }
+        }
+
+        AddressBalances {
+            address: address.to_string(),
+            balances: totals
+                .into_values()
+                .map(|(total, balance, utxos)| AddressRuneBalance {
+                    balance: RuneAmount::from(&RuneBalance {
+                        amount: total,
+                        ..balance.clone()
+                    }),
+                    utxos,
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,411 @@
+use std::collections::{HashMap, VecDeque};
+use serde::{Deserialize, Serialize};
+
+use super::error::{RuneError, RuneResult};
+
+const OP_RETURN: u8 = 0x6a;
+const OP_13: u8 = 0x5d;
+const OP_PUSHDATA1: u8 = 0x4c;
+const OP_PUSHDATA2: u8 = 0x4d;
+const OP_PUSHDATA4: u8 = 0x4e;
+const MAX_SCRIPT_ELEMENT_SIZE: usize = 520;
+
+const TAG_BODY: u128 = 0;
+const TAG_DIVISIBILITY: u128 = 1;
+const TAG_FLAGS: u128 = 2;
+const TAG_SPACERS: u128 = 3;
+const TAG_RUNE: u128 = 4;
+const TAG_SYMBOL: u128 = 5;
+const TAG_PREMINE: u128 = 6;
+const TAG_CAP: u128 = 8;
+const TAG_AMOUNT: u128 = 10;
+const TAG_HEIGHT_START: u128 = 12;
+const TAG_HEIGHT_END: u128 = 14;
+const TAG_OFFSET_START: u128 = 16;
+const TAG_OFFSET_END: u128 = 18;
+const TAG_MINT: u128 = 20;
+const TAG_POINTER: u128 = 22;
+
+const FLAG_ETCHING: u128 = 1;
+const FLAG_TERMS: u128 = 1 << 1;
+const FLAG_TURBO: u128 = 1 << 2;
+
+/// Names below this value can be etched, the ones above are assigned to
+/// etchings that do not pick a name
+const RESERVED_RUNE: u128 = 6_402_364_363_415_443_603_228_541_259_936_211_926;
+
+/// `block:tx` position of a rune's etching transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct RuneId {
+    pub block: u64,
+    pub tx: u32,
+}
+
+impl std::fmt::Display for RuneId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.block, self.tx)
+    }
+}
+
+impl std::str::FromStr for RuneId {
+    type Err = RuneError;
+
+    fn from_str(s: &str) -> RuneResult<Self> {
+        let invalid = || RuneError::InvalidRequest(format!("Invalid rune id: {}", s));
+        let (block, tx) = s.split_once(':').ok_or_else(invalid)?;
+
+        Ok(Self {
+            block: block.parse().map_err(|_| invalid())?,
+            tx: tx.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Edict {
+    pub id: RuneId,
+    pub amount: u128,
+    pub output: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EtchingTerms {
+    pub amount: Option<u128>,
+    pub cap: Option<u128>,
+    pub height: (Option<u64>, Option<u64>),
+    pub offset: (Option<u64>, Option<u64>),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Etching {
+    /// Rune name without spacers, `None` lets the protocol assign a reserved name
+    pub rune: Option<String>,
+    pub divisibility: Option<u8>,
+    pub spacers: Option<u32>,
+    pub symbol: Option<char>,
+    pub premine: Option<u128>,
+    pub terms: Option<EtchingTerms>,
+    pub turbo: bool,
+}
+
+/// Runes protocol message carried in an `OP_RETURN OP_13` output
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Runestone {
+    pub edicts: Vec<Edict>,
+    pub etching: Option<Etching>,
+    pub mint: Option<RuneId>,
+    pub pointer: Option<u32>,
+}
+
+impl Runestone {
+    /// Serializes the runestone into its output script
+    pub fn encipher(&self) -> RuneResult<Vec<u8>> {
+        let mut payload = Vec::new();
+
+        if let Some(etching) = &self.etching {
+            let mut flags = FLAG_ETCHING;
+            if etching.terms.is_some() {
+                flags |= FLAG_TERMS;
+            }
+            if etching.turbo {
+                flags |= FLAG_TURBO;
+            }
+            encode_field(&mut payload, TAG_FLAGS, Some(flags));
+
+            let rune = etching.rune.as_deref().map(rune_to_u128).transpose()?;
+            encode_field(&mut payload, TAG_RUNE, rune);
+            encode_field(&mut payload, TAG_DIVISIBILITY, etching.divisibility.map(u128::from));
+            encode_field(&mut payload, TAG_SPACERS, etching.spacers.map(u128::from));
+            encode_field(&mut payload, TAG_SYMBOL, etching.symbol.map(|c| u128::from(u32::from(c))));
+            encode_field(&mut payload, TAG_PREMINE, etching.premine);
+
+            if let Some(terms) = &etching.terms {
+                encode_field(&mut payload, TAG_AMOUNT, terms.amount);
+                encode_field(&mut payload, TAG_CAP, terms.cap);
+                encode_field(&mut payload, TAG_HEIGHT_START, terms.height.0.map(u128::from));
+                encode_field(&mut payload, TAG_HEIGHT_END, terms.height.1.map(u128::from));
+                encode_field(&mut payload, TAG_OFFSET_START, terms.offset.0.map(u128::from));
+                encode_field(&mut payload, TAG_OFFSET_END, terms.offset.1.map(u128::from));
+            }
+        }
+
+        if let Some(mint) = self.mint {
+            encode_field(&mut payload, TAG_MINT, Some(u128::from(mint.block)));
+            encode_field(&mut payload, TAG_MINT, Some(u128::from(mint.tx)));
+        }
+        encode_field(&mut payload, TAG_POINTER, self.pointer.map(u128::from));
+
+        if !self.edicts.is_empty() {
+            encode_varint(&mut payload, TAG_BODY);
+
+            let mut edicts = self.edicts.clone();
+            edicts.sort_by_key(|edict| edict.id);
+
+            // Ids are delta encoded against the previous edict
+            let mut previous = RuneId { block: 0, tx: 0 };
+            for edict in &edicts {
+                let block_delta = edict.id.block - previous.block;
+                let tx_delta = if block_delta == 0 {
+                    edict.id.tx - previous.tx
+                } else {
+                    edict.id.tx
+                };
+
+                encode_varint(&mut payload, u128::from(block_delta));
+                encode_varint(&mut payload, u128::from(tx_delta));
+                encode_varint(&mut payload, edict.amount);
+                encode_varint(&mut payload, u128::from(edict.output));
+                previous = edict.id;
+            }
+        }
+
+        let mut script = vec![OP_RETURN, OP_13];
+        for chunk in payload.chunks(MAX_SCRIPT_ELEMENT_SIZE) {
+            push_bytes(&mut script, chunk);
+        }
+        Ok(script)
+    }
+
+    /// Parses the runestone carried by an output script.
+    ///
+    /// `None` when the script is no `OP_RETURN OP_13` output or the message
+    /// is malformed. Malformed messages are cenotaphs, which burn the runes
+    /// of their transaction.
+    pub fn decipher(script: &[u8]) -> Option<Runestone> {
+        if !is_runestone(script) {
+            return None;
+        }
+
+        let payload = script_payload(&script[2..])?;
+        let mut integers = Vec::new();
+        let mut rest = payload.as_slice();
+        while !rest.is_empty() {
+            let (n, length) = decode_varint(rest)?;
+            integers.push(n);
+            rest = &rest[length..];
+        }
+
+        let mut fields: HashMap<u128, VecDeque<u128>> = HashMap::new();
+        let mut body: &[u128] = &[];
+        let mut i = 0;
+        while i < integers.len() {
+            if integers[i] == TAG_BODY {
+                body = &integers[i + 1..];
+                break;
+            }
+            let value = *integers.get(i + 1)?;
+            fields.entry(integers[i]).or_default().push_back(value);
+            i += 2;
+        }
+
+        if !body.len().is_multiple_of(4) {
+            return None;
+        }
+        let mut edicts = Vec::with_capacity(body.len() / 4);
+        let mut id = RuneId { block: 0, tx: 0 };
+        for chunk in body.chunks(4) {
+            let block_delta = u64::try_from(chunk[0]).ok()?;
+            let tx_delta = u32::try_from(chunk[1]).ok()?;
+            id = if block_delta == 0 {
+                RuneId { block: id.block, tx: id.tx.checked_add(tx_delta)? }
+            } else {
+                RuneId { block: id.block.checked_add(block_delta)?, tx: tx_delta }
+            };
+            if id.block == 0 && id.tx > 0 {
+                return None;
+            }
+            edicts.push(Edict {
+                id,
+                amount: chunk[2],
+                output: u32::try_from(chunk[3]).ok()?,
+            });
+        }
+
+        let mut take = |tag: u128| fields.get_mut(&tag).and_then(VecDeque::pop_front);
+        let flags = take(TAG_FLAGS).unwrap_or_default();
+
+        let etching = if flags & FLAG_ETCHING != 0 {
+            let terms = if flags & FLAG_TERMS != 0 {
+                Some(EtchingTerms {
+                    amount: take(TAG_AMOUNT),
+                    cap: take(TAG_CAP),
+                    height: (
+                        take(TAG_HEIGHT_START).map(u64::try_from).transpose().ok()?,
+                        take(TAG_HEIGHT_END).map(u64::try_from).transpose().ok()?,
+                    ),
+                    offset: (
+                        take(TAG_OFFSET_START).map(u64::try_from).transpose().ok()?,
+                        take(TAG_OFFSET_END).map(u64::try_from).transpose().ok()?,
+                    ),
+                })
+            } else {
+                None
+            };
+
+            Some(Etching {
+                rune: take(TAG_RUNE).map(u128_to_rune),
+                divisibility: take(TAG_DIVISIBILITY).map(u8::try_from).transpose().ok()?,
+                spacers: take(TAG_SPACERS).map(u32::try_from).transpose().ok()?,
+                symbol: match take(TAG_SYMBOL) {
+                    Some(symbol) => Some(char::from_u32(u32::try_from(symbol).ok()?)?),
+                    None => None,
+                },
+                premine: take(TAG_PREMINE),
+                terms,
+                turbo: flags & FLAG_TURBO != 0,
+            })
+        } else {
+            None
+        };
+
+        let mint = match (take(TAG_MINT), take(TAG_MINT)) {
+            (Some(block), Some(tx)) => Some(RuneId {
+                block: u64::try_from(block).ok()?,
+                tx: u32::try_from(tx).ok()?,
+            }),
+            (None, None) => None,
+            _ => return None,
+        };
+        let pointer = take(TAG_POINTER).map(u32::try_from).transpose().ok()?;
+
+        // Unknown flags and even tags change how runes move, so the message
+        // cannot be followed
+        if flags & !(FLAG_ETCHING | FLAG_TERMS | FLAG_TURBO) != 0 {
+            return None;
+        }
+        if fields.iter().any(|(tag, values)| tag % 2 == 0 && !values.is_empty()) {
+            return None;
+        }
+
+        Some(Runestone {
+            edicts,
+            etching,
+            mint,
+            pointer,
+        })
+    }
+}
+
+/// Whether `script` is an `OP_RETURN OP_13` output. Only the first such
+/// output of a transaction is its runestone.
+pub fn is_runestone(script: &[u8]) -> bool {
+    script.starts_with(&[OP_RETURN, OP_13])
+}
+
+/// Concatenates the data pushes of a script, `None` on any other opcode
+fn script_payload(mut script: &[u8]) -> Option<Vec<u8>> {
+    let mut payload = Vec::new();
+
+    while let Some((&opcode, rest)) = script.split_first() {
+        let (length, rest) = match opcode {
+            0 => (0, rest),
+            n if n < OP_PUSHDATA1 => (n as usize, rest),
+            OP_PUSHDATA1 => (*rest.first()? as usize, rest.get(1..)?),
+            OP_PUSHDATA2 => (u16::from_le_bytes(rest.get(..2)?.try_into().ok()?) as usize, rest.get(2..)?),
+            OP_PUSHDATA4 => (u32::from_le_bytes(rest.get(..4)?.try_into().ok()?) as usize, rest.get(4..)?),
+            _ => return None,
+        };
+        payload.extend_from_slice(rest.get(..length)?);
+        script = &rest[length..];
+    }
+    Some(payload)
+}
+
+fn encode_field(payload: &mut Vec<u8>, tag: u128, value: Option<u128>) {
+    if let Some(value) = value {
+        encode_varint(payload, tag);
+        encode_varint(payload, value);
+    }
+}
+
+/// LEB128 encoding used for every runestone integer
+pub fn encode_varint(buffer: &mut Vec<u8>, mut n: u128) {
+    while n >> 7 > 0 {
+        buffer.push((n as u8 & 0x7f) | 0x80);
+        n >>= 7;
+    }
+    buffer.push(n as u8);
+}
+
+/// Reads one LEB128 integer, returning it with the number of bytes it took.
+/// `None` when `buffer` ends early or the value overflows.
+pub fn decode_varint(buffer: &[u8]) -> Option<(u128, usize)> {
+    let mut n: u128 = 0;
+    for (i, byte) in buffer.iter().enumerate() {
+        if i > 18 {
+            return None;
+        }
+        let value = u128::from(byte & 0x7f);
+        if i == 18 && value > 0b11 {
+            return None;
+        }
+        n |= value << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((n, i + 1));
+        }
+    }
+    None
+}
+
+fn push_bytes(script: &mut Vec<u8>, data: &[u8]) {
+    match data.len() {
+        len if len < OP_PUSHDATA1 as usize => script.push(len as u8),
+        len if len <= u8::MAX as usize => {
+            script.push(OP_PUSHDATA1);
+            script.push(len as u8);
+        }
+        len => {
+            script.push(OP_PUSHDATA2);
+            script.extend_from_slice(&(len as u16).to_le_bytes());
+        }
+    }
+    script.extend_from_slice(data);
+}
+
+/// Converts a rune name (letters only, no spacers) into its modified base-26 value
+pub fn rune_to_u128(name: &str) -> RuneResult<u128> {
+    if name.is_empty() {
+        return Err(RuneError::InvalidRequest("Rune name is empty".to_string()));
+    }
+
+    let mut value: u128 = 0;
+    for (i, c) in name.chars().enumerate() {
+        if !c.is_ascii_uppercase() {
+            return Err(RuneError::InvalidRequest(format!("Invalid rune name: {}", name)));
+        }
+        if i > 0 {
+            value = value.checked_add(1).ok_or_else(|| overflow(name))?;
+        }
+        value = value
+            .checked_mul(26)
+            .and_then(|v| v.checked_add(u128::from(c as u8 - b'A')))
+            .ok_or_else(|| overflow(name))?;
+    }
+    Ok(value)
+}
+
+/// Converts a modified base-26 value back into a rune name
+pub fn u128_to_rune(value: u128) -> String {
+    // The only value whose successor overflows
+    if value == u128::MAX {
+        return "BCGDENLQRQWDSLRUGSNLBTMFIJAV".to_string();
+    }
+
+    let mut n = value + 1;
+    let mut name = Vec::new();
+    while n > 0 {
+        name.push(b'A' + ((n - 1) % 26) as u8);
+        n = (n - 1) / 26;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+/// Name the protocol assigns to an etching at `id` that does not pick one
+pub fn reserved_rune(id: RuneId) -> String {
+    u128_to_rune(RESERVED_RUNE + ((u128::from(id.block) << 32) | u128::from(id.tx)))
+}
+
+fn overflow(name: &str) -> RuneError {
+    RuneError::InvalidRequest(format!("Rune name out of range: {}", name))
+}
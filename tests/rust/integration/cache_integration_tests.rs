@@ -20,6 +20,7 @@ async fn create_test_app() -> impl actix_web::dev::Service<actix_http::Request,
         password: None,
         timeout: Duration::from_secs(30),
         max_retries: 3,
+        ..Default::default()
     };
 
     let node = Arc::new(NodeConnection::new(
@@ -38,6 +39,7 @@ async fn create_test_app() -> impl actix_web::dev::Service<actix_http::Request,
         transaction_ttl: Duration::from_secs(300),
         address_cache_size: 1000,
         address_ttl: Duration::from_secs(300),
+        ..Default::default()
     };
 
     let cache = Arc::new(RunesCache::new(cache_config, cache_metrics));
@@ -110,9 +112,8 @@ async fn test_batch_transaction_caching() {
     assert!(resp.status().is_success());
 
     let body: serde_json::Value = test::read_body_json(resp).await;
-    let transactions = body["transactions"].as_array().unwrap();
-    assert_eq!(transactions.len(), 2);
-    assert!(body["failed_transactions"].as_array().unwrap().is_empty());
+    assert_eq!(body["results"].as_array().unwrap().len(), 2);
+    assert_eq!(body["failed"], 0);
 }
 
 #[actix_web::test]
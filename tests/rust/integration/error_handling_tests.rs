@@ -29,6 +29,7 @@ async fn create_test_app() -> impl actix_web::dev::Service<actix_http::Request,
         password: None,
         timeout: Duration::from_secs(30),
         max_retries: 3,
+        ..Default::default()
     };
 
     let node = Arc::new(NodeConnection::new(
@@ -47,6 +48,7 @@ async fn create_test_app() -> impl actix_web::dev::Service<actix_http::Request,
         transaction_ttl: Duration::from_secs(300),
         address_cache_size: 1000,
         address_ttl: Duration::from_secs(300),
+        ..Default::default()
     };
 
     let cache = Arc::new(RunesCache::new(cache_config, cache_metrics));
@@ -195,4 +197,21 @@ async fn test_internal_error_handling() {
     assert_eq!(body["code"], "NODE_CONNECTION_ERROR");
     assert!(body["message"].as_str().unwrap().contains("Node connection error"));
     assert!(body["request_id"].as_str().is_some());
-} 
\ No newline at end of file
+} 
+#[test]
+fn test_node_rpc_error_status_codes() {
+    use actix_web::ResponseError;
+    use crate::types::error::RuneError;
+
+    let not_found = RuneError::from(crate::Error::NotFound("No such transaction".into()));
+    assert_eq!(not_found.error_response().status(), 404);
+
+    let warming_up = RuneError::from(crate::Error::NodeWarmingUp("Loading block index".into()));
+    assert_eq!(warming_up.error_response().status(), 503);
+
+    let rejected = RuneError::from(crate::Error::TransactionRejected("min relay fee not met".into()));
+    assert_eq!(rejected.error_response().status(), 422);
+
+    let invalid = RuneError::from(crate::Error::InvalidParameter("txid must be hex".into()));
+    assert_eq!(invalid.error_response().status(), 400);
+}
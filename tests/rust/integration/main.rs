@@ -44,11 +44,19 @@ async fn test_runes_api_integration() {
 
 #[tokio::test]
 async fn test_websocket_integration() {
-    let ws_server = MockServer::start().await;
-    
+    // Accepts a single WebSocket handshake
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let _socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    });
+
     // Create WebSocket client
     let config = WebSocketConfig {
-        url: format!("ws://{}", ws_server.address()),
+        url: format!("ws://{}", address),
+        api_key: None,
         reconnect_interval: Some(1000),
         max_reconnect_attempts: Some(3),
     };
@@ -56,4 +64,4 @@ async fn test_websocket_integration() {
     let ws_client = WebSocketService::new(config);
     let result = ws_client.connect().await;
     assert!(result.is_ok());
-} 
\ No newline at end of file
+}
@@ -24,6 +24,7 @@ async fn create_test_app() -> impl actix_web::dev::Service<actix_http::Request,
         password: None,
         timeout: Duration::from_secs(30),
         max_retries: 3,
+        ..Default::default()
     };
 
     let node = Arc::new(NodeConnection::new(
@@ -42,6 +43,7 @@ async fn create_test_app() -> impl actix_web::dev::Service<actix_http::Request,
         transaction_ttl: Duration::from_secs(300),
         address_cache_size: 1000,
         address_ttl: Duration::from_secs(300),
+        ..Default::default()
     };
 
     let cache = Arc::new(RunesCache::new(cache_config, cache_metrics));
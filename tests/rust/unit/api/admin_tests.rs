@@ -0,0 +1,319 @@
+use actix_web::{http::StatusCode, test, web, App};
+use metrics::{Counter, Gauge, Histogram};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::api::{
+    admin::{handlers::AdminApiContext, routes::configure_routes},
+    middleware::auth::ApiKeyAuth,
+};
+use crate::services::{
+    auth::{ApiKeyScope, ApiKeyStore},
+    cache::{CacheConfig, CacheMetrics, RunesCache},
+    events::{
+        bus::{ChainEventData, EventBus},
+        watcher::{BlockWatcher, WatcherConfig},
+    },
+    node::{
+        backend::{BackendKind, NodeBackend},
+        connection::{MetricsCollector, NodeConnection},
+        sync::SyncService,
+    },
+    rate_limit::{RateLimitConfig, RateLimitMetrics, RateLimiter},
+    webhook::manager::{WebhookConfig, WebhookEventType, WebhookManager},
+};
+use crate::testing::FakeNode;
+use crate::types::rune::{NetworkType, RunesTransactionResponse, TransactionStatus};
+
+fn context(sync: Option<Arc<SyncService>>) -> AdminApiContext {
+    AdminApiContext {
+        cache: Arc::new(RunesCache::new(CacheConfig::default(), Arc::new(CacheMetrics::default()))),
+        rate_limiter: Arc::new(RateLimiter::new(
+            RateLimitConfig {
+                window_size: Duration::from_secs(60),
+                max_requests: 10,
+                burst_size: 1,
+            },
+            Arc::new(RateLimitMetrics::default()),
+        )),
+        webhook_manager: Arc::new(WebhookManager::new(Arc::new(Counter::noop()))),
+        sync,
+        catalog: None,
+        watcher: None,
+    }
+}
+
+fn transaction(tx_id: &str) -> RunesTransactionResponse {
+    RunesTransactionResponse {
+        transaction_id: tx_id.to_string(),
+        runes: vec![],
+        block_height: Some(840_000),
+        confirmation_count: 10,
+        timestamp: 1_713_571_767,
+        network_type: NetworkType::Mainnet,
+        status: TransactionStatus::Confirmed,
+    }
+}
+
+async fn call(context: &web::Data<AdminApiContext>, req: test::TestRequest) -> (StatusCode, Value) {
+    let app = test::init_service(
+        App::new()
+            .app_data(context.clone())
+            .configure(configure_routes),
+    )
+    .await;
+
+    let resp = test::call_service(&app, req.to_request()).await;
+    let status = resp.status();
+    let body = test::read_body(resp).await;
+    let body = if body.is_empty() { Value::Null } else { serde_json::from_slice(&body).unwrap() };
+    (status, body)
+}
+
+#[actix_web::test]
+async fn test_admin_scope_is_required() {
+    let store = ApiKeyStore::new();
+    store
+        .import_key("hooks", "Hooks", "hooks-key", [ApiKeyScope::WebhooksWrite], None)
+        .unwrap();
+    store
+        .import_key("operator", "Operator", "operator-key", [ApiKeyScope::Admin], None)
+        .unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .wrap(ApiKeyAuth::new(Arc::new(store)))
+            .app_data(web::Data::new(context(None)))
+            .configure(configure_routes),
+    )
+    .await;
+
+    for (key, status) in [
+        (None, StatusCode::UNAUTHORIZED),
+        (Some("hooks-key"), StatusCode::FORBIDDEN),
+        (Some("operator-key"), StatusCode::OK),
+    ] {
+        let mut req = test::TestRequest::get().uri("/api/v1/admin/cache");
+        if let Some(key) = key {
+            req = req.insert_header(("X-API-Key", key));
+        }
+        let resp = test::try_call_service(&app, req.to_request()).await;
+        let actual = match resp {
+            Ok(resp) => resp.status(),
+            Err(e) => e.as_response_error().status_code(),
+        };
+        assert_eq!(actual, status, "{:?}", key);
+    }
+}
+
+#[actix_web::test]
+async fn test_cache_flush_and_invalidate() {
+    let context = web::Data::new(context(None));
+    let cache = context.cache.clone();
+    for tx_id in ["aa", "bb"] {
+        cache.set_transaction(tx_id.to_string(), transaction(tx_id)).await.unwrap();
+    }
+    cache.set_address_transfers("bc1qaddress".to_string(), vec![]).await.unwrap();
+
+    let (status, body) = call(&context, test::TestRequest::delete().uri("/api/v1/admin/cache/transaction/aa")).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert_eq!(body, Value::Null);
+    assert!(cache.get_transaction("aa").await.is_none());
+    assert!(cache.get_transaction("bb").await.is_some());
+
+    let (status, _) = call(&context, test::TestRequest::delete().uri("/api/v1/admin/cache/block/aa")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, body) = call(&context, test::TestRequest::delete().uri("/api/v1/admin/cache/rune/not-a-rune")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+
+    let (status, stats) = call(&context, test::TestRequest::get().uri("/api/v1/admin/cache")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(stats["transaction_cache_size"], 1);
+    assert_eq!(stats["address_cache_size"], 1);
+
+    let (status, stats) = call(&context, test::TestRequest::delete().uri("/api/v1/admin/cache")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(stats["transaction_cache_size"], 0);
+    assert_eq!(stats["address_cache_size"], 0);
+    assert!(cache.get_address_transfers("bc1qaddress").await.is_none());
+}
+
+#[actix_web::test]
+async fn test_rate_limit_buckets() {
+    let context = web::Data::new(context(None));
+    for (key, requests) in [("key:reader", 9), ("key:listener", 1), ("127.0.0.1", 3)] {
+        for _ in 0..requests {
+            context.rate_limiter.check_rate_limit(key, 1).await.unwrap();
+        }
+    }
+
+    let (status, body) = call(&context, test::TestRequest::get().uri("/api/v1/admin/rate-limits?prefix=key:&limit=1")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["stats"]["current_buckets"], 3);
+    assert_eq!(body["total"], 2);
+    // Fewest tokens left first
+    assert_eq!(body["buckets"].as_array().unwrap().len(), 1);
+    assert_eq!(body["buckets"][0]["key"], "key:reader");
+
+    let (status, _) = call(&context, test::TestRequest::get().uri("/api/v1/admin/rate-limits?limit=0")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, bucket) = call(&context, test::TestRequest::get().uri("/api/v1/admin/rate-limits/127.0.0.1")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(bucket["max_tokens"], 10.0);
+
+    let reset = || test::TestRequest::delete().uri("/api/v1/admin/rate-limits/key:reader");
+    let (status, body) = call(&context, reset()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!({"removed": 1}));
+    let (status, body) = call(&context, reset()).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], "NOT_FOUND");
+
+    let (status, body) = call(&context, test::TestRequest::delete().uri("/api/v1/admin/rate-limits")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!({"removed": 2}));
+    assert!(context.rate_limiter.buckets().await.is_empty());
+}
+
+#[actix_web::test]
+async fn test_webhooks_are_listed_without_secrets() {
+    let context = web::Data::new(context(None));
+    context
+        .webhook_manager
+        .register_webhook(WebhookConfig {
+            url: "https://example.com/webhook".to_string(),
+            secret: Some("very-secret".to_string()),
+            events: vec![WebhookEventType::RuneTransfer],
+            max_retries: 3,
+            retry_delay: 1000,
+        })
+        .await
+        .unwrap();
+
+    let (status, body) = call(&context, test::TestRequest::get().uri("/api/v1/admin/webhooks")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(!body.to_string().contains("very-secret"));
+    assert_eq!(body[0]["url"], "https://example.com/webhook");
+    assert_eq!(body[0]["signed"], true);
+    assert_eq!(body[0]["healthy"], true);
+    assert_eq!(body[0]["health"]["delivered"], 0);
+}
+
+#[actix_web::test]
+async fn test_sync_control() {
+    let (status, body) = call(&web::Data::new(context(None)), test::TestRequest::get().uri("/api/v1/admin/sync")).await;
+    assert_eq!(status, StatusCode::NOT_IMPLEMENTED);
+    assert_eq!(body["code"], "UNSUPPORTED_OPERATION");
+
+    let fake = FakeNode::start().await;
+    fake.mine_empty(20);
+    let metrics = Arc::new(MetricsCollector {
+        transaction_counter: Counter::noop(),
+        error_counter: Counter::noop(),
+        response_time: Histogram::noop(),
+        active_connections: Gauge::noop(),
+    });
+    let node: Arc<dyn NodeBackend> = Arc::new(NodeConnection::new(fake.config(BackendKind::Bitcoind), metrics));
+    let sync = Arc::new(SyncService::new(node, Duration::from_millis(5)));
+    let context = web::Data::new(context(Some(sync.clone())));
+
+    let (status, body) = call(&context, test::TestRequest::post().uri("/api/v1/admin/sync/pause")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["paused"], true);
+    assert_eq!(body["is_syncing"], false);
+
+    let rewind = |height: u64| {
+        test::TestRequest::post()
+            .uri("/api/v1/admin/sync/rewind")
+            .set_json(json!({"from_height": height}))
+    };
+    let (status, body) = call(&context, rewind(21)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["message"].as_str().unwrap().contains("above the node tip"));
+
+    let (status, body) = call(&context, rewind(5)).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    assert_eq!(body["current_height"], 4);
+    assert_eq!(body["target_height"], 20);
+    // Still paused, nothing moves until resumed
+    assert_eq!(body["is_syncing"], false);
+
+    let (status, body) = call(&context, test::TestRequest::post().uri("/api/v1/admin/sync/resume")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["paused"], false);
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while sync.get_sync_status().await.unwrap().current_height < 20 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .unwrap();
+
+    let (status, body) = call(&context, test::TestRequest::get().uri("/api/v1/admin/sync")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["current_height"], 20);
+}
+
+#[actix_web::test]
+async fn test_rewind_reindexes_from_the_height() {
+    let fake = FakeNode::start().await;
+    fake.mine_empty(20);
+    let metrics = Arc::new(MetricsCollector {
+        transaction_counter: Counter::noop(),
+        error_counter: Counter::noop(),
+        response_time: Histogram::noop(),
+        active_connections: Gauge::noop(),
+    });
+    let node: Arc<dyn NodeBackend> = Arc::new(NodeConnection::new(fake.config(BackendKind::Bitcoind), metrics));
+    let bus = Arc::new(EventBus::new(16));
+    let watcher = Arc::new(BlockWatcher::new(node.clone(), bus.clone(), WatcherConfig::default()));
+    assert_eq!(watcher.poll().await.unwrap(), 1);
+    let published = bus.last_event_id();
+
+    let sync = Arc::new(SyncService::new(node, Duration::from_millis(5)));
+    sync.pause().await.unwrap();
+    let context = web::Data::new(AdminApiContext {
+        sync: Some(sync),
+        watcher: Some(watcher.clone()),
+        ..context(None)
+    });
+
+    let cache = context.cache.clone();
+    for (tx_id, height) in [("aa", 17), ("bb", 18)] {
+        let tx = RunesTransactionResponse {
+            block_height: Some(height),
+            ..transaction(tx_id)
+        };
+        cache.set_transaction(tx_id.to_string(), tx).await.unwrap();
+    }
+    cache.set_address_transfers("bc1qaddress".to_string(), vec![]).await.unwrap();
+
+    let req = test::TestRequest::post()
+        .uri("/api/v1/admin/sync/rewind")
+        .set_json(json!({"from_height": 18}));
+    let (status, body) = call(&context, req).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    assert_eq!(body["current_height"], 17);
+
+    assert!(cache.get_transaction("aa").await.is_some());
+    assert!(cache.get_transaction("bb").await.is_none());
+    assert!(cache.get_address_transfers("bc1qaddress").await.is_none());
+
+    // Subscribers are told to drop blocks 18 and up, then get them again
+    assert_eq!(watcher.poll().await.unwrap(), 3);
+    let events = bus.subscribe(published).replay;
+    assert!(matches!(
+        events[0].data,
+        ChainEventData::Reorg { fork_height: 17, old_tip_height: 20, .. }
+    ));
+    let heights: Vec<u64> = events
+        .iter()
+        .filter_map(|event| match event.data {
+            ChainEventData::Block { height, .. } => Some(height),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(heights, vec![18, 19, 20]);
+}
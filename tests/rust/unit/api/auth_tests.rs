@@ -0,0 +1,321 @@
+use actix_web::{
+    http::{Method, StatusCode},
+    test, web, App, HttpMessage, HttpRequest, HttpResponse,
+};
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::api::middleware::{
+    auth::{route_access, Access, ApiKeyAuth},
+    error_handler::ErrorHandler,
+    logging::LoggingMiddleware,
+    metrics::MetricsMiddleware,
+    rate_limit::{is_rate_limited, RateLimitMiddleware},
+    request_id::RequestId,
+    signature::{requires_signature, SignatureAuth},
+};
+use crate::services::{
+    auth::{
+        signing::{
+            RequestVerifier, SignedRequest, SigningConfig, NONCE_HEADER, SIGNATURE_HEADER,
+            TIMESTAMP_HEADER,
+        },
+        ApiKeyId, ApiKeyScope,
+    },
+    rate_limit::{RateLimitConfig, RateLimitMetrics, RateLimiter},
+};
+use crate::testing::fixtures::key_store;
+
+/// Answers with the id of the key the request was authenticated with
+async fn whoami(req: HttpRequest) -> HttpResponse {
+    let id = req.extensions().get::<ApiKeyId>().map(|id| id.0.clone());
+    HttpResponse::Ok().body(id.unwrap_or_else(|| "anonymous".to_string()))
+}
+
+macro_rules! app {
+    ($store:expr, $max_requests:expr) => {
+        test::init_service(
+            App::new()
+                .wrap(RateLimitMiddleware::new(Arc::new(RateLimiter::new(
+                    RateLimitConfig {
+                        window_size: Duration::from_secs(60),
+                        max_requests: $max_requests,
+                        burst_size: 1,
+                    },
+                    Arc::new(RateLimitMetrics::default()),
+                ))))
+                .wrap(ApiKeyAuth::new($store))
+                .route("/api/v1/runes/{rune}", web::get().to(whoami))
+                .route("/api/v1/webhooks", web::post().to(whoami))
+                .route("/api/v1/stream", web::get().to(whoami))
+                .route("/api/v1/admin/cache", web::delete().to(whoami))
+                .route("/metrics", web::get().to(whoami)),
+        )
+        .await
+    };
+}
+
+fn get(uri: &str, key: Option<&str>) -> test::TestRequest {
+    let req = test::TestRequest::get()
+        .uri(uri)
+        .peer_addr("127.0.0.1:40000".parse().unwrap());
+    match key {
+        Some(key) => req.insert_header(("X-API-Key", key)),
+        None => req,
+    }
+}
+
+#[actix_web::test]
+async fn test_keys_and_scopes_are_enforced() {
+    let app = app!(key_store(), 100);
+
+    let resp = test::call_service(&app, get("/api/v1/runes/UNCOMMONGOODS", None).to_request()).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "UNAUTHORIZED");
+
+    let req = get("/api/v1/runes/UNCOMMONGOODS", Some("wrong-key")).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::UNAUTHORIZED);
+
+    let req = get("/api/v1/runes/UNCOMMONGOODS", Some("listener-key")).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "FORBIDDEN");
+
+    let req = get("/api/v1/runes/UNCOMMONGOODS", Some("reader-key")).to_request();
+    assert_eq!(test::call_and_read_body(&app, req).await, "reader");
+
+    let req = test::TestRequest::post()
+        .uri("/api/v1/webhooks")
+        .insert_header(("X-API-Key", "reader-key"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::FORBIDDEN);
+
+    let req = test::TestRequest::post()
+        .uri("/api/v1/webhooks")
+        .insert_header(("X-API-Key", "operator-key"))
+        .to_request();
+    assert_eq!(test::call_and_read_body(&app, req).await, "operator");
+}
+
+#[actix_web::test]
+async fn test_encoded_paths_need_the_same_key() {
+    let app = app!(key_store(), 100);
+
+    // The router decodes `%61dmin` to `admin`, so must the key check
+    let req = test::TestRequest::delete().uri("/api/v1/%61dmin/cache").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let req = test::TestRequest::delete()
+        .uri("/api/v1/%61dmin/cache")
+        .insert_header(("X-API-Key", "reader-key"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::FORBIDDEN);
+
+    let req = get("/api/v1/%72unes/UNCOMMONGOODS", None).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn test_disabled_key_is_rejected() {
+    let store = key_store();
+    let app = app!(store.clone(), 100);
+
+    store.set_enabled("reader", false).unwrap();
+    let req = get("/api/v1/runes/UNCOMMONGOODS", Some("reader-key")).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::UNAUTHORIZED);
+
+    store.set_enabled("reader", true).unwrap();
+    let req = get("/api/v1/runes/UNCOMMONGOODS", Some("reader-key")).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+    assert!(store.get_key("reader").unwrap().last_used_at.is_some());
+}
+
+#[actix_web::test]
+async fn test_rejected_requests_carry_a_request_id() {
+    // Same order as the server, keys are checked inside the request id
+    let app = test::init_service(
+        App::new()
+            .wrap(ApiKeyAuth::new(key_store()))
+            .wrap(MetricsMiddleware)
+            .wrap(LoggingMiddleware)
+            .wrap(ErrorHandler::new())
+            .wrap(RequestId::new())
+            .route("/api/v1/runes/{rune}", web::get().to(whoami)),
+    )
+    .await;
+
+    let resp = test::call_service(&app, get("/api/v1/runes/UNCOMMONGOODS", None).to_request()).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    assert!(resp.headers().contains_key("x-request-id"));
+
+    let req = get("/api/v1/runes/UNCOMMONGOODS", Some("listener-key"))
+        .insert_header(("X-Request-Id", "req-1"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    assert_eq!(resp.headers().get("x-request-id").unwrap(), "req-1");
+}
+
+#[actix_web::test]
+async fn test_public_routes_and_query_keys() {
+    let app = app!(key_store(), 100);
+
+    let req = get("/metrics", None).to_request();
+    assert_eq!(test::call_and_read_body(&app, req).await, "anonymous");
+
+    // Query keys are only for clients that cannot set headers on streams
+    let req = get("/api/v1/stream?api_key=listener-key", None).to_request();
+    assert_eq!(test::call_and_read_body(&app, req).await, "listener");
+
+    let req = get("/api/v1/runes/UNCOMMONGOODS?api_key=reader-key", None).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn test_rate_limit_buckets_per_key() {
+    let app = app!(key_store(), 1);
+
+    for key in ["reader-key", "operator-key"] {
+        let req = get("/api/v1/runes/UNCOMMONGOODS", Some(key)).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+    }
+
+    let req = get("/api/v1/runes/UNCOMMONGOODS", Some("reader-key")).to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::TOO_MANY_REQUESTS
+    );
+}
+
+#[actix_web::test]
+async fn test_probes_and_metrics_are_not_rate_limited() {
+    let app = app!(key_store(), 1);
+
+    for _ in 0..3 {
+        let req = get("/metrics", None).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+    }
+
+    assert!(!is_rate_limited("/health/live"));
+    assert!(!is_rate_limited("/health/ready"));
+    assert!(is_rate_limited("/healthz"));
+    assert!(is_rate_limited("/api/v1/runes"));
+}
+
+#[actix_web::test]
+async fn test_route_access() {
+    assert_eq!(
+        route_access(&Method::GET, "/api/v1/runes"),
+        Access::Required(ApiKeyScope::RunesRead)
+    );
+    assert_eq!(
+        route_access(&Method::POST, "/api/v1/graphql"),
+        Access::Required(ApiKeyScope::RunesRead)
+    );
+    assert_eq!(route_access(&Method::GET, "/api/v1/graphql"), Access::Public);
+    assert_eq!(
+        route_access(&Method::GET, "/api/v1/ws"),
+        Access::Optional(ApiKeyScope::EventsRead)
+    );
+    assert_eq!(
+        route_access(&Method::DELETE, "/api/v1/webhooks/https%3A%2F%2Fexample.com"),
+        Access::Required(ApiKeyScope::WebhooksWrite)
+    );
+    assert_eq!(
+        route_access(&Method::GET, "/api/v1/admin/rate-limits"),
+        Access::Required(ApiKeyScope::Admin)
+    );
+    assert!(requires_signature("/api/v1/admin/sync/pause"));
+    assert!(!requires_signature("/api/v1/administrator"));
+    assert_eq!(route_access(&Method::GET, "/api/v1/runesx"), Access::Public);
+    assert_eq!(route_access(&Method::GET, "/swagger-ui/index.html"), Access::Public);
+}
+
+/// Echoes the body the handler receives, to check it survives verification
+async fn echo(body: web::Bytes) -> HttpResponse {
+    HttpResponse::Ok().body(body)
+}
+
+fn signed_post(path: &str, key: &str, secret: &str, nonce: &str, body: &'static str) -> test::TestRequest {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let signature = SignedRequest {
+        method: "POST",
+        path,
+        timestamp,
+        nonce,
+        body: body.as_bytes(),
+    }
+    .sign(secret);
+
+    test::TestRequest::post()
+        .uri(path)
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .insert_header(("X-API-Key", key))
+        .insert_header((SIGNATURE_HEADER, signature))
+        .insert_header((TIMESTAMP_HEADER, timestamp.to_string()))
+        .insert_header((NONCE_HEADER, nonce))
+        .set_payload(body)
+}
+
+#[actix_web::test]
+async fn test_privileged_routes_need_signed_requests() {
+    let store = key_store();
+    store.set_signing_secret("operator", "operator-secret").unwrap();
+    let verifier = Arc::new(RequestVerifier::new(store.clone(), SigningConfig::default()));
+
+    let app = test::init_service(
+        App::new()
+            .wrap(SignatureAuth::new(verifier))
+            .wrap(ApiKeyAuth::new(store))
+            .route("/api/v1/webhooks", web::post().to(echo))
+            .route("/api/v1/runes/{rune}", web::get().to(whoami)),
+    )
+    .await;
+    let body = r#"{"url":"https://example.com/hook"}"#;
+
+    // Unprivileged routes are not affected
+    let req = get("/api/v1/runes/UNCOMMONGOODS", Some("operator-key")).to_request();
+    assert_eq!(test::call_and_read_body(&app, req).await, "operator");
+
+    let req = test::TestRequest::post()
+        .uri("/api/v1/webhooks")
+        .insert_header(("X-API-Key", "operator-key"))
+        .set_payload(body)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    let error: Value = test::read_body_json(resp).await;
+    assert!(error["message"].as_str().unwrap().contains("X-Signature"), "{}", error);
+
+    // Encoding the path does not get around the signature
+    let req = test::TestRequest::post()
+        .uri("/api/v1/%77ebhooks")
+        .insert_header(("X-API-Key", "operator-key"))
+        .set_payload(body)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::UNAUTHORIZED);
+
+    let req = signed_post("/api/v1/webhooks", "operator-key", "operator-secret", "nonce-0001", body);
+    assert_eq!(test::call_and_read_body(&app, req.to_request()).await, body);
+
+    // The same nonce again is a replay
+    let req = signed_post("/api/v1/webhooks", "operator-key", "operator-secret", "nonce-0001", body);
+    assert_eq!(test::call_service(&app, req.to_request()).await.status(), StatusCode::UNAUTHORIZED);
+
+    // A signature over a different body does not match
+    let req = signed_post("/api/v1/webhooks", "operator-key", "operator-secret", "nonce-0002", "{}")
+        .set_payload(body);
+    assert_eq!(test::call_service(&app, req.to_request()).await.status(), StatusCode::UNAUTHORIZED);
+
+    // Keys without a signing secret cannot make privileged calls
+    let req = signed_post("/api/v1/webhooks", "reader-key", "operator-secret", "nonce-0003", body);
+    let resp = test::call_service(&app, req.to_request()).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,211 @@
+use actix_web::{body::BoxBody, dev::ServiceResponse, test, web, App};
+use serde::de::IgnoredAny;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::api::{
+    encoding::{ResponseFormat, CBOR, JSON, MESSAGE_PACK},
+    middleware::{error_handler::ErrorHandler, request_id::RequestId},
+    webhook::{
+        handlers::{RegisterWebhookRequest, WebhookApiContext},
+        routes::configure_routes,
+    },
+};
+use crate::services::webhook::manager::{WebhookEventType, WebhookManager};
+use crate::types::{
+    error::{ErrorResponse, RuneError},
+    rune::RuneTransfer,
+};
+
+fn negotiate(accept: &str) -> ResponseFormat {
+    ResponseFormat::negotiate(&test::TestRequest::default().insert_header(("Accept", accept)).to_http_request())
+}
+
+async fn create_test_app() -> impl actix_web::dev::Service<actix_http::Request, Response = actix_web::dev::ServiceResponse<impl actix_web::body::MessageBody>, Error = actix_web::Error> {
+    let context = WebhookApiContext {
+        webhook_manager: Arc::new(WebhookManager::new(Arc::new(metrics::Counter::noop()))),
+    };
+
+    test::init_service(
+        App::new()
+            .wrap(ErrorHandler::new())
+            .app_data(web::Data::new(context))
+            .configure(configure_routes)
+    ).await
+}
+
+fn register_request() -> RegisterWebhookRequest {
+    RegisterWebhookRequest {
+        url: "https://example.com/webhook".to_string(),
+        secret: None,
+        events: vec![WebhookEventType::TransactionConfirmed],
+        max_retries: 3,
+        retry_delay: 1000,
+    }
+}
+
+#[actix_web::test]
+async fn test_accept_negotiation() {
+    let request = test::TestRequest::default().to_http_request();
+    assert_eq!(ResponseFormat::negotiate(&request), ResponseFormat::Json);
+
+    for (accept, format) in [
+        ("application/json", ResponseFormat::Json),
+        ("*/*", ResponseFormat::Json),
+        ("application/cbor", ResponseFormat::Cbor),
+        ("application/msgpack", ResponseFormat::MessagePack),
+        ("application/x-msgpack", ResponseFormat::MessagePack),
+        ("application/vnd.msgpack", ResponseFormat::MessagePack),
+        // Highest quality first, unsupported types are skipped
+        ("application/json;q=0.5, application/cbor", ResponseFormat::Cbor),
+        ("text/html, application/msgpack;q=0.1, */*;q=0.01", ResponseFormat::MessagePack),
+        ("application/cbor;q=0, application/json;q=0.2", ResponseFormat::Json),
+        ("not a media type", ResponseFormat::Json),
+    ] {
+        assert_eq!(negotiate(accept), format, "{}", accept);
+    }
+
+    // Nothing we can produce is a 406, whose error body is still JSON
+    for accept in ["text/html", "application/xml", "application/json;q=0"] {
+        let request = test::TestRequest::default().insert_header(("Accept", accept)).to_http_request();
+        assert!(
+            matches!(ResponseFormat::acceptable(&request), Err(RuneError::NotAcceptable(_))),
+            "{}",
+            accept
+        );
+        assert_eq!(negotiate(accept), ResponseFormat::Json, "{}", accept);
+    }
+}
+
+#[actix_web::test]
+async fn test_encodings_round_trip() {
+    let transfer = RuneTransfer {
+        rune_id: "840000:1".to_string(),
+        from_address: "bc1qsender".to_string(),
+        to_address: "bc1qreceiver".to_string(),
+        // Past u64, CBOR and MessagePack have to keep it exact
+        amount: u128::MAX - 1,
+        transfer_type: crate::types::rune::TransferType::Mint,
+        fee: None,
+        metadata: Some([("note".to_string(), json!({"nested": [1, 2]}))].into()),
+    };
+
+    let cbor = ResponseFormat::Cbor.encode(&transfer).unwrap();
+    let decoded: RuneTransfer = ciborium::from_reader(cbor.as_slice()).unwrap();
+    assert_eq!(decoded.amount, transfer.amount);
+    assert_eq!(decoded.metadata, transfer.metadata);
+
+    let msgpack = ResponseFormat::MessagePack.encode(&transfer).unwrap();
+    let decoded: RuneTransfer = rmp_serde::from_slice(&msgpack).unwrap();
+    assert_eq!(decoded.amount, transfer.amount);
+    // Named fields, so clients in other languages see the same keys as in JSON
+    let map: HashMap<String, IgnoredAny> = rmp_serde::from_slice(&msgpack).unwrap();
+    assert!(map.contains_key("rune_id"));
+}
+
+#[actix_web::test]
+async fn test_webhook_responses_follow_accept() {
+    let app = create_test_app().await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/v1/webhooks")
+        .insert_header(("Accept", CBOR))
+        .set_json(register_request())
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers().get("content-type").unwrap(), CBOR);
+    assert_eq!(resp.headers().get("vary").unwrap(), "Accept");
+    let body: Value = ciborium::from_reader(test::read_body(resp).await.as_ref()).unwrap();
+    assert_eq!(body["success"], true);
+
+    let req = test::TestRequest::delete()
+        .uri("/api/v1/webhooks/https%3A%2F%2Fexample.com%2Fmissing")
+        .insert_header(("Accept", MESSAGE_PACK))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+    assert_eq!(resp.headers().get("content-type").unwrap(), MESSAGE_PACK);
+    let body: Value = rmp_serde::from_slice(&test::read_body(resp).await).unwrap();
+    assert_eq!(body["success"], false);
+
+    let req = test::TestRequest::post()
+        .uri("/api/v1/webhooks")
+        .insert_header(("Accept", "application/xml"))
+        .set_json(register_request())
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 406);
+    assert_eq!(resp.headers().get("content-type").unwrap(), JSON);
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "NOT_ACCEPTABLE");
+}
+
+#[actix_web::test]
+async fn test_extractor_errors_follow_accept() {
+    let app = create_test_app().await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/v1/webhooks")
+        .insert_header(("Accept", CBOR))
+        .insert_header(("Content-Type", "application/json"))
+        .set_payload("{not json")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+    assert_eq!(resp.headers().get("content-type").unwrap(), CBOR);
+    let error: ErrorResponse = ciborium::from_reader(test::read_body(resp).await.as_ref()).unwrap();
+    assert_eq!(error.code, "INVALID_REQUEST");
+    assert!(error.message.contains("Json deserialize error"), "{}", error.message);
+}
+
+#[actix_web::test]
+async fn test_middleware_errors_follow_accept() {
+    let app = test::init_service(
+        App::new()
+            .wrap_fn(|_, _| async {
+                Err::<ServiceResponse<BoxBody>, _>(RuneError::Forbidden("no".to_string()).into())
+            })
+            .wrap(ErrorHandler::new())
+            .wrap(RequestId::new())
+            .configure(configure_routes),
+    )
+    .await;
+
+    let req = test::TestRequest::delete()
+        .uri("/api/v1/webhooks/x")
+        .insert_header(("Accept", MESSAGE_PACK))
+        .insert_header(("X-Request-ID", "req-1"))
+        .to_request();
+    // The server renders the error ErrorHandler hands back
+    let resp = test::try_call_service(&app, req).await.unwrap_err().error_response();
+    assert_eq!(resp.status(), 403);
+    assert_eq!(resp.headers().get("content-type").unwrap(), MESSAGE_PACK);
+    assert_eq!(resp.headers().get("x-request-id").unwrap(), "req-1");
+    let body = actix_web::body::to_bytes(resp.into_body()).await.unwrap();
+    let error: ErrorResponse = rmp_serde::from_slice(&body).unwrap();
+    assert_eq!(error.code, "FORBIDDEN");
+    assert_eq!(error.request_id.as_deref(), Some("req-1"));
+}
+
+#[actix_web::test]
+async fn test_responses_are_compressed_when_asked() {
+    let app = create_test_app().await;
+
+    for (accept_encoding, content_encoding) in [("gzip", Some("gzip")), ("br;q=1, gzip;q=0.5", Some("br")), ("identity", None)] {
+        let req = test::TestRequest::post()
+            .uri("/api/v1/webhooks")
+            .insert_header(("Accept-Encoding", accept_encoding))
+            .set_json(register_request())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(
+            resp.headers().get("content-encoding").map(|value| value.to_str().unwrap()),
+            content_encoding,
+            "{}",
+            accept_encoding
+        );
+    }
+}
@@ -0,0 +1,346 @@
+use actix_web::{test, web, App};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::api::graphql::{
+    handlers::GraphQLApiContext,
+    routes::configure_routes,
+    schema::{build_schema, GraphQLConfig},
+};
+use crate::api::http_cache::HttpCacheConfig;
+use crate::services::cache::{CacheConfig, CacheMetrics, RunesCache};
+use crate::services::catalog::RuneCatalog;
+use crate::services::node::{
+    backend::{BackendKind, BlockInfo, NodeBackend},
+    connection::NodeStatus,
+};
+use crate::testing::fixtures::{dog_go_to_the_moon, uncommon_goods};
+use crate::types::{
+    error::{RuneError, RuneResult},
+    rune::{
+        NetworkType, RuneBalance, RuneEntry, RuneOutput, RunePage, RunesTransactionResponse,
+        RuneTransfer, TransactionStatus, TransferType,
+    },
+};
+
+const ADDRESS: &str = "bc1qxy2kgdygjrsqtzq2n0yrf2493p83kkfjhx0wlh";
+
+fn balance(rune: &RuneEntry, amount: u128) -> RuneBalance {
+    RuneBalance {
+        rune: rune.spaced_name.clone(),
+        amount,
+        divisibility: rune.divisibility,
+        symbol: rune.symbol,
+    }
+}
+
+fn transfer(rune_id: &str) -> RuneTransfer {
+    RuneTransfer {
+        rune_id: rune_id.to_string(),
+        from_address: "bc1qsender".to_string(),
+        to_address: ADDRESS.to_string(),
+        amount: 1_000,
+        transfer_type: TransferType::Transfer,
+        fee: None,
+        metadata: None,
+    }
+}
+
+/// Ord-like node counting the lookups the resolvers make
+#[derive(Default)]
+struct CountingBackend {
+    rune_lookups: AtomicUsize,
+    output_lookups: AtomicUsize,
+    transaction_lookups: AtomicUsize,
+}
+
+#[async_trait]
+impl NodeBackend for CountingBackend {
+    fn kind(&self) -> BackendKind {
+        BackendKind::Ord
+    }
+
+    async fn health_check(&self) -> RuneResult<NodeStatus> {
+        Err(RuneError::UnsupportedOperation("health_check".to_string()))
+    }
+
+    async fn get_block_height(&self) -> RuneResult<u64> {
+        Ok(840_010)
+    }
+
+    async fn get_block_hash(&self, height: u64) -> RuneResult<String> {
+        Ok(format!("hash{}", height))
+    }
+
+    async fn get_block(&self, hash: &str) -> RuneResult<BlockInfo> {
+        Ok(BlockInfo {
+            hash: hash.to_string(),
+            height: hash.trim_start_matches("hash").parse().unwrap(),
+            previous_block_hash: None,
+            timestamp: 1_713_571_767,
+            tx_ids: (1..=3).map(|i| i.to_string().repeat(64)).collect(),
+        })
+    }
+
+    async fn get_raw_transaction(&self, _tx_id: &str) -> RuneResult<String> {
+        Err(RuneError::UnsupportedOperation("get_raw_transaction".to_string()))
+    }
+
+    async fn get_transaction(&self, tx_id: &str) -> RuneResult<RunesTransactionResponse> {
+        self.transaction_lookups.fetch_add(1, Ordering::SeqCst);
+        Ok(RunesTransactionResponse {
+            transaction_id: tx_id.to_string(),
+            runes: vec![transfer("840000:1")],
+            block_height: Some(840_000),
+            confirmation_count: 11,
+            timestamp: 1_713_571_767,
+            network_type: NetworkType::Mainnet,
+            status: TransactionStatus::Confirmed,
+        })
+    }
+
+    async fn get_address_transfers(&self, _address: &str) -> RuneResult<Vec<RuneTransfer>> {
+        Ok((0..5).map(|_| transfer("840000:1")).collect())
+    }
+
+    async fn get_rune(&self, rune: &str) -> RuneResult<RuneEntry> {
+        self.rune_lookups.fetch_add(1, Ordering::SeqCst);
+        match rune {
+            "840000:1" | "UNCOMMONGOODS" => Ok(uncommon_goods()),
+            "840000:3" | "DOGGOTOTHEMOON" => Ok(dog_go_to_the_moon()),
+            _ => Err(RuneError::NotFound(format!("Rune {} not found", rune))),
+        }
+    }
+
+    async fn get_runes(&self, _page: Option<u32>) -> RuneResult<RunePage> {
+        Ok(RunePage {
+            entries: vec![dog_go_to_the_moon(), uncommon_goods()],
+            prev: None,
+            next: None,
+        })
+    }
+
+    async fn get_address_outputs(&self, address: &str) -> RuneResult<Vec<RuneOutput>> {
+        self.output_lookups.fetch_add(1, Ordering::SeqCst);
+        let output = |vout: u32, runes: Vec<RuneBalance>| RuneOutput {
+            outpoint: format!("{}:{}", "a".repeat(64), vout),
+            address: Some(address.to_string()),
+            value: 546,
+            spent: false,
+            confirmations: 3,
+            runes,
+        };
+        Ok(vec![
+            output(0, vec![balance(&uncommon_goods(), 100)]),
+            output(1, vec![balance(&uncommon_goods(), 250)]),
+            output(2, vec![balance(&dog_go_to_the_moon(), 7), balance(&uncommon_goods(), 1)]),
+        ])
+    }
+}
+
+async fn graphql(
+    node: Arc<CountingBackend>,
+    config: GraphQLConfig,
+    query: &str,
+) -> Value {
+    let cache = Arc::new(RunesCache::new(CacheConfig::default(), Arc::new(CacheMetrics::default())));
+    graphql_with_cache(node, cache, config, query).await
+}
+
+async fn graphql_with_cache(
+    node: Arc<CountingBackend>,
+    cache: Arc<RunesCache>,
+    config: GraphQLConfig,
+    query: &str,
+) -> Value {
+    let node: Arc<dyn NodeBackend> = node;
+    let catalog = Arc::new(RuneCatalog::new(node.clone(), Duration::from_secs(60)));
+    catalog.refresh().await.unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(GraphQLApiContext {
+                schema: build_schema(node.clone(), catalog, &config),
+                node,
+                cache,
+                http_cache: HttpCacheConfig::default(),
+            }))
+            .configure(configure_routes),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/v1/graphql")
+        .set_json(json!({ "query": query }))
+        .to_request();
+    test::call_and_read_body_json(&app, req).await
+}
+
+#[actix_web::test]
+async fn test_rune_balances_and_transfers_in_one_request() {
+    let node = Arc::new(CountingBackend::default());
+    let query = format!(
+        r#"{{
+            rune(id: "UNCOMMON•GOODS") {{
+                id spacedName symbol supply {{ circulating }} stats {{ holders }}
+            }}
+            same: rune(id: "UNCOMMONGOODS") {{ id }}
+            address(address: "{ADDRESS}") {{
+                balances {{
+                    balance {{ runeName amount formatted rune {{ id }} }}
+                    utxos {{ outpoint amount }}
+                }}
+                outputs {{ outpoint }}
+                transfers(first: 3) {{ amount rune {{ spacedName }} }}
+            }}
+        }}"#
+    );
+
+    let body = graphql(node.clone(), GraphQLConfig::default(), &query).await;
+    assert!(body.get("errors").is_none(), "{}", body);
+
+    let rune = &body["data"]["rune"];
+    assert_eq!(rune["id"], "840000:1");
+    assert_eq!(rune["symbol"], "⧉");
+    assert_eq!(rune["supply"]["circulating"], "3300");
+    assert_eq!(rune["stats"]["holders"], Value::Null);
+    assert_eq!(body["data"]["same"]["id"], "840000:1");
+
+    let address = &body["data"]["address"];
+    let balances = address["balances"].as_array().unwrap();
+    assert_eq!(balances.len(), 2);
+    assert_eq!(balances[0]["balance"]["runeName"], "DOG•GO•TO•THE•MOON");
+    assert_eq!(balances[0]["balance"]["rune"]["id"], "840000:3");
+    assert_eq!(balances[1]["balance"]["amount"], "351");
+    assert_eq!(balances[1]["balance"]["formatted"], "3.51");
+    assert_eq!(balances[1]["utxos"].as_array().unwrap().len(), 3);
+    assert_eq!(address["outputs"].as_array().unwrap().len(), 3);
+
+    let transfers = address["transfers"].as_array().unwrap();
+    assert_eq!(transfers.len(), 3);
+    assert!(transfers.iter().all(|t| t["rune"]["spacedName"] == "UNCOMMON•GOODS"));
+
+    // Every distinct rune and address is looked up once, however often it is referenced
+    assert_eq!(node.rune_lookups.load(Ordering::SeqCst), 2);
+    assert_eq!(node.output_lookups.load(Ordering::SeqCst), 1);
+}
+
+#[actix_web::test]
+async fn test_rune_has_no_holder_list() {
+    let query = r#"{ rune(id: "840000:1") { id topHolders { address } } }"#;
+
+    let body = graphql(Arc::new(CountingBackend::default()), GraphQLConfig::default(), query).await;
+    assert!(body["data"].is_null(), "{}", body);
+    assert!(body["errors"][0]["message"].as_str().unwrap().contains("topHolders"));
+}
+
+#[actix_web::test]
+async fn test_block_transactions_are_batched() {
+    let node = Arc::new(CountingBackend::default());
+    let query = r#"{
+        block(height: 840000) {
+            hash
+            transactionCount
+            transactions(first: 2) { txid status block { height } transfers { runeId } }
+        }
+        transaction(txid: "1111111111111111111111111111111111111111111111111111111111111111") {
+            confirmations
+        }
+    }"#;
+
+    let body = graphql(node.clone(), GraphQLConfig::default(), query).await;
+    assert!(body.get("errors").is_none(), "{}", body);
+
+    let block = &body["data"]["block"];
+    assert_eq!(block["hash"], "hash840000");
+    assert_eq!(block["transactionCount"], 3);
+    let transactions = block["transactions"].as_array().unwrap();
+    assert_eq!(transactions.len(), 2);
+    assert_eq!(transactions[0]["txid"], "1".repeat(64));
+    assert_eq!(transactions[0]["status"], "CONFIRMED");
+    assert_eq!(transactions[1]["block"]["height"], 840_000);
+    assert_eq!(body["data"]["transaction"]["confirmations"], 11);
+
+    // The root transaction is the block's first one and shares its lookup
+    assert_eq!(node.transaction_lookups.load(Ordering::SeqCst), 2);
+}
+
+#[actix_web::test]
+async fn test_only_final_transactions_are_served_from_the_cache() {
+    let cache = Arc::new(RunesCache::new(CacheConfig::default(), Arc::new(CacheMetrics::default())));
+    for (tx_id, confirmations) in [("1", 2), ("2", 6)] {
+        let tx_id = tx_id.repeat(64);
+        let tx = RunesTransactionResponse {
+            transaction_id: tx_id.clone(),
+            runes: vec![],
+            block_height: Some(840_000),
+            confirmation_count: confirmations,
+            timestamp: 1_713_571_767,
+            network_type: NetworkType::Mainnet,
+            status: TransactionStatus::Confirmed,
+        };
+        cache.set_transaction(tx_id, tx).await.unwrap();
+    }
+
+    let node = Arc::new(CountingBackend::default());
+    let query = format!(
+        r#"{{ shallow: transaction(txid: "{}") {{ confirmations }} final: transaction(txid: "{}") {{ confirmations }} }}"#,
+        "1".repeat(64),
+        "2".repeat(64)
+    );
+    let body = graphql_with_cache(node.clone(), cache, GraphQLConfig::default(), &query).await;
+    assert!(body.get("errors").is_none(), "{}", body);
+
+    // The shallow copy would report stale confirmations, so only it is fetched again
+    assert_eq!(body["data"]["shallow"]["confirmations"], 11);
+    assert_eq!(body["data"]["final"]["confirmations"], 6);
+    assert_eq!(node.transaction_lookups.load(Ordering::SeqCst), 1);
+}
+
+#[actix_web::test]
+async fn test_runes_search_and_missing_entities() {
+    let node = Arc::new(CountingBackend::default());
+    let query = r#"{
+        runes(search: "dog•go", first: 5) { spacedName attributes { maxSupply } }
+        rune(id: "NOSUCHRUNE") { id }
+    }"#;
+
+    let body = graphql(node, GraphQLConfig::default(), query).await;
+    assert!(body.get("errors").is_none(), "{}", body);
+    assert_eq!(body["data"]["runes"], json!([
+        { "spacedName": "DOG•GO•TO•THE•MOON", "attributes": { "maxSupply": "100000000000" } }
+    ]));
+    assert_eq!(body["data"]["rune"], Value::Null);
+}
+
+#[actix_web::test]
+async fn test_invalid_arguments_report_error_codes() {
+    let node = Arc::new(CountingBackend::default());
+    let query = r#"{ transaction(txid: "nope") { txid } }"#;
+
+    let body = graphql(node, GraphQLConfig::default(), query).await;
+    assert_eq!(body["errors"][0]["extensions"]["code"], "INVALID_TRANSACTION");
+    assert_eq!(body["errors"][0]["path"], json!(["transaction"]));
+}
+
+#[actix_web::test]
+async fn test_depth_and_complexity_limits() {
+    let config = GraphQLConfig {
+        max_depth: 4,
+        max_complexity: 200,
+    };
+
+    let deep = r#"{
+        rune(id: "UNCOMMONGOODS") { etchingTransaction { block { transactions { txid } } } }
+    }"#;
+    let body = graphql(Arc::new(CountingBackend::default()), config.clone(), deep).await;
+    assert!(body["errors"][0]["message"].as_str().unwrap().contains("nested too deep"));
+    assert_eq!(body["data"], Value::Null);
+
+    let wide = r#"{ runes(first: 100) { id spacedName stats { holders } } }"#;
+    let body = graphql(Arc::new(CountingBackend::default()), config, wide).await;
+    assert!(body["errors"][0]["message"].as_str().unwrap().contains("too complex"));
+}
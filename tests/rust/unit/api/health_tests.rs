@@ -0,0 +1,204 @@
+use actix_web::{http::StatusCode, test, web, App};
+use async_trait::async_trait;
+use metrics::{Counter, Gauge, Histogram};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::api::health::{handlers::HealthApiContext, routes::configure_routes};
+use crate::services::{
+    health::{DependencyCheck, HealthChecker, HealthConfig},
+    node::{
+        backend::{BackendKind, NodeBackend},
+        connection::{MetricsCollector, NodeConnection},
+        sync::SyncService,
+    },
+};
+use crate::testing::{FakeNode, NodeFailure};
+use crate::types::error::{RuneError, RuneResult};
+
+fn connection(fake: &FakeNode) -> Arc<dyn NodeBackend> {
+    let metrics = Arc::new(MetricsCollector {
+        transaction_counter: Counter::noop(),
+        error_counter: Counter::noop(),
+        response_time: Histogram::noop(),
+        active_connections: Gauge::noop(),
+    });
+    Arc::new(NodeConnection::new(fake.config(BackendKind::Bitcoind), metrics))
+}
+
+struct StubDependency {
+    result: fn() -> RuneResult<()>,
+    delay: Duration,
+}
+
+#[async_trait]
+impl DependencyCheck for StubDependency {
+    fn name(&self) -> &str {
+        "database"
+    }
+
+    async fn check(&self) -> RuneResult<()> {
+        tokio::time::sleep(self.delay).await;
+        (self.result)()
+    }
+}
+
+async fn ready(checker: HealthChecker) -> (StatusCode, Value) {
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(HealthApiContext {
+                checker: Arc::new(checker),
+            }))
+            .configure(configure_routes),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/health/ready").to_request();
+    let resp = test::call_service(&app, req).await;
+    let status = resp.status();
+    (status, test::read_body_json(resp).await)
+}
+
+fn check<'a>(report: &'a Value, name: &str) -> &'a Value {
+    report["checks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|check| check["name"] == name)
+        .unwrap_or_else(|| panic!("no {} check in {}", name, report))
+}
+
+#[actix_web::test]
+async fn test_live_does_not_touch_dependencies() {
+    let fake = FakeNode::start().await;
+    fake.fail(NodeFailure::Unavailable);
+    let checker = HealthChecker::new(connection(&fake), HealthConfig::default());
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(HealthApiContext {
+                checker: Arc::new(checker),
+            }))
+            .configure(configure_routes),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/health/live").to_request();
+    let body: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body, json!({"status": "ok"}));
+}
+
+#[actix_web::test]
+async fn test_ready_when_sync_caught_up() {
+    let fake = FakeNode::start().await;
+    fake.mine_empty(10);
+    let node = connection(&fake);
+
+    let sync = Arc::new(SyncService::new(node.clone(), Duration::from_millis(1)));
+    sync.start_sync().await.unwrap();
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while sync.get_sync_status().await.unwrap().current_height < 10 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .unwrap();
+
+    let checker = HealthChecker::new(node, HealthConfig::default())
+        .with_sync(sync)
+        .with_dependency(Arc::new(StubDependency {
+            result: || Ok(()),
+            delay: Duration::ZERO,
+        }));
+    let (status, report) = ready(checker).await;
+
+    assert_eq!(status, StatusCode::OK, "{}", report);
+    assert_eq!(report["ready"], true);
+    assert_eq!(report["node_height"], 10);
+    assert_eq!(
+        report["sync"],
+        json!({"current_height": 10, "node_height": 10, "lag": 0, "max_lag": 6})
+    );
+    for name in ["node", "database", "sync"] {
+        assert_eq!(check(&report, name)["healthy"], true);
+    }
+}
+
+#[actix_web::test]
+async fn test_not_ready_when_sync_lags() {
+    let fake = FakeNode::start().await;
+    fake.mine_empty(10);
+    let node = connection(&fake);
+
+    // Never started, so the sync is still at height 0
+    let sync = Arc::new(SyncService::new(node.clone(), Duration::from_millis(1)));
+    let config = HealthConfig {
+        max_sync_lag: 9,
+        ..Default::default()
+    };
+    let (status, report) = ready(HealthChecker::new(node.clone(), config).with_sync(sync.clone())).await;
+
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(report["ready"], false);
+    assert_eq!(report["sync"]["lag"], 10);
+    assert_eq!(check(&report, "node")["healthy"], true);
+    let sync_check = check(&report, "sync");
+    assert_eq!(sync_check["healthy"], false);
+    assert!(sync_check["error"].as_str().unwrap().contains("10 blocks behind"));
+
+    let config = HealthConfig {
+        max_sync_lag: 10,
+        ..Default::default()
+    };
+    let (status, _) = ready(HealthChecker::new(node, config).with_sync(sync)).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn test_not_ready_when_node_is_down() {
+    let fake = FakeNode::start().await;
+    let node = connection(&fake);
+    let sync = Arc::new(SyncService::new(node.clone(), Duration::from_millis(1)));
+    fake.fail(NodeFailure::Unavailable);
+
+    let (status, report) = ready(HealthChecker::new(node, HealthConfig::default()).with_sync(sync)).await;
+
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert!(report.get("node_height").is_none());
+    assert!(report.get("sync").is_none());
+    assert_eq!(check(&report, "node")["healthy"], false);
+    assert_eq!(check(&report, "sync")["error"], "Node tip unknown");
+}
+
+#[actix_web::test]
+async fn test_failing_and_slow_dependencies() {
+    let fake = FakeNode::start().await;
+    let node = connection(&fake);
+
+    let failing = HealthChecker::new(node.clone(), HealthConfig::default()).with_dependency(Arc::new(
+        StubDependency {
+            result: || Err(RuneError::DatabaseError("connection refused".to_string())),
+            delay: Duration::ZERO,
+        },
+    ));
+    let (status, report) = ready(failing).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(check(&report, "node")["healthy"], true);
+    assert_eq!(
+        check(&report, "database")["error"],
+        "Database error: connection refused"
+    );
+
+    let config = HealthConfig {
+        check_timeout: Duration::from_millis(50),
+        ..Default::default()
+    };
+    let slow = HealthChecker::new(node, config).with_dependency(Arc::new(StubDependency {
+        result: || Ok(()),
+        delay: Duration::from_secs(5),
+    }));
+    let (status, report) = ready(slow).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert!(check(&report, "database")["error"].as_str().unwrap().contains("Timed out"));
+}
@@ -0,0 +1,173 @@
+use metrics::{Counter, Gauge, Histogram};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+
+use crate::api::lifecycle::{LifecycleConfig, Supervisor};
+use crate::services::{
+    auth::{ApiKeyScope, ApiKeyStore},
+    node::{
+        backend::{BackendKind, NodeBackend},
+        connection::{MetricsCollector, NodeConnection},
+        sync::SyncService,
+    },
+    rate_limit::{RateLimitConfig, RateLimitMetrics, RateLimiter},
+    webhook::manager::{WebhookEvent, WebhookEventType, WebhookManager},
+};
+use crate::testing::FakeNode;
+
+fn connection(fake: &FakeNode) -> Arc<dyn NodeBackend> {
+    let metrics = Arc::new(MetricsCollector {
+        transaction_counter: Counter::noop(),
+        error_counter: Counter::noop(),
+        response_time: Histogram::noop(),
+        active_connections: Gauge::noop(),
+    });
+    Arc::new(NodeConnection::new(fake.config(BackendKind::Bitcoind), metrics))
+}
+
+fn rate_limiter() -> Arc<RateLimiter> {
+    Arc::new(RateLimiter::new(
+        RateLimitConfig::default(),
+        Arc::new(RateLimitMetrics::default()),
+    ))
+}
+
+fn config() -> LifecycleConfig {
+    LifecycleConfig {
+        shutdown_timeout: Duration::from_secs(5),
+        sync_check_interval: Duration::from_millis(20),
+        rate_limit_cleanup_interval: Duration::from_millis(20),
+        rate_limit_max_idle: Duration::from_millis(50),
+        api_key_cleanup_interval: Duration::from_millis(20),
+    }
+}
+
+async fn wait_for_height(sync: &SyncService, height: u64) {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while sync.get_sync_status().await.unwrap().current_height < height {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_sync_follows_the_tip() {
+    let fake = FakeNode::start().await;
+    fake.mine_empty(5);
+    let sync = Arc::new(SyncService::new(connection(&fake), Duration::from_millis(1)));
+
+    let mut supervisor = Supervisor::new(
+        config(),
+        rate_limiter(),
+        Arc::new(WebhookManager::new(Arc::new(Counter::noop()))),
+    )
+    .with_sync(sync.clone());
+    supervisor.start();
+    wait_for_height(&sync, 5).await;
+
+    // A finished sync is started again towards the new tip
+    fake.mine_empty(3);
+    wait_for_height(&sync, 8).await;
+
+    supervisor.shutdown(Instant::now()).await;
+}
+
+#[tokio::test]
+async fn test_rate_limit_buckets_are_cleaned_up() {
+    let limiter = rate_limiter();
+    limiter.check_rate_limit("key:idle", 1).await.unwrap();
+
+    let mut supervisor = Supervisor::new(
+        config(),
+        limiter.clone(),
+        Arc::new(WebhookManager::new(Arc::new(Counter::noop()))),
+    );
+    supervisor.start();
+
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while limiter.bucket("key:idle").await.is_some() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+
+    supervisor.shutdown(Instant::now()).await;
+}
+
+#[tokio::test]
+async fn test_expired_api_keys_are_cleaned_up() {
+    let store = Arc::new(ApiKeyStore::new());
+    store
+        .import_key("expired", "Expired", "expired-key", [ApiKeyScope::RunesRead], Some(1))
+        .unwrap();
+    store
+        .import_key("reader", "Reader", "reader-key", [ApiKeyScope::RunesRead], None)
+        .unwrap();
+
+    let mut supervisor = Supervisor::new(
+        config(),
+        rate_limiter(),
+        Arc::new(WebhookManager::new(Arc::new(Counter::noop()))),
+    )
+    .with_api_keys(store.clone());
+    supervisor.start();
+
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while store.get_key("expired").is_some() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+    assert!(store.get_key("reader").is_some());
+
+    supervisor.shutdown(Instant::now()).await;
+}
+
+#[tokio::test]
+async fn test_shutdown_stops_everything_and_persists_the_sync() {
+    let path = std::env::temp_dir().join(format!("supervisor-checkpoint-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let fake = FakeNode::start().await;
+    fake.mine_empty(10);
+    let node = connection(&fake);
+    let sync = Arc::new(SyncService::new(node.clone(), Duration::from_millis(1)).with_checkpoint(&path));
+    let webhook_manager = Arc::new(WebhookManager::new(Arc::new(Counter::noop())));
+
+    let mut supervisor = Supervisor::new(config(), rate_limiter(), webhook_manager.clone())
+        .with_sync(sync.clone());
+    let managed = tokio::spawn(std::future::pending::<()>());
+    let managed_abort = managed.abort_handle();
+    supervisor.manage(managed);
+    supervisor.start();
+    wait_for_height(&sync, 10).await;
+
+    supervisor.shutdown(Instant::now() + Duration::from_secs(1)).await;
+
+    assert!(managed_abort.is_finished());
+    let status = sync.get_sync_status().await.unwrap();
+    assert!(!status.is_syncing);
+    // New blocks are no longer picked up
+    fake.mine_empty(5);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(sync.get_sync_status().await.unwrap().current_height, 10);
+
+    // Webhooks are closed for new events
+    let event = WebhookEvent {
+        event_type: WebhookEventType::BlockSynced,
+        timestamp: 0,
+        payload: serde_json::json!({}),
+    };
+    assert!(webhook_manager.send_event(event).await.is_err());
+
+    // The next process picks up where this one stopped
+    let restarted = SyncService::new(node, Duration::from_millis(1)).with_checkpoint(&path);
+    assert_eq!(restarted.get_sync_status().await.unwrap().current_height, 10);
+
+    std::fs::remove_file(&path).unwrap();
+}
@@ -1,17 +1,184 @@
 use actix_web::{test, web, App};
+use async_trait::async_trait;
 use std::sync::Arc;
+use std::time::Duration;
+use crate::api::{
+    encoding::{CBOR, MESSAGE_PACK},
+    http_cache::HttpCacheConfig,
+};
 use crate::api::runes::{
     handlers::{RunesApiContext, BatchTransactionRequest},
     routes::configure_routes,
 };
-use crate::services::node::connection::{NodeConnection, NodeConfig, MetricsCollector};
-use metrics::{Counter, Gauge, Histogram};
-use std::time::Duration;
+use crate::services::cache::{CacheConfig, CacheMetrics, RunesCache};
+use crate::services::catalog::RuneCatalog;
+use crate::services::node::{
+    backend::{BackendKind, BlockInfo, NodeBackend},
+    connection::NodeStatus,
+};
+use crate::testing::fixtures::{dog_go_to_the_moon, uncommon_goods};
+use crate::types::{
+    error::{ErrorResponse, RuneError, RuneResult},
+    rune::{
+        NetworkType, RuneBalance, RuneEntry, RuneOutput, RunePage, RuneTerms, RunesTransactionResponse, RuneTransfer,
+        TransactionStatus, TransferType,
+    },
+};
+
+/// Node that knows every transaction and reports a single transfer for it.
+/// Ids starting with `0` are pending and ids starting with `1` have two
+/// confirmations, every other transaction has eleven.
+struct StubBackend;
+
+fn dogwifhat() -> RuneEntry {
+    RuneEntry {
+        id: "840001:7".to_string(),
+        name: "DOGWIFHAT".to_string(),
+        spaced_name: "DOGWIFHAT".to_string(),
+        number: 2,
+        block: 840_001,
+        premine: 0,
+        terms: Some(RuneTerms {
+            amount: Some(1),
+            cap: Some(1_000),
+            height_start: None,
+            height_end: Some(840_005),
+            offset_start: None,
+            offset_end: None,
+        }),
+        turbo: false,
+        mints: 50,
+        burned: 0,
+        holders: Some(120),
+        ..uncommon_goods()
+    }
+}
+
+#[async_trait]
+impl NodeBackend for StubBackend {
+    fn kind(&self) -> BackendKind {
+        BackendKind::Ord
+    }
+
+    async fn health_check(&self) -> RuneResult<NodeStatus> {
+        Err(RuneError::UnsupportedOperation("health_check".to_string()))
+    }
+
+    async fn get_block_height(&self) -> RuneResult<u64> {
+        Ok(840_010)
+    }
+
+    async fn get_block_hash(&self, _height: u64) -> RuneResult<String> {
+        Err(RuneError::UnsupportedOperation("get_block_hash".to_string()))
+    }
+
+    async fn get_block(&self, _hash: &str) -> RuneResult<BlockInfo> {
+        Err(RuneError::UnsupportedOperation("get_block".to_string()))
+    }
+
+    async fn get_raw_transaction(&self, _tx_id: &str) -> RuneResult<String> {
+        Err(RuneError::UnsupportedOperation("get_raw_transaction".to_string()))
+    }
+
+    async fn get_transaction(&self, tx_id: &str) -> RuneResult<RunesTransactionResponse> {
+        let (block_height, confirmation_count, status) = match tx_id.as_bytes()[0] {
+            b'0' => (None, 0, TransactionStatus::Pending),
+            b'1' => (Some(840_009), 2, TransactionStatus::Confirmed),
+            _ => (Some(840_000), 11, TransactionStatus::Confirmed),
+        };
+        Ok(RunesTransactionResponse {
+            transaction_id: tx_id.to_string(),
+            runes: vec![RuneTransfer {
+                rune_id: "840000:1".to_string(),
+                from_address: "bc1qsender".to_string(),
+                to_address: "bc1qreceiver".to_string(),
+                amount: 1_000,
+                transfer_type: TransferType::Transfer,
+                fee: None,
+                metadata: None,
+            }],
+            block_height,
+            confirmation_count,
+            timestamp: 1_713_571_767,
+            network_type: NetworkType::Mainnet,
+            status,
+        })
+    }
+
+    async fn get_address_transfers(&self, _address: &str) -> RuneResult<Vec<RuneTransfer>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_rune(&self, rune: &str) -> RuneResult<RuneEntry> {
+        match rune {
+            "840000:1" | "UNCOMMONGOODS" => Ok(uncommon_goods()),
+            _ => Err(RuneError::NotFound(format!("/rune/{} not found", rune))),
+        }
+    }
+
+    async fn get_output(&self, outpoint: &str) -> RuneResult<RuneOutput> {
+        Ok(RuneOutput {
+            outpoint: outpoint.to_string(),
+            address: Some("bc1pexampleaddressxxxxxxxxxxxxxxxxxxxxxxx".to_string()),
+            value: 546,
+            spent: outpoint.ends_with(":1"),
+            confirmations: 6,
+            runes: vec![RuneBalance {
+                rune: "UNCOMMON•GOODS".to_string(),
+                amount: 1_050,
+                divisibility: 2,
+                symbol: Some('⧉'),
+            }],
+        })
+    }
+
+    async fn get_address_outputs(&self, address: &str) -> RuneResult<Vec<RuneOutput>> {
+        let mut outputs = Vec::new();
+        for outpoint in [format!("{}:0", "a".repeat(64)), format!("{}:2", "b".repeat(64))] {
+            let mut output = self.get_output(&outpoint).await?;
+            output.address = Some(address.to_string());
+            outputs.push(output);
+        }
+        Ok(outputs)
+    }
+
+    // Two pages, newest first like ord
+    async fn get_runes(&self, page: Option<u32>) -> RuneResult<RunePage> {
+        Ok(match page {
+            None => RunePage {
+                entries: vec![dogwifhat(), dog_go_to_the_moon()],
+                prev: None,
+                next: Some(1),
+            },
+            Some(_) => RunePage {
+                entries: vec![uncommon_goods()],
+                prev: Some(0),
+                next: None,
+            },
+        })
+    }
+}
 
 async fn create_test_app() -> impl actix_web::dev::Service<actix_http::Request, Response = actix_web::dev::ServiceResponse, Error = actix_web::Error> {
-    let node = create_test_node();
+    create_test_app_with_cache(Arc::new(RunesCache::new(
+        CacheConfig::default(),
+        Arc::new(CacheMetrics::default()),
+    )))
+    .await
+}
+
+async fn create_test_app_with_cache(
+    cache: Arc<RunesCache>,
+) -> impl actix_web::dev::Service<actix_http::Request, Response = actix_web::dev::ServiceResponse, Error = actix_web::Error> {
+    let node: Arc<dyn NodeBackend> = Arc::new(StubBackend);
+    let catalog = Arc::new(RuneCatalog::new(node.clone(), Duration::from_secs(60)));
+    catalog.refresh().await.unwrap();
+
     let context = RunesApiContext {
-        node: Arc::new(node),
+        node,
+        cache,
+        catalog,
+        http_cache: HttpCacheConfig::default(),
     };
 
     test::init_service(
@@ -21,25 +188,6 @@ async fn create_test_app() -> impl actix_web::dev::Service<actix_http::Request,
     ).await
 }
 
-fn create_test_node() -> NodeConnection {
-    let config = NodeConfig {
-        rpc_url: "http://localhost:8332".to_string(),
-        username: None,
-        password: None,
-        timeout: Duration::from_secs(30),
-        max_retries: 3,
-    };
-
-    let metrics = Arc::new(MetricsCollector {
-        transaction_counter: Counter::new("test_tx_counter"),
-        error_counter: Counter::new("test_error_counter"),
-        response_time: Histogram::new("test_response_time"),
-        active_connections: Gauge::new("test_active_connections"),
-    });
-
-    NodeConnection::new(config, metrics)
-}
-
 #[actix_web::test]
 async fn test_get_transaction() {
     let app = create_test_app().await;
@@ -54,7 +202,7 @@ async fn test_get_transaction() {
 
     let body: serde_json::Value = test::read_body_json(resp).await;
     assert_eq!(body["transaction_id"], tx_id);
-    assert!(body["runes"].as_array().unwrap().len() > 0);
+    assert!(!body["runes"].as_array().unwrap().is_empty());
 }
 
 #[actix_web::test]
@@ -70,6 +218,166 @@ async fn test_get_transaction_invalid_id() {
     assert_eq!(resp.status(), 400);
 }
 
+fn cache_headers(resp: &actix_web::dev::ServiceResponse) -> (String, String) {
+    let header = |name| resp.headers().get(name).unwrap().to_str().unwrap().to_string();
+    (header("etag"), header("cache-control"))
+}
+
+#[actix_web::test]
+async fn test_transaction_cache_control_follows_confirmations() {
+    let app = create_test_app().await;
+
+    for (tx_id, cache_control) in [
+        ("0".repeat(64), "private, max-age=5"),
+        ("1".repeat(64), "private, max-age=60"),
+        ("a".repeat(64), "private, max-age=31536000, immutable"),
+    ] {
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/runes/transaction/{}", tx_id))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(cache_headers(&resp).1, cache_control, "{}", tx_id);
+    }
+
+    let shared = HttpCacheConfig {
+        shared: true,
+        ..Default::default()
+    };
+    assert_eq!(shared.cache_control(shared.default_policy()), "public, max-age=30");
+}
+
+#[actix_web::test]
+async fn test_if_none_match_answers_not_modified() {
+    let app = create_test_app().await;
+    let uri = format!("/api/v1/runes/transaction/{}", "a".repeat(64));
+
+    let resp = test::call_service(&app, test::TestRequest::get().uri(&uri).to_request()).await;
+    let (etag, cache_control) = cache_headers(&resp);
+    // Weak, the same body is also served compressed
+    let opaque = etag.strip_prefix("W/").unwrap();
+    assert!(opaque.starts_with('"') && opaque.ends_with('"'), "{}", etag);
+
+    for if_none_match in [etag.clone(), format!("\"other\", {}", opaque), "*".to_string()] {
+        let req = test::TestRequest::get()
+            .uri(&uri)
+            .insert_header(("If-None-Match", if_none_match))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 304);
+        assert_eq!(cache_headers(&resp), (etag.clone(), cache_control.clone()));
+        assert!(test::read_body(resp).await.is_empty());
+    }
+
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .insert_header(("If-None-Match", "\"stale\""))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(cache_headers(&resp).0, etag);
+}
+
+#[actix_web::test]
+async fn test_only_final_transactions_are_served_from_cache() {
+    let metrics = Arc::new(CacheMetrics::default());
+    let cache = Arc::new(RunesCache::new(CacheConfig::default(), metrics.clone()));
+    let app = create_test_app_with_cache(cache.clone()).await;
+
+    // Same ETag whether the node or the cache answered
+    let final_uri = format!("/api/v1/runes/transaction/{}", "a".repeat(64));
+    let from_node = test::call_service(&app, test::TestRequest::get().uri(&final_uri).to_request()).await;
+    let from_cache = test::call_service(&app, test::TestRequest::get().uri(&final_uri).to_request()).await;
+    assert_eq!(cache.get_metrics().await.hits, 1);
+    assert_eq!(cache_headers(&from_node).0, cache_headers(&from_cache).0);
+
+    // A shallow transaction cached earlier has an outdated confirmation count
+    let shallow_id = "1".repeat(64);
+    let node: Arc<dyn NodeBackend> = Arc::new(StubBackend);
+    let mut stale = node.get_transaction(&shallow_id).await.unwrap();
+    stale.confirmation_count = 1;
+    cache.set_transaction(shallow_id.clone(), stale).await.unwrap();
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/v1/runes/transaction/{}", shallow_id))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["confirmation_count"], 2);
+    assert_eq!(cache.get_transaction(&shallow_id).await.unwrap().confirmation_count, 2);
+}
+
+#[actix_web::test]
+async fn test_lookups_use_the_default_max_age() {
+    let app = create_test_app().await;
+
+    for uri in [
+        "/api/v1/runes/840000:1".to_string(),
+        "/api/v1/runes?page_size=1".to_string(),
+        format!("/api/v1/runes/output/{}:0", "a".repeat(64)),
+    ] {
+        let resp = test::call_service(&app, test::TestRequest::get().uri(&uri).to_request()).await;
+        assert_eq!(resp.status(), 200, "{}", uri);
+        assert_eq!(cache_headers(&resp).1, "private, max-age=30");
+    }
+}
+
+#[actix_web::test]
+async fn test_binary_encodings() {
+    let app = create_test_app().await;
+    let uri = format!("/api/v1/runes/transaction/{}", "a".repeat(64));
+
+    let json = test::call_service(&app, test::TestRequest::get().uri(&uri).to_request()).await;
+    let (json_etag, _) = cache_headers(&json);
+
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .insert_header(("Accept", CBOR))
+        .insert_header(("If-None-Match", json_etag.as_str()))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    // Another representation, so the JSON ETag does not match
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers().get("content-type").unwrap(), CBOR);
+    assert_ne!(cache_headers(&resp).0, json_etag);
+    let tx: RunesTransactionResponse = ciborium::from_reader(test::read_body(resp).await.as_ref()).unwrap();
+    assert_eq!(tx.transaction_id, "a".repeat(64));
+    assert_eq!(tx.runes[0].amount, 1_000);
+
+    let req = test::TestRequest::get()
+        .uri("/api/v1/runes/transaction/invalid")
+        .insert_header(("Accept", MESSAGE_PACK))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+    let error: ErrorResponse = rmp_serde::from_slice(&test::read_body(resp).await).unwrap();
+    assert_eq!(error.code, "INVALID_TRANSACTION");
+
+    let req = test::TestRequest::get()
+        .uri("/api/v1/runes?page=first")
+        .insert_header(("Accept", CBOR))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+    let error: ErrorResponse = ciborium::from_reader(test::read_body(resp).await.as_ref()).unwrap();
+    assert_eq!(error.code, "INVALID_REQUEST");
+
+    let req = test::TestRequest::post()
+        .uri("/api/v1/runes/transactions/batch")
+        .insert_header(("Accept", MESSAGE_PACK))
+        .insert_header(("Accept-Encoding", "gzip"))
+        .set_json(&BatchTransactionRequest {
+            transaction_ids: vec!["a".repeat(64)],
+            include_confirmations: false,
+        })
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.headers().get("content-type").unwrap(), MESSAGE_PACK);
+    assert_eq!(resp.headers().get("content-encoding").unwrap(), "gzip");
+    let vary: Vec<_> = resp.headers().get_all("vary").map(|value| value.to_str().unwrap()).collect();
+    assert!(vary.iter().any(|value| value.eq_ignore_ascii_case("accept")), "{:?}", vary);
+    assert!(vary.iter().any(|value| value.eq_ignore_ascii_case("accept-encoding")), "{:?}", vary);
+}
+
 #[actix_web::test]
 async fn test_batch_transactions() {
     let app = create_test_app().await;
@@ -87,9 +395,15 @@ async fn test_batch_transactions() {
     assert!(resp.status().is_success());
 
     let body: serde_json::Value = test::read_body_json(resp).await;
-    let transactions = body["transactions"].as_array().unwrap();
-    assert_eq!(transactions.len(), 2);
-    assert!(body["failed_transactions"].as_array().unwrap().is_empty());
+    let results = body["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(body["succeeded"], 2);
+    assert_eq!(body["failed"], 0);
+
+    // Tip 840010, mined at 840000
+    assert_eq!(results[0]["transaction_id"], tx_ids[0]);
+    assert_eq!(results[0]["confirmations"], 11);
+    assert_eq!(results[0]["transaction"]["confirmation_count"], 11);
 }
 
 #[actix_web::test]
@@ -109,8 +423,320 @@ async fn test_batch_transactions_with_invalid() {
     assert!(resp.status().is_success());
 
     let body: serde_json::Value = test::read_body_json(resp).await;
-    let transactions = body["transactions"].as_array().unwrap();
-    let failed = body["failed_transactions"].as_array().unwrap();
-    assert_eq!(transactions.len(), 1);
-    assert_eq!(failed.len(), 1);
-} 
\ No newline at end of file
+    assert_eq!(body["succeeded"], 1);
+    assert_eq!(body["failed"], 1);
+
+    let failed = &body["results"][1];
+    assert_eq!(failed["transaction_id"], "invalid");
+    assert_eq!(failed["error"]["code"], "INVALID_TRANSACTION");
+    assert!(failed["error"]["message"].as_str().unwrap().contains("invalid"));
+    assert!(failed.get("transaction").is_none());
+}
+
+#[actix_web::test]
+async fn test_batch_transactions_dedup_and_confirmations() {
+    let app = create_test_app().await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/v1/runes/transactions/batch")
+        .set_json(&BatchTransactionRequest {
+            transaction_ids: vec!["a".repeat(64), "b".repeat(64), "a".repeat(64)],
+            include_confirmations: false,
+        })
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let results = body["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[1]["transaction_id"], "b".repeat(64));
+    assert!(results[0].get("confirmations").is_none());
+}
+
+#[actix_web::test]
+async fn test_batch_transactions_size_limit() {
+    let app = create_test_app().await;
+
+    for transaction_ids in [Vec::new(), (0..101).map(|i| format!("{:064x}", i)).collect()] {
+        let req = test::TestRequest::post()
+            .uri("/api/v1/runes/transactions/batch")
+            .set_json(&BatchTransactionRequest {
+                transaction_ids,
+                include_confirmations: false,
+            })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["code"], "INVALID_REQUEST");
+    }
+}
+
+#[actix_web::test]
+async fn test_get_rune_by_id() {
+    let app = create_test_app().await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/v1/runes/840000:1")
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["id"], "840000:1");
+    assert_eq!(body["metadata"]["spaced_name"], "UNCOMMON•GOODS");
+    assert_eq!(body["metadata"]["decimals"], 2);
+    assert_eq!(body["metadata"]["symbol"], "⧉");
+    assert_eq!(body["etching"]["block_height"], 840_000);
+    assert_eq!(body["etching"]["terms"]["cap"], u128::MAX.to_string());
+    assert_eq!(body["etching"]["turbo"], true);
+    assert_eq!(body["attributes"]["mintable"], true);
+    assert_eq!(body["supply"]["total"], "3500");
+    assert_eq!(body["supply"]["circulating"], "3300");
+    assert_eq!(body["supply"]["mints"], "30");
+    assert_eq!(body["created_at"], 1_713_571_767);
+}
+
+#[actix_web::test]
+async fn test_get_rune_by_spaced_name() {
+    let app = create_test_app().await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/v1/runes/{}", urlencoding::encode("UNCOMMON•GOODS")))
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["metadata"]["name"], "UNCOMMONGOODS");
+}
+
+#[actix_web::test]
+async fn test_get_rune_invalid_name() {
+    let app = create_test_app().await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/v1/runes/not-a-rune")
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+    let body: ErrorResponse = test::read_body_json(resp).await;
+    assert_eq!(body.code, "INVALID_RUNE");
+}
+
+#[actix_web::test]
+async fn test_get_rune_name_too_long() {
+    let app = create_test_app().await;
+
+    // 33 letters with a spacer after the last one, past the u32 spacer bitfield
+    let name = format!("{}•A", "A".repeat(33));
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/v1/runes/{}", urlencoding::encode(&name)))
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+    let body: ErrorResponse = test::read_body_json(resp).await;
+    assert_eq!(body.code, "INVALID_RUNE");
+
+    // One letter past the largest rune
+    let req = test::TestRequest::get()
+        .uri("/api/v1/runes/BCGDENLQRQWDSLRUGSNLBTMFIJAVA")
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+    let body: ErrorResponse = test::read_body_json(resp).await;
+    assert_eq!(body.code, "INVALID_RUNE");
+}
+
+#[actix_web::test]
+async fn test_get_rune_not_found() {
+    let app = create_test_app().await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/v1/runes/MISSING")
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+}
+
+async fn list_rune_ids(query: &str) -> (u16, serde_json::Value) {
+    let app = create_test_app().await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/v1/runes{}", query))
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    let status = resp.status().as_u16();
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    (status, body)
+}
+
+fn ids(body: &serde_json::Value) -> Vec<&str> {
+    body["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["id"].as_str().unwrap())
+        .collect()
+}
+
+#[actix_web::test]
+async fn test_list_runes() {
+    let (status, body) = list_rune_ids("").await;
+    assert_eq!(status, 200);
+    assert_eq!(body["total"], 3);
+    assert_eq!(body["page"], 1);
+
+    // Newest etching first by default
+    assert_eq!(ids(&body), ["840001:7", "840000:3", "840000:1"]);
+}
+
+#[actix_web::test]
+async fn test_list_runes_search() {
+    // Case and spacers are ignored, names match by prefix
+    let (_, body) = list_rune_ids("?q=dog").await;
+    assert_eq!(ids(&body), ["840001:7", "840000:3"]);
+
+    let (_, body) = list_rune_ids(&format!("?q={}", urlencoding::encode("DOG•GO"))).await;
+    assert_eq!(ids(&body), ["840000:3"]);
+
+    let (status, _) = list_rune_ids("?q=dog-1").await;
+    assert_eq!(status, 400);
+
+    // No rune name is longer than 28 letters, so neither is a prefix
+    let (status, body) = list_rune_ids(&format!("?q={}", urlencoding::encode(&format!("{}•A", "A".repeat(33))))).await;
+    assert_eq!(status, 400);
+    assert_eq!(body["code"], "INVALID_RUNE");
+}
+
+#[actix_web::test]
+async fn test_list_runes_filters() {
+    // DOGWIFHAT's mint window closed at 840005
+    let (_, body) = list_rune_ids("?mintable=true").await;
+    assert_eq!(ids(&body), ["840000:1"]);
+
+    let (_, body) = list_rune_ids("?premine=false").await;
+    assert_eq!(ids(&body), ["840001:7"]);
+
+    let (_, body) = list_rune_ids("?turbo=false&premine=true").await;
+    assert_eq!(ids(&body), ["840000:3"]);
+}
+
+#[actix_web::test]
+async fn test_list_runes_sorting() {
+    let (_, body) = list_rune_ids("?sort=mints").await;
+    assert_eq!(ids(&body), ["840001:7", "840000:1", "840000:3"]);
+
+    let (_, body) = list_rune_ids("?sort=burned").await;
+    assert_eq!(ids(&body)[0], "840000:1");
+
+    // No backend tracks holder counts, so there is nothing to sort them by
+    let (status, body) = list_rune_ids("?sort=holders").await;
+    assert_eq!(status, 400);
+    assert_eq!(body["code"], "INVALID_REQUEST");
+}
+
+#[actix_web::test]
+async fn test_list_runes_paging() {
+    let (_, body) = list_rune_ids("?page=2&page_size=2").await;
+    assert_eq!(body["total"], 3);
+    assert_eq!(ids(&body), ["840000:1"]);
+
+    let (status, _) = list_rune_ids("?page_size=101").await;
+    assert_eq!(status, 400);
+
+    let (status, _) = list_rune_ids("?page=0").await;
+    assert_eq!(status, 400);
+}
+
+#[actix_web::test]
+async fn test_catalog_rewind_drops_later_etchings() {
+    let catalog = RuneCatalog::new(Arc::new(StubBackend), Duration::from_secs(60));
+    catalog.refresh().await.unwrap();
+    assert_eq!(catalog.snapshot().await.unwrap().height, 840_010);
+
+    catalog.rewind_to(840_001).await;
+    let snapshot = catalog.snapshot().await.unwrap();
+    let ids: Vec<&str> = snapshot.entries.iter().map(|entry| entry.id.as_str()).collect();
+    assert_eq!(ids, ["840000:3", "840000:1"]);
+    assert_eq!(snapshot.height, 840_000);
+}
+
+#[actix_web::test]
+async fn test_get_address_balances() {
+    let app = create_test_app().await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/v1/runes/address/bc1pexampleaddressxxxxxxxxxxxxxxxxxxxxxxx/balances")
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let balances = body["balances"].as_array().unwrap();
+    assert_eq!(balances.len(), 1);
+    assert_eq!(balances[0]["rune"], "UNCOMMON•GOODS");
+    assert_eq!(balances[0]["amount"], "2100");
+    assert_eq!(balances[0]["formatted"], "21");
+    assert_eq!(balances[0]["utxos"].as_array().unwrap().len(), 2);
+    assert_eq!(balances[0]["utxos"][0]["formatted"], "10.5");
+
+    let req = test::TestRequest::get()
+        .uri("/api/v1/runes/address/not-an-address/balances")
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+}
+
+#[actix_web::test]
+async fn test_get_address_transfers() {
+    let app = create_test_app().await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/v1/runes/address/bc1pexampleaddressxxxxxxxxxxxxxxxxxxxxxxx/transfers")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    // Rejected before the node or the cache are asked
+    let req = test::TestRequest::get()
+        .uri("/api/v1/runes/address/not-an-address/transfers")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+    let body: ErrorResponse = test::read_body_json(resp).await;
+    assert_eq!(body.code, "INVALID_REQUEST");
+}
+
+#[actix_web::test]
+async fn test_get_output() {
+    let app = create_test_app().await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/v1/runes/output/{}:1", "a".repeat(64)))
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["outpoint"], format!("{}:1", "a".repeat(64)));
+    assert_eq!(body["spent"], true);
+    assert_eq!(body["runes"][0]["formatted"], "10.5");
+
+    for outpoint in ["invalid:0".to_string(), format!("{}:x", "a".repeat(64))] {
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/runes/output/{}", outpoint))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+    }
+}
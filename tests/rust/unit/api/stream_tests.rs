@@ -0,0 +1,166 @@
+use actix_web::{body::MessageBody, test, web, App};
+use std::future::poll_fn;
+use std::pin::pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::api::stream::{handlers::StreamApiContext, routes::configure_routes};
+use crate::services::events::{
+    bus::{ChainEventData, EventBus},
+    watcher::{BlockWatcher, WatcherConfig},
+};
+use crate::services::node::backend::{create_backend, BackendKind};
+use crate::testing::{fixtures::transfer, FakeNode, FakeTransaction};
+use crate::types::runestone::{Edict, Etching, RuneId, Runestone};
+
+/// Reads body chunks until `count` events arrived
+async fn read_events(body: impl MessageBody, count: usize) -> String {
+    let mut body = pin!(body);
+    let mut text = String::new();
+
+    while text.matches("\n\n").count() < count {
+        let chunk = tokio::time::timeout(
+            Duration::from_secs(5),
+            poll_fn(|cx| body.as_mut().poll_next(cx)),
+        )
+        .await
+        .expect("timed out waiting for events");
+
+        match chunk {
+            Some(Ok(bytes)) => text.push_str(std::str::from_utf8(&bytes).unwrap()),
+            _ => break,
+        }
+    }
+    text
+}
+
+#[actix_web::test]
+async fn test_stream_resumes_from_last_event_id() {
+    let events = Arc::new(EventBus::new(16));
+    events.publish(transfer("UNCOMMON•GOODS", "bc1qalice"));
+    events.publish(ChainEventData::Block {
+        height: 840_000,
+        hash: "hash".to_string(),
+        timestamp: 1_713_571_767,
+        tx_count: 1,
+    });
+    events.publish(transfer("DOG•GO•TO•THE•MOON", "bc1qbob"));
+    events.publish(transfer("UNCOMMON•GOODS", "bc1qbob"));
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(StreamApiContext { events: events.clone() }))
+            .configure(configure_routes),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/v1/stream?types=transfer&rune=UNCOMMONGOODS")
+        .insert_header(("Last-Event-ID", "1"))
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+    assert_eq!(resp.headers().get("content-type").unwrap(), "text/event-stream");
+
+    // Replay skips event 1, the block and the other rune, then live events follow
+    events.publish(transfer("UNCOMMON•GOODS", "bc1qcarol"));
+    let text = read_events(resp.into_body(), 2).await;
+
+    assert!(text.starts_with("id: 4\nevent: transfer\ndata: "));
+    assert!(text.contains("\"to_address\":\"bc1qbob\""));
+    assert!(text.contains("id: 5\n"));
+    assert!(text.contains("\"to_address\":\"bc1qcarol\""));
+    assert!(!text.contains("DOG"));
+}
+
+#[actix_web::test]
+async fn test_stream_rejects_unknown_event_type() {
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(StreamApiContext {
+                events: Arc::new(EventBus::new(16)),
+            }))
+            .configure(configure_routes),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/v1/stream?types=block,nope")
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+    let req = test::TestRequest::get()
+        .uri("/api/v1/stream")
+        .insert_header(("Last-Event-ID", "abc"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+}
+
+#[actix_web::test]
+async fn test_watcher_transfers_reach_the_stream() {
+    let fake = FakeNode::start().await;
+    let config = fake.config(BackendKind::Bitcoind);
+    let node = create_backend(&config, Arc::new(config.http.build_client(config.timeout).unwrap()));
+    let events = Arc::new(EventBus::new(16));
+    let watcher = BlockWatcher::new(
+        node,
+        events.clone(),
+        WatcherConfig {
+            scan_transactions: true,
+            ..Default::default()
+        },
+    );
+    watcher.poll().await.unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(StreamApiContext { events: events.clone() }))
+            .configure(configure_routes),
+    )
+    .await;
+    let req = test::TestRequest::get()
+        .uri("/api/v1/stream?types=transfer&rune=1:1")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    let etched = fake.mine(vec![FakeTransaction::new()
+        .spend(&"ab".repeat(32), 0)
+        .runestone(&Runestone {
+            etching: Some(Etching {
+                rune: Some("UNCOMMONGOODS".to_string()),
+                spacers: Some(0b1000_0000),
+                premine: Some(1_000),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .pay("bc1qalice", 546)]);
+    fake.mine(vec![FakeTransaction::new()
+        .spend(&etched.txids[1], 1)
+        .pay("bc1qbob", 546)
+        .runestone(&Runestone {
+            edicts: vec![Edict { id: RuneId { block: 1, tx: 1 }, amount: 250, output: 0 }],
+            ..Default::default()
+        })]);
+    assert_eq!(watcher.poll().await.unwrap(), 2);
+
+    let text = read_events(resp.into_body(), 2).await;
+    let data: Vec<serde_json::Value> = text
+        .lines()
+        .filter_map(|line| line.strip_prefix("data: "))
+        .map(|data| serde_json::from_str(data).unwrap())
+        .collect();
+
+    assert_eq!(data.len(), 2);
+    assert_eq!(data[0]["transfer_type"], "Etch");
+    assert_eq!(data[0]["to_address"], "bc1qalice");
+    assert_eq!(data[1]["rune"], "UNCOMMON•GOODS");
+    assert_eq!(data[1]["rune_id"], "1:1");
+    // Bitcoin Core knows no balances, the amount is what the runestone claims
+    assert_eq!(data[1]["verified"], false);
+    assert_eq!(data[1]["to_address"], "bc1qbob");
+    assert_eq!(data[1]["amount"], "250");
+    assert_eq!(data[1]["transfer_type"], "Transfer");
+}
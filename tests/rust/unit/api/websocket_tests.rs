@@ -0,0 +1,355 @@
+use actix_web::{web, App, HttpServer};
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{client::IntoClientRequest, protocol::frame::coding::CloseCode, Error, Message},
+    MaybeTlsStream, WebSocketStream,
+};
+
+use crate::api::websocket::{
+    handlers::{WebSocketApiContext, WebSocketConfig},
+    routes::configure_routes,
+};
+use crate::services::{
+    events::{
+        bus::{ChainEventData, EventBus},
+        watcher::{BlockWatcher, WatcherConfig},
+    },
+    node::backend::{create_backend, BackendKind},
+};
+use crate::testing::{
+    fixtures::{key_store, transfer},
+    FakeNode, FakeTransaction,
+};
+use crate::types::runestone::{Edict, Etching, RuneId, Runestone};
+
+type Client = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Serves the WebSocket route on a free local port and returns its url
+fn start_server(events: Arc<EventBus>, config: WebSocketConfig) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}/api/v1/ws", listener.local_addr().unwrap());
+
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(web::Data::new(WebSocketApiContext {
+                events: events.clone(),
+                config: config.clone(),
+            }))
+            .configure(configure_routes)
+    })
+    .workers(1)
+    .listen(listener)
+    .unwrap()
+    .run();
+    actix_web::rt::spawn(server);
+
+    url
+}
+
+fn keyed_config() -> WebSocketConfig {
+    WebSocketConfig {
+        api_keys: Some(key_store()),
+        ..Default::default()
+    }
+}
+
+fn block(height: u64) -> ChainEventData {
+    ChainEventData::Block {
+        height,
+        hash: format!("hash{}", height),
+        timestamp: 1_713_571_767,
+        tx_count: 1,
+    }
+}
+
+async fn send(client: &mut Client, message: Value) {
+    client.send(Message::Text(message.to_string())).await.unwrap();
+}
+
+/// Next frame other than pings and pongs
+async fn next_frame(client: &mut Client) -> Message {
+    loop {
+        let frame = tokio::time::timeout(Duration::from_secs(5), client.next())
+            .await
+            .expect("timed out waiting for a message")
+            .expect("connection ended")
+            .unwrap();
+
+        match frame {
+            Message::Ping(_) | Message::Pong(_) => continue,
+            frame => return frame,
+        }
+    }
+}
+
+async fn recv(client: &mut Client) -> Value {
+    match next_frame(client).await {
+        Message::Text(text) => serde_json::from_str(&text).unwrap(),
+        other => panic!("expected a text message, got {:?}", other),
+    }
+}
+
+async fn recv_close_code(client: &mut Client) -> CloseCode {
+    loop {
+        match next_frame(client).await {
+            Message::Close(Some(frame)) => return frame.code,
+            Message::Close(None) => panic!("close frame without a code"),
+            _ => continue,
+        }
+    }
+}
+
+#[actix_web::test]
+async fn test_subscriptions_receive_matching_events() {
+    let events = Arc::new(EventBus::new(16));
+    let url = start_server(events.clone(), WebSocketConfig::default());
+    let (mut client, _) = connect_async(url).await.unwrap();
+
+    send(&mut client, json!({"type": "subscribe", "channel": "blocks"})).await;
+    assert_eq!(
+        recv(&mut client).await,
+        json!({"type": "subscribed", "subscription": 1, "channel": "blocks"})
+    );
+
+    send(
+        &mut client,
+        json!({"type": "subscribe", "channel": "rune_transfers", "rune": "UNCOMMONGOODS"}),
+    )
+    .await;
+    assert_eq!(recv(&mut client).await["subscription"], 2);
+
+    events.publish(transfer("DOG•GO•TO•THE•MOON", "bc1qbob"));
+    events.publish(block(840_000));
+    events.publish(transfer("UNCOMMON•GOODS", "bc1qalice"));
+
+    let message = recv(&mut client).await;
+    assert_eq!(message["type"], "event");
+    assert_eq!(message["subscription"], 1);
+    assert_eq!(message["event"]["type"], "block");
+    assert_eq!(message["event"]["height"], 840_000);
+
+    let message = recv(&mut client).await;
+    assert_eq!(message["subscription"], 2);
+    assert_eq!(message["event"]["to_address"], "bc1qalice");
+
+    send(&mut client, json!({"type": "unsubscribe", "subscription": 1})).await;
+    assert_eq!(
+        recv(&mut client).await,
+        json!({"type": "unsubscribed", "subscription": 1})
+    );
+
+    send(&mut client, json!({"type": "ping"})).await;
+    assert_eq!(recv(&mut client).await, json!({"type": "pong"}));
+
+    // Replies carry the id of their request, errors included
+    send(&mut client, json!({"type": "ping", "id": 7})).await;
+    assert_eq!(recv(&mut client).await, json!({"type": "pong", "id": 7}));
+    send(&mut client, json!({"type": "unsubscribe", "subscription": 1, "id": 8})).await;
+    let reply = recv(&mut client).await;
+    assert_eq!(reply["id"], 8);
+    assert_eq!(reply["code"], "NOT_FOUND");
+    send(&mut client, json!({"type": "subscribe", "channel": "mempool", "id": 9})).await;
+    let reply = recv(&mut client).await;
+    assert_eq!(reply["id"], 9);
+    assert_eq!(reply["code"], "INVALID_REQUEST");
+}
+
+#[actix_web::test]
+async fn test_address_channel() {
+    let events = Arc::new(EventBus::new(16));
+    let url = start_server(events.clone(), WebSocketConfig::default());
+    let (mut client, _) = connect_async(url).await.unwrap();
+
+    send(&mut client, json!({"type": "subscribe", "channel": "address", "address": "bc1qbob"})).await;
+    assert_eq!(recv(&mut client).await["type"], "subscribed");
+
+    events.publish(transfer("UNCOMMON•GOODS", "bc1qalice"));
+    events.publish(block(840_000));
+    events.publish(transfer("UNCOMMON•GOODS", "bc1qbob"));
+
+    let message = recv(&mut client).await;
+    assert_eq!(message["event"]["type"], "transfer");
+    assert_eq!(message["event"]["to_address"], "bc1qbob");
+
+    send(&mut client, json!({"type": "subscribe", "channel": "address", "address": " "})).await;
+    assert_eq!(recv(&mut client).await["code"], "INVALID_REQUEST");
+
+    send(&mut client, json!({"type": "subscribe", "channel": "mempool"})).await;
+    assert_eq!(recv(&mut client).await["code"], "INVALID_REQUEST");
+}
+
+#[actix_web::test]
+async fn test_address_channel_receives_watched_transfers() {
+    let fake = FakeNode::start().await;
+    let config = fake.config(BackendKind::Bitcoind);
+    let node = create_backend(&config, Arc::new(config.http.build_client(config.timeout).unwrap()));
+    let events = Arc::new(EventBus::new(16));
+    let watcher = BlockWatcher::new(
+        node,
+        events.clone(),
+        WatcherConfig {
+            scan_transactions: true,
+            ..Default::default()
+        },
+    );
+    watcher.poll().await.unwrap();
+
+    let url = start_server(events.clone(), WebSocketConfig::default());
+    let (mut client, _) = connect_async(url).await.unwrap();
+    send(&mut client, json!({"type": "subscribe", "channel": "address", "address": "bc1qbob"})).await;
+    assert_eq!(recv(&mut client).await["type"], "subscribed");
+
+    // The premine goes to alice, then part of it moves on to bob
+    let etched = fake.mine(vec![FakeTransaction::new()
+        .spend(&"ab".repeat(32), 0)
+        .runestone(&Runestone {
+            etching: Some(Etching {
+                rune: Some("UNCOMMONGOODS".to_string()),
+                spacers: Some(0b1000_0000),
+                premine: Some(1_000),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .pay("bc1qalice", 546)]);
+    fake.mine(vec![FakeTransaction::new()
+        .spend(&etched.txids[1], 1)
+        .pay("bc1qbob", 546)
+        .runestone(&Runestone {
+            edicts: vec![Edict { id: RuneId { block: 1, tx: 1 }, amount: 250, output: 0 }],
+            ..Default::default()
+        })]);
+    assert_eq!(watcher.poll().await.unwrap(), 2);
+
+    let message = recv(&mut client).await;
+    assert_eq!(message["type"], "event");
+    assert_eq!(message["event"]["type"], "transfer");
+    assert_eq!(message["event"]["rune"], "UNCOMMON•GOODS");
+    assert_eq!(message["event"]["rune_id"], "1:1");
+    assert_eq!(message["event"]["to_address"], "bc1qbob");
+    assert_eq!(message["event"]["amount"], "250");
+    assert_eq!(message["event"]["block_height"], 2);
+}
+
+#[actix_web::test]
+async fn test_rejects_invalid_api_key_on_upgrade() {
+    let url = start_server(Arc::new(EventBus::new(16)), keyed_config());
+
+    let mut request = url.as_str().into_client_request().unwrap();
+    request.headers_mut().insert("X-API-Key", "wrong".parse().unwrap());
+    match connect_async(request).await {
+        Err(Error::Http(response)) => assert_eq!(response.status(), 401),
+        other => panic!("expected 401, got {:?}", other.map(|_| ())),
+    }
+
+    let mut request = url.as_str().into_client_request().unwrap();
+    request.headers_mut().insert("X-API-Key", "listener-key".parse().unwrap());
+    let (mut client, _) = connect_async(request).await.unwrap();
+
+    send(&mut client, json!({"type": "subscribe", "channel": "blocks"})).await;
+    assert_eq!(recv(&mut client).await["type"], "subscribed");
+
+    let (mut client, _) = connect_async(format!("{}?api_key=listener-key", url)).await.unwrap();
+    send(&mut client, json!({"type": "subscribe", "channel": "blocks"})).await;
+    assert_eq!(recv(&mut client).await["type"], "subscribed");
+}
+
+#[actix_web::test]
+async fn test_auth_message() {
+    let url = start_server(Arc::new(EventBus::new(16)), keyed_config());
+    let (mut client, _) = connect_async(url.as_str()).await.unwrap();
+
+    send(&mut client, json!({"type": "subscribe", "channel": "blocks"})).await;
+    assert_eq!(recv(&mut client).await["code"], "UNAUTHORIZED");
+
+    send(&mut client, json!({"type": "auth", "api_key": "listener-key"})).await;
+    assert_eq!(recv(&mut client).await, json!({"type": "authenticated"}));
+
+    send(&mut client, json!({"type": "subscribe", "channel": "blocks"})).await;
+    assert_eq!(recv(&mut client).await["type"], "subscribed");
+
+    // A wrong key closes the connection
+    let (mut client, _) = connect_async(url.as_str()).await.unwrap();
+    send(&mut client, json!({"type": "auth", "api_key": "wrong"})).await;
+    assert_eq!(recv(&mut client).await["code"], "UNAUTHORIZED");
+    assert_eq!(recv_close_code(&mut client).await, CloseCode::Policy);
+}
+
+#[actix_web::test]
+async fn test_auth_timeout_closes_connection() {
+    let config = WebSocketConfig {
+        auth_timeout: Duration::from_millis(100),
+        ..keyed_config()
+    };
+    let url = start_server(Arc::new(EventBus::new(16)), config);
+    let (mut client, _) = connect_async(url).await.unwrap();
+
+    assert_eq!(recv_close_code(&mut client).await, CloseCode::Policy);
+}
+
+#[actix_web::test]
+async fn test_subscription_limit() {
+    let config = WebSocketConfig {
+        max_subscriptions: 2,
+        ..Default::default()
+    };
+    let url = start_server(Arc::new(EventBus::new(16)), config);
+    let (mut client, _) = connect_async(url).await.unwrap();
+
+    for _ in 0..2 {
+        send(&mut client, json!({"type": "subscribe", "channel": "blocks"})).await;
+        assert_eq!(recv(&mut client).await["type"], "subscribed");
+    }
+
+    send(&mut client, json!({"type": "subscribe", "channel": "rune_transfers"})).await;
+    assert_eq!(recv(&mut client).await["code"], "SUBSCRIPTION_LIMIT_EXCEEDED");
+
+    send(&mut client, json!({"type": "unsubscribe", "subscription": 1})).await;
+    assert_eq!(recv(&mut client).await["type"], "unsubscribed");
+
+    send(&mut client, json!({"type": "subscribe", "channel": "rune_transfers"})).await;
+    assert_eq!(recv(&mut client).await["subscription"], 3);
+
+    send(&mut client, json!({"type": "unsubscribe", "subscription": 1})).await;
+    assert_eq!(recv(&mut client).await["code"], "NOT_FOUND");
+}
+
+#[actix_web::test]
+async fn test_heartbeat_pings_client() {
+    let config = WebSocketConfig {
+        heartbeat_interval: Duration::from_millis(50),
+        ..Default::default()
+    };
+    let url = start_server(Arc::new(EventBus::new(16)), config);
+    let (mut client, _) = connect_async(url).await.unwrap();
+
+    let frame = tokio::time::timeout(Duration::from_secs(5), client.next())
+        .await
+        .expect("timed out waiting for a ping")
+        .unwrap()
+        .unwrap();
+    assert!(matches!(frame, Message::Ping(_)));
+}
+
+#[actix_web::test]
+async fn test_slow_consumer_is_disconnected() {
+    let events = Arc::new(EventBus::new(1));
+    let url = start_server(events.clone(), WebSocketConfig::default());
+    let (mut client, _) = connect_async(url).await.unwrap();
+
+    send(&mut client, json!({"type": "subscribe", "channel": "blocks"})).await;
+    assert_eq!(recv(&mut client).await["type"], "subscribed");
+
+    // Far more events than the bus buffers per subscriber
+    for height in 0..1_000 {
+        events.publish(block(height));
+    }
+
+    assert_eq!(recv_close_code(&mut client).await, CloseCode::Policy);
+}
@@ -0,0 +1,135 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::services::auth::{ApiKeyScope, ApiKeyStore};
+use crate::types::error::RuneError;
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
+
+#[test]
+fn test_created_key_authenticates_with_its_scopes() {
+    let store = ApiKeyStore::new();
+    let (info, key) = store.create_key("explorer", [ApiKeyScope::RunesRead], None);
+
+    assert!(key.starts_with("rsk_"));
+    assert_eq!(key.len(), 4 + 64);
+    assert!(info.last_used_at.is_none());
+
+    let used = store.authenticate(&key, ApiKeyScope::RunesRead).unwrap();
+    assert_eq!(used.id, info.id);
+    assert!(used.last_used_at.is_some());
+    assert_eq!(store.get_key(&info.id).unwrap().last_used_at, used.last_used_at);
+
+    match store.authenticate(&key, ApiKeyScope::WebhooksWrite) {
+        Err(RuneError::Forbidden(msg)) => assert!(msg.contains("webhooks:write"), "{}", msg),
+        other => panic!("expected Forbidden, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_unknown_keys_are_unauthorized() {
+    let store = ApiKeyStore::new();
+    store.create_key("explorer", [ApiKeyScope::RunesRead], None);
+
+    assert!(matches!(
+        store.authenticate("rsk_unknown", ApiKeyScope::RunesRead),
+        Err(RuneError::Unauthorized(_))
+    ));
+    assert!(!store.is_key_valid("rsk_unknown"));
+}
+
+#[test]
+fn test_admin_scope_grants_everything() {
+    let store = ApiKeyStore::new();
+    let (_, key) = store.create_key("operator", [ApiKeyScope::Admin], None);
+
+    for scope in [
+        ApiKeyScope::RunesRead,
+        ApiKeyScope::EventsRead,
+        ApiKeyScope::WebhooksWrite,
+        ApiKeyScope::Admin,
+    ] {
+        assert!(store.authenticate(&key, scope).is_ok(), "{}", scope);
+    }
+}
+
+#[test]
+fn test_disabled_keys_are_rejected_until_enabled() {
+    let store = ApiKeyStore::new();
+    let (info, key) = store.create_key("explorer", [ApiKeyScope::RunesRead], None);
+
+    store.set_enabled(&info.id, false).unwrap();
+    assert!(!store.get_key(&info.id).unwrap().enabled);
+    assert!(!store.is_key_valid(&key));
+    match store.authenticate(&key, ApiKeyScope::RunesRead) {
+        Err(RuneError::Unauthorized(msg)) => assert!(msg.contains("disabled"), "{}", msg),
+        other => panic!("expected Unauthorized, got {:?}", other),
+    }
+
+    store.set_enabled(&info.id, true).unwrap();
+    assert!(store.authenticate(&key, ApiKeyScope::RunesRead).is_ok());
+
+    assert!(matches!(store.set_enabled("missing", true), Err(RuneError::NotFound(_))));
+}
+
+#[test]
+fn test_expired_keys_are_rejected_and_cleaned_up() {
+    let store = ApiKeyStore::new();
+    store
+        .import_key("old", "Old key", "expired-key", [ApiKeyScope::RunesRead], Some(unix_now() - 1))
+        .unwrap();
+    let (current, key) = store.create_key(
+        "current",
+        [ApiKeyScope::RunesRead],
+        Some(Duration::from_secs(3600)),
+    );
+    assert!(current.expires_at.unwrap() > unix_now());
+
+    match store.authenticate("expired-key", ApiKeyScope::RunesRead) {
+        Err(RuneError::Unauthorized(msg)) => assert!(msg.contains("expired"), "{}", msg),
+        other => panic!("expected Unauthorized, got {:?}", other),
+    }
+
+    assert_eq!(store.cleanup_expired_keys(), 1);
+    assert!(store.get_key("old").is_none());
+    assert_eq!(store.list_keys().len(), 1);
+    assert!(store.authenticate(&key, ApiKeyScope::RunesRead).is_ok());
+}
+
+#[test]
+fn test_import_rejects_duplicates_and_remove_forgets_key() {
+    let store = ApiKeyStore::new();
+    store
+        .import_key("ci", "CI", "configured-key", [ApiKeyScope::EventsRead], None)
+        .unwrap();
+
+    assert!(matches!(
+        store.import_key("ci", "CI again", "other-key", [], None),
+        Err(RuneError::ConfigError(_))
+    ));
+    assert!(matches!(
+        store.import_key("copy", "Copy", "configured-key", [], None),
+        Err(RuneError::ConfigError(_))
+    ));
+    assert!(matches!(
+        store.import_key("blank", "Blank", " ", [], None),
+        Err(RuneError::ConfigError(_))
+    ));
+
+    let removed = store.remove_key("ci").unwrap();
+    assert_eq!(removed.name, "CI");
+    assert!(!store.is_key_valid("configured-key"));
+    assert!(store.list_keys().is_empty());
+}
+
+#[test]
+fn test_listed_keys_do_not_expose_the_key() {
+    let store = ApiKeyStore::new();
+    let (_, key) = store.create_key("explorer", [ApiKeyScope::RunesRead], None);
+
+    let listed = serde_json::to_string(&store.list_keys()).unwrap();
+    assert!(listed.contains("runes:read"));
+    assert!(!listed.contains(&key));
+    assert!(!listed.contains(&key[4..]));
+}
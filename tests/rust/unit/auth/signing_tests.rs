@@ -0,0 +1,170 @@
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::services::auth::{
+    signing::{RequestVerifier, SignedRequest, SigningConfig},
+    ApiKeyScope, ApiKeyStore,
+};
+use crate::types::error::RuneError;
+
+const SECRET: &str = "rss_test_secret";
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
+
+fn verifier(config: SigningConfig) -> RequestVerifier {
+    let store = ApiKeyStore::new();
+    store
+        .import_key("ops", "Ops", "ops-key", [ApiKeyScope::WebhooksWrite], None)
+        .unwrap();
+    store.set_signing_secret("ops", SECRET).unwrap();
+    store
+        .import_key("reader", "Reader", "reader-key", [ApiKeyScope::RunesRead], None)
+        .unwrap();
+    RequestVerifier::new(Arc::new(store), config)
+}
+
+fn request(timestamp: u64, nonce: &str) -> SignedRequest<'_> {
+    SignedRequest {
+        method: "post",
+        path: "/api/v1/webhooks",
+        timestamp,
+        nonce,
+        body: br#"{"url":"https://example.com/hook"}"#,
+    }
+}
+
+fn hex_sha256(body: &[u8]) -> String {
+    hex::encode(Sha256::digest(body))
+}
+
+#[test]
+fn test_canonical_string_covers_every_part() {
+    let signed = request(1_713_571_767, "nonce-0001");
+    assert_eq!(
+        signed.canonical_string(),
+        format!(
+            "POST\n/api/v1/webhooks\n1713571767\nnonce-0001\n{}",
+            hex_sha256(signed.body)
+        )
+    );
+
+    let signature = signed.sign(SECRET);
+    assert_eq!(signature.len(), 64);
+    for changed in [
+        SignedRequest { method: "DELETE", ..request(1_713_571_767, "nonce-0001") },
+        SignedRequest { path: "/api/v1/webhooks/x", ..request(1_713_571_767, "nonce-0001") },
+        SignedRequest { body: b"{}", ..request(1_713_571_767, "nonce-0001") },
+        request(1_713_571_768, "nonce-0001"),
+        request(1_713_571_767, "nonce-0002"),
+    ] {
+        assert_ne!(changed.sign(SECRET), signature);
+    }
+}
+
+#[test]
+fn test_valid_signature_is_accepted_once() {
+    let verifier = verifier(SigningConfig::default());
+    let request = request(unix_now(), "nonce-0001");
+    let signature = request.sign(SECRET);
+
+    verifier.verify("ops", &request, &signature).unwrap();
+    assert_eq!(verifier.nonce_count(), 1);
+
+    match verifier.verify("ops", &request, &signature) {
+        Err(RuneError::Unauthorized(msg)) => assert!(msg.contains("already been used"), "{}", msg),
+        other => panic!("expected a replay rejection, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_bad_signatures_do_not_use_up_the_nonce() {
+    let verifier = verifier(SigningConfig::default());
+    let request = request(unix_now(), "nonce-0001");
+
+    for signature in ["zz", "00", &request.sign("wrong-secret")] {
+        match verifier.verify("ops", &request, signature) {
+            Err(RuneError::Unauthorized(msg)) => assert!(msg.contains("Invalid"), "{}", msg),
+            other => panic!("expected Unauthorized, got {:?}", other),
+        }
+    }
+    assert_eq!(verifier.nonce_count(), 0);
+    assert!(verifier.verify("ops", &request, &request.sign(SECRET)).is_ok());
+}
+
+#[test]
+fn test_timestamps_outside_the_skew_window_are_rejected() {
+    let verifier = verifier(SigningConfig {
+        max_clock_skew: Duration::from_secs(60),
+        ..Default::default()
+    });
+
+    for timestamp in [unix_now() - 120, unix_now() + 120] {
+        let request = request(timestamp, "nonce-0001");
+        match verifier.verify("ops", &request, &request.sign(SECRET)) {
+            Err(RuneError::Unauthorized(msg)) => assert!(msg.contains("clock skew"), "{}", msg),
+            other => panic!("expected Unauthorized, got {:?}", other),
+        }
+    }
+
+    let request = request(unix_now() - 30, "nonce-0001");
+    assert!(verifier.verify("ops", &request, &request.sign(SECRET)).is_ok());
+}
+
+#[test]
+fn test_nonce_rules() {
+    let verifier = verifier(SigningConfig {
+        max_nonces: 2,
+        ..Default::default()
+    });
+
+    let short = request(unix_now(), "abc");
+    assert!(matches!(
+        verifier.verify("ops", &short, &short.sign(SECRET)),
+        Err(RuneError::Unauthorized(_))
+    ));
+
+    for nonce in ["nonce-0001", "nonce-0002"] {
+        let request = request(unix_now(), nonce);
+        verifier.verify("ops", &request, &request.sign(SECRET)).unwrap();
+    }
+
+    // Both nonces are still inside the window, so no room for a third
+    let request = request(unix_now(), "nonce-0003");
+    assert!(matches!(
+        verifier.verify("ops", &request, &request.sign(SECRET)),
+        Err(RuneError::RateLimitExceeded)
+    ));
+}
+
+#[test]
+fn test_keys_without_signing_secret_are_forbidden() {
+    let verifier = verifier(SigningConfig::default());
+    let request = request(unix_now(), "nonce-0001");
+
+    assert!(matches!(
+        verifier.verify("reader", &request, &request.sign(SECRET)),
+        Err(RuneError::Forbidden(_))
+    ));
+}
+
+#[test]
+fn test_issued_secret_replaces_the_previous_one() {
+    let store = Arc::new(ApiKeyStore::new());
+    let (key, _) = store.create_key("ops", [ApiKeyScope::WebhooksWrite], None);
+    assert!(!key.has_signing_secret);
+
+    let first = store.issue_signing_secret(&key.id).unwrap();
+    let second = store.issue_signing_secret(&key.id).unwrap();
+    assert!(second.starts_with("rss_"));
+    assert_ne!(first, second);
+    assert!(store.get_key(&key.id).unwrap().has_signing_secret);
+    assert!(matches!(store.issue_signing_secret("missing"), Err(RuneError::NotFound(_))));
+
+    let verifier = RequestVerifier::new(store, SigningConfig::default());
+    let request = request(unix_now(), "nonce-0001");
+    assert!(verifier.verify(&key.id, &request, &request.sign(&first)).is_err());
+    assert!(verifier.verify(&key.id, &request, &request.sign(&second)).is_ok());
+}
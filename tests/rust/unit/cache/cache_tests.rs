@@ -2,41 +2,75 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use crate::services::cache::{RunesCache, CacheConfig, CacheMetrics};
-use crate::types::rune::{RunesTransactionResponse, RuneTransfer, TransactionStatus, NetworkType};
+use crate::types::rune::{
+    NetworkType, RuneEntry, RuneInfo, RunesTransactionResponse, RuneTransfer, TransactionStatus,
+    TransferType,
+};
+
+fn test_transaction(tx_id: &str, confirmations: u32) -> RunesTransactionResponse {
+    RunesTransactionResponse {
+        transaction_id: tx_id.to_string(),
+        runes: vec![],
+        block_height: Some(840_000),
+        confirmation_count: confirmations,
+        timestamp: 1_713_571_767,
+        network_type: NetworkType::Mainnet,
+        status: TransactionStatus::Confirmed,
+    }
+}
+
+fn test_transfer(rune_id: &str, amount: u128, from: &str, to: &str) -> RuneTransfer {
+    RuneTransfer {
+        rune_id: rune_id.to_string(),
+        from_address: from.to_string(),
+        to_address: to.to_string(),
+        amount,
+        transfer_type: TransferType::Transfer,
+        fee: None,
+        metadata: None,
+    }
+}
+
+fn test_rune(id: &str, spaced_name: &str) -> RuneInfo {
+    let entry = RuneEntry {
+        id: id.to_string(),
+        name: spaced_name.replace('•', ""),
+        spaced_name: spaced_name.to_string(),
+        number: 0,
+        etching: "0".repeat(64),
+        block: 840_000,
+        divisibility: 0,
+        symbol: None,
+        spacers: 0,
+        premine: 1_000,
+        terms: None,
+        turbo: false,
+        mints: 0,
+        burned: 0,
+        timestamp: 1_713_571_767,
+        holders: None,
+    };
+    RuneInfo::from_entry(&entry, 840_000, 1_713_571_767)
+}
 
 #[tokio::test]
 async fn test_transaction_cache() {
-    let metrics = Arc::new(CacheMetrics {
-        hits: metrics::Counter::new("test_cache_hits"),
-        misses: metrics::Counter::new("test_cache_misses"),
-        evictions: metrics::Counter::new("test_cache_evictions"),
-    });
+    let metrics = Arc::new(CacheMetrics::default());
 
     let config = CacheConfig {
         transaction_cache_size: 2,
         transaction_ttl: Duration::from_secs(1),
         address_cache_size: 2,
         address_ttl: Duration::from_secs(1),
+        ..Default::default()
     };
 
     let cache = RunesCache::new(config, metrics);
 
     // Test transaction caching
-    let tx1 = RunesTransactionResponse {
-        transaction_id: "tx1".to_string(),
-        status: TransactionStatus::Confirmed,
-        network: NetworkType::Mainnet,
-        runes: vec![],
-        confirmations: Some(6),
-    };
+    let tx1 = test_transaction("tx1", 6);
 
-    let tx2 = RunesTransactionResponse {
-        transaction_id: "tx2".to_string(),
-        status: TransactionStatus::Confirmed,
-        network: NetworkType::Mainnet,
-        runes: vec![],
-        confirmations: Some(3),
-    };
+    let tx2 = test_transaction("tx2", 3);
 
     // Cache miss for non-existent transaction
     assert!(cache.get_transaction("tx1").await.is_none());
@@ -55,35 +89,22 @@ async fn test_transaction_cache() {
 
 #[tokio::test]
 async fn test_address_cache() {
-    let metrics = Arc::new(CacheMetrics {
-        hits: metrics::Counter::new("test_cache_hits"),
-        misses: metrics::Counter::new("test_cache_misses"),
-        evictions: metrics::Counter::new("test_cache_evictions"),
-    });
+    let metrics = Arc::new(CacheMetrics::default());
 
     let config = CacheConfig {
         transaction_cache_size: 2,
         transaction_ttl: Duration::from_secs(1),
         address_cache_size: 2,
         address_ttl: Duration::from_secs(1),
+        ..Default::default()
     };
 
     let cache = RunesCache::new(config, metrics);
 
     // Test address transfers caching
-    let transfers1 = vec![RuneTransfer {
-        rune_id: "rune1".to_string(),
-        amount: 100,
-        from_address: Some("addr1".to_string()),
-        to_address: "addr2".to_string(),
-    }];
-
-    let transfers2 = vec![RuneTransfer {
-        rune_id: "rune2".to_string(),
-        amount: 200,
-        from_address: Some("addr2".to_string()),
-        to_address: "addr3".to_string(),
-    }];
+    let transfers1 = vec![test_transfer("rune1", 100, "addr1", "addr2")];
+
+    let transfers2 = vec![test_transfer("rune2", 200, "addr2", "addr3")];
 
     // Cache miss for non-existent address
     assert!(cache.get_address_transfers("addr1").await.is_none());
@@ -101,32 +122,36 @@ async fn test_address_cache() {
     assert!(cache.get_address_transfers("addr2").await.is_none());
 }
 
+#[tokio::test]
+async fn test_rune_cache() {
+    let metrics = Arc::new(CacheMetrics::default());
+    let cache = RunesCache::new(CacheConfig::default(), metrics);
+
+    cache.set_rune(test_rune("840000:1", "UNCOMMON•GOODS")).await.unwrap();
+
+    // Reachable by id and by plain name
+    let by_id = cache.get_rune("840000:1").await.unwrap();
+    let by_name = cache.get_rune("UNCOMMONGOODS").await.unwrap();
+    assert!(Arc::ptr_eq(&by_id, &by_name));
+    assert_eq!(by_id.supply.total, "1000");
+
+    // Invalidating either key drops both
+    cache.invalidate_rune("UNCOMMONGOODS").await;
+    assert!(cache.get_rune("840000:1").await.is_none());
+    assert_eq!(cache.get_metrics().await.rune_cache_size, 0);
+}
+
 #[tokio::test]
 async fn test_cache_invalidation() {
-    let metrics = Arc::new(CacheMetrics {
-        hits: metrics::Counter::new("test_cache_hits"),
-        misses: metrics::Counter::new("test_cache_misses"),
-        evictions: metrics::Counter::new("test_cache_evictions"),
-    });
+    let metrics = Arc::new(CacheMetrics::default());
 
     let config = CacheConfig::default();
     let cache = RunesCache::new(config, metrics);
 
     // Set up test data
-    let tx = RunesTransactionResponse {
-        transaction_id: "tx1".to_string(),
-        status: TransactionStatus::Confirmed,
-        network: NetworkType::Mainnet,
-        runes: vec![],
-        confirmations: Some(6),
-    };
+    let tx = test_transaction("tx1", 6);
 
-    let transfers = vec![RuneTransfer {
-        rune_id: "rune1".to_string(),
-        amount: 100,
-        from_address: Some("addr1".to_string()),
-        to_address: "addr2".to_string(),
-    }];
+    let transfers = vec![test_transfer("rune1", 100, "addr1", "addr2")];
 
     // Cache items
     cache.set_transaction("tx1".to_string(), tx.clone()).await.unwrap();
@@ -151,28 +176,19 @@ async fn test_cache_invalidation() {
 
 #[tokio::test]
 async fn test_cache_metrics() {
-    let metrics = Arc::new(CacheMetrics {
-        hits: metrics::Counter::new("test_cache_hits"),
-        misses: metrics::Counter::new("test_cache_misses"),
-        evictions: metrics::Counter::new("test_cache_evictions"),
-    });
+    let metrics = Arc::new(CacheMetrics::default());
 
     let config = CacheConfig {
         transaction_cache_size: 1,
         transaction_ttl: Duration::from_secs(60),
         address_cache_size: 1,
         address_ttl: Duration::from_secs(60),
+        ..Default::default()
     };
 
     let cache = RunesCache::new(config, metrics);
 
-    let tx = RunesTransactionResponse {
-        transaction_id: "tx1".to_string(),
-        status: TransactionStatus::Confirmed,
-        network: NetworkType::Mainnet,
-        runes: vec![],
-        confirmations: Some(6),
-    };
+    let tx = test_transaction("tx1", 6);
 
     // Test cache miss
     cache.get_transaction("tx1").await;
@@ -188,13 +204,7 @@ async fn test_cache_metrics() {
     assert_eq!(stats.hits, 1);
 
     // Test eviction
-    let tx2 = RunesTransactionResponse {
-        transaction_id: "tx2".to_string(),
-        status: TransactionStatus::Confirmed,
-        network: NetworkType::Mainnet,
-        runes: vec![],
-        confirmations: Some(3),
-    };
+    let tx2 = test_transaction("tx2", 3);
     cache.set_transaction("tx2".to_string(), tx2.clone()).await.unwrap();
     let stats = cache.get_metrics().await;
     assert_eq!(stats.evictions, 1);
@@ -0,0 +1,408 @@
+use actix_web::{web, App, HttpServer};
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio_tungstenite::{accept_async, tungstenite::Message};
+
+use super::*;
+use crate::api::websocket::{
+    handlers::{WebSocketApiContext, WebSocketConfig as ServerConfig},
+    routes::configure_routes,
+};
+use crate::services::events::{
+    bus::{ChainEventData, EventBus},
+    protocol::Channel,
+};
+use crate::testing::fixtures::key_store;
+
+fn client_config(url: String) -> WebSocketConfig {
+    WebSocketConfig {
+        url,
+        api_key: None,
+        reconnect_interval: Some(10),
+        max_reconnect_attempts: Some(3),
+    }
+}
+
+fn block_event(id: u64, height: u64) -> Value {
+    json!({
+        "id": id,
+        "published_at": 1_713_571_767,
+        "type": "block",
+        "height": height,
+        "hash": format!("hash{}", height),
+        "timestamp": 1_713_571_767,
+        "tx_count": 1
+    })
+}
+
+async fn next_event(client: &mut WebSocketClient) -> WebSocketEvent {
+    tokio::time::timeout(Duration::from_secs(5), client.next())
+        .await
+        .expect("timed out waiting for an event")
+        .expect("client ended")
+        .unwrap()
+}
+
+/// Reads the next text frame of a scripted server connection
+async fn read_json<S>(socket: &mut tokio_tungstenite::WebSocketStream<S>) -> Value
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    loop {
+        match socket.next().await.unwrap().unwrap() {
+            Message::Text(text) => return serde_json::from_str(&text).unwrap(),
+            _ => continue,
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_resubscribes_after_reconnect() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+
+    let server = tokio::spawn(async move {
+        // First connection: acknowledge the subscription, send an event, drop
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut socket = accept_async(stream).await.unwrap();
+        assert_eq!(
+            read_json(&mut socket).await,
+            json!({"type": "subscribe", "channel": "blocks", "id": 1})
+        );
+        let subscribed = json!({"type": "subscribed", "subscription": 1, "channel": "blocks"});
+        socket.send(Message::Text(subscribed.to_string())).await.unwrap();
+        let event = json!({"type": "event", "subscription": 1, "event": block_event(1, 840_000)});
+        socket.send(Message::Text(event.to_string())).await.unwrap();
+        drop(socket);
+
+        // Second connection: the subscription is re-sent and gets a new id
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut socket = accept_async(stream).await.unwrap();
+        assert_eq!(
+            read_json(&mut socket).await,
+            json!({"type": "subscribe", "channel": "blocks", "id": 2})
+        );
+        let subscribed = json!({"type": "subscribed", "subscription": 7, "channel": "blocks"});
+        socket.send(Message::Text(subscribed.to_string())).await.unwrap();
+        let event = json!({"type": "event", "subscription": 7, "event": block_event(2, 840_001)});
+        socket.send(Message::Text(event.to_string())).await.unwrap();
+
+        // Keep the connection open until the client closes it
+        while let Some(Ok(message)) = socket.next().await {
+            if message.is_close() {
+                break;
+            }
+        }
+    });
+
+    let mut client = WebSocketService::new(client_config(url)).connect().await.unwrap();
+    client.subscribe(Channel::Blocks).unwrap();
+
+    match next_event(&mut client).await {
+        WebSocketEvent::Chain { channel, event } => {
+            assert_eq!(channel, Channel::Blocks);
+            assert_eq!(event.id, 1);
+        }
+        other => panic!("expected a chain event, got {:?}", other),
+    }
+    assert!(matches!(next_event(&mut client).await, WebSocketEvent::Reconnected));
+    match next_event(&mut client).await {
+        WebSocketEvent::Chain { event, .. } => assert_eq!(event.id, 2),
+        other => panic!("expected a chain event, got {:?}", other),
+    }
+
+    drop(client);
+    tokio::time::timeout(Duration::from_secs(5), server)
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_resubscribing_before_the_ack_subscribes_once() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut socket = accept_async(stream).await.unwrap();
+        assert_eq!(
+            read_json(&mut socket).await,
+            json!({"type": "subscribe", "channel": "blocks", "id": 1})
+        );
+        // The unsubscribe and second subscribe wait for this one's reply
+        let next = tokio::time::timeout(Duration::from_millis(200), read_json(&mut socket)).await;
+        assert!(next.is_err(), "unexpected message {:?}", next);
+
+        // A second subscription of the same channel is dropped again. Replies
+        // without an id are matched to requests by what they acknowledge.
+        for subscription in [1, 2] {
+            let subscribed = json!({"type": "subscribed", "subscription": subscription, "channel": "blocks"});
+            socket.send(Message::Text(subscribed.to_string())).await.unwrap();
+        }
+        assert_eq!(
+            read_json(&mut socket).await,
+            json!({"type": "unsubscribe", "subscription": 2, "id": 2})
+        );
+        for (subscription, id) in [(2, 5), (1, 6)] {
+            let event = json!({"type": "event", "subscription": subscription, "event": block_event(id, 840_000)});
+            socket.send(Message::Text(event.to_string())).await.unwrap();
+        }
+
+        while let Some(Ok(message)) = socket.next().await {
+            if message.is_close() {
+                break;
+            }
+        }
+    });
+
+    let mut client = WebSocketService::new(client_config(url)).connect().await.unwrap();
+    client.subscribe(Channel::Blocks).unwrap();
+    client.unsubscribe(Channel::Blocks).unwrap();
+    client.subscribe(Channel::Blocks).unwrap();
+
+    match next_event(&mut client).await {
+        WebSocketEvent::Chain { channel, event } => {
+            assert_eq!(channel, Channel::Blocks);
+            assert_eq!(event.id, 6);
+        }
+        other => panic!("expected a chain event, got {:?}", other),
+    }
+
+    drop(client);
+    tokio::time::timeout(Duration::from_secs(5), server)
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_authenticates_after_every_connect() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+
+    let server = tokio::spawn(async move {
+        for subscription in [1, 2] {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = accept_async(stream).await.unwrap();
+            assert_eq!(
+                read_json(&mut socket).await,
+                json!({"type": "auth", "api_key": "listener-key", "id": subscription * 2 - 1})
+            );
+            socket.send(Message::Text(json!({"type": "authenticated"}).to_string())).await.unwrap();
+            assert_eq!(
+                read_json(&mut socket).await,
+                json!({"type": "subscribe", "channel": "blocks", "id": subscription * 2})
+            );
+            let subscribed = json!({"type": "subscribed", "subscription": subscription, "channel": "blocks"});
+            socket.send(Message::Text(subscribed.to_string())).await.unwrap();
+            let event = json!({"type": "event", "subscription": subscription, "event": block_event(subscription as u64, 840_000)});
+            socket.send(Message::Text(event.to_string())).await.unwrap();
+
+            if subscription == 2 {
+                while let Some(Ok(message)) = socket.next().await {
+                    if message.is_close() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+
+    let config = WebSocketConfig {
+        api_key: Some("listener-key".to_string()),
+        ..client_config(url)
+    };
+    let mut client = WebSocketService::new(config).connect().await.unwrap();
+    client.subscribe(Channel::Blocks).unwrap();
+
+    assert!(matches!(next_event(&mut client).await, WebSocketEvent::Chain { event, .. } if event.id == 1));
+    assert!(matches!(next_event(&mut client).await, WebSocketEvent::Reconnected));
+    assert!(matches!(next_event(&mut client).await, WebSocketEvent::Chain { event, .. } if event.id == 2));
+
+    drop(client);
+    tokio::time::timeout(Duration::from_secs(5), server)
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_errors_are_matched_to_requests_by_id() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut socket = accept_async(stream).await.unwrap();
+        assert_eq!(read_json(&mut socket).await["id"], 1);
+        assert_eq!(read_json(&mut socket).await["id"], 2);
+
+        // Answers neither subscribe, both stay pending
+        let unsolicited = json!({"type": "error", "code": "INTERNAL_ERROR", "message": "oops"});
+        socket.send(Message::Text(unsolicited.to_string())).await.unwrap();
+        // Replies arrive out of order
+        let rejected = json!({"type": "error", "id": 2, "code": "SUBSCRIPTION_LIMIT_EXCEEDED", "message": "full"});
+        socket.send(Message::Text(rejected.to_string())).await.unwrap();
+        let subscribed = json!({"type": "subscribed", "id": 1, "subscription": 1, "channel": "blocks"});
+        socket.send(Message::Text(subscribed.to_string())).await.unwrap();
+        let event = json!({"type": "event", "subscription": 1, "event": block_event(1, 840_000)});
+        socket.send(Message::Text(event.to_string())).await.unwrap();
+
+        while let Some(Ok(message)) = socket.next().await {
+            if message.is_close() {
+                break;
+            }
+        }
+    });
+
+    let mut client = WebSocketService::new(client_config(url)).connect().await.unwrap();
+    client.subscribe(Channel::Blocks).unwrap();
+    client.subscribe(Channel::RuneTransfers { rune: None }).unwrap();
+
+    match next_event(&mut client).await {
+        WebSocketEvent::Rejected { channel, code, .. } => {
+            assert_eq!(channel, Channel::RuneTransfers { rune: None });
+            assert_eq!(code, "SUBSCRIPTION_LIMIT_EXCEEDED");
+        }
+        other => panic!("expected a rejection, got {:?}", other),
+    }
+    match next_event(&mut client).await {
+        WebSocketEvent::Chain { channel, event } => {
+            assert_eq!(channel, Channel::Blocks);
+            assert_eq!(event.id, 1);
+        }
+        other => panic!("expected a chain event, got {:?}", other),
+    }
+
+    drop(client);
+    tokio::time::timeout(Duration::from_secs(5), server)
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_gives_up_after_max_reconnect_attempts() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let socket = accept_async(stream).await.unwrap();
+        // Dropping the listener makes every reconnect attempt fail
+        drop(listener);
+        drop(socket);
+    });
+
+    let mut client = WebSocketService::new(client_config(url)).connect().await.unwrap();
+    server.await.unwrap();
+
+    let result = tokio::time::timeout(Duration::from_secs(5), client.next())
+        .await
+        .unwrap()
+        .unwrap();
+    match result {
+        Err(Error::WebSocketError(msg)) => assert!(msg.contains("after 3 attempts"), "{}", msg),
+        other => panic!("expected a WebSocket error, got {:?}", other),
+    }
+    assert!(client.next().await.is_none());
+    assert!(client.subscribe(Channel::Blocks).is_err());
+}
+
+#[tokio::test]
+async fn test_connect_fails_without_server() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    drop(listener);
+
+    let result = WebSocketService::new(client_config(url)).connect().await;
+    match result {
+        Err(Error::WebSocketError(msg)) => assert!(msg.contains("after 4 attempts"), "{}", msg),
+        other => panic!("expected a WebSocket error, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[actix_web::test]
+async fn test_against_websocket_endpoint() {
+    let events = Arc::new(EventBus::new(16));
+    let config = ServerConfig {
+        api_keys: Some(key_store()),
+        max_subscriptions: 1,
+        ..Default::default()
+    };
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let bus = events.clone();
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(web::Data::new(WebSocketApiContext {
+                events: bus.clone(),
+                config: config.clone(),
+            }))
+            .configure(configure_routes)
+    })
+    .workers(1)
+    .listen(listener)
+    .unwrap()
+    .run();
+    actix_web::rt::spawn(server);
+
+    let rejected = WebSocketService::new(client_config(format!(
+        "ws://{}/api/v1/ws?api_key=wrong",
+        address
+    )))
+    .connect()
+    .await;
+    assert!(matches!(rejected, Err(Error::WebSocketError(msg)) if msg.contains("401")));
+
+    // A key sent in-band is checked after the handshake
+    let mut rejected = WebSocketService::new(WebSocketConfig {
+        api_key: Some("wrong".to_string()),
+        ..client_config(format!("ws://{}/api/v1/ws", address))
+    })
+    .connect()
+    .await
+    .unwrap();
+    let result = tokio::time::timeout(Duration::from_secs(5), rejected.next()).await.unwrap().unwrap();
+    assert!(matches!(result, Err(Error::WebSocketError(msg)) if msg.contains("Authentication rejected")));
+    assert!(rejected.next().await.is_none());
+
+    let url = format!("ws://{}/api/v1/ws", address);
+    let config = WebSocketConfig {
+        api_key: Some("listener-key".to_string()),
+        ..client_config(url)
+    };
+    let mut client = WebSocketService::new(config).connect().await.unwrap();
+    client.subscribe(Channel::Blocks).unwrap();
+    client
+        .subscribe(Channel::RuneTransfers { rune: None })
+        .unwrap();
+
+    match next_event(&mut client).await {
+        WebSocketEvent::Rejected { channel, code, .. } => {
+            assert_eq!(channel, Channel::RuneTransfers { rune: None });
+            assert_eq!(code, "SUBSCRIPTION_LIMIT_EXCEEDED");
+        }
+        other => panic!("expected a rejection, got {:?}", other),
+    }
+
+    events.publish(ChainEventData::Block {
+        height: 840_000,
+        hash: "hash".to_string(),
+        timestamp: 1_713_571_767,
+        tx_count: 1,
+    });
+
+    match next_event(&mut client).await {
+        WebSocketEvent::Chain { channel, event } => {
+            assert_eq!(channel, Channel::Blocks);
+            assert!(matches!(event.data, ChainEventData::Block { height: 840_000, .. }));
+        }
+        other => panic!("expected a chain event, got {:?}", other),
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::HashSet;
+
+use crate::services::events::bus::{
+    ChainEvent, ChainEventData, ChainEventType, EventBus, EventFilter,
+};
+use crate::testing::fixtures::transfer;
+
+fn block(height: u64) -> ChainEventData {
+    ChainEventData::Block {
+        height,
+        hash: format!("hash{}", height),
+        timestamp: 1_713_571_767,
+        tx_count: 1,
+    }
+}
+
+#[tokio::test]
+async fn test_event_bus_publish_and_subscribe() {
+    let bus = EventBus::new(16);
+    let mut subscription = bus.subscribe(None);
+    assert!(subscription.replay.is_empty());
+
+    let first = bus.publish(block(1));
+    let second = bus.publish(block(2));
+    assert_eq!((first.id, second.id), (1, 2));
+    assert_eq!(bus.last_event_id(), Some(2));
+
+    assert_eq!(subscription.receiver.recv().await.unwrap().id, 1);
+    assert_eq!(subscription.receiver.recv().await.unwrap().id, 2);
+}
+
+#[tokio::test]
+async fn test_event_bus_replay_is_bounded() {
+    let bus = EventBus::new(3);
+    for height in 0..5 {
+        bus.publish(block(height));
+    }
+
+    // Ids 1 and 2 fell out of the buffer
+    let ids: Vec<u64> = bus.subscribe(Some(0)).replay.iter().map(|e| e.id).collect();
+    assert_eq!(ids, vec![3, 4, 5]);
+
+    let ids: Vec<u64> = bus.subscribe(Some(4)).replay.iter().map(|e| e.id).collect();
+    assert_eq!(ids, vec![5]);
+
+    assert!(bus.subscribe(Some(5)).replay.is_empty());
+}
+
+#[test]
+fn test_event_filter() {
+    let bus = EventBus::new(8);
+    let block = bus.publish(block(1));
+    let transfer = bus.publish(transfer("UNCOMMON•GOODS", "bc1qreceiver"));
+
+    assert!(EventFilter::default().matches(&block));
+
+    let types = EventFilter {
+        types: Some(HashSet::from([ChainEventType::Transfer])),
+        ..Default::default()
+    };
+    assert!(!types.matches(&block));
+    assert!(types.matches(&transfer));
+
+    // Spacers are ignored when comparing rune names
+    let rune = EventFilter {
+        rune: Some("UNCOMMONGOODS".to_string()),
+        ..Default::default()
+    };
+    assert!(rune.matches(&transfer));
+    assert!(!rune.matches(&block));
+
+    // Rune ids are compared with the id the event carries
+    let by_id = |id: &str| EventFilter {
+        rune: Some(id.to_string()),
+        ..Default::default()
+    };
+    assert!(by_id("840000:1").matches(&transfer));
+    assert!(!by_id("840000:2").matches(&transfer));
+    let etching = bus.publish(ChainEventData::Etching {
+        txid: "b".repeat(64),
+        rune_id: "840000:1".to_string(),
+        rune: "UNCOMMON•GOODS".to_string(),
+        block_height: 840_000,
+    });
+    assert!(by_id("840000:1").matches(&etching));
+    // A node that cannot name runes reports the id as the rune
+    let unnamed = bus.publish(ChainEventData::Mint {
+        txid: "c".repeat(64),
+        rune: "840000:1".to_string(),
+        rune_id: None,
+        to_address: "bc1qminter".to_string(),
+        amount: "1".to_string(),
+        block_height: 840_001,
+        verified: true,
+    });
+    assert!(by_id("840000:1").matches(&unnamed));
+    assert!(!rune.matches(&unnamed));
+
+    let address = EventFilter {
+        address: Some("bc1qreceiver".to_string()),
+        ..Default::default()
+    };
+    assert!(address.matches(&transfer));
+    assert!(!EventFilter {
+        address: Some("bc1qother".to_string()),
+        ..Default::default()
+    }
+    .matches(&transfer));
+}
+
+#[test]
+fn test_event_json_round_trip() {
+    let bus = EventBus::new(16);
+    let event = bus.publish(block(840_000));
+
+    let json = serde_json::to_value(event.as_ref()).unwrap();
+    assert_eq!(json["type"], "block");
+    assert_eq!(json["timestamp"], 1_713_571_767);
+    assert!(json["published_at"].is_u64());
+
+    let decoded: ChainEvent = serde_json::from_value(json).unwrap();
+    assert_eq!(decoded.id, event.id);
+    assert_eq!(decoded.data, event.data);
+
+    let transfer = bus.publish(transfer("UNCOMMON•GOODS", "bc1qreceiver"));
+    let json = serde_json::to_value(transfer.as_ref()).unwrap();
+    assert_eq!(json["rune_id"], "840000:1");
+    let decoded: ChainEvent = serde_json::from_value(json).unwrap();
+    assert_eq!(decoded.rune_id(), Some("840000:1"));
+}
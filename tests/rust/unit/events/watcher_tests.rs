@@ -0,0 +1,375 @@
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::services::events::{
+    bus::{ChainEventData, EventBus},
+    watcher::{BlockWatcher, WatcherConfig},
+};
+use crate::services::node::{
+    backend::{create_backend, BackendKind, BlockInfo, NodeBackend},
+    connection::NodeStatus,
+};
+use crate::testing::{FakeNode, FakeTransaction};
+use crate::types::{
+    error::{RuneError, RuneResult},
+    rune::{
+        NetworkType, RuneEntry, RunesTransactionResponse, RuneTransfer, TransactionStatus,
+        TransferType,
+    },
+    runestone::{Edict, Etching, RuneId, Runestone},
+};
+
+/// Chain where block 101 etches a rune and block 102 carries a mint
+struct ChainStub {
+    tip: AtomicU64,
+    /// Fails the next read of the block 102 transactions
+    fail_mint: AtomicBool,
+}
+
+impl ChainStub {
+    fn new(tip: u64) -> Self {
+        Self {
+            tip: AtomicU64::new(tip),
+            fail_mint: AtomicBool::new(false),
+        }
+    }
+}
+
+fn uncommon_goods() -> RuneEntry {
+    RuneEntry {
+        id: "101:1".to_string(),
+        name: "UNCOMMONGOODS".to_string(),
+        spaced_name: "UNCOMMON•GOODS".to_string(),
+        number: 0,
+        etching: "tx101".to_string(),
+        block: 101,
+        divisibility: 0,
+        symbol: None,
+        spacers: 0b1000_0000,
+        premine: 0,
+        terms: None,
+        turbo: false,
+        mints: 0,
+        burned: 0,
+        timestamp: 0,
+        holders: None,
+    }
+}
+
+#[async_trait]
+impl NodeBackend for ChainStub {
+    fn kind(&self) -> BackendKind {
+        BackendKind::Ord
+    }
+
+    async fn health_check(&self) -> RuneResult<NodeStatus> {
+        Err(RuneError::UnsupportedOperation("health_check".to_string()))
+    }
+
+    async fn get_block_height(&self) -> RuneResult<u64> {
+        Ok(self.tip.load(Ordering::SeqCst))
+    }
+
+    async fn get_block_hash(&self, height: u64) -> RuneResult<String> {
+        Ok(format!("hash{}", height))
+    }
+
+    async fn get_block(&self, hash: &str) -> RuneResult<BlockInfo> {
+        let height: u64 = hash.trim_start_matches("hash").parse().unwrap();
+        Ok(BlockInfo {
+            hash: hash.to_string(),
+            height,
+            previous_block_hash: None,
+            timestamp: 1_713_571_767 + height,
+            tx_ids: vec![format!("tx{}", height)],
+        })
+    }
+
+    async fn get_raw_transaction(&self, _tx_id: &str) -> RuneResult<String> {
+        Err(RuneError::UnsupportedOperation("get_raw_transaction".to_string()))
+    }
+
+    async fn get_transaction(&self, _tx_id: &str) -> RuneResult<RunesTransactionResponse> {
+        Err(RuneError::UnsupportedOperation("get_transaction".to_string()))
+    }
+
+    async fn get_block_transactions(&self, hash: &str) -> RuneResult<Vec<RunesTransactionResponse>> {
+        if hash != "hash102" {
+            return Ok(Vec::new());
+        }
+        if self.fail_mint.swap(false, Ordering::SeqCst) {
+            return Err(RuneError::NodeConnectionError("connection reset".to_string()));
+        }
+
+        Ok(vec![RunesTransactionResponse {
+            transaction_id: "tx102".to_string(),
+            runes: vec![RuneTransfer {
+                rune_id: "UNCOMMON•GOODS".to_string(),
+                from_address: String::new(),
+                to_address: "bc1qminter".to_string(),
+                amount: 1,
+                transfer_type: TransferType::Mint,
+                fee: None,
+                metadata: None,
+            }],
+            block_height: Some(102),
+            confirmation_count: 1,
+            timestamp: 0,
+            network_type: NetworkType::Mainnet,
+            status: TransactionStatus::Confirmed,
+        }])
+    }
+
+    async fn get_address_transfers(&self, _address: &str) -> RuneResult<Vec<RuneTransfer>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_rune(&self, rune: &str) -> RuneResult<RuneEntry> {
+        match rune {
+            "UNCOMMON•GOODS" => Ok(uncommon_goods()),
+            _ => Err(RuneError::NotFound(rune.to_string())),
+        }
+    }
+
+    async fn get_block_etchings(&self, height: u64) -> RuneResult<Vec<RuneEntry>> {
+        if height != 101 {
+            return Ok(Vec::new());
+        }
+        Ok(vec![uncommon_goods()])
+    }
+}
+
+fn summary(data: &ChainEventData) -> String {
+    match data {
+        ChainEventData::Block { height, .. } => format!("block {}", height),
+        ChainEventData::Etching { txid, .. } => format!("etching {}", txid),
+        ChainEventData::Mint { txid, .. } => format!("mint {}", txid),
+        ChainEventData::Transfer { txid, .. } => format!("transfer {}", txid),
+        ChainEventData::Confirmation { txid, confirmations, .. } => {
+            format!("confirmation {} {}", txid, confirmations)
+        }
+        ChainEventData::Reorg { fork_height, old_tip_height, .. } => {
+            format!("reorg {} {}", old_tip_height, fork_height)
+        }
+    }
+}
+
+fn replay(bus: &EventBus) -> Vec<String> {
+    bus.subscribe(Some(0))
+        .replay
+        .iter()
+        .map(|event| summary(&event.data))
+        .collect()
+}
+
+fn scanning() -> WatcherConfig {
+    WatcherConfig {
+        scan_transactions: true,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_block_watcher_publishes_chain_events() {
+    let node = Arc::new(ChainStub::new(100));
+    let bus = Arc::new(EventBus::new(64));
+    let watcher = BlockWatcher::new(
+        node.clone(),
+        bus.clone(),
+        WatcherConfig {
+            confirmation_depth: 3,
+            scan_transactions: true,
+            ..Default::default()
+        },
+    );
+
+    // The first poll starts at the tip
+    assert_eq!(watcher.poll().await.unwrap(), 1);
+    assert_eq!(watcher.poll().await.unwrap(), 0);
+
+    node.tip.store(103, Ordering::SeqCst);
+    assert_eq!(watcher.poll().await.unwrap(), 3);
+
+    assert_eq!(
+        replay(&bus),
+        vec![
+            "block 100",
+            "block 101",
+            "etching tx101",
+            "block 102",
+            "mint tx102",
+            "confirmation tx101 2",
+            "block 103",
+            "confirmation tx101 3",
+            "confirmation tx102 2",
+        ]
+    );
+
+    // The mint is tagged with the id of its rune, and comes from a rune index
+    let mint = bus.subscribe(Some(0)).replay.into_iter().find(|event| summary(&event.data) == "mint tx102");
+    let mint = mint.unwrap();
+    assert_eq!(mint.rune_id(), Some("101:1"));
+    assert!(matches!(mint.data, ChainEventData::Mint { verified: true, .. }));
+
+    // tx101 reached the confirmation depth
+    node.tip.store(104, Ordering::SeqCst);
+    watcher.poll().await.unwrap();
+    let last = bus.subscribe(Some(bus.last_event_id().unwrap() - 1)).replay;
+    assert_eq!(summary(&last[0].data), "confirmation tx102 3");
+}
+
+#[tokio::test]
+async fn test_block_watcher_publishes_blocks_only_once_read() {
+    let node = Arc::new(ChainStub::new(101));
+    let bus = Arc::new(EventBus::new(64));
+    let watcher = BlockWatcher::new(node.clone(), bus.clone(), scanning());
+    watcher.poll().await.unwrap();
+
+    node.tip.store(103, Ordering::SeqCst);
+    node.fail_mint.store(true, Ordering::SeqCst);
+    assert!(watcher.poll().await.is_err());
+    assert_eq!(replay(&bus), vec!["block 101", "etching tx101"]);
+
+    // The failed block is read again as a whole
+    assert_eq!(watcher.poll().await.unwrap(), 2);
+    assert_eq!(
+        replay(&bus),
+        vec![
+            "block 101",
+            "etching tx101",
+            "block 102",
+            "mint tx102",
+            "confirmation tx101 2",
+            "block 103",
+            "confirmation tx101 3",
+            "confirmation tx102 2",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_block_watcher_follows_reorgs() {
+    let fake = FakeNode::start().await;
+    let config = fake.config(BackendKind::Bitcoind);
+    let node = create_backend(&config, Arc::new(config.http.build_client(config.timeout).unwrap()));
+    let bus = Arc::new(EventBus::new(64));
+    let watcher = BlockWatcher::new(node, bus.clone(), scanning());
+
+    let etching = Runestone {
+        edicts: vec![Edict { id: RuneId { block: 0, tx: 0 }, amount: 0, output: 1 }],
+        etching: Some(Etching {
+            rune: Some("UNCOMMONGOODS".to_string()),
+            spacers: Some(0b1000_0000),
+            premine: Some(1_000),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let etch = FakeTransaction::new()
+        .spend(&"ab".repeat(32), 0)
+        .runestone(&etching)
+        .pay("bc1qalice", 546);
+    watcher.poll().await.unwrap();
+    let etched = fake.mine(vec![etch.clone()]);
+    fake.mine_empty(1);
+    assert_eq!(watcher.poll().await.unwrap(), 2);
+    let old_tip = fake.tip_hash();
+
+    // Both blocks are replaced, the etching lands in the second new block
+    fake.reorg(2, vec![vec![], vec![etch], vec![]]);
+    assert_eq!(watcher.poll().await.unwrap(), 3);
+
+    let events = bus.subscribe(Some(0)).replay;
+    let summaries: Vec<String> = events.iter().map(|event| summary(&event.data)).collect();
+    let txid = &etched.txids[1];
+    assert_eq!(
+        summaries,
+        vec![
+            "block 0".to_string(),
+            "block 1".to_string(),
+            format!("transfer {}", txid),
+            "block 2".to_string(),
+            format!("confirmation {} 2", txid),
+            "reorg 2 0".to_string(),
+            "block 1".to_string(),
+            "block 2".to_string(),
+            format!("transfer {}", txid),
+            "block 3".to_string(),
+            format!("confirmation {} 2", txid),
+        ]
+    );
+
+    let reorg = events.iter().find(|event| summary(&event.data) == "reorg 2 0").unwrap();
+    assert!(matches!(&reorg.data, ChainEventData::Reorg { old_tip_hash, .. } if *old_tip_hash == old_tip));
+    // The premine moved to the id of the new position
+    let etch_ids: Vec<Option<&str>> = events
+        .iter()
+        .filter(|event| summary(&event.data).starts_with("transfer"))
+        .map(|event| event.rune_id())
+        .collect();
+    assert_eq!(etch_ids, vec![Some("1:1"), Some("2:1")]);
+}
+
+#[tokio::test]
+async fn test_block_watcher_only_scans_transactions_when_asked() {
+    let node = Arc::new(ChainStub::new(101));
+    let bus = Arc::new(EventBus::new(64));
+    let watcher = BlockWatcher::new(node.clone(), bus.clone(), WatcherConfig::default());
+    watcher.poll().await.unwrap();
+
+    node.tip.store(102, Ordering::SeqCst);
+    watcher.poll().await.unwrap();
+    assert_eq!(
+        replay(&bus),
+        vec!["block 101", "etching tx101", "block 102", "confirmation tx101 2"]
+    );
+}
+
+#[tokio::test]
+async fn test_block_watcher_scans_without_txindex() {
+    let fake = FakeNode::start().await;
+    fake.disable_txindex();
+    let config = fake.config(BackendKind::Bitcoind);
+    let node = create_backend(&config, Arc::new(config.http.build_client(config.timeout).unwrap()));
+    let bus = Arc::new(EventBus::new(64));
+    let watcher = BlockWatcher::new(node, bus.clone(), scanning());
+    watcher.poll().await.unwrap();
+
+    let etched = fake.mine(vec![FakeTransaction::new()
+        .spend(&"ab".repeat(32), 0)
+        .runestone(&Runestone {
+            etching: Some(Etching {
+                rune: Some("UNCOMMONGOODS".to_string()),
+                spacers: Some(0b1000_0000),
+                premine: Some(1_000),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .pay("bc1qalice", 546)]);
+    // The edict names a rune etched in an earlier block, read back by its block hash
+    let moved = fake.mine(vec![FakeTransaction::new()
+        .spend(&etched.txids[1], 1)
+        .pay("bc1qbob", 546)
+        .runestone(&Runestone {
+            edicts: vec![Edict { id: RuneId { block: 1, tx: 1 }, amount: 250, output: 0 }],
+            ..Default::default()
+        })]);
+    assert_eq!(watcher.poll().await.unwrap(), 2);
+
+    let transfers: Vec<(String, Option<String>)> = bus
+        .subscribe(Some(0))
+        .replay
+        .iter()
+        .filter(|event| summary(&event.data).starts_with("transfer"))
+        .map(|event| (summary(&event.data), event.rune_id().map(str::to_string)))
+        .collect();
+    assert_eq!(
+        transfers,
+        vec![
+            (format!("transfer {}", etched.txids[1]), Some("1:1".to_string())),
+            (format!("transfer {}", moved.txids[1]), Some("1:1".to_string())),
+        ]
+    );
+}
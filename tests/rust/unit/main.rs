@@ -1,9 +1,69 @@
 use runes_sdk_rust::*;
 
+#[path = "api"]
+mod api_tests {
+    mod admin_tests;
+    mod auth_tests;
+    mod encoding_tests;
+    mod graphql_tests;
+    mod health_tests;
+    mod lifecycle_tests;
+    mod runes_tests;
+    mod stream_tests;
+    mod websocket_tests;
+}
+
+mod auth {
+    mod key_store_tests;
+    mod signing_tests;
+}
+
+mod cache {
+    mod cache_tests;
+}
+
+mod client {
+    use super::*;
+
+    mod websocket_client_tests;
+}
+
+mod events {
+    mod bus_tests;
+    mod watcher_tests;
+}
+
+mod node {
+    mod backend_tests;
+    mod breaker_tests;
+    mod connection_tests;
+    mod esplora_tests;
+    mod fake_node_tests;
+    mod hedge_tests;
+    mod ord_tests;
+    mod pool_tests;
+    mod sync_tests;
+}
+
+mod rate_limit {
+    mod rate_limit_tests;
+}
+
+#[path = "types"]
+mod types_tests {
+    mod rune_tests;
+    mod runestone_tests;
+}
+
+mod webhook {
+    mod webhook_tests;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use mockall::predicate::*;
+    use runes_sdk_rust::api::runes::handlers::validate_tx_id;
+    use runes_sdk_rust::testing::FakeNode;
     use test_case::test_case;
 
     #[test]
@@ -23,17 +83,18 @@ mod tests {
         assert_eq!(transfer.fee, Some(10));
     }
 
-    #[test_case("valid_txid" => true; "when valid transaction id")]
+    #[test_case("4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b" => true; "when valid transaction id")]
     #[test_case("invalid#txid" => false; "when invalid transaction id")]
+    #[test_case("4a5e1e4baab89f3a" => false; "when transaction id is too short")]
     fn test_validate_transaction_id(txid: &str) -> bool {
-        // Transaction ID validation logic would go here
-        txid.chars().all(|c| c.is_ascii_alphanumeric())
+        validate_tx_id(txid).is_ok()
     }
 
     #[tokio::test]
     async fn test_rpc_client() {
-        let client = RpcClient::new("http://localhost:8332", 5000);
+        let node = FakeNode::start().await;
+        let client = RpcClient::new(node.uri(), 5000);
         let result = client.health_check().await;
         assert!(result.is_ok());
     }
-} 
\ No newline at end of file
+}
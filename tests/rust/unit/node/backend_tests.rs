@@ -0,0 +1,292 @@
+use std::sync::Arc;
+use serde_json::json;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use crate::services::node::{
+    backend::{create_backend, BackendKind, NodeBackend},
+    connection::NodeConfig,
+};
+use crate::types::{
+    error::RuneError,
+    rune::{TransactionStatus, TransferType},
+};
+
+fn backend_for(kind: BackendKind, url: String) -> Arc<dyn NodeBackend> {
+    let config = NodeConfig {
+        rpc_url: url,
+        backend: kind,
+        ..Default::default()
+    };
+    let client = config.http.build_client(config.timeout).unwrap();
+    create_backend(&config, Arc::new(client))
+}
+
+fn rpc_result(result: serde_json::Value) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({ "result": result, "error": null, "id": 1 }))
+}
+
+#[tokio::test]
+async fn test_backend_selected_by_config() {
+    for kind in [BackendKind::Bitcoind, BackendKind::Esplora, BackendKind::Ord] {
+        let backend = backend_for(kind, "http://localhost:8332".to_string());
+        assert_eq!(backend.kind(), kind);
+    }
+}
+
+#[tokio::test]
+async fn test_bitcoind_get_transaction() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({ "method": "getrawtransaction" })))
+        .respond_with(rpc_result(json!({
+            "txid": "abc",
+            "blockhash": "000000hash",
+            "confirmations": 3,
+            "time": 1_700_000_000
+        })))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({ "method": "getblockheader" })))
+        .respond_with(rpc_result(json!({ "height": 840_000 })))
+        .mount(&mock_server)
+        .await;
+
+    let backend = backend_for(BackendKind::Bitcoind, mock_server.uri());
+    let tx = backend.get_transaction("abc").await.unwrap();
+
+    assert_eq!(tx.transaction_id, "abc");
+    assert_eq!(tx.block_height, Some(840_000));
+    assert_eq!(tx.confirmation_count, 3);
+    assert_eq!(tx.status, TransactionStatus::Confirmed);
+}
+
+#[tokio::test]
+async fn test_bitcoind_address_lookup_unsupported() {
+    let backend = backend_for(BackendKind::Bitcoind, "http://localhost:8332".to_string());
+    let result = backend.get_address_transfers("bc1qexample").await;
+    assert!(matches!(result, Err(RuneError::UnsupportedOperation(_))));
+}
+
+#[tokio::test]
+async fn test_esplora_get_transaction() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/tx/abc"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "txid": "abc",
+            "version": 2,
+            "locktime": 0,
+            "vin": [],
+            "vout": [],
+            "size": 200,
+            "weight": 800,
+            "fee": 1000,
+            "status": {
+                "confirmed": true,
+                "block_height": 100,
+                "block_hash": "000000hash",
+                "block_time": 1_700_000_000
+            }
+        })))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/blocks/tip/height"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("105"))
+        .mount(&mock_server)
+        .await;
+
+    let backend = backend_for(BackendKind::Esplora, mock_server.uri());
+    let tx = backend.get_transaction("abc").await.unwrap();
+
+    assert_eq!(tx.block_height, Some(100));
+    assert_eq!(tx.confirmation_count, 6);
+    assert_eq!(tx.timestamp, 1_700_000_000);
+}
+
+#[tokio::test]
+async fn test_esplora_not_found() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/tx/missing"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("Transaction not found"))
+        .mount(&mock_server)
+        .await;
+
+    let backend = backend_for(BackendKind::Esplora, mock_server.uri());
+    let result = backend.get_transaction("missing").await;
+    assert!(matches!(result, Err(RuneError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_ord_get_transaction() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/tx/abc"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "chain": "mainnet",
+            "etching": null,
+            "inscription_count": 0,
+            "transaction": {
+                "version": 2,
+                "lock_time": 0,
+                "input": [],
+                "output": [{ "value": 546, "script_pubkey": "5120aa" }]
+            },
+            "txid": "abc"
+        })))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/output/abc:0"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "address": "bc1pexample",
+            "confirmations": 1,
+            "runes": {
+                "UNCOMMON•GOODS": { "amount": 2500, "divisibility": 0, "symbol": "⧉" }
+            },
+            "spent": false,
+            "value": 546
+        })))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/blockheight"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("840000"))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/r/blockinfo/840000"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "hash": "000000hash",
+            "height": 840_000,
+            "previous_blockhash": "000000prev",
+            "timestamp": 1_713_571_767
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let backend = backend_for(BackendKind::Ord, mock_server.uri());
+    let tx = backend.get_transaction("abc").await.unwrap();
+
+    assert_eq!(tx.block_height, Some(840_000));
+    assert_eq!(tx.timestamp, 1_713_571_767);
+    assert_eq!(tx.runes.len(), 1);
+    assert_eq!(tx.runes[0].rune_id, "UNCOMMON•GOODS");
+    assert_eq!(tx.runes[0].to_address, "bc1pexample");
+    assert_eq!(tx.runes[0].amount, 2500);
+    assert_eq!(tx.runes[0].transfer_type, TransferType::Transfer);
+}
+
+#[tokio::test]
+async fn test_ord_get_etching_transaction() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/tx/abc"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "chain": "mainnet",
+            "etching": "UNCOMMON•GOODS",
+            "inscription_count": 0,
+            "transaction": {
+                "version": 2,
+                "lock_time": 0,
+                "input": [],
+                "output": [
+                    { "value": 546, "script_pubkey": "5120aa" },
+                    { "value": 0, "script_pubkey": "6a5d00" },
+                    { "value": 546, "script_pubkey": "5120bb" }
+                ]
+            },
+            "txid": "abc"
+        })))
+        .mount(&mock_server)
+        .await;
+
+    // ord answers `null` rather than `{}` for the OP_RETURN output
+    for (vout, runes) in [
+        (0, json!({ "UNCOMMON•GOODS": { "amount": 1000, "divisibility": 0, "symbol": "⧉" } })),
+        (1, json!(null)),
+        (2, json!({ "DOG•GO•TO•THE•MOON": { "amount": 7, "divisibility": 5, "symbol": "🐕" } })),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(format!("/output/abc:{}", vout)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "address": if vout == 1 { json!(null) } else { json!("bc1pexample") },
+                "confirmations": 0,
+                "runes": runes,
+                "spent": false,
+                "value": 546
+            })))
+            .mount(&mock_server)
+            .await;
+    }
+
+    let backend = backend_for(BackendKind::Ord, mock_server.uri());
+    let tx = backend.get_transaction("abc").await.unwrap();
+
+    assert_eq!(tx.status, TransactionStatus::Pending);
+    assert_eq!(tx.runes.len(), 2);
+    assert_eq!(tx.runes[0].rune_id, "UNCOMMON•GOODS");
+    assert_eq!(tx.runes[0].transfer_type, TransferType::Etch);
+    assert_eq!(tx.runes[1].rune_id, "DOG•GO•TO•THE•MOON");
+    assert_eq!(tx.runes[1].transfer_type, TransferType::Transfer);
+    assert_eq!(tx.runes[1].metadata.as_ref().unwrap()["vout"], 2);
+}
+
+#[tokio::test]
+async fn test_ord_address_outputs() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/address/bc1pexample"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "outputs": ["abc:0", "abc:1", "def:0"],
+            "inscriptions": [],
+            "sat_balance": 1638
+        })))
+        .mount(&mock_server)
+        .await;
+
+    for (outpoint, spent, runes) in [
+        ("abc:0", false, json!({ "UNCOMMON•GOODS": { "amount": 2500, "divisibility": 0, "symbol": "⧉" } })),
+        ("abc:1", false, json!({})),
+        ("def:0", true, json!({ "UNCOMMON•GOODS": { "amount": 1, "divisibility": 0, "symbol": "⧉" } })),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(format!("/output/{}", outpoint)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "address": "bc1pexample",
+                "confirmations": 3,
+                "outpoint": outpoint,
+                "runes": runes,
+                "spent": spent,
+                "value": 546
+            })))
+            .mount(&mock_server)
+            .await;
+    }
+
+    // Outputs without runes and spent ones are left out
+    let backend = backend_for(BackendKind::Ord, mock_server.uri());
+    let outputs = backend.get_address_outputs("bc1pexample").await.unwrap();
+    assert_eq!(outputs.len(), 1);
+    assert_eq!(outputs[0].outpoint, "abc:0");
+
+    let esplora = backend_for(BackendKind::Esplora, mock_server.uri());
+    assert!(matches!(
+        esplora.get_output("abc:0").await,
+        Err(RuneError::UnsupportedOperation(_))
+    ));
+}
@@ -0,0 +1,127 @@
+use std::sync::Arc;
+use std::time::Duration;
+use metrics::{Counter, Gauge, Histogram};
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use crate::services::node::{
+    backend::NodeBackend,
+    breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitState},
+    connection::{MetricsCollector, NodeConfig, NodeConnection},
+};
+use crate::types::error::{RuneError, RuneResult};
+
+fn test_config() -> CircuitBreakerConfig {
+    CircuitBreakerConfig {
+        window_size: 4,
+        minimum_calls: 4,
+        failure_rate_threshold: 0.5,
+        open_duration: Duration::from_millis(50),
+        half_open_probes: 1,
+    }
+}
+
+async fn fail(breaker: &CircuitBreaker) -> RuneResult<()> {
+    breaker
+        .call(async { Err(RuneError::NodeConnectionError("connection refused".to_string())) })
+        .await
+}
+
+async fn succeed(breaker: &CircuitBreaker) -> RuneResult<()> {
+    breaker.call(async { Ok(()) }).await
+}
+
+#[tokio::test]
+async fn test_circuit_opens_at_failure_rate() {
+    let breaker = CircuitBreaker::new("test-open", test_config());
+
+    succeed(&breaker).await.unwrap();
+    succeed(&breaker).await.unwrap();
+    let _ = fail(&breaker).await;
+    assert_eq!(breaker.state(), CircuitState::Closed);
+
+    let _ = fail(&breaker).await;
+    assert_eq!(breaker.state(), CircuitState::Open);
+
+    // Open circuit rejects without running the call
+    let result: RuneResult<()> = breaker
+        .call(async { panic!("call must not run while the circuit is open") })
+        .await;
+    assert!(matches!(result, Err(RuneError::NodeConnectionError(_))));
+}
+
+#[tokio::test]
+async fn test_request_errors_do_not_trip_circuit() {
+    let breaker = CircuitBreaker::new("test-request-errors", test_config());
+
+    for _ in 0..4 {
+        let result: RuneResult<()> = breaker
+            .call(async { Err(RuneError::NotFound("tx not found".to_string())) })
+            .await;
+        assert!(matches!(result, Err(RuneError::NotFound(_))));
+    }
+
+    assert_eq!(breaker.state(), CircuitState::Closed);
+}
+
+#[tokio::test]
+async fn test_half_open_probe_closes_or_reopens() {
+    let breaker = CircuitBreaker::new("test-half-open", test_config());
+    for _ in 0..4 {
+        let _ = fail(&breaker).await;
+    }
+    assert_eq!(breaker.state(), CircuitState::Open);
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert_eq!(breaker.state(), CircuitState::HalfOpen);
+
+    // A failed probe reopens the circuit
+    let _ = fail(&breaker).await;
+    assert_eq!(breaker.state(), CircuitState::Open);
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    let permit = breaker.try_acquire().unwrap();
+    // Only one probe is allowed in flight
+    assert!(breaker.try_acquire().is_err());
+    permit.record(true);
+
+    assert_eq!(breaker.state(), CircuitState::Closed);
+}
+
+#[tokio::test]
+async fn test_node_connection_fails_fast_when_open() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(4)
+        .mount(&mock_server)
+        .await;
+
+    let config = NodeConfig {
+        rpc_url: mock_server.uri(),
+        circuit_breaker: Some(CircuitBreakerConfig {
+            open_duration: Duration::from_secs(60),
+            ..test_config()
+        }),
+        ..Default::default()
+    };
+    let metrics = Arc::new(MetricsCollector {
+        transaction_counter: Counter::noop(),
+        error_counter: Counter::noop(),
+        response_time: Histogram::noop(),
+        active_connections: Gauge::noop(),
+    });
+    let connection = NodeConnection::new(config, metrics);
+
+    for _ in 0..4 {
+        assert!(connection.get_block_height().await.is_err());
+    }
+
+    let status = connection.circuit_status().unwrap();
+    assert_eq!(status.state, CircuitState::Open);
+
+    // The fifth call never reaches the node, `expect(4)` verifies it on drop
+    let result = connection.get_block_height().await;
+    assert!(matches!(result, Err(RuneError::NodeConnectionError(_))));
+}
@@ -1,25 +1,40 @@
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use metrics::{Counter, Gauge, Histogram};
-use mockall::predicate::*;
-use crate::services::node::connection::{NodeConfig, NodeConnection, MetricsCollector, NodeStatus};
+use crate::services::node::backend::{BackendKind, NodeBackend};
+use crate::services::node::connection::{NodeConfig, NodeConnection, MetricsCollector};
+use crate::testing::{FakeNode, NodeFailure};
+use crate::types::error::RuneError;
+
+fn test_metrics() -> Arc<MetricsCollector> {
+    Arc::new(MetricsCollector {
+        transaction_counter: Counter::noop(),
+        error_counter: Counter::noop(),
+        response_time: Histogram::noop(),
+        active_connections: Gauge::noop(),
+    })
+}
+
+async fn running_node() -> FakeNode {
+    let node = FakeNode::start().await;
+    node.mine_empty(3);
+    node
+}
 
 #[tokio::test]
 async fn test_successful_connection() {
+    let fake = running_node().await;
     let config = NodeConfig {
-        rpc_url: "http://localhost:8332".to_string(),
+        rpc_url: fake.uri(),
         username: None,
         password: None,
         timeout: Duration::from_secs(30),
         max_retries: 3,
+        ..Default::default()
     };
 
-    let metrics = Arc::new(MetricsCollector {
-        transaction_counter: Counter::new("test_tx_counter"),
-        error_counter: Counter::new("test_error_counter"),
-        response_time: Histogram::new("test_response_time"),
-        active_connections: Gauge::new("test_active_connections"),
-    });
+    let metrics = test_metrics();
 
     let node = NodeConnection::new(config, metrics);
     let result = node.connect().await;
@@ -28,20 +43,17 @@ async fn test_successful_connection() {
 
 #[tokio::test]
 async fn test_health_check() {
+    let fake = running_node().await;
     let config = NodeConfig {
-        rpc_url: "http://localhost:8332".to_string(),
+        rpc_url: fake.uri(),
         username: None,
         password: None,
         timeout: Duration::from_secs(30),
         max_retries: 3,
+        ..Default::default()
     };
 
-    let metrics = Arc::new(MetricsCollector {
-        transaction_counter: Counter::new("test_tx_counter"),
-        error_counter: Counter::new("test_error_counter"),
-        response_time: Histogram::new("test_response_time"),
-        active_connections: Gauge::new("test_active_connections"),
-    });
+    let metrics = test_metrics();
 
     let node = NodeConnection::new(config, metrics);
     let result = node.health_check().await;
@@ -59,20 +71,17 @@ async fn test_health_check() {
 
 #[tokio::test]
 async fn test_get_block_height() {
+    let fake = running_node().await;
     let config = NodeConfig {
-        rpc_url: "http://localhost:8332".to_string(),
+        rpc_url: fake.uri(),
         username: None,
         password: None,
         timeout: Duration::from_secs(30),
         max_retries: 3,
+        ..Default::default()
     };
 
-    let metrics = Arc::new(MetricsCollector {
-        transaction_counter: Counter::new("test_tx_counter"),
-        error_counter: Counter::new("test_error_counter"),
-        response_time: Histogram::new("test_response_time"),
-        active_connections: Gauge::new("test_active_connections"),
-    });
+    let metrics = test_metrics();
 
     let node = NodeConnection::new(config, metrics);
     let result = node.get_block_height().await;
@@ -89,14 +98,10 @@ async fn test_connection_retry() {
         password: None,
         timeout: Duration::from_secs(1),
         max_retries: 2,
+        ..Default::default()
     };
 
-    let metrics = Arc::new(MetricsCollector {
-        transaction_counter: Counter::new("test_tx_counter"),
-        error_counter: Counter::new("test_error_counter"),
-        response_time: Histogram::new("test_response_time"),
-        active_connections: Gauge::new("test_active_connections"),
-    });
+    let metrics = test_metrics();
 
     let node = NodeConnection::new(config, metrics);
     let result = node.connect().await;
@@ -106,4 +111,67 @@ async fn test_connection_retry() {
         Err(e) => assert!(e.to_string().contains("Max retries exceeded")),
         _ => panic!("Expected error"),
     }
-} 
\ No newline at end of file
+} 
+
+#[tokio::test]
+async fn test_connect_waits_between_attempts_only() {
+    let fake = running_node().await;
+    fake.fail(NodeFailure::Unavailable);
+
+    let active_connections = Arc::new(AtomicU64::new(0));
+    let metrics = Arc::new(MetricsCollector {
+        transaction_counter: Counter::noop(),
+        error_counter: Counter::noop(),
+        response_time: Histogram::noop(),
+        active_connections: Gauge::from_arc(active_connections.clone()),
+    });
+    let config = NodeConfig {
+        rpc_url: fake.uri(),
+        max_retries: 2,
+        circuit_breaker: None,
+        ..Default::default()
+    };
+    let node = NodeConnection::new(config, metrics);
+
+    // One second between the two attempts, no wait after the last one
+    let started = Instant::now();
+    let error = node.connect().await.unwrap_err();
+    let elapsed = started.elapsed();
+    assert!(elapsed >= Duration::from_secs(1), "{:?}", elapsed);
+    assert!(elapsed < Duration::from_secs(2), "{:?}", elapsed);
+
+    assert!(matches!(error, RuneError::NodeConnectionError(_)), "{:?}", error);
+    assert!(error.to_string().contains("Max retries exceeded: "), "{}", error);
+    assert_eq!(f64::from_bits(active_connections.load(Ordering::SeqCst)), 0.0);
+
+    fake.recover();
+    node.connect().await.unwrap();
+    assert_eq!(f64::from_bits(active_connections.load(Ordering::SeqCst)), 1.0);
+}
+
+#[tokio::test]
+async fn test_basic_auth_credentials() {
+    use wiremock::matchers::{basic_auth, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/blocks/tip/height"))
+        .and(basic_auth("rpcuser", "rpcpassword"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("840000"))
+        .mount(&mock_server)
+        .await;
+
+    let config = NodeConfig {
+        rpc_url: mock_server.uri(),
+        backend: BackendKind::Esplora,
+        username: Some("rpcuser".to_string()),
+        password: Some("rpcpassword".to_string()),
+        ..Default::default()
+    };
+
+    let metrics = test_metrics();
+
+    let node = NodeConnection::new(config, metrics);
+    assert_eq!(node.get_block_height().await.unwrap(), 840000);
+}
@@ -0,0 +1,232 @@
+use std::sync::Arc;
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use crate::services::node::esplora::EsploraClient;
+use crate::types::error::RuneError;
+use crate::{RpcAuth, RpcCredentials};
+
+fn esplora_client(url: &str) -> EsploraClient {
+    EsploraClient::new(url, Arc::new(reqwest::Client::new()), RpcCredentials::new(RpcAuth::None))
+}
+
+fn esplora_tx(txid: &str) -> serde_json::Value {
+    json!({
+        "txid": txid,
+        "version": 2,
+        "locktime": 0,
+        "vin": [{
+            "txid": "prev",
+            "vout": 1,
+            "prevout": {
+                "scriptpubkey": "0014aa",
+                "scriptpubkey_type": "v0_p2wpkh",
+                "scriptpubkey_address": "bc1qsender",
+                "value": 20_000
+            },
+            "is_coinbase": false,
+            "sequence": 4_294_967_293u32
+        }],
+        "vout": [{
+            "scriptpubkey": "6a5d0414011400",
+            "scriptpubkey_type": "op_return",
+            "value": 0
+        }, {
+            "scriptpubkey": "5120bb",
+            "scriptpubkey_type": "v1_p2tr",
+            "scriptpubkey_address": "bc1preceiver",
+            "value": 546
+        }],
+        "size": 250,
+        "weight": 670,
+        "fee": 1_200,
+        "status": {
+            "confirmed": true,
+            "block_height": 840_000,
+            "block_hash": "000000hash",
+            "block_time": 1_713_571_767
+        }
+    })
+}
+
+#[tokio::test]
+async fn test_esplora_transaction_endpoints() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/tx/abc"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(esplora_tx("abc")))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/tx/abc/hex"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("0200000001\n"))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/tx/abc/status"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "confirmed": false })))
+        .mount(&mock_server)
+        .await;
+
+    let client = esplora_client(&mock_server.uri());
+
+    let tx = client.tx("abc").await.unwrap();
+    assert_eq!(tx.fee, 1_200);
+    assert_eq!(tx.vin[0].prevout.as_ref().unwrap().value, 20_000);
+    assert_eq!(tx.vout[0].scriptpubkey_address, None);
+    assert_eq!(tx.vout[1].scriptpubkey_address.as_deref(), Some("bc1preceiver"));
+
+    assert_eq!(client.tx_hex("abc").await.unwrap(), "0200000001");
+
+    let status = client.tx_status("abc").await.unwrap();
+    assert!(!status.confirmed);
+    assert_eq!(status.block_height, None);
+}
+
+#[tokio::test]
+async fn test_esplora_address_endpoints() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/address/bc1preceiver/utxo"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+            "txid": "abc",
+            "vout": 1,
+            "value": 546,
+            "status": { "confirmed": true, "block_height": 840_000 }
+        }])))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/address/bc1preceiver/txs"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([esplora_tx("abc")])))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/address/bc1preceiver/txs/chain/abc"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([esplora_tx("older")])))
+        .mount(&mock_server)
+        .await;
+
+    let client = esplora_client(&mock_server.uri());
+
+    let utxos = client.address_utxos("bc1preceiver").await.unwrap();
+    assert_eq!(utxos[0].vout, 1);
+    assert_eq!(utxos[0].status.block_height, Some(840_000));
+
+    let first_page = client.address_txs("bc1preceiver").await.unwrap();
+    let last_seen = first_page.last().map(|tx| tx.txid.as_str());
+    let next_page = client.address_txs_chain("bc1preceiver", last_seen).await.unwrap();
+    assert_eq!(next_page[0].txid, "older");
+}
+
+#[tokio::test]
+async fn test_esplora_block_txs_paging() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/block/000000hash/txs/25"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([esplora_tx("abc")])))
+        .mount(&mock_server)
+        .await;
+
+    let client = esplora_client(&mock_server.uri());
+
+    let txs = client.block_txs("000000hash", 25).await.unwrap();
+    assert_eq!(txs.len(), 1);
+
+    let result = client.block_txs("000000hash", 10).await;
+    assert!(matches!(result, Err(RuneError::InvalidRequest(_))));
+}
+
+#[tokio::test]
+async fn test_esplora_mempool_and_fees() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/mempool"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "count": 8_134,
+            "vsize": 3_444_604,
+            "total_fee": 29_204_625,
+            "fee_histogram": [[53.01, 102_131], [38.56, 110_990]]
+        })))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/fee-estimates"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "1": 87.882,
+            "6": 68.285,
+            "144": 1.027
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let client = esplora_client(&mock_server.uri());
+
+    let mempool = client.mempool().await.unwrap();
+    assert_eq!(mempool.count, 8_134);
+    assert_eq!(mempool.fee_histogram[0], (53.01, 102_131));
+
+    let fees = client.fee_estimates().await.unwrap();
+    assert_eq!(fees.keys().copied().collect::<Vec<_>>(), vec![1, 6, 144]);
+    assert_eq!(fees[&6], 68.285);
+}
+
+#[tokio::test]
+async fn test_esplora_error_mapping() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/address/invalid/utxo"))
+        .respond_with(ResponseTemplate::new(400).set_body_string("Invalid Bitcoin address"))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/mempool"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&mock_server)
+        .await;
+
+    let client = esplora_client(&mock_server.uri());
+
+    let result = client.address_utxos("invalid").await;
+    assert!(matches!(result, Err(RuneError::InvalidRequest(msg)) if msg == "Invalid Bitcoin address"));
+    assert!(matches!(client.tx_status("missing").await, Err(RuneError::NotFound(_))));
+    assert!(matches!(client.mempool().await, Err(RuneError::NodeResponseError(_))));
+}
+
+#[tokio::test]
+async fn test_max_concurrent_requests() {
+    use std::time::{Duration, Instant};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/blocks/tip/height"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("840000").set_delay(Duration::from_millis(200)))
+        .mount(&mock_server)
+        .await;
+
+    let client = esplora_client(&mock_server.uri()).with_max_concurrent_requests(Some(1));
+    let started = Instant::now();
+    let (first, second) = tokio::join!(client.tip_height(), client.tip_height());
+    assert_eq!(first.unwrap(), 840_000);
+    assert_eq!(second.unwrap(), 840_000);
+    // One request at a time, so the second waits for the first
+    assert!(started.elapsed() >= Duration::from_millis(400));
+
+    let unlimited = esplora_client(&mock_server.uri());
+    let started = Instant::now();
+    let (first, second) = tokio::join!(unlimited.tip_height(), unlimited.tip_height());
+    assert!(first.is_ok() && second.is_ok());
+    assert!(started.elapsed() < Duration::from_millis(400));
+}
@@ -0,0 +1,217 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::services::node::{
+    backend::{create_backend, BackendKind, NodeBackend},
+    esplora::EsploraClient,
+};
+use crate::testing::{FakeNode, FakeTransaction, NodeFailure};
+use crate::types::{
+    error::RuneError,
+    rune::{TransactionStatus, TransferType},
+    runestone::{Edict, Etching, EtchingTerms, RuneId, Runestone},
+};
+use crate::{RpcAuth, RpcCredentials};
+
+fn backend(node: &FakeNode, kind: BackendKind) -> Arc<dyn NodeBackend> {
+    let config = node.config(kind);
+    let client = config.http.build_client(config.timeout).unwrap();
+    create_backend(&config, Arc::new(client))
+}
+
+fn esplora(node: &FakeNode) -> EsploraClient {
+    EsploraClient::new(&node.uri(), Arc::new(reqwest::Client::new()), RpcCredentials::new(RpcAuth::None))
+}
+
+#[tokio::test]
+async fn test_both_backends_see_the_same_chain() {
+    let node = FakeNode::start().await;
+    let block = node.mine(vec![FakeTransaction::new().pay("bc1qalice", 10_000)]);
+    node.mine_empty(2);
+
+    for kind in [BackendKind::Bitcoind, BackendKind::Esplora] {
+        let backend = backend(&node, kind);
+        assert_eq!(backend.get_block_height().await.unwrap(), 3);
+        assert_eq!(backend.get_block_hash(1).await.unwrap(), block.hash);
+
+        let info = backend.get_block(&block.hash).await.unwrap();
+        assert_eq!(info.tx_ids, block.txids);
+
+        let tx = backend.get_transaction(&block.txids[1]).await.unwrap();
+        assert_eq!(tx.block_height, Some(1));
+        assert_eq!(tx.confirmation_count, 3);
+        assert_eq!(tx.status, TransactionStatus::Confirmed);
+    }
+}
+
+#[tokio::test]
+async fn test_runestone_is_served_in_raw_transaction() {
+    let node = FakeNode::start().await;
+    let runestone = Runestone {
+        edicts: vec![Edict {
+            id: RuneId { block: 840_000, tx: 1 },
+            amount: 500,
+            output: 1,
+        }],
+        ..Default::default()
+    };
+    let tx = FakeTransaction::new()
+        .spend(&"ab".repeat(32), 0)
+        .runestone(&runestone)
+        .pay("bc1qbob", 546);
+    let txid = node.submit(tx);
+
+    let backend = backend(&node, BackendKind::Bitcoind);
+    let hex = backend.get_raw_transaction(&txid).await.unwrap();
+    let script: String = runestone
+        .encipher()
+        .unwrap()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    assert!(hex.contains(&script));
+
+    let pending = backend.get_transaction(&txid).await.unwrap();
+    assert_eq!(pending.status, TransactionStatus::Pending);
+}
+
+#[tokio::test]
+async fn test_runestones_are_decoded() {
+    let node = FakeNode::start().await;
+    let etching = Runestone {
+        edicts: vec![Edict { id: RuneId { block: 0, tx: 0 }, amount: 400, output: 2 }],
+        etching: Some(Etching {
+            rune: Some("UNCOMMONGOODS".to_string()),
+            spacers: Some(0b1000_0000),
+            symbol: Some('⧉'),
+            premine: Some(1_000),
+            terms: Some(EtchingTerms { amount: Some(100), ..Default::default() }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let etched = node.mine(vec![FakeTransaction::new()
+        .spend(&"ab".repeat(32), 0)
+        .runestone(&etching)
+        .pay("bc1qalice", 546)
+        .pay("bc1qbob", 546)]);
+    let id = RuneId { block: 1, tx: 1 };
+
+    let mint = FakeTransaction::new()
+        .spend(&"cd".repeat(32), 0)
+        .runestone(&Runestone { mint: Some(id), ..Default::default() })
+        .pay("bc1qcarol", 546);
+    let transfer = FakeTransaction::new()
+        .spend(&etched.txids[1], 1)
+        .pay("bc1qdave", 546)
+        .runestone(&Runestone {
+            edicts: vec![Edict { id, amount: 50, output: 0 }],
+            ..Default::default()
+        });
+    let block = node.mine(vec![mint, transfer]);
+
+    for kind in [BackendKind::Bitcoind, BackendKind::Esplora] {
+        let backend = backend(&node, kind);
+        let summary = |tx: crate::types::rune::RunesTransactionResponse| {
+            tx.runes
+                .into_iter()
+                .map(|transfer| {
+                    let metadata = transfer.metadata.unwrap();
+                    assert_eq!(metadata["id"], "1:1");
+                    assert_eq!(metadata["symbol"], "⧉");
+                    (transfer.rune_id, transfer.to_address, transfer.amount, transfer.transfer_type)
+                })
+                .collect::<Vec<_>>()
+        };
+        let rune = "UNCOMMON•GOODS".to_string();
+
+        let etch = backend.get_transaction(&etched.txids[1]).await.unwrap();
+        assert_eq!(
+            summary(etch),
+            vec![
+                (rune.clone(), "bc1qbob".to_string(), 400, TransferType::Etch),
+                (rune.clone(), "bc1qalice".to_string(), 600, TransferType::Etch),
+            ],
+            "{:?}",
+            kind
+        );
+
+        let mint = backend.get_transaction(&block.txids[1]).await.unwrap();
+        assert_eq!(summary(mint), vec![(rune.clone(), "bc1qcarol".to_string(), 100, TransferType::Mint)]);
+
+        let transfer = backend.get_transaction(&block.txids[2]).await.unwrap();
+        assert_eq!(summary(transfer), vec![(rune.clone(), "bc1qdave".to_string(), 50, TransferType::Transfer)]);
+    }
+}
+
+#[tokio::test]
+async fn test_mempool_and_utxos() {
+    let node = FakeNode::start().await;
+    let funding = node.mine(vec![FakeTransaction::new().pay("bc1qalice", 10_000)]);
+    let payment = node.submit(
+        FakeTransaction::new()
+            .spend(&funding.txids[1], 0)
+            .pay("bc1qbob", 9_000),
+    );
+
+    let client = esplora(&node);
+    assert!(client.address_utxos("bc1qalice").await.unwrap().is_empty());
+
+    let utxos = client.address_utxos("bc1qbob").await.unwrap();
+    assert_eq!(utxos[0].txid, payment);
+    assert!(!utxos[0].status.confirmed);
+
+    let mempool = client.mempool().await.unwrap();
+    assert_eq!(mempool.count, 1);
+    assert_eq!(mempool.total_fee, 1_000);
+
+    let alice = client.address_txs("bc1qalice").await.unwrap();
+    assert_eq!(alice.len(), 2);
+    assert_eq!(alice[0].txid, payment);
+
+    node.mine(vec![]);
+    assert!(node.mempool().is_empty());
+    assert!(client.tx_status(&payment).await.unwrap().confirmed);
+}
+
+#[tokio::test]
+async fn test_reorg_returns_transactions_to_mempool() {
+    let node = FakeNode::start().await;
+    let block = node.mine(vec![FakeTransaction::new().pay("bc1qalice", 10_000)]);
+    let txid = block.txids[1].clone();
+    let old_tip = node.tip_hash();
+
+    let replacement = node.reorg(1, vec![vec![], vec![]]);
+    assert_eq!(node.height(), 2);
+    assert_ne!(replacement[0].hash, old_tip);
+    assert_eq!(node.mempool(), vec![txid.clone()]);
+
+    let backend = backend(&node, BackendKind::Bitcoind);
+    let tx = backend.get_transaction(&txid).await.unwrap();
+    assert_eq!(tx.status, TransactionStatus::Pending);
+
+    let stale = backend.get_block(&old_tip).await;
+    assert!(matches!(stale, Err(RuneError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_simulated_failures() {
+    let node = FakeNode::start().await;
+    let bitcoind = backend(&node, BackendKind::Bitcoind);
+    let esplora = backend(&node, BackendKind::Esplora);
+
+    node.fail(NodeFailure::WarmingUp);
+    assert!(matches!(bitcoind.get_block_height().await, Err(RuneError::NodeWarmingUp(_))));
+
+    node.fail(NodeFailure::Unavailable);
+    assert!(matches!(bitcoind.get_block_height().await, Err(RuneError::NodeConnectionError(_))));
+    assert!(matches!(esplora.get_block_height().await, Err(RuneError::NodeResponseError(_))));
+
+    node.fail(NodeFailure::Delay(Duration::from_millis(100)));
+    let started = std::time::Instant::now();
+    assert_eq!(esplora.get_block_height().await.unwrap(), 0);
+    assert!(started.elapsed() >= Duration::from_millis(100));
+
+    node.recover();
+    assert_eq!(bitcoind.get_block_height().await.unwrap(), 0);
+}
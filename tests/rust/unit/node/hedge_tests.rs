@@ -0,0 +1,148 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use async_trait::async_trait;
+
+use crate::services::metrics::NODE_REQUEST_DURATION_SECONDS;
+use crate::services::node::{
+    backend::{unsupported, BackendKind, BlockInfo, NodeBackend},
+    connection::NodeStatus,
+    hedge::{HedgeConfig, HedgedBackend},
+};
+use crate::types::{
+    error::RuneResult,
+    rune::{RunesTransactionResponse, RuneTransfer},
+};
+
+/// Answers raw transaction lookups with its own name after a fixed delay
+struct SlowBackend {
+    name: &'static str,
+    delay: Duration,
+    calls: AtomicUsize,
+}
+
+impl SlowBackend {
+    fn new(name: &'static str, delay_ms: u64) -> Arc<Self> {
+        Arc::new(Self {
+            name,
+            delay: Duration::from_millis(delay_ms),
+            calls: AtomicUsize::new(0),
+        })
+    }
+}
+
+#[async_trait]
+impl NodeBackend for SlowBackend {
+    fn kind(&self) -> BackendKind {
+        BackendKind::Bitcoind
+    }
+
+    async fn health_check(&self) -> RuneResult<NodeStatus> {
+        Err(unsupported(self.kind(), "Health check"))
+    }
+
+    async fn get_block_height(&self) -> RuneResult<u64> {
+        Err(unsupported(self.kind(), "Block height"))
+    }
+
+    async fn get_block_hash(&self, _height: u64) -> RuneResult<String> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(self.delay).await;
+        Ok(self.name.to_string())
+    }
+
+    async fn get_block(&self, _hash: &str) -> RuneResult<BlockInfo> {
+        Err(unsupported(self.kind(), "Block lookup"))
+    }
+
+    async fn get_raw_transaction(&self, _tx_id: &str) -> RuneResult<String> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(self.delay).await;
+        Ok(self.name.to_string())
+    }
+
+    async fn get_transaction(&self, _tx_id: &str) -> RuneResult<RunesTransactionResponse> {
+        Err(unsupported(self.kind(), "Transaction lookup"))
+    }
+
+    async fn get_address_transfers(&self, _address: &str) -> RuneResult<Vec<RuneTransfer>> {
+        Err(unsupported(self.kind(), "Address lookup"))
+    }
+}
+
+fn hedge_config() -> HedgeConfig {
+    HedgeConfig {
+        min_samples: u64::MAX,
+        default_delay: Duration::from_millis(20),
+        ..Default::default()
+    }
+    .hedge("get_raw_transaction")
+}
+
+#[tokio::test]
+async fn test_hedge_answers_when_primary_is_slow() {
+    let primary = SlowBackend::new("primary", 500);
+    let secondary = SlowBackend::new("secondary", 0);
+
+    let backend = HedgedBackend::new(primary.clone(), secondary.clone(), hedge_config());
+    let answer = backend.get_raw_transaction("abc").await.unwrap();
+
+    assert_eq!(answer, "secondary");
+    assert_eq!(primary.calls.load(Ordering::SeqCst), 1);
+    assert_eq!(secondary.calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_no_hedge_when_primary_is_fast() {
+    let primary = SlowBackend::new("primary", 0);
+    let secondary = SlowBackend::new("secondary", 0);
+
+    let backend = HedgedBackend::new(primary, secondary.clone(), hedge_config());
+    let answer = backend.get_raw_transaction("abc").await.unwrap();
+
+    assert_eq!(answer, "primary");
+    assert_eq!(secondary.calls.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn test_methods_without_opt_in_are_not_hedged() {
+    let primary = SlowBackend::new("primary", 100);
+    let secondary = SlowBackend::new("secondary", 0);
+
+    let backend = HedgedBackend::new(primary, secondary.clone(), hedge_config());
+    let answer = backend.get_block_hash(1).await.unwrap();
+
+    assert_eq!(answer, "primary");
+    assert_eq!(secondary.calls.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn test_hedge_delay_follows_latency_percentile() {
+    let histogram = NODE_REQUEST_DURATION_SECONDS.with_label_values(&["hedge_delay_test"]);
+    for _ in 0..90 {
+        histogram.observe(0.004);
+    }
+    for _ in 0..10 {
+        histogram.observe(0.8);
+    }
+
+    let primary = SlowBackend::new("primary", 0);
+    let secondary = SlowBackend::new("secondary", 0);
+    let config = HedgeConfig {
+        min_samples: 100,
+        ..Default::default()
+    };
+
+    let p90 = HedgedBackend::new(primary.clone(), secondary.clone(), HedgeConfig {
+        percentile: 0.9,
+        ..config.clone()
+    });
+    let p95 = HedgedBackend::new(primary, secondary, HedgeConfig {
+        percentile: 0.95,
+        ..config
+    });
+
+    // Bucket upper bounds: 0.005 for the fast calls, 1.0 for the slow ones
+    assert_eq!(p90.hedge_delay("hedge_delay_test"), Duration::from_millis(10));
+    assert_eq!(p95.hedge_delay("hedge_delay_test"), Duration::from_secs(1));
+}
@@ -0,0 +1,189 @@
+use std::sync::Arc;
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use crate::services::node::ord::OrdClient;
+use crate::types::{
+    error::RuneError,
+    rune::parse_spaced_rune,
+};
+use crate::{RpcAuth, RpcCredentials};
+
+fn ord_client(url: &str) -> OrdClient {
+    OrdClient::new(url, Arc::new(reqwest::Client::new()), RpcCredentials::new(RpcAuth::None))
+}
+
+// Raw JSON because the cap does not fit into a `serde_json::Value` number
+const UNCOMMON_GOODS: &str = r#"{
+    "block": 1,
+    "burned": 123,
+    "divisibility": 0,
+    "etching": "0000000000000000000000000000000000000000000000000000000000000000",
+    "mints": 34000,
+    "number": 0,
+    "premine": 0,
+    "spaced_rune": "UNCOMMON•GOODS",
+    "symbol": "⧉",
+    "terms": {
+        "amount": 1,
+        "cap": 340282366920938463463374607431768211455,
+        "height": [840000, 1050000],
+        "offset": [null, null]
+    },
+    "timestamp": 0,
+    "turbo": true
+}"#;
+
+fn json_body(body: String) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_raw(body, "application/json")
+}
+
+#[tokio::test]
+async fn test_ord_rune_lookup() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/rune/UNCOMMON%E2%80%A2GOODS"))
+        .respond_with(json_body(format!(
+            r#"{{ "entry": {}, "id": "1:0", "mintable": true, "parent": null }}"#,
+            UNCOMMON_GOODS
+        )))
+        .mount(&mock_server)
+        .await;
+
+    // The spacer is percent-encoded on the wire
+    let client = ord_client(&mock_server.uri());
+    let entry = client.rune("UNCOMMON•GOODS").await.unwrap();
+
+    assert_eq!(entry.id, "1:0");
+    assert_eq!(entry.name, "UNCOMMONGOODS");
+    assert_eq!(entry.spacers, 0b1000_0000);
+    assert_eq!(entry.symbol, Some('⧉'));
+    assert_eq!(entry.mints, 34_000);
+    assert_eq!(entry.burned, 123);
+    assert!(entry.turbo);
+
+    let terms = entry.terms.unwrap();
+    assert_eq!(terms.cap, Some(u128::MAX));
+    assert_eq!(terms.height_start, Some(840_000));
+    assert_eq!(terms.offset_end, None);
+}
+
+#[tokio::test]
+async fn test_ord_runes_paging() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/runes/1"))
+        .respond_with(json_body(format!(
+            r#"{{ "entries": [["1:0", {}]], "more": true, "prev": 0, "next": 2 }}"#,
+            UNCOMMON_GOODS
+        )))
+        .mount(&mock_server)
+        .await;
+
+    let client = ord_client(&mock_server.uri());
+    let page = client.runes(Some(1)).await.unwrap();
+
+    assert_eq!(page.entries.len(), 1);
+    assert_eq!(page.entries[0].spaced_name, "UNCOMMON•GOODS");
+    assert_eq!(page.prev, Some(0));
+    assert_eq!(page.next, Some(2));
+}
+
+#[tokio::test]
+async fn test_ord_output_and_status() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/output/abc:1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "address": "bc1pexample",
+            "confirmations": 6,
+            "indexed": true,
+            "outpoint": "abc:1",
+            "runes": {
+                "UNCOMMON•GOODS": { "amount": 2500, "divisibility": 0, "symbol": "⧉" }
+            },
+            "spent": true,
+            "value": 546
+        })))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/status"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "chain": "mainnet",
+            "height": 840_100,
+            "rune_index": true,
+            "runes": 52,
+            "unrecoverably_reorged": false,
+            "uptime": { "secs": 10, "nanos": 0 }
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let client = ord_client(&mock_server.uri());
+
+    let output = client.output("abc:1").await.unwrap();
+    assert!(output.spent);
+    assert_eq!(output.value, 546);
+    assert_eq!(output.runes[0].amount, 2500);
+
+    let status = client.status().await.unwrap();
+    assert_eq!(status.height, Some(840_100));
+    assert!(status.rune_index);
+}
+
+#[tokio::test]
+async fn test_ord_cross_check_reports_mismatches() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/rune/1:0"))
+        .respond_with(json_body(format!(r#"{{ "entry": {}, "id": "1:0" }}"#, UNCOMMON_GOODS)))
+        .mount(&mock_server)
+        .await;
+
+    let client = ord_client(&mock_server.uri());
+    let mut local = client.rune("1:0").await.unwrap();
+    assert!(client.cross_check(&local).await.unwrap().is_empty());
+
+    local.mints += 1;
+    local.divisibility = 2;
+    assert_eq!(client.cross_check(&local).await.unwrap(), vec!["divisibility", "mints"]);
+}
+
+#[tokio::test]
+async fn test_ord_unknown_rune() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/rune/NOPE"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&mock_server)
+        .await;
+
+    let client = ord_client(&mock_server.uri());
+    assert!(matches!(client.rune("NOPE").await, Err(RuneError::NotFound(_))));
+}
+
+#[test]
+fn test_parse_spaced_rune() {
+    assert_eq!(parse_spaced_rune("UNCOMMON•GOODS").unwrap(), ("UNCOMMONGOODS".to_string(), 0b1000_0000));
+    assert_eq!(parse_spaced_rune("A.B.C").unwrap(), ("ABC".to_string(), 0b11));
+    assert_eq!(parse_spaced_rune("DOG").unwrap(), ("DOG".to_string(), 0));
+}
+
+#[test]
+fn test_parse_spaced_rune_rejects_overlong_names() {
+    let longest = "BCGDENLQRQWDSLRUGSNLBTMFIJA•V•";
+    assert_eq!(parse_spaced_rune(longest).unwrap().1, 1 << 27 | 1 << 26);
+
+    // A spacer after the 32nd letter would shift past the u32 bitfield
+    let spaced = format!("{}•A", "A".repeat(33));
+    assert!(matches!(parse_spaced_rune(&spaced), Err(RuneError::InvalidRune(_))));
+    assert!(matches!(parse_spaced_rune(&"A".repeat(29)), Err(RuneError::InvalidRune(_))));
+}
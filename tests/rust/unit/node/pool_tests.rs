@@ -0,0 +1,207 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use async_trait::async_trait;
+
+use crate::services::node::{
+    backend::{BackendKind, BlockInfo, NodeBackend},
+    connection::NodeStatus,
+    pool::{NodePool, PoolConfig},
+};
+use crate::types::{
+    error::{RuneError, RuneResult},
+    rune::{NetworkType, RunesTransactionResponse, RuneTransfer, TransactionStatus},
+};
+
+/// Backend with a fixed tip that can be switched off
+struct FakeBackend {
+    height: AtomicU64,
+    down: AtomicBool,
+    delay: Duration,
+    calls: AtomicUsize,
+}
+
+impl FakeBackend {
+    fn new(height: u64) -> Arc<Self> {
+        Self::with_delay(height, Duration::ZERO)
+    }
+
+    fn with_delay(height: u64, delay: Duration) -> Arc<Self> {
+        Arc::new(Self {
+            height: AtomicU64::new(height),
+            down: AtomicBool::new(false),
+            delay,
+            calls: AtomicUsize::new(0),
+        })
+    }
+
+    async fn respond<T>(&self, value: T) -> RuneResult<T> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(self.delay).await;
+        if self.down.load(Ordering::SeqCst) {
+            return Err(RuneError::NodeConnectionError("connection refused".to_string()));
+        }
+        Ok(value)
+    }
+}
+
+#[async_trait]
+impl NodeBackend for FakeBackend {
+    fn kind(&self) -> BackendKind {
+        BackendKind::Bitcoind
+    }
+
+    async fn health_check(&self) -> RuneResult<NodeStatus> {
+        self.respond(NodeStatus {
+            is_connected: true,
+            block_height: self.height.load(Ordering::SeqCst),
+            peer_count: 8,
+            sync_progress: 1.0,
+        })
+        .await
+    }
+
+    async fn get_block_height(&self) -> RuneResult<u64> {
+        self.respond(self.height.load(Ordering::SeqCst)).await
+    }
+
+    async fn get_block_hash(&self, height: u64) -> RuneResult<String> {
+        self.respond(format!("hash{}", height)).await
+    }
+
+    async fn get_block(&self, hash: &str) -> RuneResult<BlockInfo> {
+        self.respond(BlockInfo {
+            hash: hash.to_string(),
+            height: 0,
+            previous_block_hash: None,
+            timestamp: 0,
+            tx_ids: Vec::new(),
+        })
+        .await
+    }
+
+    async fn get_raw_transaction(&self, _tx_id: &str) -> RuneResult<String> {
+        self.respond("00".to_string()).await
+    }
+
+    async fn get_transaction(&self, tx_id: &str) -> RuneResult<RunesTransactionResponse> {
+        if tx_id == "missing" {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            return Err(RuneError::NotFound(format!("{} not found", tx_id)));
+        }
+
+        self.respond(RunesTransactionResponse {
+            transaction_id: tx_id.to_string(),
+            runes: Vec::new(),
+            block_height: None,
+            confirmation_count: 0,
+            timestamp: 0,
+            network_type: NetworkType::Mainnet,
+            status: TransactionStatus::Pending,
+        })
+        .await
+    }
+
+    async fn get_address_transfers(&self, _address: &str) -> RuneResult<Vec<RuneTransfer>> {
+        self.respond(Vec::new()).await
+    }
+}
+
+fn pool_of(nodes: &[Arc<FakeBackend>]) -> NodePool {
+    let nodes = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| (format!("node{}", i), node.clone() as Arc<dyn NodeBackend>))
+        .collect();
+    NodePool::new(nodes, PoolConfig::default())
+}
+
+#[tokio::test]
+async fn test_pool_fails_over_to_next_node() {
+    let primary = FakeBackend::new(100);
+    let secondary = FakeBackend::new(100);
+    primary.down.store(true, Ordering::SeqCst);
+
+    let pool = pool_of(&[primary.clone(), secondary.clone()]);
+    let tx = pool.get_transaction("abc").await.unwrap();
+
+    assert_eq!(tx.transaction_id, "abc");
+    assert_eq!(primary.calls.load(Ordering::SeqCst), 1);
+    assert_eq!(secondary.calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_pool_does_not_fail_over_on_not_found() {
+    let primary = FakeBackend::new(100);
+    let secondary = FakeBackend::new(100);
+
+    let pool = pool_of(&[primary.clone(), secondary.clone()]);
+    let result = pool.get_transaction("missing").await;
+
+    assert!(matches!(result, Err(RuneError::NotFound(_))));
+    assert_eq!(secondary.calls.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn test_pool_returns_last_error_when_all_nodes_fail() {
+    let primary = FakeBackend::new(100);
+    let secondary = FakeBackend::new(100);
+    primary.down.store(true, Ordering::SeqCst);
+    secondary.down.store(true, Ordering::SeqCst);
+
+    let pool = pool_of(&[primary, secondary]);
+    let result = pool.get_block_height().await;
+
+    assert!(matches!(result, Err(RuneError::NodeConnectionError(_))));
+}
+
+#[tokio::test]
+async fn test_health_check_marks_failed_and_lagging_nodes() {
+    let lagging = FakeBackend::new(95);
+    let down = FakeBackend::new(100);
+    let synced = FakeBackend::new(100);
+    down.down.store(true, Ordering::SeqCst);
+
+    let pool = pool_of(&[lagging.clone(), down, synced.clone()]);
+    pool.check_health().await;
+
+    let status = pool.status();
+    assert!(status[0].healthy && status[0].lagging);
+    assert!(!status[1].healthy);
+    assert!(status[2].healthy && !status[2].lagging);
+    assert_eq!(status[2].last_status.as_ref().unwrap().block_height, 100);
+
+    // The synced node is preferred even though it was configured last
+    let calls_before = lagging.calls.load(Ordering::SeqCst);
+    assert_eq!(pool.get_block_height().await.unwrap(), 100);
+    assert_eq!(lagging.calls.load(Ordering::SeqCst), calls_before);
+}
+
+#[tokio::test]
+async fn test_pool_prefers_lower_latency() {
+    let slow = FakeBackend::with_delay(100, Duration::from_millis(50));
+    let fast = FakeBackend::new(100);
+
+    let pool = pool_of(&[slow.clone(), fast.clone()]);
+    pool.check_health().await;
+
+    let slow_calls = slow.calls.load(Ordering::SeqCst);
+    pool.get_raw_transaction("abc").await.unwrap();
+
+    assert_eq!(slow.calls.load(Ordering::SeqCst), slow_calls);
+    assert_eq!(fast.calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_unhealthy_node_recovers_after_successful_check() {
+    let node = FakeBackend::new(100);
+    let pool = pool_of(std::slice::from_ref(&node));
+
+    node.down.store(true, Ordering::SeqCst);
+    pool.check_health().await;
+    assert!(!pool.status()[0].healthy);
+
+    node.down.store(false, Ordering::SeqCst);
+    pool.check_health().await;
+    assert!(pool.status()[0].healthy);
+}
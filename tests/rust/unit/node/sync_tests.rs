@@ -3,13 +3,14 @@ use std::time::Duration;
 use tokio::time;
 use crate::services::node::{
     connection::{NodeConnection, NodeConfig, MetricsCollector},
-    sync::{SyncService, SyncStatus},
+    sync::SyncService,
 };
+use crate::testing::{FakeNode, NodeFailure};
 use metrics::{Counter, Gauge, Histogram};
 
 #[tokio::test]
 async fn test_sync_service_initialization() {
-    let node = create_test_node();
+    let (_fake, node) = create_test_node().await;
     let sync_service = SyncService::new(
         Arc::new(node),
         Duration::from_millis(100),
@@ -25,7 +26,7 @@ async fn test_sync_service_initialization() {
 
 #[tokio::test]
 async fn test_start_sync() {
-    let node = create_test_node();
+    let (_fake, node) = create_test_node().await;
     let sync_service = SyncService::new(
         Arc::new(node),
         Duration::from_millis(100),
@@ -41,7 +42,7 @@ async fn test_start_sync() {
 
 #[tokio::test]
 async fn test_stop_sync() {
-    let node = create_test_node();
+    let (_fake, node) = create_test_node().await;
     let sync_service = SyncService::new(
         Arc::new(node),
         Duration::from_millis(100),
@@ -63,7 +64,7 @@ async fn test_stop_sync() {
 
 #[tokio::test]
 async fn test_sync_progress() {
-    let node = create_test_node();
+    let (_fake, node) = create_test_node().await;
     let sync_service = SyncService::new(
         Arc::new(node),
         Duration::from_millis(100),
@@ -80,21 +81,188 @@ async fn test_sync_progress() {
     assert!(status.estimated_time_remaining.is_some());
 }
 
-fn create_test_node() -> NodeConnection {
+#[tokio::test]
+async fn test_start_sync_returns_before_the_blocks_are_fetched() {
+    let (_fake, node) = create_test_node().await;
+    let sync_service = SyncService::new(
+        Arc::new(node),
+        Duration::from_millis(5),
+    );
+
+    // The blocks are walked by a spawned task, none is fetched yet
+    sync_service.start_sync().await.unwrap();
+    let status = sync_service.get_sync_status().await.unwrap();
+    assert!(status.is_syncing);
+    assert_eq!(status.current_height, 0);
+    assert_eq!(status.target_height, 20);
+
+    time::timeout(Duration::from_secs(5), async {
+        while sync_service.get_sync_status().await.unwrap().is_syncing {
+            time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .unwrap();
+    let status = sync_service.get_sync_status().await.unwrap();
+    assert_eq!(status.current_height, 20);
+    assert_eq!(status.progress, 1.0);
+    assert!(status.estimated_time_remaining.is_none());
+}
+
+#[tokio::test]
+async fn test_failed_sync_stops_syncing() {
+    let (fake, node) = create_test_node().await;
+    let sync_service = SyncService::new(
+        Arc::new(node),
+        Duration::from_millis(5),
+    );
+
+    // Without the tip there is no target, the sync is not left running
+    fake.fail(NodeFailure::Unavailable);
+    assert!(sync_service.start_sync().await.is_err());
+    assert!(!sync_service.get_sync_status().await.unwrap().is_syncing);
+
+    fake.recover();
+    sync_service.start_sync().await.unwrap();
+    time::sleep(Duration::from_millis(20)).await;
+    fake.fail(NodeFailure::Unavailable);
+
+    time::timeout(Duration::from_secs(5), async {
+        while sync_service.get_sync_status().await.unwrap().is_syncing {
+            time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .unwrap();
+    assert!(sync_service.get_sync_status().await.unwrap().current_height < 20);
+}
+
+#[tokio::test]
+async fn test_pause_and_resume() {
+    let (_fake, node) = create_test_node().await;
+    let sync_service = SyncService::new(
+        Arc::new(node),
+        Duration::from_millis(10),
+    );
+
+    sync_service.start_sync().await.unwrap();
+    time::sleep(Duration::from_millis(50)).await;
+    sync_service.pause().await.unwrap();
+
+    let paused = sync_service.get_sync_status().await.unwrap();
+    assert!(paused.paused);
+    assert!(!paused.is_syncing);
+    assert!(paused.current_height < 20);
+
+    // A paused sync stays put, even when started again
+    sync_service.start_sync().await.unwrap();
+    time::sleep(Duration::from_millis(50)).await;
+    let status = sync_service.get_sync_status().await.unwrap();
+    assert_eq!(status.current_height, paused.current_height);
+    assert!(!status.is_syncing);
+
+    sync_service.resume().await.unwrap();
+    time::timeout(Duration::from_secs(5), async {
+        while sync_service.get_sync_status().await.unwrap().current_height < 20 {
+            time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+    assert!(!sync_service.get_sync_status().await.unwrap().paused);
+}
+
+#[tokio::test]
+async fn test_rewind_to_height() {
+    let (_fake, node) = create_test_node().await;
+    let sync_service = SyncService::new(
+        Arc::new(node),
+        Duration::from_millis(10),
+    );
+
+    assert!(sync_service.rewind_to(21).await.is_err());
+
+    // Paused, so the sync only moves back and waits for `resume`
+    sync_service.pause().await.unwrap();
+    sync_service.rewind_to(15).await.unwrap();
+    let status = sync_service.get_sync_status().await.unwrap();
+    assert_eq!(status.current_height, 14);
+    assert_eq!(status.target_height, 20);
+    assert!(!status.is_syncing);
+
+    sync_service.resume().await.unwrap();
+    time::timeout(Duration::from_secs(5), async {
+        while sync_service.get_sync_status().await.unwrap().is_syncing {
+            time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+    let status = sync_service.get_sync_status().await.unwrap();
+    assert_eq!(status.current_height, 20);
+
+    // Not paused, syncing restarts from the height right away
+    sync_service.rewind_to(1).await.unwrap();
+    let status = sync_service.get_sync_status().await.unwrap();
+    assert!(status.is_syncing);
+    assert!(status.current_height < 20);
+}
+
+#[tokio::test]
+async fn test_checkpoint_round_trip() {
+    let path = std::env::temp_dir().join(format!("sync-checkpoint-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let (_fake, node) = create_test_node().await;
+    let node = Arc::new(node);
+
+    // Nothing to resume from yet
+    let sync_service = SyncService::new(node.clone(), Duration::from_millis(1)).with_checkpoint(&path);
+    assert_eq!(sync_service.get_sync_status().await.unwrap().current_height, 0);
+    sync_service.rewind_to(13).await.unwrap();
+    sync_service.stop_sync().await.unwrap();
+    let height = sync_service.get_sync_status().await.unwrap().current_height;
+    sync_service.persist_progress().await.unwrap();
+
+    let restarted = SyncService::new(node.clone(), Duration::from_millis(1)).with_checkpoint(&path);
+    assert_eq!(restarted.get_sync_status().await.unwrap().current_height, height);
+    restarted.start_sync().await.unwrap();
+    time::timeout(Duration::from_secs(5), async {
+        while restarted.get_sync_status().await.unwrap().is_syncing {
+            time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(restarted.get_sync_status().await.unwrap().current_height, 20);
+
+    // A corrupt checkpoint starts over instead of failing
+    std::fs::write(&path, "not json").unwrap();
+    let corrupt = SyncService::new(node, Duration::from_millis(1)).with_checkpoint(&path);
+    assert_eq!(corrupt.get_sync_status().await.unwrap().current_height, 0);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+async fn create_test_node() -> (FakeNode, NodeConnection) {
+    let fake = FakeNode::start().await;
+    fake.mine_empty(20);
+
     let config = NodeConfig {
-        rpc_url: "http://localhost:8332".to_string(),
+        rpc_url: fake.uri(),
         username: None,
         password: None,
         timeout: Duration::from_secs(30),
         max_retries: 3,
+        ..Default::default()
     };
 
     let metrics = Arc::new(MetricsCollector {
-        transaction_counter: Counter::new("test_tx_counter"),
-        error_counter: Counter::new("test_error_counter"),
-        response_time: Histogram::new("test_response_time"),
-        active_connections: Gauge::new("test_active_connections"),
+        transaction_counter: Counter::noop(),
+        error_counter: Counter::noop(),
+        response_time: Histogram::noop(),
+        active_connections: Gauge::noop(),
     });
 
-    NodeConnection::new(config, metrics)
+    (fake, NodeConnection::new(config, metrics))
 } 
\ No newline at end of file
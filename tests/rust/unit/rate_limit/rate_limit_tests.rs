@@ -1,3 +1,4 @@
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::sleep;
@@ -6,11 +7,7 @@ use crate::services::rate_limit::{RateLimiter, RateLimitConfig, RateLimitMetrics
 
 #[tokio::test]
 async fn test_basic_rate_limiting() {
-    let metrics = Arc::new(RateLimitMetrics {
-        allowed_requests: metrics::Counter::new("test_allowed_requests"),
-        rejected_requests: metrics::Counter::new("test_rejected_requests"),
-        current_buckets: metrics::Gauge::new("test_current_buckets"),
-    });
+    let metrics = Arc::new(RateLimitMetrics::default());
 
     let config = RateLimitConfig {
         window_size: Duration::from_secs(1),
@@ -36,11 +33,7 @@ async fn test_basic_rate_limiting() {
 
 #[tokio::test]
 async fn test_burst_handling() {
-    let metrics = Arc::new(RateLimitMetrics {
-        allowed_requests: metrics::Counter::new("test_allowed_requests"),
-        rejected_requests: metrics::Counter::new("test_rejected_requests"),
-        current_buckets: metrics::Gauge::new("test_current_buckets"),
-    });
+    let metrics = Arc::new(RateLimitMetrics::default());
 
     let config = RateLimitConfig {
         window_size: Duration::from_secs(1),
@@ -65,11 +58,7 @@ async fn test_burst_handling() {
 
 #[tokio::test]
 async fn test_multiple_clients() {
-    let metrics = Arc::new(RateLimitMetrics {
-        allowed_requests: metrics::Counter::new("test_allowed_requests"),
-        rejected_requests: metrics::Counter::new("test_rejected_requests"),
-        current_buckets: metrics::Gauge::new("test_current_buckets"),
-    });
+    let metrics = Arc::new(RateLimitMetrics::default());
 
     let config = RateLimitConfig {
         window_size: Duration::from_secs(1),
@@ -99,11 +88,7 @@ async fn test_multiple_clients() {
 
 #[tokio::test]
 async fn test_cleanup() {
-    let metrics = Arc::new(RateLimitMetrics {
-        allowed_requests: metrics::Counter::new("test_allowed_requests"),
-        rejected_requests: metrics::Counter::new("test_rejected_requests"),
-        current_buckets: metrics::Gauge::new("test_current_buckets"),
-    });
+    let metrics = Arc::new(RateLimitMetrics::default());
 
     let config = RateLimitConfig {
         window_size: Duration::from_secs(1),
@@ -120,7 +105,7 @@ async fn test_cleanup() {
     }
 
     // Bucket sayısını kontrol et
-    assert_eq!(metrics.current_buckets.get_value(), 3);
+    assert_eq!(metrics.current_buckets.load(Ordering::Relaxed), 3);
 
     // Cleanup çağır
     limiter.cleanup_old_buckets(Duration::from_millis(100));
@@ -128,16 +113,12 @@ async fn test_cleanup() {
     limiter.cleanup_old_buckets(Duration::from_millis(100));
 
     // Bucket sayısı azalmış olmalı
-    assert_eq!(metrics.current_buckets.get_value(), 0);
+    assert_eq!(metrics.current_buckets.load(Ordering::Relaxed), 0);
 }
 
 #[tokio::test]
 async fn test_metrics() {
-    let metrics = Arc::new(RateLimitMetrics {
-        allowed_requests: metrics::Counter::new("test_allowed_requests"),
-        rejected_requests: metrics::Counter::new("test_rejected_requests"),
-        current_buckets: metrics::Gauge::new("test_current_buckets"),
-    });
+    let metrics = Arc::new(RateLimitMetrics::default());
 
     let config = RateLimitConfig {
         window_size: Duration::from_secs(1),
@@ -160,4 +141,44 @@ async fn test_metrics() {
     assert_eq!(stats.allowed_requests, 2);
     assert_eq!(stats.rejected_requests, 1);
     assert_eq!(stats.current_buckets, 1);
-} 
\ No newline at end of file
+} 
+
+#[tokio::test]
+async fn test_inspect_and_reset_buckets() {
+    let metrics = Arc::new(RateLimitMetrics::default());
+
+    let config = RateLimitConfig {
+        window_size: Duration::from_secs(60),
+        max_requests: 5,
+        burst_size: 1,
+    };
+
+    let limiter = RateLimiter::new(config, metrics.clone());
+    for _ in 0..5 {
+        assert!(limiter.check_rate_limit("heavy", 1).await.is_ok());
+    }
+    assert!(limiter.check_rate_limit("light", 1).await.is_ok());
+    assert!(limiter.check_rate_limit("heavy", 1).await.is_err());
+
+    // En çok kısıtlanan client önce gelir
+    let buckets = limiter.buckets().await;
+    assert_eq!(buckets.iter().map(|b| b.key.as_str()).collect::<Vec<_>>(), ["heavy", "light"]);
+    assert!(buckets[0].tokens < 1.0);
+    assert!((buckets[1].tokens - 4.0).abs() < 0.1);
+    assert_eq!(buckets[1].max_tokens, 5.0);
+
+    assert!(limiter.bucket("missing").await.is_none());
+    assert!(limiter.reset_bucket("heavy"));
+    assert!(!limiter.reset_bucket("heavy"));
+    assert!(limiter.bucket("heavy").await.is_none());
+    assert_eq!(metrics.current_buckets.load(Ordering::Relaxed), 1);
+
+    // Sıfırlanan client tam bir bucket ile başlar
+    for _ in 0..5 {
+        assert!(limiter.check_rate_limit("heavy", 1).await.is_ok());
+    }
+
+    assert_eq!(limiter.reset_all(), 2);
+    assert!(limiter.buckets().await.is_empty());
+    assert_eq!(metrics.current_buckets.load(Ordering::Relaxed), 0);
+}
@@ -0,0 +1,103 @@
+use crate::types::rune::{
+    format_rune_amount, AddressBalances, RuneBalance, RuneEntry, RuneOutput, RuneTerms,
+};
+
+fn balance(amount: u128) -> RuneBalance {
+    RuneBalance {
+        rune: "UNCOMMON•GOODS".to_string(),
+        amount,
+        divisibility: 2,
+        symbol: Some('⧉'),
+    }
+}
+
+fn output(outpoint: &str, spent: bool, runes: Vec<RuneBalance>) -> RuneOutput {
+    RuneOutput {
+        outpoint: outpoint.to_string(),
+        address: Some("bc1pexample".to_string()),
+        value: 546,
+        spent,
+        confirmations: 6,
+        runes,
+    }
+}
+
+#[test]
+fn test_format_rune_amount() {
+    assert_eq!(format_rune_amount(12_345, 0), "12345");
+    assert_eq!(format_rune_amount(12_345, 2), "123.45");
+    assert_eq!(format_rune_amount(12_300, 2), "123");
+    assert_eq!(format_rune_amount(12_310, 2), "123.1");
+    assert_eq!(format_rune_amount(5, 3), "0.005");
+    assert_eq!(format_rune_amount(u128::MAX, 38), "3.40282366920938463463374607431768211455");
+}
+
+#[test]
+fn test_address_balances_from_outputs() {
+    let mut other = balance(7);
+    other.rune = "DOG•GO•TO•THE•MOON".to_string();
+    other.divisibility = 0;
+
+    let outputs = vec![
+        output("abc:0", false, vec![balance(150)]),
+        output("abc:1", false, vec![balance(50), other]),
+        output("def:0", true, vec![balance(1_000)]),
+    ];
+
+    let balances = AddressBalances::from_outputs("bc1pexample", &outputs);
+    assert_eq!(balances.balances.len(), 2);
+
+    let dog = &balances.balances[0];
+    assert_eq!(dog.balance.rune, "DOG•GO•TO•THE•MOON");
+    assert_eq!(dog.balance.formatted, "7");
+
+    // Spent outputs do not count
+    let goods = &balances.balances[1];
+    assert_eq!(goods.balance.amount, "200");
+    assert_eq!(goods.balance.formatted, "2");
+    assert_eq!(goods.utxos.len(), 2);
+    assert_eq!(goods.utxos[0].formatted, "1.5");
+}
+
+#[test]
+fn test_rune_mint_window() {
+    let entry = RuneEntry {
+        id: "840000:1".to_string(),
+        name: "UNCOMMONGOODS".to_string(),
+        spaced_name: "UNCOMMON•GOODS".to_string(),
+        number: 0,
+        etching: "0".repeat(64),
+        block: 840_000,
+        divisibility: 0,
+        symbol: None,
+        spacers: 0,
+        premine: 100,
+        terms: Some(RuneTerms {
+            amount: Some(10),
+            cap: Some(5),
+            height_start: Some(840_010),
+            height_end: None,
+            offset_start: None,
+            offset_end: Some(20),
+        }),
+        turbo: false,
+        mints: 4,
+        burned: 0,
+        timestamp: 0,
+        holders: None,
+    };
+
+    assert!(!entry.mintable_at(840_009));
+    assert!(entry.mintable_at(840_010));
+    assert!(entry.mintable_at(840_019));
+    assert!(!entry.mintable_at(840_020));
+    assert_eq!(entry.supply(), 140);
+    assert_eq!(entry.max_supply(), 150);
+
+    let minted_out = RuneEntry { mints: 5, ..entry.clone() };
+    assert!(!minted_out.mintable_at(840_010));
+
+    let no_terms = RuneEntry { terms: None, ..entry };
+    assert!(!no_terms.mintable_at(840_010));
+    assert_eq!(no_terms.max_supply(), 100);
+}
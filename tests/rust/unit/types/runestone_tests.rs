@@ -0,0 +1,162 @@
+use crate::types::runestone::{
+    decode_varint, encode_varint, reserved_rune, rune_to_u128, u128_to_rune, Edict, Etching,
+    EtchingTerms, RuneId, Runestone,
+};
+
+#[test]
+fn test_varint_encoding() {
+    let encode = |n| {
+        let mut buffer = Vec::new();
+        encode_varint(&mut buffer, n);
+        buffer
+    };
+
+    assert_eq!(encode(0), vec![0x00]);
+    assert_eq!(encode(127), vec![0x7f]);
+    assert_eq!(encode(128), vec![0x80, 0x01]);
+    assert_eq!(encode(300), vec![0xac, 0x02]);
+    assert_eq!(encode(u128::MAX).len(), 19);
+
+    for n in [0, 127, 128, 300, u128::MAX] {
+        assert_eq!(decode_varint(&encode(n)), Some((n, encode(n).len())));
+    }
+    assert_eq!(decode_varint(&[0x80]), None);
+    assert_eq!(decode_varint(&[0xff; 19]), None);
+}
+
+#[test]
+fn test_rune_name_values() {
+    assert_eq!(rune_to_u128("A").unwrap(), 0);
+    assert_eq!(rune_to_u128("Z").unwrap(), 25);
+    assert_eq!(rune_to_u128("AA").unwrap(), 26);
+    assert_eq!(rune_to_u128("ZZ").unwrap(), 701);
+    assert_eq!(rune_to_u128("AAA").unwrap(), 702);
+    assert!(rune_to_u128("abc").is_err());
+    assert!(rune_to_u128("").is_err());
+
+    for name in ["A", "Z", "AA", "ZZ", "AAA", "UNCOMMONGOODS"] {
+        assert_eq!(u128_to_rune(rune_to_u128(name).unwrap()), name);
+    }
+    assert_eq!(u128_to_rune(u128::MAX), "BCGDENLQRQWDSLRUGSNLBTMFIJAV");
+    assert_eq!(reserved_rune(RuneId { block: 0, tx: 0 }), "AAAAAAAAAAAAAAAAAAAAAAAAAAA");
+}
+
+#[test]
+fn test_rune_id_round_trip() {
+    let id: RuneId = "840000:1".parse().unwrap();
+    assert_eq!(id, RuneId { block: 840_000, tx: 1 });
+    assert_eq!(id.to_string(), "840000:1");
+    assert!("840000".parse::<RuneId>().is_err());
+}
+
+#[test]
+fn test_edicts_are_delta_encoded() {
+    let runestone = Runestone {
+        edicts: vec![
+            Edict { id: RuneId { block: 3, tx: 5 }, amount: 7, output: 0 },
+            Edict { id: RuneId { block: 2, tx: 3 }, amount: 1, output: 1 },
+            Edict { id: RuneId { block: 2, tx: 4 }, amount: 1, output: 1 },
+        ],
+        ..Default::default()
+    };
+
+    assert_eq!(
+        runestone.encipher().unwrap(),
+        vec![
+            0x6a, 0x5d, 0x0d,
+            0x00,
+            0x02, 0x03, 0x01, 0x01,
+            0x00, 0x01, 0x01, 0x01,
+            0x01, 0x05, 0x07, 0x00,
+        ]
+    );
+}
+
+#[test]
+fn test_etching_fields() {
+    let runestone = Runestone {
+        etching: Some(Etching {
+            rune: Some("AA".to_string()),
+            divisibility: Some(2),
+            spacers: Some(1),
+            symbol: Some('$'),
+            premine: Some(1_000),
+            terms: Some(EtchingTerms {
+                amount: Some(100),
+                cap: Some(10),
+                ..Default::default()
+            }),
+            turbo: true,
+        }),
+        mint: Some(RuneId { block: 1, tx: 0 }),
+        pointer: Some(1),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        runestone.encipher().unwrap(),
+        vec![
+            0x6a, 0x5d, 0x17,
+            0x02, 0x07, // flags: etching | terms | turbo
+            0x04, 0x1a, // rune AA
+            0x01, 0x02, // divisibility
+            0x03, 0x01, // spacers
+            0x05, 0x24, // symbol '$'
+            0x06, 0xe8, 0x07, // premine
+            0x0a, 0x64, // amount
+            0x08, 0x0a, // cap
+            0x14, 0x01, 0x14, 0x00, // mint 1:0
+            0x16, 0x01, // pointer
+        ]
+    );
+}
+
+#[test]
+fn test_decipher_round_trip() {
+    let runestone = Runestone {
+        edicts: vec![
+            Edict { id: RuneId { block: 2, tx: 3 }, amount: 1, output: 1 },
+            Edict { id: RuneId { block: 2, tx: 4 }, amount: 0, output: 2 },
+            Edict { id: RuneId { block: 840_000, tx: 1 }, amount: u128::MAX, output: 0 },
+        ],
+        etching: Some(Etching {
+            rune: Some("UNCOMMONGOODS".to_string()),
+            divisibility: Some(2),
+            spacers: Some(0b1000_0000),
+            symbol: Some('⧉'),
+            premine: Some(1_000),
+            terms: Some(EtchingTerms {
+                amount: Some(1),
+                cap: Some(u128::MAX),
+                height: (Some(840_000), Some(1_050_000)),
+                offset: (None, Some(10)),
+            }),
+            turbo: true,
+        }),
+        mint: Some(RuneId { block: 1, tx: 0 }),
+        pointer: Some(1),
+    };
+
+    assert_eq!(Runestone::decipher(&runestone.encipher().unwrap()), Some(runestone));
+    assert_eq!(Runestone::decipher(&Runestone::default().encipher().unwrap()), Some(Runestone::default()));
+}
+
+#[test]
+fn test_decipher_rejects_cenotaphs() {
+    // Not a runestone at all
+    assert_eq!(Runestone::decipher(&[0x6a, 0x04, 0x01, 0x02, 0x03, 0x04]), None);
+    // Unknown even tag
+    assert_eq!(Runestone::decipher(&[0x6a, 0x5d, 0x02, 0x7e, 0x01]), None);
+    // Unknown odd tags are ignored
+    assert_eq!(Runestone::decipher(&[0x6a, 0x5d, 0x02, 0x7f, 0x01]), Some(Runestone::default()));
+    // Unknown flag
+    assert_eq!(Runestone::decipher(&[0x6a, 0x5d, 0x02, 0x02, 0x08]), None);
+    // Tag without a value
+    assert_eq!(Runestone::decipher(&[0x6a, 0x5d, 0x01, 0x16]), None);
+    // Truncated varint
+    assert_eq!(Runestone::decipher(&[0x6a, 0x5d, 0x01, 0x80]), None);
+    // Edict body not a multiple of four
+    assert_eq!(Runestone::decipher(&[0x6a, 0x5d, 0x04, 0x00, 0x01, 0x01, 0x01]), None);
+    // Opcode other than a push
+    assert_eq!(Runestone::decipher(&[0x6a, 0x5d, 0x51]), None);
+}
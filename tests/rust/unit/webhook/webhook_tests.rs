@@ -1,18 +1,17 @@
 use std::sync::Arc;
 use actix_web::{test, web, App};
-use serde_json::json;
 
 use crate::{
     api::webhook::{
         handlers::{WebhookApiContext, RegisterWebhookRequest},
         routes::configure_routes,
     },
-    services::webhook::manager::{WebhookManager, WebhookEventType},
+    services::webhook::manager::{WebhookConfig, WebhookEvent, WebhookManager, WebhookEventType},
 };
 
 async fn create_test_app() -> impl actix_web::dev::Service<actix_http::Request, Response = actix_web::dev::ServiceResponse, Error = actix_web::Error> {
     let webhook_manager = WebhookManager::new(
-        Arc::new(metrics::Counter::new("test_webhook_errors"))
+        Arc::new(metrics::Counter::noop())
     );
 
     let context = WebhookApiContext {
@@ -120,4 +119,158 @@ async fn test_unregister_nonexistent_webhook() {
 
     let body: serde_json::Value = test::read_body_json(resp).await;
     assert!(!body["success"].as_bool().unwrap());
-} 
\ No newline at end of file
+} 
+
+#[actix_web::test]
+async fn test_delivery_health() {
+    use wiremock::{matchers::method, Mock, MockServer, ResponseTemplate};
+
+    let healthy = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&healthy)
+        .await;
+    let failing = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&failing)
+        .await;
+
+    let manager = WebhookManager::new(Arc::new(metrics::Counter::noop()));
+    for (url, secret) in [(healthy.uri(), Some("test-secret".to_string())), (failing.uri(), None)] {
+        manager.register_webhook(WebhookConfig {
+            url,
+            secret,
+            events: vec![WebhookEventType::BlockSynced],
+            max_retries: 2,
+            retry_delay: 1,
+        }).await.unwrap();
+    }
+
+    let event = WebhookEvent {
+        event_type: WebhookEventType::BlockSynced,
+        timestamp: 0,
+        payload: serde_json::json!({"height": 1}),
+    };
+    manager.send_event(event.clone()).await.unwrap();
+    manager.send_event(event).await.unwrap();
+
+    let webhooks = manager.list_webhooks().await;
+    assert_eq!(webhooks.len(), 2);
+
+    let ok = &webhooks[0];
+    assert!(ok.healthy);
+    assert!(ok.signed);
+    assert_eq!(ok.health.delivered, 2);
+    assert_eq!(ok.health.failed, 0);
+    assert!(ok.health.last_success_at.is_some());
+
+    // Retries count towards a single failed delivery
+    let bad = &webhooks[1];
+    assert!(!bad.healthy);
+    assert!(!bad.signed);
+    assert_eq!(bad.health.delivered, 0);
+    assert_eq!(bad.health.failed, 2);
+    assert_eq!(bad.health.consecutive_failures, 2);
+    assert!(bad.health.last_error.as_deref().unwrap().contains("500"));
+    assert_eq!(failing.received_requests().await.unwrap().len(), 4);
+
+    // Health goes away with the webhook
+    manager.unregister_webhook(&failing.uri()).await.unwrap();
+    manager.register_webhook(WebhookConfig {
+        url: failing.uri(),
+        secret: None,
+        events: vec![WebhookEventType::BlockSynced],
+        max_retries: 2,
+        retry_delay: 1,
+    }).await.unwrap();
+    assert_eq!(manager.list_webhooks().await[1].health.failed, 0);
+}
+
+#[actix_web::test]
+async fn test_drain_waits_for_deliveries() {
+    use std::time::Duration;
+    use wiremock::{matchers::method, Mock, MockServer, ResponseTemplate};
+
+    let slow = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(300)))
+        .mount(&slow)
+        .await;
+
+    let manager = Arc::new(WebhookManager::new(Arc::new(metrics::Counter::noop())));
+    manager.register_webhook(WebhookConfig {
+        url: slow.uri(),
+        secret: None,
+        events: vec![WebhookEventType::BlockSynced],
+        max_retries: 1,
+        retry_delay: 1,
+    }).await.unwrap();
+    let event = WebhookEvent {
+        event_type: WebhookEventType::BlockSynced,
+        timestamp: 0,
+        payload: serde_json::json!({"height": 1}),
+    };
+
+    // Nothing in flight, nothing to wait for
+    let idle = Arc::new(WebhookManager::new(Arc::new(metrics::Counter::noop())));
+    assert_eq!(idle.drain(Duration::from_secs(5)).await, 0);
+
+    let delivery = tokio::spawn({
+        let manager = manager.clone();
+        let event = event.clone();
+        async move { manager.send_event(event).await }
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    assert_eq!(manager.drain(Duration::from_secs(5)).await, 0);
+    assert!(delivery.is_finished());
+    delivery.await.unwrap().unwrap();
+    assert_eq!(manager.list_webhooks().await[0].health.delivered, 1);
+
+    // Closed for good once drained
+    assert!(manager.send_event(event).await.is_err());
+    assert_eq!(slow.received_requests().await.unwrap().len(), 1);
+}
+
+#[actix_web::test]
+async fn test_drain_gives_up_at_the_deadline() {
+    use std::time::{Duration, Instant};
+    use wiremock::{matchers::method, Mock, MockServer, ResponseTemplate};
+
+    let stuck = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(10)))
+        .mount(&stuck)
+        .await;
+
+    let manager = Arc::new(WebhookManager::new(Arc::new(metrics::Counter::noop())));
+    manager.register_webhook(WebhookConfig {
+        url: stuck.uri(),
+        secret: None,
+        events: vec![WebhookEventType::BlockSynced],
+        max_retries: 1,
+        retry_delay: 1,
+    }).await.unwrap();
+
+    let delivery = tokio::spawn({
+        let manager = manager.clone();
+        async move {
+            manager.send_event(WebhookEvent {
+                event_type: WebhookEventType::BlockSynced,
+                timestamp: 0,
+                payload: serde_json::json!({}),
+            }).await
+        }
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let started = Instant::now();
+    assert_eq!(manager.drain(Duration::from_millis(200)).await, 1);
+    assert!(started.elapsed() < Duration::from_secs(2));
+
+    // An abandoned delivery no longer counts once its caller drops it
+    delivery.abort();
+    let _ = delivery.await;
+    assert_eq!(manager.drain(Duration::ZERO).await, 0);
+}